@@ -53,12 +53,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("启动 Axum + Kafka 示例应用...");
 
-    // 创建 Kafka AppState
-    let kafka_state = create_default_kafka_app_state(
-        vec!["localhost:9092".to_string()],
-        "axum-example-group".to_string(),
-    )
-    .await?;
+    // 创建 Kafka AppState，关闭自动提交，由轮询服务在处理成功后提交偏移量
+    let mut producer_config = KafkaProducerConfig::default();
+    producer_config.base.bootstrap_servers = vec!["localhost:9092".to_string()];
+    let mut consumer_config = KafkaConsumerConfig::default();
+    consumer_config.base.bootstrap_servers = vec!["localhost:9092".to_string()];
+    consumer_config.group_id = "axum-example-group".to_string();
+    consumer_config.enable_auto_commit = Some(false);
+    let kafka_state = KafkaAppState::new(producer_config, consumer_config).await?;
 
     println!("Kafka AppState 创建成功");
 
@@ -190,7 +192,9 @@ async fn start_polling_consumer(state: AppState) {
         topics,
         Duration::from_secs(1), // 每秒轮询一次
         10,                     // 每次最多处理10条消息
-    );
+    )
+    // 每条消息处理成功后提交，失败的消息会被重新投递（至少一次）
+    .with_commit_strategy(CommitStrategy::AfterEachMessage);
 
     // 在后台任务中启动轮询
     task::spawn(async move {
@@ -0,0 +1,67 @@
+//! Axum + Redis AppState 示例
+//!
+//! 演示如何将 [`RedisAppState`] 作为 Axum 应用状态，在处理函数中读写缓存；
+//! 也可以通过 `State<RedisConnection>` 直接取得连接
+
+use axum::{
+    Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+};
+use clamber_web_core::redis::{RedisAppState, RedisConnection, create_redis_app_state_from_url};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 初始化日志
+    tracing_subscriber::fmt::init();
+
+    let state = create_redis_app_state_from_url("redis://127.0.0.1:6379").await?;
+
+    let app = Router::new()
+        .route("/cache/{key}", get(get_value).put(put_value))
+        .route("/ping", get(ping))
+        .with_state(state);
+
+    let listener = TcpListener::bind("0.0.0.0:3000").await?;
+    println!("服务器启动在 http://0.0.0.0:3000");
+    println!("  GET  /cache/{{key}} - 读取缓存");
+    println!("  PUT  /cache/{{key}} - 写入缓存（60 秒后过期）");
+    println!("  GET  /ping        - 检查 Redis 连接");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// 读取缓存，键不存在时返回 404
+async fn get_value(
+    State(state): State<RedisAppState>,
+    Path(key): Path<String>,
+) -> Result<String, StatusCode> {
+    state
+        .get(&key)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// 写入缓存，60 秒后过期
+async fn put_value(
+    State(state): State<RedisAppState>,
+    Path(key): Path<String>,
+    value: String,
+) -> StatusCode {
+    match state.set_ex(&key, &value, Duration::from_secs(60)).await {
+        Ok(()) => StatusCode::NO_CONTENT,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// 直接提取连接执行 PING
+async fn ping(State(mut connection): State<RedisConnection>) -> StatusCode {
+    match connection.ping().await {
+        Ok(()) => StatusCode::OK,
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
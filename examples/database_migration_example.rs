@@ -0,0 +1,115 @@
+//! SeaORM 迁移示例
+//!
+//! 定义创建 `users` 表的迁移（与分页示例中的用户实体一致），在内存 SQLite 上执行后插入一条记录。
+//! 运行：`cargo run --example database_migration_example --features database-migration,database-sqlite`
+
+use clamber_web_core::database::{DatabaseConfig, MigrationRunner, Repository, SeaOrmConnection};
+use sea_orm::Set;
+use sea_orm_migration::prelude::*;
+
+/// 用户实体
+mod user {
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "users")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        pub name: String,
+        pub email: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// 创建 users 表
+mod m20250101_000001_create_users {
+    use sea_orm_migration::prelude::*;
+
+    #[derive(DeriveIden)]
+    enum Users {
+        Table,
+        Id,
+        Name,
+        Email,
+    }
+
+    #[derive(DeriveMigrationName)]
+    pub struct Migration;
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for Migration {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(Users::Table)
+                        .if_not_exists()
+                        .col(
+                            ColumnDef::new(Users::Id)
+                                .big_integer()
+                                .not_null()
+                                .auto_increment()
+                                .primary_key(),
+                        )
+                        .col(ColumnDef::new(Users::Name).string().not_null())
+                        .col(ColumnDef::new(Users::Email).string().not_null())
+                        .to_owned(),
+                )
+                .await
+        }
+
+        async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .drop_table(Table::drop().table(Users::Table).to_owned())
+                .await
+        }
+    }
+}
+
+/// 按顺序列出所有迁移
+struct Migrator;
+
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![Box::new(m20250101_000001_create_users::Migration)]
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 初始化日志
+    tracing_subscriber::fmt::init();
+
+    // 单连接保证内存 SQLite 在各语句间共享同一个数据库
+    let config = DatabaseConfig {
+        max_connections: 1,
+        min_connections: 1,
+        run_migrations_on_startup: true,
+        ..DatabaseConfig::for_url("sqlite::memory:")
+    };
+    let connection = SeaOrmConnection::new_with_migrator::<Migrator>(config).await?;
+
+    let runner = MigrationRunner::new(&connection, Migrator);
+    for status in runner.status().await? {
+        println!("{} 已应用: {}", status.name, status.applied);
+    }
+
+    let users = Repository::<user::Entity>::from_connection(&connection);
+    let user = users
+        .insert(user::ActiveModel {
+            name: Set("alice".to_string()),
+            email: Set("alice@example.com".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    println!("插入用户: {:?}", user);
+
+    runner.down(1).await?;
+    println!("已回滚最近一次迁移");
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Axum + SeaORM 分页示例
+//!
+//! 演示如何在列表接口中使用 `paginate_into`，
+//! 请求示例：`GET /users?page=2&page_size=10&sort_by=name&sort_dir=desc`；
+//! 创建用户时邮箱重复（唯一约束冲突）返回 409
+
+use axum::{
+    Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+};
+use clamber_web_core::database::{
+    DatabaseError, PageParams, PaginateExt, Paginated, SeaOrmConnection,
+};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serde::Deserialize;
+use tokio::net::TcpListener;
+
+/// 用户实体
+mod user {
+    use sea_orm::entity::prelude::*;
+    use serde::Serialize;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+    #[sea_orm(table_name = "users")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        pub name: String,
+        pub email: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 初始化日志
+    tracing_subscriber::fmt::init();
+
+    let connection = SeaOrmConnection::from_env().await?;
+
+    let app = Router::new()
+        .route("/users", get(list_users).post(create_user))
+        .with_state(connection.inner);
+
+    let listener = TcpListener::bind("0.0.0.0:3000").await?;
+    println!("服务器启动在 http://0.0.0.0:3000，用户列表: GET /users?page=1&page_size=20");
+    println!("创建用户: POST /users {{\"name\": \"alice\", \"email\": \"alice@example.com\"}}");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// 用户列表接口的每页条数上限
+const MAX_USERS_PAGE_SIZE: u64 = 100;
+
+/// 分页查询用户列表，`sort_by` 可以是任意用户列名
+async fn list_users(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<PageParams>,
+) -> Result<Json<Paginated<user::Model>>, (StatusCode, String)> {
+    user::Entity::find()
+        .paginate_into_with_limit(&db, params, MAX_USERS_PAGE_SIZE)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            let code = if e.is_query_error() {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (code, e.to_string())
+        })
+}
+
+/// 创建用户请求
+#[derive(Deserialize)]
+struct CreateUser {
+    name: String,
+    email: String,
+}
+
+/// 创建用户，邮箱已存在时返回 409
+async fn create_user(
+    State(db): State<DatabaseConnection>,
+    Json(input): Json<CreateUser>,
+) -> Result<(StatusCode, Json<user::Model>), (StatusCode, String)> {
+    user::ActiveModel {
+        name: Set(input.name),
+        email: Set(input.email),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .map(|user| (StatusCode::CREATED, Json(user)))
+    .map_err(|e| {
+        let e = DatabaseError::from(e);
+        let code = if e.is_constraint_error() {
+            StatusCode::CONFLICT
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        (code, e.to_string())
+    })
+}
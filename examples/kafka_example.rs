@@ -72,15 +72,20 @@ async fn basic_producer_example() -> Result<(), Box<dyn std::error::Error>> {
         .await?;
     println!("发送序列化消息成功");
 
-    // 批量发送消息
+    // 并行批量发送消息
     let messages = vec![
-        (Some("batch_key1".to_string()), b"Batch message 1".to_vec()),
-        (Some("batch_key2".to_string()), b"Batch message 2".to_vec()),
-        (Some("batch_key3".to_string()), b"Batch message 3".to_vec()),
+        BatchMessage::new(Some("batch_key1".to_string()), "Batch message 1"),
+        BatchMessage::new(Some("batch_key2".to_string()), "Batch message 2"),
+        BatchMessage::new(Some("batch_key3".to_string()), "Batch message 3"),
     ];
 
-    producer.send_batch("batch-topic", messages).await?;
-    println!("批量发送消息成功");
+    for result in producer.send_batch_parallel("batch-topic", messages).await {
+        let (partition, offset) = result?;
+        println!(
+            "批量消息发送成功: partition={}, offset={}",
+            partition, offset
+        );
+    }
 
     // 刷新缓冲区
     producer.flush().await?;
@@ -242,30 +247,24 @@ async fn consumer_group_example() -> Result<(), Box<dyn std::error::Error>> {
         manager.consumer_count()
     );
 
-    // 启动所有消费者
-    manager
-        .start_all(&["test-topic", "user-events", "batch-topic"])
+    // 每个消费者在独立任务中并行消费，由 Kafka 在组内分配分区
+    let handle = manager
+        .start_all_with_handler(&["test-topic", "user-events", "batch-topic"], |message| {
+            println!(
+                "消费者组收到消息: topic={}, partition={}, payload={:?}",
+                message.topic(),
+                message.partition(),
+                String::from_utf8_lossy(message.payload().unwrap_or(&[]))
+            );
+            Ok(())
+        })
         .await?;
-    println!("所有消费者启动成功");
+    println!("所有消费者启动成功，共 {} 个消费任务", handle.task_count());
 
-    // 获取第一个消费者并消费一条消息
-    if let Some(consumer) = manager.get_consumer(0) {
-        match consumer
-            .consume_message_with_timeout(Duration::from_secs(3))
-            .await?
-        {
-            Some(message) => {
-                println!(
-                    "消费者组中的消费者0收到消息: topic={}, payload={:?}",
-                    message.topic(),
-                    String::from_utf8_lossy(message.payload().unwrap_or(&[]))
-                );
-            }
-            None => {
-                println!("消费者组中的消费者0: 超时，未收到消息");
-            }
-        }
-    }
+    // 运行一段时间后停止所有消费者
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    handle.shutdown().await?;
+    println!("所有消费者已停止");
 
     Ok(())
 }
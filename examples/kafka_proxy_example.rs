@@ -3,7 +3,9 @@
 //! 使用 Pingora 实现代理服务器，将请求转发到 Kafka example API 和静态文件服务
 
 use clamber_web_core::proxy::{ProxyConfig, SimpleProxyServer};
-use clamber_web_core::proxy_config::{LocationConfig, LocationType, UpstreamConfig};
+use clamber_web_core::proxy_config::{
+    LocationConfig, LocationType, LogFormat, MatchType, UpstreamConfig,
+};
 use std::collections::HashMap;
 use std::fs;
 
@@ -43,6 +45,7 @@ fn create_default_config() -> ProxyConfig {
         UpstreamConfig {
             servers: vec!["127.0.0.1:3000".to_string()],
             lb_strategy: "roundrobin".to_string(),
+            ..Default::default()
         },
     );
 
@@ -52,6 +55,7 @@ fn create_default_config() -> ProxyConfig {
         UpstreamConfig {
             servers: vec!["127.0.0.1:3001".to_string()],
             lb_strategy: "roundrobin".to_string(),
+            ..Default::default()
         },
     );
 
@@ -61,33 +65,65 @@ fn create_default_config() -> ProxyConfig {
         LocationConfig {
             path: "/api/kafka/".to_string(),
             location_type: LocationType::Proxy,
+            match_type: MatchType::Prefix,
             proxy_pass: Some("kafka_api".to_string()),
             root: None,
             index: None,
+            request_headers: None,
+            response_headers: None,
+            rate_limit: None,
+            websocket: false,
+            cache_ttl_secs: None,
+            allow: None,
+            deny: None,
         },
         // 配置 API 路由 - 转发到 Kafka config example
         LocationConfig {
             path: "/api/config/".to_string(),
             location_type: LocationType::Proxy,
+            match_type: MatchType::Prefix,
             proxy_pass: Some("kafka_config_api".to_string()),
             root: None,
             index: None,
+            request_headers: None,
+            response_headers: None,
+            rate_limit: None,
+            websocket: false,
+            cache_ttl_secs: None,
+            allow: None,
+            deny: None,
         },
         // 静态文件服务
         LocationConfig {
             path: "/static/".to_string(),
             location_type: LocationType::Static,
+            match_type: MatchType::Prefix,
             proxy_pass: None,
             root: Some("./static".to_string()),
             index: Some(vec!["index.html".to_string(), "index.htm".to_string()]),
+            request_headers: None,
+            response_headers: None,
+            rate_limit: None,
+            websocket: false,
+            cache_ttl_secs: None,
+            allow: None,
+            deny: None,
         },
         // 根路径 - 提供默认页面
         LocationConfig {
             path: "/".to_string(),
             location_type: LocationType::Static,
+            match_type: MatchType::Prefix,
             proxy_pass: None,
             root: Some("./static".to_string()),
             index: Some(vec!["index.html".to_string(), "index.htm".to_string()]),
+            request_headers: None,
+            response_headers: None,
+            rate_limit: None,
+            websocket: false,
+            cache_ttl_secs: None,
+            allow: None,
+            deny: None,
         },
     ];
 
@@ -100,6 +136,7 @@ fn create_default_config() -> ProxyConfig {
         ssl_key: None,
         upstreams,
         locations,
+        log_format: LogFormat::Combined,
     }
 }
 
@@ -2,9 +2,11 @@
 //!
 //! 展示如何使用 clamber-web-core 的 proxy 模块创建反向代理服务器
 
-use std::collections::HashMap;
-use clamber_web_core::proxy_config::{LocationConfig, LocationType, UpstreamConfig};
+use clamber_web_core::proxy_config::{
+    LocationConfig, LocationType, LogFormat, MatchType, UpstreamConfig,
+};
 use clamber_web_core::{ProxyConfig, ProxyServer};
+use std::collections::HashMap;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 创建上游服务器配置
@@ -14,6 +16,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         UpstreamConfig {
             servers: vec!["127.0.0.1:3000".to_string()], // 后端服务地址
             lb_strategy: "roundrobin".to_string(),
+            ..Default::default()
         },
     );
 
@@ -22,16 +25,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         LocationConfig {
             path: "/api/".to_string(),
             location_type: LocationType::Proxy,
+            match_type: MatchType::Prefix,
             proxy_pass: Some("backend".to_string()), // 代理到 backend 上游
             root: None,
             index: None,
+            request_headers: None,
+            response_headers: None,
+            rate_limit: None,
+            websocket: false,
+            cache_ttl_secs: None,
+            allow: None,
+            deny: None,
         },
         LocationConfig {
             path: "/static/".to_string(),
             location_type: LocationType::Static,
+            match_type: MatchType::Prefix,
             proxy_pass: None,
             root: Some("./static".to_string()), // 静态文件根目录
             index: Some(vec!["index.html".to_string()]),
+            request_headers: None,
+            response_headers: None,
+            rate_limit: None,
+            websocket: false,
+            cache_ttl_secs: None,
+            allow: None,
+            deny: None,
         },
     ];
 
@@ -44,6 +63,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         ssl_key: None,
         upstreams,
         locations,
+        log_format: LogFormat::Combined,
     };
 
     // 创建并启动代理服务器
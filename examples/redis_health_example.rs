@@ -0,0 +1,59 @@
+//! Axum + Redis 健康监控示例
+//!
+//! 演示如何在后台监控 Redis 可用性，并在就绪探针中直接读取最新状态
+
+use axum::{Router, extract::State, http::StatusCode, response::Json, routing::get};
+use clamber_web_core::redis::{
+    RedisConnection, RedisHealthMonitor, RedisHealthMonitorConfig, RedisHealthStatus,
+};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+
+/// 应用状态
+#[derive(Clone)]
+struct AppState {
+    redis_health: watch::Receiver<RedisHealthStatus>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 初始化日志
+    tracing_subscriber::fmt::init();
+
+    let connection = RedisConnection::from_url("redis://127.0.0.1:6379").await?;
+
+    // 每 5 秒检查一次，连续失败 3 次才判定为不健康
+    let monitor = RedisHealthMonitor::start(
+        connection,
+        RedisHealthMonitorConfig {
+            interval: Duration::from_secs(5),
+            failure_threshold: 3,
+        },
+    );
+
+    let app = Router::new()
+        .route("/ready", get(readiness))
+        .with_state(AppState {
+            redis_health: monitor.subscribe(),
+        });
+
+    let listener = TcpListener::bind("0.0.0.0:3000").await?;
+    println!("服务器启动在 http://0.0.0.0:3000，就绪探针: GET /ready");
+    axum::serve(listener, app).await?;
+
+    // monitor 被丢弃时后台检查任务随之停止
+    drop(monitor);
+    Ok(())
+}
+
+/// 就绪探针：读取后台监控发布的最新状态，不在请求中执行 PING
+async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<RedisHealthStatus>) {
+    let status = state.redis_health.borrow().clone();
+    let code = if status.is_healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(status))
+}
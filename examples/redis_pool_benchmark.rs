@@ -0,0 +1,116 @@
+//! Redis 连接池基准测试
+//!
+//! 对比 64 个并发任务下单连接与多连接池的吞吐量
+//!
+//! 运行: cargo run --example redis_pool_benchmark --release
+
+use clamber_web_core::redis::{RedisConfig, RedisConnection, RedisPool, RedisPoolStrategy};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const WORKERS: usize = 64;
+const OPS_PER_WORKER: usize = 500;
+const POOL_SIZE: usize = 8;
+
+/// 单连接：所有任务共享同一个 ConnectionManager
+async fn bench_single_connection(url: &str) -> Result<Duration, Box<dyn std::error::Error>> {
+    let connection = RedisConnection::from_url(url).await?;
+    let start = Instant::now();
+
+    let mut handles = Vec::with_capacity(WORKERS);
+    for worker in 0..WORKERS {
+        let mut conn = connection.clone();
+        handles.push(tokio::spawn(async move {
+            for i in 0..OPS_PER_WORKER {
+                let key = format!("bench:single:{}:{}", worker, i % 16);
+                conn.set_builtin(&key, i).await?;
+                conn.get_builtin(&key).await?;
+            }
+            Ok::<(), clamber_web_core::redis::RedisError>(())
+        }));
+    }
+    for handle in handles {
+        handle.await??;
+    }
+
+    Ok(start.elapsed())
+}
+
+/// 连接池：任务按策略分散到多个 ConnectionManager
+async fn bench_pool(
+    url: &str,
+    strategy: RedisPoolStrategy,
+) -> Result<Duration, Box<dyn std::error::Error>> {
+    let config = RedisConfig {
+        pool_size: POOL_SIZE,
+        pool_strategy: strategy,
+        ..RedisConfig::from_url(url)
+    };
+    let pool = RedisPool::new(config).await?;
+    let start = Instant::now();
+
+    let mut handles = Vec::with_capacity(WORKERS);
+    for worker in 0..WORKERS {
+        let pool = pool.clone();
+        handles.push(tokio::spawn(async move {
+            for i in 0..OPS_PER_WORKER {
+                let key = format!("bench:pool:{}:{}", worker, i % 16);
+                let mut conn = pool.get();
+                conn.set_builtin(&key, i).await?;
+                conn.get_builtin(&key).await?;
+            }
+            Ok::<(), clamber_web_core::redis::RedisError>(())
+        }));
+    }
+    for handle in handles {
+        handle.await??;
+    }
+
+    let stats = pool.get_pool_stats();
+    info!(
+        "📊 连接池统计: 连接数={}, 在途请求={:?}, 已执行命令={}",
+        stats.pool_size, stats.in_flight, stats.total_commands
+    );
+
+    Ok(start.elapsed())
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let total_ops = (WORKERS * OPS_PER_WORKER * 2) as f64;
+    info!(
+        "⏱️ {}: 耗时 {:?}, 吞吐量 {:.0} ops/s",
+        name,
+        elapsed,
+        total_ops / elapsed.as_secs_f64()
+    );
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    info!(
+        "🚀 Redis 连接池基准测试: {} 个并发任务，每个任务 {} 次 SET+GET",
+        WORKERS, OPS_PER_WORKER
+    );
+
+    let single = bench_single_connection(&url).await?;
+    report("单连接", single);
+
+    let round_robin = bench_pool(&url, RedisPoolStrategy::RoundRobin).await?;
+    report(&format!("连接池({}) 轮询", POOL_SIZE), round_robin);
+
+    let least_in_flight = bench_pool(&url, RedisPoolStrategy::LeastInFlight).await?;
+    report(&format!("连接池({}) 最少在途", POOL_SIZE), least_in_flight);
+
+    info!(
+        "📈 连接池相对单连接加速: 轮询 {:.2}x, 最少在途 {:.2}x",
+        single.as_secs_f64() / round_robin.as_secs_f64(),
+        single.as_secs_f64() / least_in_flight.as_secs_f64()
+    );
+
+    Ok(())
+}
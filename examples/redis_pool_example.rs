@@ -123,15 +123,12 @@ async fn example_pool_configuration() -> Result<(), Box<dyn std::error::Error>>
     info!("🧪 示例4: 连接池配置优化");
 
     // 创建自定义配置
-    let config = RedisConfig {
-        url: "redis://localhost:6379".to_string(),
-        database_index: 0,
-        connection_timeout_secs: 10, // 自定义连接超时
-        response_timeout_secs: 3,    // 自定义响应超时
-        retry_count: 3,              // 自定义重试次数
-        retry_factor_ms: 200,        // 自定义重试延迟因子
-        max_retry_delay_ms: 5000,    // 自定义最大重试延迟
-    };
+    let config = RedisConfig::builder()
+        .url("redis://localhost:6379")
+        .connection_timeout_secs(10) // 自定义连接超时
+        .response_timeout_secs(3) // 自定义响应超时
+        .retries(3, 200, 5000) // 自定义重试次数、延迟因子与最大延迟
+        .build()?;
 
     // 使用自定义配置创建连接
     let mut connection = RedisConnection::new(config).await?;
@@ -139,8 +136,8 @@ async fn example_pool_configuration() -> Result<(), Box<dyn std::error::Error>>
 
     let stats = connection.get_pool_stats();
     info!(
-        "📊 自定义配置连接池统计: 最大连接数={}, 连接超时={}秒",
-        stats.max_connections, stats.connect_timeout
+        "📊 自定义配置连接池统计: 连接超时={}秒, 响应超时={}秒, 重试延迟={:?}毫秒, 已执行命令={}",
+        stats.connect_timeout, stats.read_timeout, stats.retry_delays_ms, stats.total_commands
     );
 
     info!("✅ 自定义配置连接池测试完成");
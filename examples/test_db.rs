@@ -50,6 +50,7 @@ async fn test_database_connection_struct() -> Result<(), Box<dyn std::error::Err
         max_lifetime_secs: 3600,
         sql_logging: true,
         slow_threshold_ms: 1000,
+        run_migrations_on_startup: false,
     };
 
     let db_conn = SeaOrmConnection::new(config.clone()).await?;
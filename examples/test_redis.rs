@@ -51,6 +51,7 @@ async fn test_redis_connection_struct() -> Result<(), Box<dyn std::error::Error>
         retry_count: 5,
         retry_factor_ms: 5,
         max_retry_delay_ms: 5,
+        ..RedisConfig::default()
     };
 
     let mut redis_conn = RedisConnection::new(config.clone()).await?;
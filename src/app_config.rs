@@ -0,0 +1,301 @@
+//! 应用统一配置模块
+//!
+//! 将数据库、Redis、Kafka 和代理的配置汇总到一个 [`AppConfig`] 中，
+//! 可以从单个 YAML 文件（例如 `app.yaml`）或环境变量加载。各段均为可选，
+//! 且只有启用了对应 feature 的段才会被编译和解析
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+use crate::env_interpolation::expand_env_vars;
+
+#[cfg(feature = "database")]
+use crate::database::DatabaseConfig;
+#[cfg(feature = "kafka")]
+use crate::kafka::{KafkaConsumerConfig, KafkaProducerConfig};
+#[cfg(feature = "proxy")]
+use crate::proxy::ProxyConfig;
+#[cfg(feature = "redis")]
+use crate::redis::{RedisConfig, RedisConfigBuilder, redis_config_builder::DEFAULT_ENV_PREFIX};
+
+/// 应用配置错误类型
+#[derive(Error, Debug)]
+pub enum AppConfigError {
+    /// 读取配置文件失败
+    #[error("读取配置文件 {path} 失败: {message}")]
+    Io { path: String, message: String },
+
+    /// 解析配置失败
+    #[error("解析应用配置失败: {message}")]
+    Parse { message: String },
+
+    /// 某个配置段无效
+    #[error("{section} 配置无效: {message}")]
+    Invalid {
+        section: &'static str,
+        message: String,
+    },
+}
+
+impl AppConfigError {
+    /// 创建配置段无效错误
+    pub fn invalid(section: &'static str, message: impl ToString) -> Self {
+        Self::Invalid {
+            section,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// 应用配置结果类型
+pub type AppConfigResult<T> = Result<T, AppConfigError>;
+
+/// 应用统一配置
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AppConfig {
+    /// 数据库配置
+    #[cfg(feature = "database")]
+    #[serde(default)]
+    pub database: Option<DatabaseConfig>,
+
+    /// Redis 配置
+    #[cfg(feature = "redis")]
+    #[serde(default)]
+    pub redis: Option<RedisConfig>,
+
+    /// Kafka 生产者配置
+    #[cfg(feature = "kafka")]
+    #[serde(default)]
+    pub kafka_producer: Option<KafkaProducerConfig>,
+
+    /// Kafka 消费者配置
+    #[cfg(feature = "kafka")]
+    #[serde(default)]
+    pub kafka_consumer: Option<KafkaConsumerConfig>,
+
+    /// 代理配置
+    #[cfg(feature = "proxy")]
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
+}
+
+impl AppConfig {
+    /// 从 YAML 字符串加载并校验配置，支持 `${ENV_VAR}` 占位符
+    pub fn from_yaml_str(yaml: &str) -> AppConfigResult<Self> {
+        let yaml = expand_env_vars(yaml).map_err(|message| AppConfigError::Parse { message })?;
+        let config: Self = serde_yaml::from_str(&yaml).map_err(|e| AppConfigError::Parse {
+            message: e.to_string(),
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 从 YAML 文件加载并校验配置
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> AppConfigResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| AppConfigError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::from_yaml_str(&content)
+    }
+
+    /// 从环境变量加载并校验配置
+    ///
+    /// - 设置了 `DATABASE_URL` 时加载数据库段，见 [`DatabaseConfig::from_env`]
+    /// - 设置了 `REDIS_URL` 时加载 Redis 段，见 [`RedisConfig::from_env`]
+    /// - 设置了 `KAFKA_BOOTSTRAP_SERVERS`（逗号分隔）时加载 Kafka 生产者段，
+    ///   同时设置了 `KAFKA_GROUP_ID` 时加载 Kafka 消费者段，其余字段使用默认值
+    /// - 设置了 `PROXY_CONFIG_FILE` 时从该 YAML 文件加载代理段
+    pub fn from_env() -> AppConfigResult<Self> {
+        Self::from_env_with(|name| std::env::var(name).ok())
+    }
+
+    /// 使用指定的查找函数读取环境变量
+    fn from_env_with(lookup: impl Fn(&str) -> Option<String>) -> AppConfigResult<Self> {
+        #[allow(unused_variables)]
+        let is_set = |name: &str| lookup(name).is_some_and(|value| !value.trim().is_empty());
+        #[allow(unused_mut)]
+        let mut config = Self::default();
+
+        #[cfg(feature = "database")]
+        if is_set("DATABASE_URL") {
+            config.database = Some(
+                DatabaseConfig::from_env_with(&lookup)
+                    .map_err(|e| AppConfigError::invalid("database", e))?,
+            );
+        }
+
+        #[cfg(feature = "redis")]
+        if is_set("REDIS_URL") {
+            config.redis = Some(
+                RedisConfigBuilder::new()
+                    .apply_env(DEFAULT_ENV_PREFIX, &lookup)
+                    .and_then(RedisConfigBuilder::build)
+                    .map_err(|e| AppConfigError::invalid("redis", e))?,
+            );
+        }
+
+        #[cfg(feature = "kafka")]
+        if let Some(servers) = lookup("KAFKA_BOOTSTRAP_SERVERS") {
+            let servers: Vec<String> = servers
+                .split(',')
+                .map(str::trim)
+                .filter(|server| !server.is_empty())
+                .map(str::to_string)
+                .collect();
+
+            let mut producer = KafkaProducerConfig::default();
+            producer.base.bootstrap_servers = servers.clone();
+            config.kafka_producer = Some(producer);
+
+            if let Some(group_id) = lookup("KAFKA_GROUP_ID") {
+                let mut consumer = KafkaConsumerConfig::default();
+                consumer.base.bootstrap_servers = servers;
+                consumer.group_id = group_id.trim().to_string();
+                config.kafka_consumer = Some(consumer);
+            }
+        }
+
+        #[cfg(feature = "proxy")]
+        if let Some(path) = lookup("PROXY_CONFIG_FILE") {
+            let path = path.trim();
+            let content = std::fs::read_to_string(path).map_err(|e| AppConfigError::Io {
+                path: path.to_string(),
+                message: e.to_string(),
+            })?;
+            let content =
+                expand_env_vars(&content).map_err(|e| AppConfigError::invalid("proxy", e))?;
+            config.proxy = Some(
+                serde_yaml::from_str(&content).map_err(|e| AppConfigError::invalid("proxy", e))?,
+            );
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 逐段验证配置的有效性，返回第一个无效段的错误
+    pub fn validate(&self) -> AppConfigResult<()> {
+        #[cfg(feature = "database")]
+        if let Some(database) = &self.database {
+            database
+                .validate()
+                .map_err(|e| AppConfigError::invalid("database", e))?;
+        }
+
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            redis
+                .validate()
+                .map_err(|e| AppConfigError::invalid("redis", e))?;
+        }
+
+        #[cfg(feature = "kafka")]
+        if let Some(producer) = &self.kafka_producer {
+            producer
+                .validate()
+                .map_err(|e| AppConfigError::invalid("kafka_producer", e))?;
+        }
+
+        #[cfg(feature = "kafka")]
+        if let Some(consumer) = &self.kafka_consumer {
+            consumer
+                .validate()
+                .map_err(|e| AppConfigError::invalid("kafka_consumer", e))?;
+        }
+
+        #[cfg(feature = "proxy")]
+        if let Some(proxy) = &self.proxy {
+            proxy
+                .validate()
+                .map_err(|e| AppConfigError::invalid("proxy", e))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/app.yaml");
+
+    #[test]
+    fn test_load_all_sections_from_fixture() {
+        let config = AppConfig::from_yaml_file(FIXTURE).unwrap();
+
+        #[cfg(feature = "database")]
+        {
+            let database = config.database.as_ref().unwrap();
+            assert_eq!(database.max_connections, 20);
+            assert_eq!(database.slow_threshold_ms, 500);
+        }
+        #[cfg(feature = "redis")]
+        {
+            let redis = config.redis.as_ref().unwrap();
+            assert_eq!(redis.database_index, 1);
+            assert_eq!(redis.key_prefix.as_deref(), Some("clamber:"));
+        }
+        #[cfg(feature = "kafka")]
+        {
+            let producer = config.kafka_producer.as_ref().unwrap();
+            assert_eq!(producer.acks.as_deref(), Some("all"));
+            let consumer = config.kafka_consumer.as_ref().unwrap();
+            assert_eq!(consumer.group_id, "clamber-app-group");
+        }
+        #[cfg(feature = "proxy")]
+        {
+            let proxy = config.proxy.as_ref().unwrap();
+            assert_eq!(proxy.listen, "0.0.0.0:8080");
+            assert!(proxy.upstreams.contains_key("api"));
+        }
+    }
+
+    #[test]
+    fn test_missing_sections_are_none() {
+        let config = AppConfig::from_yaml_str("{}").unwrap();
+        assert!(config.validate().is_ok());
+        #[cfg(feature = "database")]
+        assert!(config.database.is_none());
+        #[cfg(feature = "redis")]
+        assert!(config.redis.is_none());
+    }
+
+    #[cfg(feature = "redis")]
+    #[test]
+    fn test_invalid_section_is_reported() {
+        let error = AppConfig::from_yaml_str("redis:\n  url: \"\"\n").unwrap_err();
+        assert!(matches!(
+            error,
+            AppConfigError::Invalid {
+                section: "redis",
+                ..
+            }
+        ));
+    }
+
+    #[cfg(all(feature = "database", feature = "redis", feature = "kafka"))]
+    #[test]
+    fn test_from_env_with_lookup() {
+        use std::collections::HashMap;
+
+        let vars = HashMap::from([
+            ("DATABASE_URL", "mysql://root@localhost/test"),
+            ("REDIS_URL", "redis://127.0.0.1:6379"),
+            ("KAFKA_BOOTSTRAP_SERVERS", "kafka-1:9092, kafka-2:9092"),
+        ]);
+        let config =
+            AppConfig::from_env_with(|name| vars.get(name).map(|value| value.to_string())).unwrap();
+
+        assert_eq!(config.database.unwrap().url, "mysql://root@localhost/test");
+        assert_eq!(config.redis.unwrap().url, "redis://127.0.0.1:6379");
+        assert_eq!(
+            config.kafka_producer.unwrap().base.bootstrap_servers,
+            ["kafka-1:9092", "kafka-2:9092"]
+        );
+        assert!(config.kafka_consumer.is_none());
+    }
+}
@@ -0,0 +1,305 @@
+//! Axum 集成模块
+//!
+//! 为 axum 项目提供数据库的 AppState 集成。[`DatabaseAppState`] 不绑定具体实体，
+//! 可以与 `RedisAppState`、`KafkaAppState` 一起组合进应用自己的状态，
+//! 处理函数通过 `State<DatabaseAppState>` 或 `State<Arc<DatabaseConnection>>` 取得连接：
+//!
+//! ```rust,ignore
+//! use axum::extract::FromRef;
+//! use clamber_web_core::database::DatabaseAppState;
+//! use clamber_web_core::kafka::KafkaAppState;
+//! use clamber_web_core::redis::RedisAppState;
+//!
+//! #[derive(Clone, FromRef)]
+//! struct AppState {
+//!     database: DatabaseAppState,
+//!     redis: RedisAppState,
+//!     kafka: KafkaAppState,
+//! }
+//!
+//! let app = Router::new()
+//!     .route("/users", get(list_users)) // State<DatabaseAppState>
+//!     .route("/cache/{key}", get(get_cache)) // State<RedisAppState>
+//!     .with_state(AppState { database, redis, kafka });
+//! ```
+//!
+//! [`transaction_middleware`] 为每个请求开启事务，处理函数通过 [`Tx`] 提取器在同一事务中执行多步写入：
+//!
+//! ```rust,ignore
+//! let app = Router::new()
+//!     .route("/orders", post(create_order)) // Tx 提取器
+//!     .layer(axum::middleware::from_fn_with_state(
+//!         database.clone(),
+//!         transaction_middleware,
+//!     ));
+//! ```
+
+use crate::database::database_config::DatabaseConfig;
+use crate::database::database_connection::SeaOrmConnection;
+use crate::database::database_error::DatabaseResult;
+use axum::extract::{FromRef, FromRequestParts, Request, State};
+use axum::http::StatusCode;
+use axum::http::request::Parts;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sea_orm::{DatabaseConnection, DatabaseTransaction, TransactionTrait};
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Axum 应用的数据库状态
+#[derive(Clone)]
+pub struct DatabaseAppState {
+    /// 数据库连接
+    pub db: Arc<DatabaseConnection>,
+}
+
+impl DatabaseAppState {
+    /// 使用已建立的连接创建 AppState
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db: Arc::new(db) }
+    }
+
+    /// 按配置建立连接并创建 AppState
+    pub async fn from_config(config: DatabaseConfig) -> DatabaseResult<Self> {
+        Ok(Self::new(SeaOrmConnection::new(config).await?.inner))
+    }
+
+    /// 获取数据库连接
+    pub fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+}
+
+impl FromRef<DatabaseAppState> for Arc<DatabaseConnection> {
+    fn from_ref(state: &DatabaseAppState) -> Self {
+        state.db.clone()
+    }
+}
+
+/// 请求级事务提取器，需要启用 [`transaction_middleware`]
+///
+/// 解引用为 [`DatabaseTransaction`]，可直接用于 SeaORM 的查询与写入
+#[derive(Clone)]
+pub struct Tx(Arc<DatabaseTransaction>);
+
+impl Deref for Tx {
+    type Target = DatabaseTransaction;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Tx {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Tx>()
+            .cloned()
+            .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "未启用请求事务中间件"))
+    }
+}
+
+/// 请求事务中间件，配合 `axum::middleware::from_fn_with_state` 使用
+///
+/// 请求开始时开启事务，响应为 2xx 时提交，其余状态码回滚。处理函数在独立任务中运行，
+/// panic 时同样回滚并返回 500。提交失败时返回 500
+pub async fn transaction_middleware(
+    State(state): State<DatabaseAppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let txn = match state.db.begin().await {
+        Ok(txn) => Arc::new(txn),
+        Err(e) => {
+            warn!("开启请求事务失败: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    request.extensions_mut().insert(Tx(txn.clone()));
+
+    let response = match tokio::spawn(next.run(request)).await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("请求处理异常，回滚事务: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    };
+
+    // 处理函数结束后事务应只剩中间件持有；仍被引用时无法提交，释放时自动回滚
+    let Ok(txn) = Arc::try_unwrap(txn) else {
+        warn!("请求结束时事务仍被引用，无法提交");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    if !response.status().is_success() {
+        if let Err(e) = txn.rollback().await {
+            warn!("回滚请求事务失败: {}", e);
+        }
+        return response;
+    }
+    match txn.commit().await {
+        Ok(()) => response,
+        Err(e) => {
+            warn!("提交请求事务失败: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// 便捷函数：从 URL 创建数据库 AppState
+pub async fn create_database_app_state_from_url(
+    database_url: &str,
+) -> DatabaseResult<DatabaseAppState> {
+    DatabaseAppState::from_config(DatabaseConfig::for_url(database_url)).await
+}
+
+/// 便捷函数：从 YAML 配置文件创建数据库 AppState
+pub async fn create_database_app_state_from_config(
+    config_path: impl AsRef<Path>,
+) -> DatabaseResult<DatabaseAppState> {
+    DatabaseAppState::from_config(DatabaseConfig::from_yaml_file(config_path)?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 组合后的状态可以克隆，并能从中提取各模块的状态
+    #[cfg(all(feature = "redis", feature = "kafka"))]
+    #[allow(dead_code)]
+    mod combined {
+        use super::*;
+        use crate::kafka::KafkaAppState;
+        use crate::redis::{RedisAppState, RedisConnection};
+
+        #[derive(Clone, FromRef)]
+        struct AppState {
+            database: DatabaseAppState,
+            redis: RedisAppState,
+            kafka: KafkaAppState,
+        }
+
+        fn assert_from_ref<S, T: FromRef<S>>() {}
+
+        #[test]
+        fn test_combined_state_extractors() {
+            assert_from_ref::<AppState, AppState>();
+            assert_from_ref::<AppState, DatabaseAppState>();
+            assert_from_ref::<AppState, RedisAppState>();
+            assert_from_ref::<AppState, KafkaAppState>();
+            assert_from_ref::<DatabaseAppState, Arc<DatabaseConnection>>();
+            assert_from_ref::<RedisAppState, RedisConnection>();
+        }
+    }
+
+    #[cfg(feature = "database-sqlite")]
+    #[tokio::test]
+    async fn test_from_config() {
+        use sea_orm::ConnectionTrait;
+
+        let state = DatabaseAppState::from_config(DatabaseConfig::for_url("sqlite::memory:"))
+            .await
+            .unwrap();
+        state.db().execute_unprepared("SELECT 1").await.unwrap();
+
+        // 克隆的状态共享同一个连接
+        let db = Arc::<DatabaseConnection>::from_ref(&state.clone());
+        assert!(Arc::ptr_eq(&db, &state.db));
+    }
+
+    #[cfg(feature = "database-sqlite")]
+    #[tokio::test]
+    async fn test_transaction_middleware() {
+        use axum::Router;
+        use axum::body::Body;
+        use axum::routing::post;
+        use sea_orm::{ConnectionTrait, DbBackend, Statement};
+        use tower::ServiceExt;
+
+        /// 写入两行后返回指定状态码
+        async fn insert_two(tx: &Tx, status: StatusCode) -> StatusCode {
+            for id in [1, 2] {
+                tx.execute_unprepared(&format!(
+                    "INSERT INTO clamber_tx_layer_test (id) VALUES ({})",
+                    id
+                ))
+                .await
+                .unwrap();
+            }
+            status
+        }
+
+        async fn fail(tx: Tx) -> StatusCode {
+            insert_two(&tx, StatusCode::INTERNAL_SERVER_ERROR).await
+        }
+
+        async fn crash(tx: Tx) -> StatusCode {
+            insert_two(&tx, StatusCode::OK).await;
+            panic!("处理函数 panic");
+        }
+
+        async fn create(tx: Tx) -> StatusCode {
+            insert_two(&tx, StatusCode::CREATED).await
+        }
+
+        async fn row_count(db: &DatabaseConnection) -> i64 {
+            let row = db
+                .query_one(Statement::from_string(
+                    DbBackend::Sqlite,
+                    "SELECT COUNT(*) AS n FROM clamber_tx_layer_test",
+                ))
+                .await
+                .unwrap()
+                .unwrap();
+            row.try_get("", "n").unwrap()
+        }
+
+        // 单连接保证内存 SQLite 在各语句间共享同一个数据库
+        let config = DatabaseConfig {
+            max_connections: 1,
+            min_connections: 1,
+            ..DatabaseConfig::for_url("sqlite::memory:")
+        };
+        let state = DatabaseAppState::from_config(config).await.unwrap();
+        state
+            .db()
+            .execute_unprepared("CREATE TABLE clamber_tx_layer_test (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+
+        let app = Router::new()
+            .route("/fail", post(fail))
+            .route("/panic", post(crash))
+            .route("/ok", post(create))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                transaction_middleware,
+            ));
+        let call = async |uri: &str| {
+            let request = axum::http::Request::post(uri).body(Body::empty()).unwrap();
+            app.clone().oneshot(request).await.unwrap().status()
+        };
+
+        // 返回 500 时两行都不保留
+        assert_eq!(call("/fail").await, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(row_count(state.db()).await, 0);
+
+        assert_eq!(call("/panic").await, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(row_count(state.db()).await, 0);
+
+        assert_eq!(call("/ok").await, StatusCode::CREATED);
+        assert_eq!(row_count(state.db()).await, 2);
+
+        // 未启用中间件时提取失败
+        let app = Router::new().route("/ok", post(create));
+        let request = axum::http::Request::post("/ok")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,325 @@
+//! 数据库配置构建器模块
+//!
+//! 通过链式调用构造 [`DatabaseConfig`]，支持先从 YAML 文件或环境变量加载基础配置，
+//! 再覆盖个别字段；后调用的方法覆盖先前的值，`build` 时统一校验
+
+use crate::database::{DatabaseConfig, DatabaseError, DatabaseResult, ReadWriteConfig};
+use std::path::Path;
+
+/// 数据库配置构建器
+///
+/// ```ignore
+/// let config = DatabaseConfig::builder()
+///     .url("postgres://app:secret@db:5432/app")
+///     .max_connections(20)
+///     .slow_threshold_ms(500)
+///     .build()?;
+///
+/// // 以配置文件为基础，先用环境变量覆盖，再在代码中覆盖
+/// let config = DatabaseConfigBuilder::from_yaml_file("config/database.yaml")?
+///     .merge_env()?
+///     .sql_logging(false)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseConfigBuilder {
+    config: DatabaseConfig,
+    replica_urls: Vec<String>,
+}
+
+impl DatabaseConfig {
+    /// 创建以默认配置为基础的构建器
+    pub fn builder() -> DatabaseConfigBuilder {
+        DatabaseConfigBuilder::new()
+    }
+}
+
+impl DatabaseConfigBuilder {
+    /// 创建以默认配置为基础的构建器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 以已有配置为基础创建构建器
+    pub fn from_config(config: DatabaseConfig) -> Self {
+        Self {
+            config,
+            replica_urls: Vec::new(),
+        }
+    }
+
+    /// 以 YAML 文件中的配置为基础创建构建器，支持 `${ENV_VAR}` 占位符
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> DatabaseResult<Self> {
+        DatabaseConfig::from_yaml_file(path).map(Self::from_config)
+    }
+
+    /// 以 JSON 文件中的配置为基础创建构建器
+    pub fn from_json_file(path: impl AsRef<Path>) -> DatabaseResult<Self> {
+        DatabaseConfig::from_json_file(path).map(Self::from_config)
+    }
+
+    /// 以环境变量为基础创建构建器，变量见 [`DatabaseConfig::from_env`]
+    pub fn from_env() -> DatabaseResult<Self> {
+        DatabaseConfig::from_env().map(Self::from_config)
+    }
+
+    /// 用环境变量覆盖当前配置，变量见 [`DatabaseConfig::merge_env`]
+    pub fn merge_env(self) -> DatabaseResult<Self> {
+        self.merge_env_with(|name| std::env::var(name).ok())
+    }
+
+    /// 使用指定的查找函数读取环境变量并覆盖对应字段
+    pub(crate) fn merge_env_with(
+        mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> DatabaseResult<Self> {
+        self.config.merge_env_with(lookup)?;
+        Ok(self)
+    }
+
+    /// 设置数据库连接 URL
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.config.url = url.into();
+        self
+    }
+
+    /// 设置最大连接数
+    pub fn max_connections(mut self, max_connections: u32) -> Self {
+        self.config.max_connections = max_connections;
+        self
+    }
+
+    /// 设置最小连接数
+    pub fn min_connections(mut self, min_connections: u32) -> Self {
+        self.config.min_connections = min_connections;
+        self
+    }
+
+    /// 设置连接超时时间（秒）
+    pub fn connect_timeout_secs(mut self, secs: u64) -> Self {
+        self.config.connect_timeout_secs = secs;
+        self
+    }
+
+    /// 设置获取连接超时时间（秒）
+    pub fn acquire_timeout_secs(mut self, secs: u64) -> Self {
+        self.config.acquire_timeout_secs = secs;
+        self
+    }
+
+    /// 设置空闲超时时间（秒）
+    pub fn idle_timeout_secs(mut self, secs: u64) -> Self {
+        self.config.idle_timeout_secs = secs;
+        self
+    }
+
+    /// 设置连接最大生命周期（秒）
+    pub fn max_lifetime_secs(mut self, secs: u64) -> Self {
+        self.config.max_lifetime_secs = secs;
+        self
+    }
+
+    /// 启用或关闭 SQL 日志
+    pub fn sql_logging(mut self, enable: bool) -> Self {
+        self.config.sql_logging = enable;
+        self
+    }
+
+    /// 设置慢查询阈值（毫秒）
+    pub fn slow_threshold_ms(mut self, ms: u64) -> Self {
+        self.config.slow_threshold_ms = ms;
+        self
+    }
+
+    /// 设置建立连接后是否自动执行未应用的迁移
+    pub fn run_migrations_on_startup(mut self, enable: bool) -> Self {
+        self.config.run_migrations_on_startup = enable;
+        self
+    }
+
+    /// 设置建立连接的重试次数、首次重试延迟与最大延迟（毫秒）
+    pub fn connect_retry(mut self, count: u32, delay_ms: u64, max_delay_ms: u64) -> Self {
+        self.config.connect_retry_count = count;
+        self.config.connect_retry_delay_ms = delay_ms;
+        self.config.connect_retry_max_delay_ms = max_delay_ms;
+        self
+    }
+
+    /// 设置只读副本 URL，仅 [`build_read_write`](Self::build_read_write) 使用
+    pub fn replicas<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.replica_urls = urls.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 校验并返回配置
+    pub fn build(self) -> DatabaseResult<DatabaseConfig> {
+        self.config.validate().map_err(DatabaseError::config)?;
+        Ok(self.config)
+    }
+
+    /// 校验并返回读写分离配置，副本沿用主库的连接池参数
+    pub fn build_read_write(self) -> DatabaseResult<ReadWriteConfig> {
+        let config = ReadWriteConfig {
+            primary: self.config,
+            replica_urls: self.replica_urls,
+        };
+        config.validate().map_err(DatabaseError::config)?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 用固定的键值对模拟环境变量
+    fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name: &str| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_builder_sets_fields() {
+        let config = DatabaseConfig::builder()
+            .url("mysql://root@db:3306/app")
+            .max_connections(20)
+            .min_connections(2)
+            .connect_timeout_secs(5)
+            .acquire_timeout_secs(3)
+            .idle_timeout_secs(60)
+            .max_lifetime_secs(600)
+            .sql_logging(false)
+            .slow_threshold_ms(250)
+            .connect_retry(3, 100, 2000)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.url, "mysql://root@db:3306/app");
+        assert_eq!(config.max_connections, 20);
+        assert_eq!(config.min_connections, 2);
+        assert_eq!(config.connect_timeout_secs, 5);
+        assert_eq!(config.acquire_timeout_secs, 3);
+        assert_eq!(config.idle_timeout_secs, 60);
+        assert_eq!(config.max_lifetime_secs, 600);
+        assert!(!config.sql_logging);
+        assert_eq!(config.slow_threshold_ms, 250);
+        assert_eq!(config.connect_retry_count, 3);
+        assert_eq!(config.connect_retry_delay_ms, 100);
+        assert_eq!(config.connect_retry_max_delay_ms, 2000);
+        assert!(!config.run_migrations_on_startup);
+    }
+
+    #[test]
+    fn test_override_precedence() {
+        let path = std::env::temp_dir().join(format!(
+            "clamber-{}-builder-database.yaml",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "url: mysql://root@file:3306/app\nmax_connections: 50\nmin_connections: 10\nslow_threshold_ms: 2000\n",
+        )
+        .unwrap();
+        let builder = DatabaseConfigBuilder::from_yaml_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        // 文件 < 环境变量 < 代码中的覆盖
+        let config = builder
+            .unwrap()
+            .merge_env_with(env(&[
+                ("DATABASE_URL", "mysql://root@env:3306/app"),
+                ("DATABASE_MAX_CONNECTIONS", "30"),
+            ]))
+            .unwrap()
+            .max_connections(40)
+            .build()
+            .unwrap();
+        assert_eq!(config.url, "mysql://root@env:3306/app");
+        assert_eq!(config.max_connections, 40);
+        assert_eq!(config.min_connections, 10);
+        assert_eq!(config.slow_threshold_ms, 2000);
+        // 文件和环境变量都未设置的字段保持默认值
+        assert_eq!(
+            config.connect_timeout_secs,
+            DatabaseConfig::default().connect_timeout_secs
+        );
+
+        // 后调用的方法覆盖先前的值
+        let config = DatabaseConfigBuilder::from_config(config)
+            .url("mysql://root@first:3306/app")
+            .url("mysql://root@second:3306/app")
+            .build()
+            .unwrap();
+        assert_eq!(config.url, "mysql://root@second:3306/app");
+
+        assert!(DatabaseConfigBuilder::from_yaml_file("/nonexistent/database.yaml").is_err());
+    }
+
+    #[test]
+    fn test_build_runs_validation() {
+        let error = DatabaseConfig::builder()
+            .max_connections(0)
+            .build()
+            .unwrap_err();
+        assert!(error.is_config_error());
+        assert!(
+            DatabaseConfig::builder()
+                .min_connections(20)
+                .max_connections(10)
+                .build()
+                .is_err()
+        );
+        assert!(
+            DatabaseConfig::builder()
+                .url("mysql://localhost:99999/app")
+                .build()
+                .is_err()
+        );
+        assert!(
+            DatabaseConfig::builder()
+                .connect_timeout_secs(0)
+                .build()
+                .is_err()
+        );
+
+        // 环境变量值无效时在合并阶段报错
+        let error = DatabaseConfig::builder()
+            .merge_env_with(env(&[("DATABASE_MIN_CONNECTIONS", "few")]))
+            .unwrap_err();
+        assert!(error.to_string().contains("DATABASE_MIN_CONNECTIONS"));
+    }
+
+    #[test]
+    fn test_build_read_write() {
+        let config = DatabaseConfig::builder()
+            .url("mysql://root@primary:3306/app")
+            .max_connections(8)
+            .replicas([
+                "mysql://root@replica1:3306/app",
+                "mysql://root@replica2:3306/app",
+            ])
+            .build_read_write()
+            .unwrap();
+        assert_eq!(config.primary.url, "mysql://root@primary:3306/app");
+        assert_eq!(config.replica_urls.len(), 2);
+        assert!(
+            config
+                .replica_configs()
+                .iter()
+                .all(|replica| replica.max_connections == 8)
+        );
+
+        let error = DatabaseConfig::builder()
+            .replicas(["mysql://replica:0/app"])
+            .build_read_write()
+            .unwrap_err();
+        assert!(error.to_string().contains("副本 0"), "{}", error);
+    }
+}
@@ -2,14 +2,17 @@
 //!
 //! 定义数据库相关的错误类型，集成 clamber-core 的错误处理系统
 
+use sea_orm::{DbErr, RuntimeErr, SqlErr, sqlx};
 use thiserror::Error;
 
 /// 数据库相关错误类型
 #[derive(Error, Debug)]
 pub enum DatabaseError {
     /// SeaORM 数据库错误
+    ///
+    /// 通过 `From<DbErr>` 转换时，唯一约束与外键约束冲突会转换为 [`DatabaseError::ConstraintViolation`]
     #[error("数据库操作错误: {0}")]
-    SeaOrm(#[from] sea_orm::DbErr),
+    SeaOrm(#[source] sea_orm::DbErr),
 
     /// 连接错误
     #[error("数据库连接错误: {message}")]
@@ -39,6 +42,10 @@ pub enum DatabaseError {
     #[error("约束违反: {constraint}")]
     ConstraintViolation { constraint: String },
 
+    /// 密码哈希错误
+    #[error("密码哈希错误: {message}")]
+    Password { message: String },
+
     /// 核心库错误
     #[error("核心库错误: {0}")]
     Core(#[from] clamber_core::ClamberError),
@@ -95,6 +102,13 @@ impl DatabaseError {
         }
     }
 
+    /// 创建密码哈希错误
+    pub fn password(message: impl Into<String>) -> Self {
+        Self::Password {
+            message: message.into(),
+        }
+    }
+
     /// 判断是否为连接错误
     pub fn is_connection_error(&self) -> bool {
         matches!(
@@ -108,17 +122,59 @@ impl DatabaseError {
         matches!(self, DatabaseError::Config { .. })
     }
 
+    /// 判断是否为迁移错误
+    pub fn is_migration_error(&self) -> bool {
+        matches!(self, DatabaseError::Migration { .. })
+    }
+
+    /// 判断是否为查询错误（如分页参数无效）
+    pub fn is_query_error(&self) -> bool {
+        matches!(self, DatabaseError::Query { .. })
+    }
+
     /// 判断是否为约束违反错误
     pub fn is_constraint_error(&self) -> bool {
         matches!(self, DatabaseError::ConstraintViolation { .. })
     }
 
+    /// 判断是否为密码哈希错误
+    pub fn is_password_error(&self) -> bool {
+        matches!(self, DatabaseError::Password { .. })
+    }
+
     /// 判断是否为实体不存在错误
     pub fn is_not_found_error(&self) -> bool {
         matches!(self, DatabaseError::EntityNotFound { .. })
     }
 }
 
+impl From<DbErr> for DatabaseError {
+    fn from(error: DbErr) -> Self {
+        match constraint_violation_name(&error) {
+            Some(constraint) => Self::ConstraintViolation { constraint },
+            None => Self::SeaOrm(error),
+        }
+    }
+}
+
+/// 识别驱动返回的约束冲突（MySQL 1062/1452、PostgreSQL 23505/23503、SQLite 2067/1555/787），
+/// 返回约束名；驱动未提供约束名时（如 SQLite、MySQL）返回驱动的错误信息
+fn constraint_violation_name(error: &DbErr) -> Option<String> {
+    let message = match error.sql_err()? {
+        SqlErr::UniqueConstraintViolation(message) => message,
+        SqlErr::ForeignKeyConstraintViolation(message) => message,
+        _ => return None,
+    };
+    let constraint = match error {
+        DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+        | DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(e))) => {
+            e.constraint().map(str::to_string)
+        }
+        _ => None,
+    };
+    Some(constraint.unwrap_or(message))
+}
+
 /// 数据库操作结果类型
 pub type DatabaseResult<T> = Result<T, DatabaseError>;
 
@@ -146,4 +202,45 @@ mod tests {
         assert!(error.is_constraint_error());
         assert_eq!(error.to_string(), "约束违反: unique_email");
     }
+
+    #[test]
+    fn test_other_db_errors_are_not_constraint_violations() {
+        let error = DatabaseError::from(DbErr::Custom("boom".to_string()));
+        assert!(!error.is_constraint_error());
+        assert!(matches!(error, DatabaseError::SeaOrm(DbErr::Custom(_))));
+    }
+
+    #[cfg(feature = "database-sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_unique_violation() {
+        use sea_orm::{ConnectionTrait, Database};
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.execute_unprepared(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL UNIQUE)",
+        )
+        .await
+        .unwrap();
+        db.execute_unprepared("INSERT INTO users (id, email) VALUES (1, 'a@example.com')")
+            .await
+            .unwrap();
+
+        // 唯一索引冲突（SQLite 2067）
+        let error = DatabaseError::from(
+            db.execute_unprepared("INSERT INTO users (id, email) VALUES (2, 'a@example.com')")
+                .await
+                .unwrap_err(),
+        );
+        assert!(error.is_constraint_error());
+        assert!(error.to_string().contains("users.email"));
+
+        // 主键冲突（SQLite 1555）
+        let error = DatabaseError::from(
+            db.execute_unprepared("INSERT INTO users (id, email) VALUES (1, 'b@example.com')")
+                .await
+                .unwrap_err(),
+        );
+        assert!(error.is_constraint_error());
+        assert!(error.to_string().contains("users.id"));
+    }
 }
@@ -0,0 +1,243 @@
+//! 数据库迁移模块
+//!
+//! 基于 sea-orm-migration 执行 schema 迁移。[`MigrationRunner`] 封装迁移器的升级、回滚与状态查询，
+//! 错误统一转换为 [`DatabaseError::Migration`]；配置了 `run_migrations_on_startup` 的连接
+//! 可通过 [`SeaOrmConnection::new_with_migrator`] 在建立连接后自动执行迁移
+//!
+//! ```ignore
+//! let connection = SeaOrmConnection::from_env().await?;
+//! let runner = MigrationRunner::new(&connection, Migrator);
+//! runner.up().await?;
+//! for status in runner.status().await? {
+//!     println!("{} applied={}", status.name, status.applied);
+//! }
+//! ```
+
+use crate::database::{DatabaseConfig, DatabaseError, DatabaseResult, SeaOrmConnection};
+use sea_orm::{DatabaseConnection, DbErr};
+use sea_orm_migration::MigratorTrait;
+use std::marker::PhantomData;
+use tracing::info;
+
+/// 单个迁移的执行状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// 迁移名称
+    pub name: String,
+    /// 是否已应用
+    pub applied: bool,
+}
+
+/// 迁移执行器
+pub struct MigrationRunner<M> {
+    db: DatabaseConnection,
+    _migrator: PhantomData<fn() -> M>,
+}
+
+impl<M: MigratorTrait> MigrationRunner<M> {
+    /// 使用数据库连接与迁移器创建执行器
+    pub fn new(connection: &SeaOrmConnection, _migrator: M) -> Self {
+        Self {
+            db: connection.inner.clone(),
+            _migrator: PhantomData,
+        }
+    }
+
+    /// 应用所有未执行的迁移
+    pub async fn up(&self) -> DatabaseResult<()> {
+        M::up(&self.db, None).await.map_err(into_migration_error)
+    }
+
+    /// 按应用顺序倒序回滚最近的 `steps` 个迁移
+    pub async fn down(&self, steps: u32) -> DatabaseResult<()> {
+        M::down(&self.db, Some(steps))
+            .await
+            .map_err(into_migration_error)
+    }
+
+    /// 按定义顺序返回每个迁移的状态
+    pub async fn status(&self) -> DatabaseResult<Vec<MigrationStatus>> {
+        let migrations = M::get_migration_with_status(&self.db)
+            .await
+            .map_err(into_migration_error)?;
+        Ok(migrations
+            .iter()
+            .map(|migration| MigrationStatus {
+                name: migration.name().to_string(),
+                applied: matches!(
+                    migration.status(),
+                    sea_orm_migration::MigrationStatus::Applied
+                ),
+            })
+            .collect())
+    }
+}
+
+impl SeaOrmConnection {
+    /// 创建数据库连接，`run_migrations_on_startup` 为 true 时随后应用迁移器 `M` 中所有未执行的迁移
+    pub async fn new_with_migrator<M: MigratorTrait>(
+        config: DatabaseConfig,
+    ) -> DatabaseResult<Self> {
+        let run_migrations = config.run_migrations_on_startup;
+        let connection = Self::new(config).await?;
+        if run_migrations {
+            info!("正在执行数据库迁移");
+            M::up(&connection.inner, None)
+                .await
+                .map_err(into_migration_error)?;
+            info!("数据库迁移执行完成");
+        }
+        Ok(connection)
+    }
+}
+
+/// 将 SeaORM 错误转换为迁移错误
+fn into_migration_error(error: DbErr) -> DatabaseError {
+    DatabaseError::migration(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{MultiDatabaseManager, Repository};
+    use sea_orm::Set;
+    use sea_orm_migration::prelude::*;
+
+    mod user {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+        #[sea_orm(table_name = "users")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i64,
+            pub name: String,
+            pub email: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    #[derive(DeriveIden)]
+    enum Users {
+        Table,
+        Id,
+        Name,
+        Email,
+    }
+
+    #[derive(DeriveMigrationName)]
+    struct CreateUsers;
+
+    #[async_trait::async_trait]
+    impl MigrationTrait for CreateUsers {
+        async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(Users::Table)
+                        .col(
+                            ColumnDef::new(Users::Id)
+                                .big_integer()
+                                .not_null()
+                                .auto_increment()
+                                .primary_key(),
+                        )
+                        .col(ColumnDef::new(Users::Name).string().not_null())
+                        .col(ColumnDef::new(Users::Email).string().not_null())
+                        .to_owned(),
+                )
+                .await
+        }
+
+        async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+            manager
+                .drop_table(Table::drop().table(Users::Table).to_owned())
+                .await
+        }
+    }
+
+    struct Migrator;
+
+    impl MigratorTrait for Migrator {
+        fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+            vec![Box::new(CreateUsers)]
+        }
+    }
+
+    #[cfg(feature = "database-sqlite")]
+    #[tokio::test]
+    async fn test_migrate_sqlite_then_insert() {
+        // 单连接保证内存 SQLite 在各语句间共享同一个数据库
+        let config = DatabaseConfig {
+            max_connections: 1,
+            min_connections: 1,
+            run_migrations_on_startup: true,
+            ..DatabaseConfig::for_url("sqlite::memory:")
+        };
+        let connection = SeaOrmConnection::new_with_migrator::<Migrator>(config)
+            .await
+            .unwrap();
+
+        let runner = MigrationRunner::new(&connection, Migrator);
+        let status = runner.status().await.unwrap();
+        assert_eq!(status.len(), 1);
+        assert!(status[0].applied);
+
+        let users = Repository::<user::Entity>::from_connection(&connection);
+        let created = users
+            .insert(user::ActiveModel {
+                name: Set("alice".to_string()),
+                email: Set("alice@example.com".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(users.find_by_id(created.id).await.unwrap().name, "alice");
+
+        runner.down(1).await.unwrap();
+        assert!(!runner.status().await.unwrap()[0].applied);
+        assert!(users.find_all().await.is_err());
+    }
+
+    #[cfg(feature = "database-sqlite")]
+    #[tokio::test]
+    async fn test_manager_honors_run_migrations_on_startup() {
+        let config = |run_migrations_on_startup| DatabaseConfig {
+            max_connections: 1,
+            min_connections: 1,
+            run_migrations_on_startup,
+            ..DatabaseConfig::for_url("sqlite::memory:")
+        };
+        let manager = MultiDatabaseManager::new_with_migrator::<Migrator>(
+            [
+                ("main".to_string(), config(true)),
+                ("cache".to_string(), config(false)),
+            ]
+            .into(),
+        )
+        .await
+        .unwrap();
+
+        for (name, applied) in [("main", true), ("cache", false)] {
+            let runner = MigrationRunner::new(manager.connection(name).unwrap(), Migrator);
+            assert_eq!(
+                runner.status().await.unwrap()[0].applied,
+                applied,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_migration_error_mapping() {
+        let error = into_migration_error(DbErr::Migration("duplicate table users".to_string()));
+        assert!(error.is_migration_error());
+        assert!(error.to_string().starts_with("数据库迁移错误"));
+        assert!(error.to_string().contains("duplicate table users"));
+    }
+}
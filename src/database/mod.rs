@@ -3,14 +3,43 @@
 //! 提供基于 SeaORM 的数据库连接管理、配置和工具函数
 //! 集成 clamber-core 的配置管理功能
 
+pub mod database_axum;
 pub mod database_config;
+pub mod database_config_builder;
 pub mod database_connection;
 pub mod database_error;
+pub mod database_manager;
+#[cfg(feature = "database-migration")]
+pub mod migrations;
+pub mod pagination;
+pub mod password;
+pub mod read_write_connection;
+pub mod repository;
+pub mod soft_delete;
+pub mod transaction;
 
 // 重新导出主要组件
-pub use database_config::DatabaseConfig;
-pub use database_connection::{DatabaseConnectionStats, DatabaseHealthStatus, SeaOrmConnection};
+pub use database_axum::{
+    DatabaseAppState, Tx, create_database_app_state_from_config,
+    create_database_app_state_from_url, transaction_middleware,
+};
+pub use database_config::{DatabaseBackend, DatabaseConfig};
+pub use database_config_builder::DatabaseConfigBuilder;
+pub use database_connection::{
+    DatabaseConnectionStats, DatabaseHealthStatus, PoolMetrics, SeaOrmConnection, wait_for_database,
+};
 pub use database_error::{DatabaseError, DatabaseResult};
+pub use database_manager::{DEFAULT_DATABASE_NAME, MultiDatabaseManager, parse_database_configs};
+#[cfg(feature = "database-migration")]
+pub use migrations::{MigrationRunner, MigrationStatus};
+pub use pagination::{
+    PageParams, PaginateExt, Paginated, SortDirection, paginate, paginate_select,
+};
+pub use password::{PasswordPolicy, PasswordVerification, hash_password, verify_password};
+pub use read_write_connection::{ReadWriteConfig, ReadWriteConnection};
+pub use repository::{PrimaryKeyOf, Repository};
+pub use soft_delete::{SoftDeleteExt, SoftDeleteRepository};
+pub use transaction::TransactionOptions;
 
 // 便利函数
 pub use database_connection::{
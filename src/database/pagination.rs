@@ -0,0 +1,429 @@
+//! 分页模块
+//!
+//! 基于 SeaORM `Paginator` 的通用分页工具，页码从 1 开始。
+//! [`PageParams`] 可以直接作为 Axum 的 `Query` 参数使用，
+//! 通过 [`PaginateExt::paginate_into`] 对任意 `Select` 按参数排序并分页
+
+use crate::database::{DatabaseError, DatabaseResult};
+use sea_orm::{
+    ConnectionTrait, EntityTrait, FromQueryResult, Order, PaginatorTrait, QueryOrder, Select,
+};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::str::FromStr;
+
+/// 单页最大条数，防止客户端一次请求过多数据
+pub const MAX_PAGE_SIZE: u64 = 1000;
+
+/// 排序方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    /// 升序
+    #[default]
+    Asc,
+    /// 降序
+    Desc,
+}
+
+impl From<SortDirection> for Order {
+    fn from(direction: SortDirection) -> Self {
+        match direction {
+            SortDirection::Asc => Order::Asc,
+            SortDirection::Desc => Order::Desc,
+        }
+    }
+}
+
+/// 分页请求参数
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PageParams {
+    /// 页码，从 1 开始
+    #[serde(default = "default_page")]
+    pub page: u64,
+
+    /// 每页条数
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+
+    /// 排序字段（实体列名，例如 `created_at`），None 时不追加排序
+    #[serde(default)]
+    pub sort_by: Option<String>,
+
+    /// 排序方向，仅在设置 `sort_by` 时生效
+    #[serde(default)]
+    pub sort_dir: SortDirection,
+}
+
+impl Default for PageParams {
+    fn default() -> Self {
+        Self {
+            page: default_page(),
+            page_size: default_page_size(),
+            sort_by: None,
+            sort_dir: SortDirection::default(),
+        }
+    }
+}
+
+/// 分页结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Paginated<T> {
+    /// 当前页数据
+    pub items: Vec<T>,
+    /// 总条数
+    pub total: u64,
+    /// 当前页码，从 1 开始
+    pub page: u64,
+    /// 每页条数
+    pub page_size: u64,
+    /// 总页数
+    pub total_pages: u64,
+}
+
+impl<T> Paginated<T> {
+    /// 转换每一项，例如将 Model 转换为响应 DTO
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Paginated<U> {
+        Paginated {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            page_size: self.page_size,
+            total_pages: self.total_pages,
+        }
+    }
+
+    /// 是否还有下一页
+    pub fn has_next(&self) -> bool {
+        self.page < self.total_pages
+    }
+}
+
+/// 分页查询实体的全部数据
+pub async fn paginate<E, C>(
+    db: &C,
+    page: u64,
+    page_size: u64,
+) -> DatabaseResult<Paginated<E::Model>>
+where
+    E: EntityTrait,
+    E::Model: FromQueryResult + Send + Sync,
+    C: ConnectionTrait,
+{
+    paginate_select(db, E::find(), page, page_size).await
+}
+
+/// 分页执行查询，可以在 `select` 中预先设置过滤与排序条件
+pub async fn paginate_select<E, C>(
+    db: &C,
+    select: Select<E>,
+    page: u64,
+    page_size: u64,
+) -> DatabaseResult<Paginated<E::Model>>
+where
+    E: EntityTrait,
+    E::Model: FromQueryResult + Send + Sync,
+    C: ConnectionTrait,
+{
+    validate_page(page, page_size, MAX_PAGE_SIZE)?;
+    fetch_page(db, select, page, page_size).await
+}
+
+/// 为 SeaORM `Select` 提供按 [`PageParams`] 排序并分页的扩展方法
+///
+/// ```ignore
+/// let page = user::Entity::find()
+///     .filter(user::Column::Active.eq(true))
+///     .paginate_into(&db, params)
+///     .await?;
+/// ```
+pub trait PaginateExt<E: EntityTrait> {
+    /// 按参数排序并分页，每页条数上限为 [`MAX_PAGE_SIZE`]
+    fn paginate_into<C>(
+        self,
+        db: &C,
+        params: PageParams,
+    ) -> impl Future<Output = DatabaseResult<Paginated<E::Model>>> + Send
+    where
+        C: ConnectionTrait;
+
+    /// 按参数排序并分页，使用自定义的每页条数上限
+    fn paginate_into_with_limit<C>(
+        self,
+        db: &C,
+        params: PageParams,
+        max_page_size: u64,
+    ) -> impl Future<Output = DatabaseResult<Paginated<E::Model>>> + Send
+    where
+        C: ConnectionTrait;
+}
+
+impl<E> PaginateExt<E> for Select<E>
+where
+    E: EntityTrait,
+    E::Model: FromQueryResult + Send + Sync,
+{
+    fn paginate_into<C>(
+        self,
+        db: &C,
+        params: PageParams,
+    ) -> impl Future<Output = DatabaseResult<Paginated<E::Model>>> + Send
+    where
+        C: ConnectionTrait,
+    {
+        self.paginate_into_with_limit(db, params, MAX_PAGE_SIZE)
+    }
+
+    fn paginate_into_with_limit<C>(
+        self,
+        db: &C,
+        params: PageParams,
+        max_page_size: u64,
+    ) -> impl Future<Output = DatabaseResult<Paginated<E::Model>>> + Send
+    where
+        C: ConnectionTrait,
+    {
+        async move {
+            validate_page(params.page, params.page_size, max_page_size)?;
+            let select = apply_sort(self, &params)?;
+            fetch_page(db, select, params.page, params.page_size).await
+        }
+    }
+}
+
+/// 按 `sort_by` 与 `sort_dir` 追加排序，列名不属于实体时返回查询错误
+fn apply_sort<E: EntityTrait>(select: Select<E>, params: &PageParams) -> DatabaseResult<Select<E>> {
+    let Some(sort_by) = params.sort_by.as_deref() else {
+        return Ok(select);
+    };
+    let column = E::Column::from_str(sort_by)
+        .map_err(|_| DatabaseError::query(format!("不支持的排序字段: {}", sort_by)))?;
+    Ok(select.order_by(column, params.sort_dir.into()))
+}
+
+/// 执行分页查询，调用前需已校验分页参数
+async fn fetch_page<E, C>(
+    db: &C,
+    select: Select<E>,
+    page: u64,
+    page_size: u64,
+) -> DatabaseResult<Paginated<E::Model>>
+where
+    E: EntityTrait,
+    E::Model: FromQueryResult + Send + Sync,
+    C: ConnectionTrait,
+{
+    let paginator = select.paginate(db, page_size);
+    let counts = paginator.num_items_and_pages().await?;
+    // Paginator 的页码从 0 开始
+    let items = paginator.fetch_page(page - 1).await?;
+
+    Ok(Paginated {
+        items,
+        total: counts.number_of_items,
+        page,
+        page_size,
+        total_pages: counts.number_of_pages,
+    })
+}
+
+/// 校验分页参数
+fn validate_page(page: u64, page_size: u64, max_page_size: u64) -> DatabaseResult<()> {
+    if page == 0 {
+        return Err(DatabaseError::query("页码必须从 1 开始"));
+    }
+    if page_size == 0 {
+        return Err(DatabaseError::query("每页条数必须大于 0"));
+    }
+    if page_size > max_page_size {
+        return Err(DatabaseError::query(format!(
+            "每页条数不能超过 {}",
+            max_page_size
+        )));
+    }
+    Ok(())
+}
+
+fn default_page() -> u64 {
+    1
+}
+fn default_page_size() -> u64 {
+    20
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{DatabaseConfig, SeaOrmConnection};
+    use sea_orm::{ActiveModelTrait, DatabaseConnection, QueryOrder, Set};
+
+    mod item {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+        #[sea_orm(table_name = "clamber_pagination_test_items")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub name: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    #[tokio::test]
+    async fn test_invalid_page_params() {
+        let db = DatabaseConnection::Disconnected;
+
+        let error = paginate::<item::Entity, _>(&db, 1, 0).await.unwrap_err();
+        assert!(error.is_query_error());
+        assert!(error.to_string().contains("每页条数"));
+        assert!(paginate::<item::Entity, _>(&db, 0, 10).await.is_err());
+        assert!(
+            paginate::<item::Entity, _>(&db, 1, MAX_PAGE_SIZE + 1)
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_page_params_defaults() {
+        let params: PageParams = serde_json::from_str("{\"page\": 3}").unwrap();
+        assert_eq!(params.page, 3);
+        assert_eq!(params.page_size, 20);
+        assert_eq!(PageParams::default().page, 1);
+        assert_eq!(params.sort_by, None);
+
+        let params: PageParams =
+            serde_json::from_str("{\"sort_by\": \"name\", \"sort_dir\": \"desc\"}").unwrap();
+        assert_eq!(params.sort_by.as_deref(), Some("name"));
+        assert_eq!(params.sort_dir, SortDirection::Desc);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_into_rejects_invalid_params() {
+        let db = DatabaseConnection::Disconnected;
+
+        let params = PageParams {
+            page_size: 0,
+            ..PageParams::default()
+        };
+        assert!(
+            item::Entity::find()
+                .paginate_into(&db, params)
+                .await
+                .is_err()
+        );
+
+        let params = PageParams {
+            page_size: 51,
+            ..PageParams::default()
+        };
+        let error = item::Entity::find()
+            .paginate_into_with_limit(&db, params, 50)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("50"));
+
+        let params = PageParams {
+            sort_by: Some("password".to_string()),
+            ..PageParams::default()
+        };
+        let error = item::Entity::find()
+            .paginate_into(&db, params)
+            .await
+            .unwrap_err();
+        assert!(error.is_query_error());
+        assert!(error.to_string().contains("password"));
+    }
+
+    #[test]
+    fn test_paginated_map() {
+        let page = Paginated {
+            items: vec![1, 2],
+            total: 5,
+            page: 1,
+            page_size: 2,
+            total_pages: 3,
+        };
+        assert!(page.has_next());
+
+        let mapped = page.map(|n| n.to_string());
+        assert_eq!(mapped.items, ["1", "2"]);
+        assert_eq!(mapped.total_pages, 3);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_seeded_table() {
+        // 通过 DATABASE_URL 指定测试数据库，未设置或不可用时跳过
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let config = DatabaseConfig {
+            url,
+            min_connections: 0,
+            connect_timeout_secs: 2,
+            ..DatabaseConfig::default()
+        };
+        let Ok(connection) = SeaOrmConnection::new(config).await else {
+            return;
+        };
+        let db = &connection.inner;
+
+        db.execute_unprepared("DROP TABLE IF EXISTS clamber_pagination_test_items")
+            .await
+            .unwrap();
+        db.execute_unprepared(
+            "CREATE TABLE clamber_pagination_test_items (id INT PRIMARY KEY, name VARCHAR(32) NOT NULL)",
+        )
+        .await
+        .unwrap();
+        for id in 1..=25 {
+            item::ActiveModel {
+                id: Set(id),
+                name: Set(format!("item-{}", id)),
+            }
+            .insert(db)
+            .await
+            .unwrap();
+        }
+
+        let first = paginate::<item::Entity, _>(db, 1, 10).await.unwrap();
+        assert_eq!(first.items.len(), 10);
+        assert_eq!(first.total, 25);
+        assert_eq!(first.total_pages, 3);
+
+        let select = item::Entity::find().order_by_asc(item::Column::Id);
+        let last = paginate_select(db, select, 3, 10).await.unwrap();
+        assert_eq!(last.items.len(), 5);
+        assert_eq!(last.items[0].id, 21);
+        assert!(!last.has_next());
+
+        let beyond = paginate::<item::Entity, _>(db, 4, 10).await.unwrap();
+        assert!(beyond.items.is_empty());
+        assert_eq!(beyond.total, 25);
+
+        let params = PageParams {
+            page: 2,
+            page_size: 10,
+            sort_by: Some("id".to_string()),
+            sort_dir: SortDirection::Desc,
+        };
+        let second = item::Entity::find()
+            .paginate_into(db, params)
+            .await
+            .unwrap();
+        assert_eq!(second.items.len(), 10);
+        assert_eq!(second.items[0].id, 15);
+        assert_eq!(second.items[9].id, 6);
+        assert_eq!(second.total_pages, 3);
+        assert!(second.has_next());
+
+        db.execute_unprepared("DROP TABLE clamber_pagination_test_items")
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,224 @@
+//! 密码哈希模块
+//!
+//! 基于 Argon2id 的密码哈希与校验，哈希结果为 PHC 字符串格式，包含算法参数与随机盐，
+//! 可直接保存到用户表的密码字段。哈希参数由 [`PasswordPolicy`] 配置，
+//! 调高参数后，校验旧哈希时会按新参数重新计算，供调用方保存
+
+use crate::database::{DatabaseError, DatabaseResult};
+use argon2::password_hash::{
+    PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Serialize};
+
+/// Argon2id 哈希参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PasswordPolicy {
+    /// 内存开销（KiB）
+    #[serde(default = "default_memory_kib")]
+    pub memory_kib: u32,
+
+    /// 迭代次数
+    #[serde(default = "default_iterations")]
+    pub iterations: u32,
+
+    /// 并行度
+    #[serde(default = "default_parallelism")]
+    pub parallelism: u32,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            memory_kib: default_memory_kib(),
+            iterations: default_iterations(),
+            parallelism: default_parallelism(),
+        }
+    }
+}
+
+/// 密码校验结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordVerification {
+    /// 密码不匹配
+    Mismatch,
+    /// 密码匹配
+    Match,
+    /// 密码匹配，但保存的哈希参数与当前策略不一致；附带按当前策略重新计算的哈希，调用方应保存
+    Rehashed(String),
+}
+
+impl PasswordVerification {
+    /// 密码是否匹配
+    pub fn is_match(&self) -> bool {
+        !matches!(self, PasswordVerification::Mismatch)
+    }
+}
+
+impl PasswordPolicy {
+    /// 按当前策略构建 Argon2id 哈希器，参数无效时返回错误
+    fn argon2(&self) -> DatabaseResult<Argon2<'static>> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| DatabaseError::password(format!("密码哈希参数无效: {}", e)))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    /// 使用随机盐计算密码哈希，返回 PHC 格式字符串（如 `$argon2id$v=19$...`）
+    pub fn hash(&self, password: &str) -> DatabaseResult<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        self.argon2()?
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| DatabaseError::password(format!("计算密码哈希失败: {}", e)))
+    }
+
+    /// 校验密码，匹配且哈希参数已过时时返回按当前策略重新计算的哈希
+    ///
+    /// 哈希格式无效时返回错误
+    pub fn verify(&self, password: &str, hash: &str) -> DatabaseResult<PasswordVerification> {
+        let parsed = parse_hash(hash)?;
+        // 校验使用哈希中记录的参数，与当前策略无关
+        match Argon2::default().verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => {}
+            Err(argon2::password_hash::Error::Password) => {
+                return Ok(PasswordVerification::Mismatch);
+            }
+            Err(e) => return Err(DatabaseError::password(format!("校验密码失败: {}", e))),
+        }
+
+        if self.is_current(&parsed) {
+            Ok(PasswordVerification::Match)
+        } else {
+            Ok(PasswordVerification::Rehashed(self.hash(password)?))
+        }
+    }
+
+    /// 保存的哈希是否需要按当前策略重新计算
+    pub fn needs_rehash(&self, hash: &str) -> DatabaseResult<bool> {
+        Ok(!self.is_current(&parse_hash(hash)?))
+    }
+
+    fn is_current(&self, parsed: &PasswordHash<'_>) -> bool {
+        if parsed.algorithm != Algorithm::Argon2id.ident()
+            || parsed.version != Some(Version::V0x13.into())
+        {
+            return false;
+        }
+        Params::try_from(parsed).is_ok_and(|params| {
+            params.m_cost() == self.memory_kib
+                && params.t_cost() == self.iterations
+                && params.p_cost() == self.parallelism
+        })
+    }
+}
+
+/// 使用默认策略计算密码哈希，见 [`PasswordPolicy::hash`]
+pub fn hash_password(password: &str) -> DatabaseResult<String> {
+    PasswordPolicy::default().hash(password)
+}
+
+/// 校验密码与已保存的哈希是否匹配
+///
+/// 密码不匹配时返回 `Ok(false)`，哈希格式无效时返回错误
+pub fn verify_password(password: &str, hash: &str) -> DatabaseResult<bool> {
+    let parsed = parse_hash(hash)?;
+    match Argon2::default().verify_password(password.as_bytes(), &parsed) {
+        Ok(()) => Ok(true),
+        Err(argon2::password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(DatabaseError::password(format!("校验密码失败: {}", e))),
+    }
+}
+
+fn parse_hash(hash: &str) -> DatabaseResult<PasswordHash<'_>> {
+    PasswordHash::new(hash).map_err(|e| DatabaseError::password(format!("密码哈希格式无效: {}", e)))
+}
+
+fn default_memory_kib() -> u32 {
+    Params::DEFAULT_M_COST
+}
+
+fn default_iterations() -> u32 {
+    Params::DEFAULT_T_COST
+}
+
+fn default_parallelism() -> u32 {
+    Params::DEFAULT_P_COST
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_is_not_plaintext() {
+        let hash = hash_password("s3cret!").unwrap();
+        assert_ne!(hash, "s3cret!");
+        assert!(!hash.contains("s3cret!"));
+        assert!(hash.starts_with("$argon2id$"));
+
+        // 每次使用不同的随机盐
+        assert_ne!(hash, hash_password("s3cret!").unwrap());
+    }
+
+    #[test]
+    fn test_verify_password() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(verify_password("correct horse", &hash).unwrap());
+        assert!(!verify_password("wrong horse", &hash).unwrap());
+        assert!(!verify_password("", &hash).unwrap());
+    }
+
+    /// 低开销参数，模拟旧版本保存的哈希
+    fn weak_policy() -> PasswordPolicy {
+        PasswordPolicy {
+            memory_kib: 1024,
+            iterations: 1,
+            parallelism: 1,
+        }
+    }
+
+    #[test]
+    fn test_policy_verify_and_rehash() {
+        let policy = PasswordPolicy::default();
+        let hash = policy.hash("correct horse").unwrap();
+        assert!(!policy.needs_rehash(&hash).unwrap());
+        assert_eq!(
+            policy.verify("correct horse", &hash).unwrap(),
+            PasswordVerification::Match
+        );
+        assert_eq!(
+            policy.verify("wrong horse", &hash).unwrap(),
+            PasswordVerification::Mismatch
+        );
+
+        // 旧参数的哈希校验成功后按当前策略升级
+        let outdated = weak_policy().hash("correct horse").unwrap();
+        assert!(outdated.contains("m=1024,t=1,p=1"));
+        assert!(policy.needs_rehash(&outdated).unwrap());
+        let PasswordVerification::Rehashed(upgraded) =
+            policy.verify("correct horse", &outdated).unwrap()
+        else {
+            panic!("outdated hash should be rehashed");
+        };
+        assert!(!policy.needs_rehash(&upgraded).unwrap());
+        assert!(verify_password("correct horse", &upgraded).unwrap());
+
+        // 密码错误时不升级
+        assert!(!policy.verify("wrong horse", &outdated).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_invalid_policy() {
+        let policy = PasswordPolicy {
+            memory_kib: 1,
+            ..PasswordPolicy::default()
+        };
+        assert!(policy.hash("password").unwrap_err().is_password_error());
+    }
+
+    #[test]
+    fn test_verify_invalid_hash() {
+        let error = verify_password("password", "hashed_password").unwrap_err();
+        assert!(error.is_password_error());
+    }
+}
@@ -0,0 +1,186 @@
+//! 读写分离连接模块
+//!
+//! [`ReadWriteConnection`] 持有一个主库连接和若干只读副本连接：
+//! 写操作通过 [`ReadWriteConnection::writer`] 发往主库，读操作通过
+//! [`ReadWriteConnection::reader`] 在副本之间轮询，未配置副本时回退到主库
+
+use crate::database::{DatabaseConfig, DatabaseResult, SeaOrmConnection};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::info;
+
+/// 读写分离配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReadWriteConfig {
+    /// 主库配置
+    pub primary: DatabaseConfig,
+    /// 只读副本 URL，连接池参数沿用主库配置
+    #[serde(default)]
+    pub replica_urls: Vec<String>,
+}
+
+impl ReadWriteConfig {
+    /// 根据主库 URL 和副本 URL 列表创建配置
+    pub fn new(primary_url: impl Into<String>, replica_urls: Vec<String>) -> Self {
+        Self {
+            primary: DatabaseConfig::for_url(primary_url),
+            replica_urls,
+        }
+    }
+
+    /// 生成每个副本的连接配置
+    pub fn replica_configs(&self) -> Vec<DatabaseConfig> {
+        self.replica_urls
+            .iter()
+            .map(|url| DatabaseConfig {
+                url: url.clone(),
+                ..self.primary.clone()
+            })
+            .collect()
+    }
+
+    /// 验证主库与所有副本配置的有效性
+    pub fn validate(&self) -> Result<(), String> {
+        self.primary.validate()?;
+        for (index, replica) in self.replica_configs().iter().enumerate() {
+            replica
+                .validate()
+                .map_err(|msg| format!("副本 {} 配置无效: {}", index, msg))?;
+        }
+        Ok(())
+    }
+}
+
+/// 读写分离连接，克隆的连接共享同一组底层连接与轮询位置
+#[derive(Debug, Clone)]
+pub struct ReadWriteConnection {
+    writer: SeaOrmConnection,
+    readers: Arc<Vec<SeaOrmConnection>>,
+    next: Arc<AtomicUsize>,
+}
+
+impl ReadWriteConnection {
+    /// 建立主库与所有副本的连接
+    pub async fn new(config: ReadWriteConfig) -> DatabaseResult<Self> {
+        let writer = SeaOrmConnection::new(config.primary.clone()).await?;
+        let mut readers = Vec::with_capacity(config.replica_urls.len());
+        for replica in config.replica_configs() {
+            readers.push(SeaOrmConnection::new(replica).await?);
+        }
+
+        info!("读写分离连接已建立: 副本数={}", readers.len());
+        Ok(Self::from_connections(writer, readers))
+    }
+
+    /// 由已建立的主库连接和副本连接组成读写分离连接
+    pub fn from_connections(writer: SeaOrmConnection, readers: Vec<SeaOrmConnection>) -> Self {
+        Self {
+            writer,
+            readers: Arc::new(readers),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// 主库连接，用于写操作及需要读取最新数据的查询
+    pub fn writer(&self) -> &SeaOrmConnection {
+        &self.writer
+    }
+
+    /// 轮询选择一个副本连接，未配置副本时返回主库连接
+    ///
+    /// 副本存在复制延迟，刚写入的数据可能暂时读不到
+    pub fn reader(&self) -> &SeaOrmConnection {
+        if self.readers.is_empty() {
+            return &self.writer;
+        }
+        let turn = self.next.fetch_add(1, Ordering::Relaxed);
+        &self.readers[turn % self.readers.len()]
+    }
+
+    /// 副本数量
+    pub fn replica_count(&self) -> usize {
+        self.readers.len()
+    }
+
+    /// 测试主库与所有副本连接是否有效
+    pub async fn ping(&self) -> DatabaseResult<()> {
+        self.writer.ping().await?;
+        for reader in self.readers.iter() {
+            reader.ping().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::DatabaseConnection;
+
+    fn connection(url: &str) -> SeaOrmConnection {
+        SeaOrmConnection::with_inner(
+            DatabaseConnection::Disconnected,
+            DatabaseConfig::for_url(url),
+        )
+    }
+
+    #[test]
+    fn test_reader_cycles_replicas() {
+        let connection = ReadWriteConnection::from_connections(
+            connection("mysql://primary/app"),
+            vec![
+                connection("mysql://replica-1/app"),
+                connection("mysql://replica-2/app"),
+            ],
+        );
+        assert_eq!(connection.replica_count(), 2);
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| connection.reader().get_config().url.clone())
+            .collect();
+        assert_eq!(
+            readers,
+            [
+                "mysql://replica-1/app",
+                "mysql://replica-2/app",
+                "mysql://replica-1/app",
+                "mysql://replica-2/app",
+            ]
+        );
+
+        // 克隆的连接共享轮询位置
+        let cloned = connection.clone();
+        assert_eq!(cloned.reader().get_config().url, "mysql://replica-1/app");
+
+        for _ in 0..3 {
+            assert_eq!(connection.writer().get_config().url, "mysql://primary/app");
+        }
+    }
+
+    #[test]
+    fn test_reader_falls_back_to_writer() {
+        let connection =
+            ReadWriteConnection::from_connections(connection("mysql://primary/app"), Vec::new());
+        assert_eq!(connection.replica_count(), 0);
+        assert_eq!(connection.reader().get_config().url, "mysql://primary/app");
+    }
+
+    #[test]
+    fn test_replica_configs_inherit_primary() {
+        let mut config = ReadWriteConfig::new(
+            "mysql://primary/app",
+            vec!["mysql://replica-1/app".to_string()],
+        );
+        config.primary.max_connections = 12;
+
+        let replicas = config.replica_configs();
+        assert_eq!(replicas.len(), 1);
+        assert_eq!(replicas[0].url, "mysql://replica-1/app");
+        assert_eq!(replicas[0].max_connections, 12);
+        assert!(config.validate().is_ok());
+
+        config.replica_urls.push(String::new());
+        assert!(config.validate().unwrap_err().contains("副本 1"));
+    }
+}
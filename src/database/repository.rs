@@ -0,0 +1,333 @@
+//! 通用仓储模块
+//!
+//! [`Repository`] 为任意 SeaORM 实体提供插入、按主键或列值查询、全量与条件分页查询、计数与存在性检查、更新与按主键删除，
+//! 新实体无需再手写重复的 CRUD 代码。记录不存在时统一返回 [`DatabaseError::EntityNotFound`]
+
+use crate::database::{
+    DatabaseError, DatabaseResult, PageParams, PaginateExt, Paginated, SeaOrmConnection,
+};
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr,
+    EntityName, EntityTrait, IdenStatic, IntoActiveModel, Iterable, PaginatorTrait,
+    PrimaryKeyToColumn, PrimaryKeyTrait, QueryFilter, Value, sea_query::IntoValueTuple,
+};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// 实体主键值类型
+pub type PrimaryKeyOf<E> = <<E as EntityTrait>::PrimaryKey as PrimaryKeyTrait>::ValueType;
+
+/// 实体的通用仓储
+///
+/// ```ignore
+/// let users = Repository::<user::Entity>::new(db.clone());
+/// let user = users.find_by_id(42).await?;
+/// ```
+pub struct Repository<E> {
+    db: DatabaseConnection,
+    _marker: PhantomData<fn() -> E>,
+}
+
+// 手动实现 Clone，避免要求实体类型 E 实现 Clone
+impl<E> Clone for Repository<E> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<E: EntityTrait> Repository<E> {
+    /// 使用数据库连接创建仓储
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            _marker: PhantomData,
+        }
+    }
+
+    /// 使用 [`SeaOrmConnection`] 创建仓储
+    pub fn from_connection(connection: &SeaOrmConnection) -> Self {
+        Self::new(connection.inner.clone())
+    }
+
+    /// 仓储使用的数据库连接
+    pub fn connection(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    /// 插入记录并返回插入后的模型
+    pub async fn insert<A>(&self, model: A) -> DatabaseResult<E::Model>
+    where
+        A: ActiveModelTrait<Entity = E> + ActiveModelBehavior + Send,
+        E::Model: IntoActiveModel<A>,
+    {
+        Ok(model.insert(&self.db).await?)
+    }
+
+    /// 按主键查询，记录不存在时返回 EntityNotFound 错误
+    pub async fn find_by_id(&self, id: impl Into<PrimaryKeyOf<E>>) -> DatabaseResult<E::Model> {
+        let id = id.into();
+        let label = format!("{:?}", id);
+        E::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found(entity_name::<E>(), label))
+    }
+
+    /// 按列值查询单条记录（例如按邮箱或用户名查找），记录不存在时返回 EntityNotFound 错误
+    pub async fn find_one_by<V>(&self, column: E::Column, value: V) -> DatabaseResult<E::Model>
+    where
+        V: Into<Value> + Debug,
+    {
+        let label = format!("{} = {:?}", column.as_str(), value);
+        E::find()
+            .filter(column.eq(value))
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found(entity_name::<E>(), label))
+    }
+
+    /// 查询全部记录
+    pub async fn find_all(&self) -> DatabaseResult<Vec<E::Model>> {
+        Ok(E::find().all(&self.db).await?)
+    }
+
+    /// 记录总数
+    pub async fn count(&self) -> DatabaseResult<u64> {
+        Ok(E::find().count(&self.db).await?)
+    }
+
+    /// 按条件统计记录数
+    pub async fn count_by(&self, condition: Condition) -> DatabaseResult<u64> {
+        Ok(E::find().filter(condition).count(&self.db).await?)
+    }
+
+    /// 指定主键的记录是否存在
+    pub async fn exists(&self, id: impl Into<PrimaryKeyOf<E>>) -> DatabaseResult<bool> {
+        let condition = primary_key_condition::<E>(id.into());
+        Ok(self.count_by(condition).await? > 0)
+    }
+
+    /// 按条件过滤后分页查询，例如按角色、启用状态或关键字筛选列表
+    pub async fn find_page(
+        &self,
+        condition: Condition,
+        params: PageParams,
+    ) -> DatabaseResult<Paginated<E::Model>>
+    where
+        E::Model: Send + Sync,
+    {
+        E::find()
+            .filter(condition)
+            .paginate_into(&self.db, params)
+            .await
+    }
+
+    /// 更新记录（仅写入已修改的字段），记录不存在时返回 EntityNotFound 错误
+    pub async fn update<A>(&self, model: A) -> DatabaseResult<E::Model>
+    where
+        A: ActiveModelTrait<Entity = E> + ActiveModelBehavior + Send,
+        E::Model: IntoActiveModel<A>,
+    {
+        model.update(&self.db).await.map_err(map_not_found::<E>)
+    }
+
+    /// 按主键删除，记录不存在时返回 EntityNotFound 错误
+    pub async fn delete_by_id(&self, id: impl Into<PrimaryKeyOf<E>>) -> DatabaseResult<()> {
+        let id = id.into();
+        let label = format!("{:?}", id);
+        let result = E::delete_by_id(id).exec(&self.db).await?;
+        if result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found(entity_name::<E>(), label));
+        }
+        Ok(())
+    }
+}
+
+/// 实体对应的表名，用于错误信息
+pub(crate) fn entity_name<E: EntityTrait>() -> String {
+    E::default().table_name().to_string()
+}
+
+/// 按主键值构造过滤条件，支持复合主键
+pub(crate) fn primary_key_condition<E: EntityTrait>(id: PrimaryKeyOf<E>) -> Condition {
+    E::PrimaryKey::iter()
+        .zip(id.into_value_tuple())
+        .fold(Condition::all(), |condition, (key, value)| {
+            condition.add(key.into_column().eq(value))
+        })
+}
+
+/// 将 SeaORM 的记录不存在错误转换为 EntityNotFound，其余错误原样保留
+fn map_not_found<E: EntityTrait>(error: DbErr) -> DatabaseError {
+    match error {
+        DbErr::RecordNotFound(detail) => {
+            DatabaseError::entity_not_found(entity_name::<E>(), detail)
+        }
+        DbErr::RecordNotUpdated => DatabaseError::entity_not_found(entity_name::<E>(), "unknown"),
+        other => DatabaseError::from(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseConfig;
+    use sea_orm::{ConnectionTrait, Set};
+
+    mod user {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+        #[sea_orm(table_name = "clamber_repository_test_users")]
+        pub struct Model {
+            #[sea_orm(primary_key, auto_increment = false)]
+            pub id: i32,
+            pub username: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    #[test]
+    fn test_map_not_found() {
+        let error = map_not_found::<user::Entity>(DbErr::RecordNotUpdated);
+        assert!(error.is_not_found_error());
+        assert!(error.to_string().contains("clamber_repository_test_users"));
+
+        let error = map_not_found::<user::Entity>(DbErr::RecordNotFound("id = 7".to_string()));
+        assert_eq!(
+            error.to_string(),
+            "实体不存在: clamber_repository_test_users with id: id = 7"
+        );
+
+        let error = map_not_found::<user::Entity>(DbErr::Custom("boom".to_string()));
+        assert!(!error.is_not_found_error());
+    }
+
+    #[tokio::test]
+    async fn test_crud_live_database() {
+        // 通过 DATABASE_URL 指定测试数据库；未设置时启用 database-sqlite 则使用内存 SQLite，否则跳过
+        let url = match std::env::var("DATABASE_URL") {
+            Ok(url) => url,
+            Err(_) if cfg!(feature = "database-sqlite") => "sqlite::memory:".to_string(),
+            Err(_) => return,
+        };
+        // 单连接保证内存 SQLite 在各语句间共享同一个数据库
+        let config = DatabaseConfig {
+            max_connections: 1,
+            min_connections: 1,
+            connect_timeout_secs: 2,
+            ..DatabaseConfig::for_url(url)
+        };
+        let Ok(connection) = SeaOrmConnection::new(config).await else {
+            return;
+        };
+        let db = &connection.inner;
+        db.execute_unprepared("DROP TABLE IF EXISTS clamber_repository_test_users")
+            .await
+            .unwrap();
+        db.execute_unprepared(
+            "CREATE TABLE clamber_repository_test_users (id INT PRIMARY KEY, username VARCHAR(32) NOT NULL UNIQUE)",
+        )
+        .await
+        .unwrap();
+
+        let users = Repository::<user::Entity>::from_connection(&connection);
+        let alice = users
+            .insert(user::ActiveModel {
+                id: Set(1),
+                username: Set("alice".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(alice.username, "alice");
+        users
+            .insert(user::ActiveModel {
+                id: Set(2),
+                username: Set("bob".to_string()),
+            })
+            .await
+            .unwrap();
+        let duplicate = users
+            .insert(user::ActiveModel {
+                id: Set(3),
+                username: Set("bob".to_string()),
+            })
+            .await
+            .unwrap_err();
+        assert!(duplicate.is_constraint_error());
+
+        assert_eq!(users.find_by_id(1).await.unwrap(), alice);
+        assert_eq!(users.find_all().await.unwrap().len(), 2);
+        assert!(users.find_by_id(99).await.unwrap_err().is_not_found_error());
+        assert_eq!(users.count().await.unwrap(), 2);
+        assert_eq!(
+            users
+                .count_by(Condition::all().add(user::Column::Username.eq("bob")))
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(users.exists(1).await.unwrap());
+        assert!(!users.exists(99).await.unwrap());
+
+        let bob = users
+            .find_one_by(user::Column::Username, "bob")
+            .await
+            .unwrap();
+        assert_eq!(bob.id, 2);
+        let error = users
+            .find_one_by(user::Column::Username, "carol")
+            .await
+            .unwrap_err();
+        assert!(error.is_not_found_error());
+        assert!(error.to_string().contains("username = \"carol\""));
+
+        let page = users
+            .find_page(
+                Condition::all().add(user::Column::Username.like("a%")),
+                PageParams::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items, vec![alice.clone()]);
+
+        let mut renamed: user::ActiveModel = alice.into();
+        renamed.username = Set("alice2".to_string());
+        assert_eq!(users.update(renamed).await.unwrap().username, "alice2");
+
+        let missing = user::ActiveModel {
+            id: Set(99),
+            username: Set("ghost".to_string()),
+        };
+        assert!(
+            users
+                .update(missing)
+                .await
+                .unwrap_err()
+                .is_not_found_error()
+        );
+
+        users.delete_by_id(2).await.unwrap();
+        assert!(
+            users
+                .delete_by_id(2)
+                .await
+                .unwrap_err()
+                .is_not_found_error()
+        );
+        assert_eq!(users.find_all().await.unwrap().len(), 1);
+        assert!(!users.exists(2).await.unwrap());
+
+        db.execute_unprepared("DROP TABLE clamber_repository_test_users")
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,336 @@
+//! 软删除模块
+//!
+//! 实体实现 [`SoftDeleteExt`] 指定可空的删除时间列（通常为 `deleted_at`）后，即可使用
+//! [`SoftDeleteRepository`]：删除只写入删除时间，查询默认排除已删除记录，
+//! 需要时可通过 `include_deleted(true)` 查看全部记录，或使用 `hard_delete_by_id` 物理删除
+//!
+//! ```ignore
+//! impl SoftDeleteExt for user::Entity {
+//!     fn deleted_at_column() -> Self::Column {
+//!         user::Column::DeletedAt
+//!     }
+//! }
+//!
+//! let users = SoftDeleteRepository::<user::Entity>::new(db.clone());
+//! users.delete_by_id(42).await?;
+//! assert!(users.find_by_id(42).await.is_err());
+//! users.restore_by_id(42).await?;
+//! ```
+
+use crate::database::repository::{entity_name, primary_key_condition};
+use crate::database::{
+    DatabaseError, DatabaseResult, PageParams, PaginateExt, Paginated, PrimaryKeyOf, Repository,
+    SeaOrmConnection,
+};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, IdenStatic, QueryFilter, Select, Value,
+};
+use std::fmt::Debug;
+
+/// 支持软删除的实体
+pub trait SoftDeleteExt: EntityTrait {
+    /// 记录删除时间的可空列，值为 NULL 表示未删除
+    fn deleted_at_column() -> Self::Column;
+
+    /// 软删除时写入的删除时间，默认为当前 UTC 时间；列类型不是 `DateTimeUtc` 时可覆盖
+    fn deleted_at_value() -> Value {
+        chrono::Utc::now().into()
+    }
+
+    /// 查询未删除的记录
+    fn find_not_deleted() -> Select<Self> {
+        Self::find().filter(Self::deleted_at_column().is_null())
+    }
+
+    /// 查询已删除的记录
+    fn find_deleted() -> Select<Self> {
+        Self::find().filter(Self::deleted_at_column().is_not_null())
+    }
+}
+
+/// 支持软删除实体的仓储
+///
+/// 插入与更新通过 [`SoftDeleteRepository::repository`] 使用底层的 [`Repository`]
+pub struct SoftDeleteRepository<E> {
+    inner: Repository<E>,
+    include_deleted: bool,
+}
+
+// 手动实现 Clone，避免要求实体类型 E 实现 Clone
+impl<E> Clone for SoftDeleteRepository<E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            include_deleted: self.include_deleted,
+        }
+    }
+}
+
+impl<E: SoftDeleteExt> SoftDeleteRepository<E> {
+    /// 使用数据库连接创建仓储，查询默认排除已删除记录
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            inner: Repository::new(db),
+            include_deleted: false,
+        }
+    }
+
+    /// 使用 [`SeaOrmConnection`] 创建仓储
+    pub fn from_connection(connection: &SeaOrmConnection) -> Self {
+        Self::new(connection.inner.clone())
+    }
+
+    /// 设置查询是否包含已删除记录
+    pub fn include_deleted(mut self, include_deleted: bool) -> Self {
+        self.include_deleted = include_deleted;
+        self
+    }
+
+    /// 底层的通用仓储，用于插入与更新
+    pub fn repository(&self) -> &Repository<E> {
+        &self.inner
+    }
+
+    /// 仓储使用的数据库连接
+    pub fn connection(&self) -> &DatabaseConnection {
+        self.inner.connection()
+    }
+
+    /// 按 include_deleted 设置过滤后的查询
+    fn select(&self) -> Select<E> {
+        if self.include_deleted {
+            E::find()
+        } else {
+            E::find_not_deleted()
+        }
+    }
+
+    /// 按主键查询，记录不存在或已删除时返回 EntityNotFound 错误
+    pub async fn find_by_id(&self, id: impl Into<PrimaryKeyOf<E>>) -> DatabaseResult<E::Model> {
+        let id = id.into();
+        let label = format!("{:?}", id);
+        self.select()
+            .filter(primary_key_condition::<E>(id))
+            .one(self.connection())
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found(entity_name::<E>(), label))
+    }
+
+    /// 按列值查询单条记录，记录不存在或已删除时返回 EntityNotFound 错误
+    pub async fn find_one_by<V>(&self, column: E::Column, value: V) -> DatabaseResult<E::Model>
+    where
+        V: Into<Value> + Debug,
+    {
+        let label = format!("{} = {:?}", column.as_str(), value);
+        self.select()
+            .filter(column.eq(value))
+            .one(self.connection())
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found(entity_name::<E>(), label))
+    }
+
+    /// 查询全部记录
+    pub async fn find_all(&self) -> DatabaseResult<Vec<E::Model>> {
+        Ok(self.select().all(self.connection()).await?)
+    }
+
+    /// 按条件过滤后分页查询
+    pub async fn find_page(
+        &self,
+        condition: Condition,
+        params: PageParams,
+    ) -> DatabaseResult<Paginated<E::Model>>
+    where
+        E::Model: Send + Sync,
+    {
+        self.select()
+            .filter(condition)
+            .paginate_into(self.connection(), params)
+            .await
+    }
+
+    /// 软删除：写入删除时间，记录不存在或已删除时返回 EntityNotFound 错误
+    pub async fn delete_by_id(&self, id: impl Into<PrimaryKeyOf<E>>) -> DatabaseResult<()> {
+        self.set_deleted_at(id.into(), E::deleted_at_value(), false)
+            .await
+    }
+
+    /// 恢复已软删除的记录，记录不存在或未删除时返回 EntityNotFound 错误
+    pub async fn restore_by_id(&self, id: impl Into<PrimaryKeyOf<E>>) -> DatabaseResult<()> {
+        // 使用与删除时间相同类型的 NULL，避免部分数据库的类型推断问题
+        let null = E::deleted_at_value().as_null();
+        self.set_deleted_at(id.into(), null, true).await
+    }
+
+    /// 物理删除记录（无论是否已软删除），记录不存在时返回 EntityNotFound 错误
+    pub async fn hard_delete_by_id(&self, id: impl Into<PrimaryKeyOf<E>>) -> DatabaseResult<()> {
+        self.inner.delete_by_id(id).await
+    }
+
+    /// 更新删除时间列，`deleted` 指定目标记录当前应处于的删除状态
+    async fn set_deleted_at(
+        &self,
+        id: PrimaryKeyOf<E>,
+        value: Value,
+        deleted: bool,
+    ) -> DatabaseResult<()> {
+        let label = format!("{:?}", id);
+        let column = E::deleted_at_column();
+        let state = if deleted {
+            column.is_not_null()
+        } else {
+            column.is_null()
+        };
+        let result = E::update_many()
+            .col_expr(column, Expr::value(value))
+            .filter(primary_key_condition::<E>(id))
+            .filter(state)
+            .exec(self.connection())
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found(entity_name::<E>(), label));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod user {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+        #[sea_orm(table_name = "clamber_soft_delete_test_users")]
+        pub struct Model {
+            #[sea_orm(primary_key, auto_increment = false)]
+            pub id: i32,
+            pub username: String,
+            pub deleted_at: Option<DateTimeUtc>,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+
+        impl crate::database::SoftDeleteExt for Entity {
+            fn deleted_at_column() -> Self::Column {
+                Column::DeletedAt
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_not_deleted_query() {
+        use sea_orm::{DbBackend, QueryTrait};
+
+        let sql = user::Entity::find_not_deleted()
+            .build(DbBackend::Postgres)
+            .to_string();
+        assert!(sql.contains(r#""deleted_at" IS NULL"#), "{}", sql);
+
+        let sql = user::Entity::find_deleted()
+            .build(DbBackend::Postgres)
+            .to_string();
+        assert!(sql.contains(r#""deleted_at" IS NOT NULL"#), "{}", sql);
+    }
+
+    #[cfg(feature = "database-sqlite")]
+    #[tokio::test]
+    async fn test_soft_delete_restore_and_hard_delete() {
+        use crate::database::DatabaseConfig;
+        use sea_orm::{ConnectionTrait, Set};
+
+        // 单连接保证内存 SQLite 在各语句间共享同一个数据库
+        let config = DatabaseConfig {
+            max_connections: 1,
+            min_connections: 1,
+            ..DatabaseConfig::for_url("sqlite::memory:")
+        };
+        let connection = SeaOrmConnection::new(config).await.unwrap();
+        connection
+            .inner
+            .execute_unprepared(
+                "CREATE TABLE clamber_soft_delete_test_users \
+                 (id INTEGER PRIMARY KEY, username TEXT NOT NULL, deleted_at TEXT NULL)",
+            )
+            .await
+            .unwrap();
+
+        let users = SoftDeleteRepository::<user::Entity>::from_connection(&connection);
+        for (id, username) in [(1, "alice"), (2, "bob")] {
+            users
+                .repository()
+                .insert(user::ActiveModel {
+                    id: Set(id),
+                    username: Set(username.to_string()),
+                    deleted_at: Set(None),
+                })
+                .await
+                .unwrap();
+        }
+
+        // 软删除后从查询结果中消失
+        users.delete_by_id(1).await.unwrap();
+        assert!(users.find_by_id(1).await.unwrap_err().is_not_found_error());
+        assert!(
+            users
+                .find_one_by(user::Column::Username, "alice")
+                .await
+                .unwrap_err()
+                .is_not_found_error()
+        );
+        assert_eq!(users.find_all().await.unwrap().len(), 1);
+        let page = users
+            .find_page(Condition::all(), PageParams::default())
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert!(
+            users
+                .delete_by_id(1)
+                .await
+                .unwrap_err()
+                .is_not_found_error()
+        );
+
+        // include_deleted 时仍可查到，并带有删除时间
+        let all = users.clone().include_deleted(true);
+        assert!(all.find_by_id(1).await.unwrap().deleted_at.is_some());
+        assert_eq!(all.find_all().await.unwrap().len(), 2);
+
+        // 恢复后重新出现
+        users.restore_by_id(1).await.unwrap();
+        assert!(users.find_by_id(1).await.unwrap().deleted_at.is_none());
+        assert!(
+            users
+                .restore_by_id(1)
+                .await
+                .unwrap_err()
+                .is_not_found_error()
+        );
+        assert_eq!(users.find_all().await.unwrap().len(), 2);
+
+        // 物理删除后即使包含已删除记录也查不到
+        users.delete_by_id(2).await.unwrap();
+        users.hard_delete_by_id(2).await.unwrap();
+        assert!(all.find_by_id(2).await.unwrap_err().is_not_found_error());
+        assert!(
+            users
+                .hard_delete_by_id(2)
+                .await
+                .unwrap_err()
+                .is_not_found_error()
+        );
+        assert!(
+            users
+                .delete_by_id(99)
+                .await
+                .unwrap_err()
+                .is_not_found_error()
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! 数据库事务模块
+//!
+//! [`SeaOrmConnection::transaction`] 以闭包形式执行事务：闭包返回 Ok 时提交，返回 Err 时回滚。
+//! 遇到死锁或序列化失败时可以按 [`TransactionOptions::max_retries`] 重新执行整个闭包
+
+use crate::database::{DatabaseError, DatabaseResult, SeaOrmConnection};
+use sea_orm::{DatabaseTransaction, DbErr, IsolationLevel, RuntimeErr, TransactionTrait, sqlx};
+use tracing::warn;
+
+/// 事务选项
+#[derive(Debug, Clone, Default)]
+pub struct TransactionOptions {
+    /// 隔离级别，None 时使用数据库默认级别（SQLite 不支持设置隔离级别，会被忽略）
+    pub isolation_level: Option<IsolationLevel>,
+    /// 遇到死锁或序列化失败时的最大重试次数，0 表示不重试
+    pub max_retries: u32,
+}
+
+impl TransactionOptions {
+    /// 设置隔离级别
+    pub fn isolation_level(mut self, level: IsolationLevel) -> Self {
+        self.isolation_level = Some(level);
+        self
+    }
+
+    /// 设置死锁或序列化失败时的最大重试次数
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+}
+
+impl SeaOrmConnection {
+    /// 使用默认选项执行事务，见 [`SeaOrmConnection::transaction_with`]
+    pub async fn transaction<T, F>(&self, body: F) -> DatabaseResult<T>
+    where
+        F: AsyncFnMut(&DatabaseTransaction) -> DatabaseResult<T>,
+    {
+        self.transaction_with(TransactionOptions::default(), body)
+            .await
+    }
+
+    /// 在事务中执行 `body`，返回 Ok 时提交，返回 Err 时回滚
+    ///
+    /// 开启、提交、回滚事务失败以及 `body` 返回的 SeaORM 错误都会转换为 [`DatabaseError::Transaction`]，
+    /// 其他错误原样返回。死锁或序列化失败且未超过 `max_retries` 时回滚后重新执行 `body`，
+    /// 因此 `body` 的副作用应只发生在事务内
+    pub async fn transaction_with<T, F>(
+        &self,
+        options: TransactionOptions,
+        mut body: F,
+    ) -> DatabaseResult<T>
+    where
+        F: AsyncFnMut(&DatabaseTransaction) -> DatabaseResult<T>,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let txn = self
+                .inner
+                .begin_with_config(options.isolation_level, None)
+                .await
+                .map_err(|e| {
+                    self.observe_error(&e);
+                    DatabaseError::transaction(format!("开启事务失败: {}", e))
+                })?;
+
+            let error = match body(&txn).await {
+                Ok(value) => match txn.commit().await {
+                    Ok(()) => return Ok(value),
+                    Err(e) => DatabaseError::SeaOrm(e),
+                },
+                Err(e) => {
+                    if let Err(rollback) = txn.rollback().await {
+                        warn!("事务回滚失败: {}", rollback);
+                    }
+                    e
+                }
+            };
+
+            if attempt <= options.max_retries && error.is_retriable_transaction_error() {
+                warn!(
+                    "事务第 {} 次执行遇到可重试错误，重新执行: {}",
+                    attempt, error
+                );
+                continue;
+            }
+            return Err(into_transaction_error(error));
+        }
+    }
+}
+
+impl DatabaseError {
+    /// 判断是否为可通过重新执行事务解决的错误（死锁、序列化失败、锁等待超时、SQLite 数据库被锁）
+    ///
+    /// 根据驱动返回的错误码判断：MySQL 1213/1205、PostgreSQL SQLSTATE 40001/40P01、
+    /// SQLite SQLITE_BUSY/SQLITE_LOCKED，不匹配错误信息
+    pub fn is_retriable_transaction_error(&self) -> bool {
+        match self {
+            DatabaseError::SeaOrm(
+                DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+                | DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+                | DbErr::Conn(RuntimeErr::SqlxError(sqlx::Error::Database(e))),
+            ) => is_retriable_database_error(e.as_ref()),
+            _ => false,
+        }
+    }
+}
+
+/// 判断驱动返回的数据库错误是否可以通过重新执行事务解决
+fn is_retriable_database_error(error: &dyn sqlx::error::DatabaseError) -> bool {
+    // MySQL 的 code() 为 SQLSTATE，锁等待超时（1205）只能通过错误号区分
+    if let Some(error) = error.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>() {
+        return matches!(error.number(), 1213 | 1205);
+    }
+    // SQLite 的 code() 为扩展错误码，低 8 位为主错误码：5 SQLITE_BUSY、6 SQLITE_LOCKED
+    #[cfg(feature = "database-sqlite")]
+    if error
+        .try_downcast_ref::<sqlx::sqlite::SqliteError>()
+        .is_some()
+    {
+        return error
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, 5 | 6));
+    }
+    // PostgreSQL 40001 serialization_failure、40P01 deadlock_detected
+    matches!(error.code().as_deref(), Some("40001" | "40P01"))
+}
+
+/// 将 SeaORM 错误转换为事务错误，其他错误原样返回
+fn into_transaction_error(error: DatabaseError) -> DatabaseError {
+    match error {
+        DatabaseError::SeaOrm(e) => DatabaseError::transaction(e.to_string()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseConfig;
+    use sea_orm::{ConnectionTrait, Statement};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// 通过 DATABASE_URL 指定测试数据库；未设置时启用 database-sqlite 则使用内存 SQLite，否则跳过
+    async fn test_connection(table: &str) -> Option<SeaOrmConnection> {
+        let url = match std::env::var("DATABASE_URL") {
+            Ok(url) => url,
+            Err(_) if cfg!(feature = "database-sqlite") => "sqlite::memory:".to_string(),
+            Err(_) => return None,
+        };
+        // 单连接保证内存 SQLite 在各语句间共享同一个数据库
+        let config = DatabaseConfig {
+            max_connections: 1,
+            min_connections: 1,
+            connect_timeout_secs: 2,
+            ..DatabaseConfig::for_url(url)
+        };
+        let connection = SeaOrmConnection::new(config).await.ok()?;
+        let db = &connection.inner;
+        db.execute_unprepared(&format!("DROP TABLE IF EXISTS {}", table))
+            .await
+            .unwrap();
+        db.execute_unprepared(&format!("CREATE TABLE {} (id INT PRIMARY KEY)", table))
+            .await
+            .unwrap();
+        Some(connection)
+    }
+
+    async fn row_count(connection: &SeaOrmConnection, table: &str) -> i64 {
+        let db = &connection.inner;
+        let row = db
+            .query_one(Statement::from_string(
+                db.get_database_backend(),
+                format!("SELECT COUNT(*) AS n FROM {}", table),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        row.try_get("", "n").unwrap()
+    }
+
+    async fn insert(txn: &DatabaseTransaction, table: &str, id: i32) -> DatabaseResult<()> {
+        txn.execute_unprepared(&format!("INSERT INTO {} (id) VALUES ({})", table, id))
+            .await?;
+        Ok(())
+    }
+
+    /// 携带 SQLSTATE 的驱动错误
+    #[derive(Debug)]
+    struct SqlState(&'static str);
+
+    impl std::fmt::Display for SqlState {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "SQLSTATE {}", self.0)
+        }
+    }
+
+    impl std::error::Error for SqlState {}
+
+    impl sqlx::error::DatabaseError for SqlState {
+        fn message(&self) -> &str {
+            self.0
+        }
+
+        fn code(&self) -> Option<std::borrow::Cow<'_, str>> {
+            Some(self.0.into())
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            sqlx::error::ErrorKind::Other
+        }
+    }
+
+    fn driver_error(code: &'static str) -> DatabaseError {
+        DatabaseError::SeaOrm(DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(
+            Box::new(SqlState(code)),
+        ))))
+    }
+
+    fn deadlock() -> DatabaseError {
+        driver_error("40P01")
+    }
+
+    #[test]
+    fn test_retriable_error_detection() {
+        assert!(deadlock().is_retriable_transaction_error());
+        assert!(driver_error("40001").is_retriable_transaction_error());
+        // 唯一约束冲突不可重试
+        assert!(!driver_error("23505").is_retriable_transaction_error());
+
+        // 仅错误信息包含关键字或数字时不视为可重试
+        for message in [
+            "Deadlock found when trying to get lock",
+            "duplicate entry '1205' for key 'PRIMARY'",
+            "order 40001 not found",
+        ] {
+            assert!(
+                !DatabaseError::SeaOrm(DbErr::Custom(message.to_string()))
+                    .is_retriable_transaction_error()
+            );
+        }
+        assert!(!DatabaseError::transaction("database is locked").is_retriable_transaction_error());
+        assert!(!DatabaseError::query("deadlock").is_retriable_transaction_error());
+    }
+
+    #[test]
+    fn test_errors_map_to_transaction() {
+        let error = into_transaction_error(DatabaseError::SeaOrm(DbErr::Custom("boom".into())));
+        assert!(matches!(error, DatabaseError::Transaction { .. }));
+
+        let error = into_transaction_error(DatabaseError::entity_not_found("User", "1"));
+        assert!(error.is_not_found_error());
+    }
+
+    #[tokio::test]
+    async fn test_commit_and_rollback() {
+        let table = "clamber_transaction_commit_test";
+        let Some(connection) = test_connection(table).await else {
+            return;
+        };
+
+        let id = connection
+            .transaction(async |txn| {
+                insert(txn, table, 1).await?;
+                Ok(1)
+            })
+            .await
+            .unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(row_count(&connection, table).await, 1);
+
+        // 闭包返回错误时回滚已执行的写入
+        let error = connection
+            .transaction(async |txn| -> DatabaseResult<()> {
+                insert(txn, table, 2).await?;
+                Err(DatabaseError::query("业务校验失败"))
+            })
+            .await
+            .unwrap_err();
+        assert!(error.is_query_error());
+        assert_eq!(row_count(&connection, table).await, 1);
+
+        // SQL 错误转换为事务错误
+        let error = connection
+            .transaction(async |txn| insert(txn, table, 1).await)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, DatabaseError::Transaction { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_retry_reexecutes_from_scratch() {
+        let table = "clamber_transaction_retry_test";
+        let Some(connection) = test_connection(table).await else {
+            return;
+        };
+
+        let attempts = AtomicU32::new(0);
+        let options = TransactionOptions::default()
+            .isolation_level(IsolationLevel::Serializable)
+            .max_retries(2);
+        connection
+            .transaction_with(options.clone(), async |txn| {
+                insert(txn, table, 10).await?;
+                // 第一次执行模拟死锁，重试时上一轮的写入已被回滚，不会主键冲突
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(deadlock());
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(row_count(&connection, table).await, 1);
+
+        // 超过重试次数后返回事务错误
+        attempts.store(0, Ordering::SeqCst);
+        let error = connection
+            .transaction_with(options, async |_txn| -> DatabaseResult<()> {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(deadlock())
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert!(matches!(error, DatabaseError::Transaction { .. }));
+    }
+}
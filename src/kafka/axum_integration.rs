@@ -10,7 +10,7 @@ use tokio::time::timeout;
 use crate::kafka::OwnedMessage;
 use crate::kafka::kafka_config::{KafkaConsumerConfig, KafkaProducerConfig};
 use crate::kafka::kafka_consumer::KafkaConsumer;
-use crate::kafka::kafka_error::{KafkaError, KafkaResult};
+use crate::kafka::kafka_error::KafkaResult;
 use crate::kafka::kafka_producer::KafkaProducer;
 
 /// Axum 应用的 Kafka 状态
@@ -97,6 +97,16 @@ impl KafkaAppState {
         Ok(())
     }
 
+    /// 重建生产者客户端（发送时遇到连接错误也会自动重建一次）
+    pub async fn recreate_producer(&self) -> KafkaResult<()> {
+        self.producer.recreate()
+    }
+
+    /// 检查生产者能否连接 broker，返回可见的 broker 数量
+    pub async fn producer_health_check(&self) -> KafkaResult<usize> {
+        self.producer.health_check().await
+    }
+
     /// 获取生产者统计信息
     pub fn get_producer_stats(&self) -> KafkaResult<String> {
         self.producer.get_stats()
@@ -109,12 +119,36 @@ impl KafkaAppState {
     }
 }
 
+/// 偏移量提交策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommitStrategy {
+    /// 由消费者配置决定（`enable_auto_commit`），处理失败的消息不会重新投递
+    #[default]
+    Auto,
+    /// 每条消息处理成功后提交
+    AfterEachMessage,
+    /// 整批消息全部处理成功后提交
+    AfterBatch,
+}
+
+impl CommitStrategy {
+    /// 是否由轮询服务手动提交偏移量
+    pub fn is_manual(&self) -> bool {
+        !matches!(self, CommitStrategy::Auto)
+    }
+}
+
 /// 轮询消费者服务
+///
+/// 使用 [`CommitStrategy::AfterEachMessage`] 或 [`CommitStrategy::AfterBatch`] 时，
+/// 处理失败的消息不提交，其所在分区回退到失败位置（`AfterBatch` 时回退到整批开头），
+/// 下次轮询重新投递，实现至少一次语义。此时消费者需关闭自动提交（`enable_auto_commit: Some(false)`）
 pub struct PollingConsumerService {
     app_state: KafkaAppState,
     topics: Vec<String>,
     poll_interval: Duration,
     max_messages_per_poll: usize,
+    commit_strategy: CommitStrategy,
 }
 
 impl PollingConsumerService {
@@ -130,31 +164,29 @@ impl PollingConsumerService {
             topics,
             poll_interval,
             max_messages_per_poll,
+            commit_strategy: CommitStrategy::default(),
         }
     }
 
-    /// 开始轮询消费
+    /// 设置偏移量提交策略
+    pub fn with_commit_strategy(mut self, commit_strategy: CommitStrategy) -> Self {
+        self.commit_strategy = commit_strategy;
+        self
+    }
+
+    /// 开始轮询消费，手动提交时回退消费位置失败会返回错误
     pub async fn start_polling<F>(&self, message_handler: F) -> KafkaResult<()>
     where
         F: Fn(OwnedMessage) -> KafkaResult<()> + Send + Sync + 'static,
     {
-        // 订阅主题
-        let topic_refs: Vec<&str> = self.topics.iter().map(|s| s.as_str()).collect();
-        self.app_state.subscribe(&topic_refs).await?;
+        self.subscribe_topics().await?;
 
         println!("开始轮询消费主题: {:?}", self.topics);
 
         loop {
             // 轮询消息
             match self.app_state.poll_batch(self.max_messages_per_poll).await {
-                Ok(messages) => {
-                    for message in messages {
-                        if let Err(e) = message_handler(message) {
-                            eprintln!("处理消息失败: {}", e);
-                            // 可以选择继续处理或返回错误
-                        }
-                    }
-                }
+                Ok(messages) => self.handle_batch(messages, &message_handler).await?,
                 Err(e) => {
                     eprintln!("轮询消息失败: {}", e);
                     // 可以选择重试或返回错误
@@ -175,9 +207,7 @@ impl PollingConsumerService {
     where
         F: Fn(OwnedMessage) -> KafkaResult<()> + Send + Sync + 'static,
     {
-        // 订阅主题
-        let topic_refs: Vec<&str> = self.topics.iter().map(|s| s.as_str()).collect();
-        self.app_state.subscribe(&topic_refs).await?;
+        self.subscribe_topics().await?;
 
         println!(
             "开始轮询消费主题: {:?} (超时: {:?})",
@@ -192,13 +222,7 @@ impl PollingConsumerService {
             )
             .await
             {
-                Ok(Ok(messages)) => {
-                    for message in messages {
-                        if let Err(e) = message_handler(message) {
-                            eprintln!("处理消息失败: {}", e);
-                        }
-                    }
-                }
+                Ok(Ok(messages)) => self.handle_batch(messages, &message_handler).await?,
                 Ok(Err(e)) => {
                     eprintln!("轮询消息失败: {}", e);
                 }
@@ -211,6 +235,83 @@ impl PollingConsumerService {
             tokio::time::sleep(self.poll_interval).await;
         }
     }
+
+    /// 订阅主题，并检查提交策略与消费者配置是否冲突
+    async fn subscribe_topics(&self) -> KafkaResult<()> {
+        if self.commit_strategy.is_manual()
+            && self
+                .app_state
+                .consumer_config
+                .enable_auto_commit
+                .unwrap_or(true)
+        {
+            tracing::warn!(
+                "提交策略为 {:?} 但消费者启用了自动提交，处理失败的消息可能不会重新投递",
+                self.commit_strategy
+            );
+        }
+
+        let topic_refs: Vec<&str> = self.topics.iter().map(|s| s.as_str()).collect();
+        self.app_state.subscribe(&topic_refs).await
+    }
+
+    /// 按提交策略处理一批消息
+    ///
+    /// 回退消费位置失败时返回错误并停止轮询，否则之后的提交会越过处理失败的消息
+    async fn handle_batch<F>(
+        &self,
+        messages: Vec<OwnedMessage>,
+        message_handler: &F,
+    ) -> KafkaResult<()>
+    where
+        F: Fn(OwnedMessage) -> KafkaResult<()>,
+    {
+        match self.commit_strategy {
+            CommitStrategy::Auto => {
+                for message in messages {
+                    if let Err(e) = message_handler(message) {
+                        tracing::warn!("处理消息失败: {}", e);
+                    }
+                }
+            }
+            CommitStrategy::AfterEachMessage => {
+                for (index, message) in messages.iter().enumerate() {
+                    if let Err(e) = message_handler(message.clone()) {
+                        tracing::warn!("处理消息失败，回退后重新投递: {}", e);
+                        // 回退失败消息及其后尚未处理的消息
+                        return self.rewind(&messages[index..]).await;
+                    }
+                    self.commit(std::slice::from_ref(message)).await;
+                }
+            }
+            CommitStrategy::AfterBatch => {
+                for message in &messages {
+                    if let Err(e) = message_handler(message.clone()) {
+                        tracing::warn!("处理消息失败，整批回退后重新投递: {}", e);
+                        return self.rewind(&messages).await;
+                    }
+                }
+                self.commit(&messages).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// 提交消息偏移量，失败时仅记录（下次提交会覆盖）
+    async fn commit(&self, messages: &[OwnedMessage]) {
+        let consumer = self.app_state.consumer.read().await;
+        if let Err(e) = consumer.commit_messages(messages) {
+            tracing::warn!("提交偏移量失败: {}", e);
+        }
+    }
+
+    /// 回退消费位置，使消息重新投递
+    async fn rewind(&self, messages: &[OwnedMessage]) -> KafkaResult<()> {
+        let consumer = self.app_state.consumer.read().await;
+        consumer.rewind(messages).inspect_err(|e| {
+            tracing::error!("回退消费位置失败，停止轮询: {}", e);
+        })
+    }
 }
 
 /// 便捷函数：创建默认的 Kafka AppState
@@ -233,17 +334,8 @@ pub async fn create_kafka_app_state_from_config(
     producer_config_path: &str,
     consumer_config_path: &str,
 ) -> KafkaResult<KafkaAppState> {
-    let producer_config_content = std::fs::read_to_string(producer_config_path)
-        .map_err(|e| KafkaError::ConfigError(format!("读取生产者配置文件失败: {}", e)))?;
-
-    let consumer_config_content = std::fs::read_to_string(consumer_config_path)
-        .map_err(|e| KafkaError::ConfigError(format!("读取消费者配置文件失败: {}", e)))?;
-
-    let producer_config: KafkaProducerConfig = serde_yaml::from_str(&producer_config_content)
-        .map_err(|e| KafkaError::ConfigError(format!("解析生产者配置文件失败: {}", e)))?;
-
-    let consumer_config: KafkaConsumerConfig = serde_yaml::from_str(&consumer_config_content)
-        .map_err(|e| KafkaError::ConfigError(format!("解析消费者配置文件失败: {}", e)))?;
+    let producer_config = KafkaProducerConfig::from_yaml_file(producer_config_path)?;
+    let consumer_config = KafkaConsumerConfig::from_yaml_file(consumer_config_path)?;
 
     KafkaAppState::new(producer_config, consumer_config).await
 }
@@ -278,6 +370,72 @@ mod tests {
             assert_eq!(service.topics, vec!["test-topic"]);
             assert_eq!(service.poll_interval, Duration::from_secs(1));
             assert_eq!(service.max_messages_per_poll, 10);
+            assert_eq!(service.commit_strategy, CommitStrategy::Auto);
+
+            let service = service.with_commit_strategy(CommitStrategy::AfterBatch);
+            assert_eq!(service.commit_strategy, CommitStrategy::AfterBatch);
         }
     }
+
+    #[tokio::test]
+    async fn test_failed_message_is_redelivered() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // 仅在设置了 KAFKA_BOOTSTRAP_SERVERS 时运行，需要实际的 Kafka 服务器
+        let Ok(servers) = std::env::var("KAFKA_BOOTSTRAP_SERVERS") else {
+            return;
+        };
+        let topic = "clamber-commit-strategy-test";
+
+        let mut producer_config = KafkaProducerConfig::default();
+        producer_config.base.bootstrap_servers = vec![servers.clone()];
+        let mut consumer_config = KafkaConsumerConfig::default();
+        consumer_config.base.bootstrap_servers = vec![servers];
+        consumer_config.group_id = format!("clamber-commit-strategy-{}", std::process::id());
+        consumer_config.auto_offset_reset = Some("earliest".to_string());
+        consumer_config.enable_auto_commit = Some(false);
+        let app_state = KafkaAppState::new(producer_config, consumer_config)
+            .await
+            .unwrap();
+        app_state
+            .send_message(topic, None, "redeliver-me")
+            .await
+            .unwrap();
+
+        let service = PollingConsumerService::new(
+            app_state,
+            vec![topic.to_string()],
+            Duration::from_millis(100),
+            10,
+        )
+        .with_commit_strategy(CommitStrategy::AfterEachMessage);
+
+        // 第一次处理失败，重新投递后成功
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let (done_tx, mut done_rx) = tokio::sync::mpsc::unbounded_channel();
+        let handler_attempts = attempts.clone();
+        let handler = move |message: OwnedMessage| -> KafkaResult<()> {
+            use rdkafka::Message;
+
+            if message.payload() != Some(b"redeliver-me".as_slice()) {
+                return Ok(());
+            }
+            if handler_attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(crate::kafka::KafkaError::ConsumerError(
+                    "首次处理失败".to_string(),
+                ));
+            }
+            let _ = done_tx.send(message.offset());
+            Ok(())
+        };
+        let task = tokio::spawn(async move { service.start_polling(handler).await });
+
+        let offset = timeout(Duration::from_secs(60), done_rx.recv())
+            .await
+            .expect("消息未被重新投递")
+            .unwrap();
+        task.abort();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(offset >= 0);
+    }
 }
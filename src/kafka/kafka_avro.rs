@@ -0,0 +1,352 @@
+//! Avro 序列化模块
+//!
+//! 基于 Confluent Schema Registry 的 Avro 编解码，消息格式与 JVM 生产者/消费者兼容：
+//! 1 字节魔数 `0` + 4 字节大端 schema ID + Avro 二进制数据。
+//! 发送时以 `{topic}-value` 为 subject 注册 schema（TopicNameStrategy），
+//! 注册结果与按 ID 查询到的 schema 都会缓存在客户端中
+
+use apache_avro::Schema;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::kafka::kafka_config::KafkaBaseConfig;
+use crate::kafka::kafka_error::{KafkaError, KafkaResult};
+
+/// Confluent 消息格式的魔数
+pub const MAGIC_BYTE: u8 = 0;
+
+/// 消息头长度：魔数 + schema ID
+const HEADER_LEN: usize = 5;
+
+/// Confluent Schema Registry 客户端
+#[derive(Debug)]
+pub struct SchemaRegistryClient {
+    base_url: String,
+    http: reqwest::Client,
+    /// schema ID -> schema
+    schemas: RwLock<HashMap<u32, Arc<Schema>>>,
+    /// (subject, 规范形式的 schema) -> schema ID
+    registered: RwLock<HashMap<(String, String), u32>>,
+}
+
+#[derive(serde::Deserialize)]
+struct RegisterResponse {
+    id: u32,
+}
+
+#[derive(serde::Deserialize)]
+struct SchemaResponse {
+    schema: String,
+}
+
+impl SchemaRegistryClient {
+    /// 创建客户端，`base_url` 如 `http://localhost:8081`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            schemas: RwLock::new(HashMap::new()),
+            registered: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 根据 `schema_registry_url` 创建客户端，未配置时返回 None
+    pub fn from_config(config: &KafkaBaseConfig) -> Option<Self> {
+        config.schema_registry_url.as_deref().map(Self::new)
+    }
+
+    /// 在 `subject` 下注册 schema 并返回 ID；schema 已存在时 Registry 返回已有 ID
+    pub async fn register(&self, subject: &str, schema: &Schema) -> KafkaResult<u32> {
+        let canonical = schema.canonical_form();
+        let cache_key = (subject.to_string(), canonical);
+        if let Some(id) = self.registered.read().unwrap().get(&cache_key) {
+            return Ok(*id);
+        }
+
+        let url = format!("{}/subjects/{}/versions", self.base_url, subject);
+        let response = self
+            .http
+            .post(&url)
+            .json(&serde_json::json!({ "schema": cache_key.1 }))
+            .send()
+            .await
+            .map_err(registry_unreachable)?;
+        let response = check_status(response)
+            .await
+            .map_err(KafkaError::SerializationError)?;
+        let RegisterResponse { id } = response.json().await.map_err(|e| {
+            KafkaError::SerializationError(format!("解析 Schema Registry 响应失败: {}", e))
+        })?;
+
+        self.schemas
+            .write()
+            .unwrap()
+            .insert(id, Arc::new(schema.clone()));
+        self.registered.write().unwrap().insert(cache_key, id);
+        Ok(id)
+    }
+
+    /// 按 ID 获取 schema
+    pub async fn schema(&self, id: u32) -> KafkaResult<Arc<Schema>> {
+        if let Some(schema) = self.schemas.read().unwrap().get(&id) {
+            return Ok(schema.clone());
+        }
+
+        let url = format!("{}/schemas/ids/{}", self.base_url, id);
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .map_err(registry_unreachable)?;
+        let response = check_status(response)
+            .await
+            .map_err(KafkaError::DeserializationError)?;
+        let SchemaResponse { schema } = response.json().await.map_err(|e| {
+            KafkaError::DeserializationError(format!("解析 Schema Registry 响应失败: {}", e))
+        })?;
+        let schema =
+            Arc::new(Schema::parse_str(&schema).map_err(|e| {
+                KafkaError::DeserializationError(format!("schema {} 无效: {}", id, e))
+            })?);
+
+        self.schemas.write().unwrap().insert(id, schema.clone());
+        Ok(schema)
+    }
+
+    /// 按 `schema`（Avro JSON 定义）序列化 `data`，schema 注册在 `{topic}-value` 下
+    pub async fn serialize<T: Serialize>(
+        &self,
+        topic: &str,
+        data: &T,
+        schema: &str,
+    ) -> KafkaResult<Vec<u8>> {
+        let schema = Schema::parse_str(schema)
+            .map_err(|e| KafkaError::SerializationError(format!("Avro schema 无效: {}", e)))?;
+        let id = self.register(&format!("{}-value", topic), &schema).await?;
+        encode_avro(&schema, id, data)
+    }
+
+    /// 按消息头中的 schema ID 反序列化 Confluent 格式的负载
+    pub async fn deserialize<T: DeserializeOwned>(&self, payload: &[u8]) -> KafkaResult<T> {
+        let (id, body) = split_wire_format(payload)?;
+        let schema = self.schema(id).await?;
+        decode_avro(&schema, body)
+    }
+}
+
+/// 将 `data` 编码为 Confluent 格式：魔数 + schema ID + Avro 二进制数据
+pub fn encode_avro<T: Serialize>(
+    schema: &Schema,
+    schema_id: u32,
+    data: &T,
+) -> KafkaResult<Vec<u8>> {
+    let serialize_error = |e: apache_avro::Error| KafkaError::SerializationError(e.to_string());
+    let value = apache_avro::to_value(data)
+        .and_then(|value| value.resolve(schema))
+        .map_err(serialize_error)?;
+    let body = apache_avro::to_avro_datum(schema, value).map_err(serialize_error)?;
+
+    let mut payload = Vec::with_capacity(HEADER_LEN + body.len());
+    payload.push(MAGIC_BYTE);
+    payload.extend_from_slice(&schema_id.to_be_bytes());
+    payload.extend_from_slice(&body);
+    Ok(payload)
+}
+
+/// 按 `schema` 解码 Avro 二进制数据（不含 Confluent 消息头）
+pub fn decode_avro<T: DeserializeOwned>(schema: &Schema, body: &[u8]) -> KafkaResult<T> {
+    let deserialize_error = |e: apache_avro::Error| KafkaError::DeserializationError(e.to_string());
+    let value =
+        apache_avro::from_avro_datum(schema, &mut &body[..], None).map_err(deserialize_error)?;
+    apache_avro::from_value(&value).map_err(deserialize_error)
+}
+
+/// 拆分 Confluent 格式的负载，返回 (schema ID, Avro 数据)
+pub fn split_wire_format(payload: &[u8]) -> KafkaResult<(u32, &[u8])> {
+    if payload.len() < HEADER_LEN {
+        return Err(KafkaError::DeserializationError(format!(
+            "Avro 消息长度 {} 小于消息头长度 {}",
+            payload.len(),
+            HEADER_LEN
+        )));
+    }
+    if payload[0] != MAGIC_BYTE {
+        return Err(KafkaError::DeserializationError(format!(
+            "Avro 消息魔数无效: {}",
+            payload[0]
+        )));
+    }
+    let id = u32::from_be_bytes([payload[1], payload[2], payload[3], payload[4]]);
+    Ok((id, &payload[HEADER_LEN..]))
+}
+
+fn registry_unreachable(error: reqwest::Error) -> KafkaError {
+    KafkaError::ConnectionError(format!("访问 Schema Registry 失败: {}", error))
+}
+
+/// 非 2xx 响应转换为包含状态码与响应体的错误信息
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(format!("Schema Registry 返回 {}: {}", status, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::{Path, State};
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde::Deserialize;
+    use std::sync::Mutex;
+
+    const USER_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "UserEvent",
+        "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "name", "type": "string"}
+        ]
+    }"#;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct UserEvent {
+        id: i64,
+        name: String,
+    }
+
+    /// 内存中的 Schema Registry：记录注册请求，schema ID 从 1 开始分配
+    #[derive(Clone, Default)]
+    struct MockRegistry {
+        schemas: Arc<Mutex<Vec<String>>>,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    async fn register(
+        State(registry): State<MockRegistry>,
+        Path(subject): Path<String>,
+        Json(body): Json<serde_json::Value>,
+    ) -> Json<serde_json::Value> {
+        registry
+            .requests
+            .lock()
+            .unwrap()
+            .push(format!("POST {}", subject));
+        let schema = body["schema"].as_str().unwrap().to_string();
+        let mut schemas = registry.schemas.lock().unwrap();
+        let id = match schemas.iter().position(|s| *s == schema) {
+            Some(index) => index + 1,
+            None => {
+                schemas.push(schema);
+                schemas.len()
+            }
+        };
+        Json(serde_json::json!({ "id": id }))
+    }
+
+    async fn schema_by_id(
+        State(registry): State<MockRegistry>,
+        Path(id): Path<usize>,
+    ) -> Result<Json<serde_json::Value>, axum::http::StatusCode> {
+        registry
+            .requests
+            .lock()
+            .unwrap()
+            .push(format!("GET {}", id));
+        let schemas = registry.schemas.lock().unwrap();
+        let schema = schemas
+            .get(id.wrapping_sub(1))
+            .ok_or(axum::http::StatusCode::NOT_FOUND)?;
+        Ok(Json(serde_json::json!({ "schema": schema })))
+    }
+
+    async fn start_mock_registry() -> (String, MockRegistry) {
+        let registry = MockRegistry::default();
+        let app = Router::new()
+            .route("/subjects/{subject}/versions", post(register))
+            .route("/schemas/ids/{id}", get(schema_by_id))
+            .with_state(registry.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, registry)
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_through_registry() {
+        let (url, registry) = start_mock_registry().await;
+        let producer_side = SchemaRegistryClient::new(format!("{}/", url));
+        let event = UserEvent {
+            id: 42,
+            name: "alice".to_string(),
+        };
+
+        let payload = producer_side
+            .serialize("user-events", &event, USER_SCHEMA)
+            .await
+            .unwrap();
+        assert_eq!(&payload[..HEADER_LEN], &[MAGIC_BYTE, 0, 0, 0, 1]);
+
+        // 注册结果被缓存，第二次发送不再请求 Registry
+        producer_side
+            .serialize("user-events", &event, USER_SCHEMA)
+            .await
+            .unwrap();
+        assert_eq!(
+            *registry.requests.lock().unwrap(),
+            ["POST user-events-value"]
+        );
+
+        // 另一端按消息头中的 ID 获取 schema 并解码
+        let consumer_side = SchemaRegistryClient::new(url);
+        let decoded: UserEvent = consumer_side.deserialize(&payload).await.unwrap();
+        assert_eq!(decoded, event);
+        let _: UserEvent = consumer_side.deserialize(&payload).await.unwrap();
+        assert_eq!(
+            *registry.requests.lock().unwrap(),
+            ["POST user-events-value", "GET 1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_schema_id() {
+        let (url, _registry) = start_mock_registry().await;
+        let client = SchemaRegistryClient::new(url);
+
+        let error = client
+            .deserialize::<UserEvent>(&[MAGIC_BYTE, 0, 0, 0, 9, 0])
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, KafkaError::DeserializationError(ref msg) if msg.contains("404")),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let schema = Schema::parse_str(USER_SCHEMA).unwrap();
+        let event = UserEvent {
+            id: 7,
+            name: "bob".to_string(),
+        };
+        let payload = encode_avro(&schema, 0x0102_0304, &event).unwrap();
+        let (id, body) = split_wire_format(&payload).unwrap();
+        assert_eq!(id, 0x0102_0304);
+        assert_eq!(decode_avro::<UserEvent>(&schema, body).unwrap(), event);
+
+        assert!(split_wire_format(&[MAGIC_BYTE, 0, 0]).is_err());
+        assert!(split_wire_format(&[1, 0, 0, 0, 1, 0]).is_err());
+
+        // 数据与 schema 不匹配
+        let error = encode_avro(&schema, 1, &serde_json::json!({ "id": "x" })).unwrap_err();
+        assert!(matches!(error, KafkaError::SerializationError(_)));
+    }
+}
@@ -0,0 +1,178 @@
+//! Kafka 消费通道模块
+//!
+//! [`ConsumerChannel`] 在后台任务中轮询消费者，把消息写入有界通道，由 Axum 处理函数或异步 worker 读取。
+//! 通道已满时暂停拉取消息（背压），不会丢弃消息
+
+use rdkafka::message::OwnedMessage;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::kafka::axum_integration::KafkaAppState;
+use crate::kafka::kafka_error::{KafkaError, KafkaResult};
+
+/// 单次拉取的超时时间，超时后释放消费者的读锁，避免长期阻塞订阅或重建消费者
+const POLL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 拉取失败后的等待时间
+const POLL_ERROR_BACKOFF: Duration = Duration::from_millis(500);
+
+/// 从消费者到有界通道的桥接
+pub struct ConsumerChannel {
+    receiver: mpsc::Receiver<OwnedMessage>,
+    handle: JoinHandle<()>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl ConsumerChannel {
+    /// 订阅主题并在后台轮询，消息写入容量为 `capacity` 的通道
+    pub async fn start(
+        app_state: &KafkaAppState,
+        topics: &[&str],
+        capacity: usize,
+    ) -> KafkaResult<Self> {
+        if capacity == 0 {
+            return Err(KafkaError::ConfigError("通道容量必须大于 0".to_string()));
+        }
+        app_state.subscribe(topics).await?;
+
+        let consumer = app_state.consumer.clone();
+        Ok(Self::spawn(capacity, move || {
+            let consumer = consumer.clone();
+            async move {
+                consumer
+                    .read()
+                    .await
+                    .consume_message_with_timeout(POLL_TIMEOUT)
+                    .await
+            }
+        }))
+    }
+
+    /// 启动轮询任务，`poll` 每次拉取至多一条消息
+    fn spawn<P, Fut>(capacity: usize, mut poll: P) -> Self
+    where
+        P: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = KafkaResult<Option<OwnedMessage>>> + Send,
+    {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let (shutdown, mut shutdown_rx) = watch::channel(false);
+
+        let handle = tokio::spawn(async move {
+            loop {
+                // 先占用通道空位再拉取，通道已满时不再从 broker 取消息
+                let permit = tokio::select! {
+                    _ = shutdown_rx.changed() => break,
+                    permit = sender.reserve() => match permit {
+                        Ok(permit) => permit,
+                        // 接收端已关闭
+                        Err(_) => break,
+                    },
+                };
+
+                let result = tokio::select! {
+                    _ = shutdown_rx.changed() => break,
+                    result = poll() => result,
+                };
+                match result {
+                    Ok(Some(message)) => permit.send(message),
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::warn!("轮询消息失败: {}", e);
+                        tokio::time::sleep(POLL_ERROR_BACKOFF).await;
+                    }
+                }
+            }
+        });
+
+        Self {
+            receiver,
+            handle,
+            shutdown,
+        }
+    }
+
+    /// 接收下一条消息，轮询任务已停止且通道为空时返回 None
+    pub async fn recv(&mut self) -> Option<OwnedMessage> {
+        self.receiver.recv().await
+    }
+
+    /// 通道的接收端
+    pub fn receiver(&mut self) -> &mut mpsc::Receiver<OwnedMessage> {
+        &mut self.receiver
+    }
+
+    /// 通知轮询任务停止，并等待其退出
+    pub async fn shutdown(self) -> KafkaResult<()> {
+        let _ = self.shutdown.send(true);
+        self.handle
+            .await
+            .map_err(|e| KafkaError::InternalError(format!("轮询任务异常退出: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn test_message(offset: i64) -> OwnedMessage {
+        OwnedMessage::new(
+            Some(b"payload".to_vec()),
+            None,
+            "orders".to_string(),
+            rdkafka::message::Timestamp::NotAvailable,
+            0,
+            offset,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_full_channel_pauses_polling() {
+        use rdkafka::Message;
+
+        let polls = Arc::new(AtomicUsize::new(0));
+        let counter = polls.clone();
+        let mut channel = ConsumerChannel::spawn(2, move || {
+            let offset = counter.fetch_add(1, Ordering::SeqCst) as i64;
+            async move { Ok(Some(test_message(offset))) }
+        });
+
+        // 通道已满后不再拉取
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(polls.load(Ordering::SeqCst), 2);
+
+        // 取出一条后恢复拉取，消息按顺序到达
+        assert_eq!(channel.recv().await.unwrap().offset(), 0);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(polls.load(Ordering::SeqCst), 3);
+        assert_eq!(channel.receiver().recv().await.unwrap().offset(), 1);
+        assert_eq!(channel.recv().await.unwrap().offset(), 2);
+
+        channel.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_poll_errors_do_not_stop_channel() {
+        let polls = Arc::new(AtomicUsize::new(0));
+        let counter = polls.clone();
+        let mut channel = ConsumerChannel::spawn(1, move || {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match attempt {
+                    0 => Err(KafkaError::ReceiveError("broker 不可用".to_string())),
+                    1 => Ok(None),
+                    _ => Ok(Some(test_message(7))),
+                }
+            }
+        });
+
+        let message = tokio::time::timeout(Duration::from_secs(5), channel.recv())
+            .await
+            .unwrap();
+        assert!(message.is_some());
+        channel.shutdown().await.unwrap();
+    }
+}
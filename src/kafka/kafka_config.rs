@@ -2,10 +2,13 @@
 //!
 //! 提供 Kafka 生产者和消费者的配置管理
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 
-use crate::kafka::kafka_error::KafkaResult;
+use crate::env_interpolation::expand_env_vars_with;
+use crate::kafka::kafka_error::{KafkaError, KafkaResult};
 
 /// Kafka 基础配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +37,8 @@ pub struct KafkaBaseConfig {
     pub request_timeout_ms: Option<u64>,
     /// 自定义配置参数
     pub custom_configs: Option<HashMap<String, String>>,
+    /// Confluent Schema Registry 地址（如 `http://localhost:8081`），用于 Avro 消息（需要 `kafka-avro` feature）
+    pub schema_registry_url: Option<String>,
 }
 
 impl Default for KafkaBaseConfig {
@@ -51,6 +56,7 @@ impl Default for KafkaBaseConfig {
             connection_timeout_ms: Some(30000),
             request_timeout_ms: Some(30000),
             custom_configs: None,
+            schema_registry_url: None,
         }
     }
 }
@@ -164,8 +170,107 @@ impl Default for KafkaConsumerConfig {
 }
 
 impl KafkaBaseConfig {
+    /// 不加密、不认证的连接配置，其余字段使用默认值
+    pub fn plaintext<S: Into<String>>(servers: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            bootstrap_servers: servers.into_iter().map(Into::into).collect(),
+            security_protocol: Some("PLAINTEXT".to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// TLS 加密连接配置，需要自定义 CA 或客户端证书时再设置 `ssl_*` 字段
+    pub fn ssl<S: Into<String>>(servers: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            security_protocol: Some("SSL".to_string()),
+            ..Self::plaintext(servers)
+        }
+    }
+
+    /// TLS 加密并使用 SASL 认证的连接配置
+    ///
+    /// `mechanism` 为 PLAIN、SCRAM-SHA-256 或 SCRAM-SHA-512
+    pub fn sasl_ssl<S: Into<String>>(
+        servers: impl IntoIterator<Item = S>,
+        mechanism: impl Into<String>,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            security_protocol: Some("SASL_SSL".to_string()),
+            sasl_mechanism: Some(mechanism.into()),
+            sasl_username: Some(username.into()),
+            sasl_password: Some(password.into()),
+            ..Self::plaintext(servers)
+        }
+    }
+
+    /// 安全协议是否需要 SASL 认证
+    fn uses_sasl(&self) -> bool {
+        self.security_protocol
+            .as_deref()
+            .is_some_and(|protocol| protocol.to_ascii_lowercase().starts_with("sasl_"))
+    }
+
+    /// 检查安全协议与 SASL、SSL 字段是否匹配
+    fn validate_security(&self) -> Result<(), String> {
+        if self.uses_sasl() {
+            let missing: Vec<&str> = [
+                ("sasl_mechanism", &self.sasl_mechanism),
+                ("sasl_username", &self.sasl_username),
+                ("sasl_password", &self.sasl_password),
+            ]
+            .into_iter()
+            .filter(|(_, value)| value.as_deref().is_none_or(|value| value.trim().is_empty()))
+            .map(|(field, _)| field)
+            .collect();
+            if !missing.is_empty() {
+                return Err(format!(
+                    "安全协议 {} 需要配置 {}",
+                    self.security_protocol.as_deref().unwrap_or_default(),
+                    missing.join("、")
+                ));
+            }
+        }
+        if self.ssl_certificate_location.is_some() != self.ssl_key_location.is_some() {
+            return Err("ssl_certificate_location 与 ssl_key_location 必须同时配置".to_string());
+        }
+        Ok(())
+    }
+
+    /// 验证配置的有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bootstrap_servers.is_empty() {
+            return Err("Kafka 服务器地址列表不能为空".to_string());
+        }
+        if self
+            .bootstrap_servers
+            .iter()
+            .any(|server| server.trim().is_empty())
+        {
+            return Err("Kafka 服务器地址不能为空字符串".to_string());
+        }
+        if self.sasl_mechanism.is_some()
+            && (self.sasl_username.is_none() || self.sasl_password.is_none())
+        {
+            return Err("配置了 SASL 机制时必须同时配置用户名和密码".to_string());
+        }
+        if let Some(protocol) = &self.security_protocol {
+            if !["plaintext", "ssl", "sasl_plaintext", "sasl_ssl"]
+                .contains(&protocol.to_ascii_lowercase().as_str())
+            {
+                return Err(format!("不支持的安全协议: {}", protocol));
+            }
+        }
+        self.validate_security()
+    }
+
     /// 转换为 rdkafka 客户端配置
+    ///
+    /// 安全协议需要 SASL 但缺少机制、用户名或密码时返回 [`KafkaError::ConfigError`]，
+    /// 避免 librdkafka 在连接时才报出难以理解的错误
     pub fn to_client_config(&self) -> KafkaResult<rdkafka::ClientConfig> {
+        self.validate_security().map_err(KafkaError::ConfigError)?;
         let mut config = rdkafka::ClientConfig::new();
 
         // 设置基础配置
@@ -211,18 +316,68 @@ impl KafkaBaseConfig {
             config.set("request.timeout.ms", timeout.to_string());
         }
 
-        // 设置自定义配置
+        self.apply_custom_configs(&mut config);
+        Ok(config)
+    }
+
+    /// 设置任意 librdkafka 配置项，写入 `custom_configs`
+    pub fn with_property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.custom_configs
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// 批量设置 librdkafka 配置项，写入 `custom_configs`
+    pub fn with_properties<K, V>(self, properties: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        properties.into_iter().fold(self, |config, (key, value)| {
+            config.with_property(key, value)
+        })
+    }
+
+    /// 写入自定义配置，自定义配置覆盖同名的显式字段
+    fn apply_custom_configs(&self, config: &mut rdkafka::ClientConfig) {
         if let Some(custom_configs) = &self.custom_configs {
             for (key, value) in custom_configs {
                 config.set(key, value);
             }
         }
-
-        Ok(config)
     }
 }
 
 impl KafkaProducerConfig {
+    /// 从 YAML 字符串加载配置，支持 `${ENV_VAR}` 占位符
+    pub fn from_yaml_str(yaml: &str) -> KafkaResult<Self> {
+        parse_yaml(yaml)
+    }
+
+    /// 从 YAML 文件加载配置，支持 `${ENV_VAR}` 占位符
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> KafkaResult<Self> {
+        parse_yaml(&read_config_file(path.as_ref())?)
+    }
+
+    /// 验证配置的有效性
+    pub fn validate(&self) -> Result<(), String> {
+        self.base.validate()?;
+        match self.acks.as_deref() {
+            None | Some("0" | "1" | "-1" | "all") => {}
+            Some(acks) => return Err(format!("不支持的 acks 配置: {}", acks)),
+        }
+        if self.enable_idempotence == Some(true)
+            && !matches!(self.acks.as_deref(), None | Some("-1" | "all"))
+        {
+            return Err("启用幂等性时 acks 必须为 all".to_string());
+        }
+        match self.compression_type.as_deref() {
+            None | Some("none" | "gzip" | "snappy" | "lz4" | "zstd") => Ok(()),
+            Some(compression) => Err(format!("不支持的压缩类型: {}", compression)),
+        }
+    }
+
     /// 转换为 rdkafka 客户端配置（用于生产者）
     pub fn to_producer_config(&self) -> KafkaResult<rdkafka::ClientConfig> {
         let mut config = self.base.to_client_config()?;
@@ -276,11 +431,50 @@ impl KafkaProducerConfig {
             config.set("transaction.timeout.ms", timeout.to_string());
         }
 
+        self.base.apply_custom_configs(&mut config);
         Ok(config)
     }
+
+    /// 设置任意 librdkafka 配置项，优先级高于显式字段
+    pub fn with_property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.base = self.base.with_property(key, value);
+        self
+    }
+
+    /// 批量设置 librdkafka 配置项，优先级高于显式字段
+    pub fn with_properties<K, V>(mut self, properties: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.base = self.base.with_properties(properties);
+        self
+    }
 }
 
 impl KafkaConsumerConfig {
+    /// 从 YAML 字符串加载配置，支持 `${ENV_VAR}` 占位符
+    pub fn from_yaml_str(yaml: &str) -> KafkaResult<Self> {
+        parse_yaml(yaml)
+    }
+
+    /// 从 YAML 文件加载配置，支持 `${ENV_VAR}` 占位符
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> KafkaResult<Self> {
+        parse_yaml(&read_config_file(path.as_ref())?)
+    }
+
+    /// 验证配置的有效性
+    pub fn validate(&self) -> Result<(), String> {
+        self.base.validate()?;
+        if self.group_id.trim().is_empty() {
+            return Err("消费者组ID不能为空".to_string());
+        }
+        match self.auto_offset_reset.as_deref() {
+            None | Some("earliest" | "latest" | "none") => Ok(()),
+            Some(reset) => Err(format!("不支持的偏移量重置策略: {}", reset)),
+        }
+    }
+
     /// 转换为 rdkafka 客户端配置（用于消费者）
     pub fn to_consumer_config(&self) -> KafkaResult<rdkafka::ClientConfig> {
         let mut config = self.base.to_client_config()?;
@@ -340,8 +534,47 @@ impl KafkaConsumerConfig {
             config.set("isolation.level", isolation);
         }
 
+        self.base.apply_custom_configs(&mut config);
         Ok(config)
     }
+
+    /// 设置任意 librdkafka 配置项，优先级高于显式字段
+    pub fn with_property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.base = self.base.with_property(key, value);
+        self
+    }
+
+    /// 批量设置 librdkafka 配置项，优先级高于显式字段
+    pub fn with_properties<K, V>(mut self, properties: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.base = self.base.with_properties(properties);
+        self
+    }
+}
+
+/// 读取配置文件
+fn read_config_file(path: &Path) -> KafkaResult<String> {
+    std::fs::read_to_string(path).map_err(|e| {
+        KafkaError::ConfigError(format!("读取配置文件 {} 失败: {}", path.display(), e))
+    })
+}
+
+/// 展开环境变量占位符后解析 YAML 配置
+fn parse_yaml<T: DeserializeOwned>(yaml: &str) -> KafkaResult<T> {
+    parse_yaml_with(yaml, |name| std::env::var(name).ok())
+}
+
+/// 使用指定的查找函数展开占位符后解析 YAML 配置
+fn parse_yaml_with<T: DeserializeOwned>(
+    yaml: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> KafkaResult<T> {
+    let yaml = expand_env_vars_with(yaml, lookup).map_err(KafkaError::ConfigError)?;
+    serde_yaml::from_str(&yaml)
+        .map_err(|e| KafkaError::ConfigError(format!("解析配置文件失败: {}", e)))
 }
 
 #[cfg(test)]
@@ -370,4 +603,166 @@ mod tests {
         let deserialized: KafkaProducerConfig = serde_json::from_str(&serialized).unwrap();
         assert_eq!(producer_config.acks, deserialized.acks);
     }
+
+    #[test]
+    fn test_yaml_env_interpolation() {
+        let yaml = "base:\n  bootstrap_servers: [\"localhost:9092\"]\n  sasl_mechanism: PLAIN\n  sasl_username: app\n  sasl_password: ${KAFKA_PW}\n";
+
+        let config: KafkaProducerConfig = parse_yaml_with(yaml, |name| {
+            (name == "KAFKA_PW").then(|| "kafka-secret".to_string())
+        })
+        .unwrap();
+        assert_eq!(config.base.sasl_password.as_deref(), Some("kafka-secret"));
+
+        let error = parse_yaml_with::<KafkaProducerConfig>(yaml, |_| None).unwrap_err();
+        assert!(matches!(error, KafkaError::ConfigError(message) if message.contains("KAFKA_PW")));
+    }
+
+    #[test]
+    fn test_with_property_overrides_explicit_fields() {
+        let producer = KafkaProducerConfig::default()
+            .with_property("socket.keepalive.enable", "true")
+            .with_properties([("acks", "all"), ("linger.ms", "25")]);
+        let config = producer.to_producer_config().unwrap();
+        assert_eq!(config.get("socket.keepalive.enable"), Some("true"));
+        // 自定义配置覆盖显式字段 acks = 1
+        assert_eq!(config.get("acks"), Some("all"));
+        assert_eq!(config.get("linger.ms"), Some("25"));
+
+        let consumer = KafkaConsumerConfig::default().with_property("group.id", "override-group");
+        let config = consumer.to_consumer_config().unwrap();
+        assert_eq!(config.get("group.id"), Some("override-group"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(KafkaProducerConfig::default().validate().is_ok());
+        assert!(KafkaConsumerConfig::default().validate().is_ok());
+
+        let mut producer = KafkaProducerConfig::default();
+        producer.acks = Some("2".to_string());
+        assert!(producer.validate().is_err());
+
+        let mut consumer = KafkaConsumerConfig::default();
+        consumer.base.bootstrap_servers.clear();
+        assert!(consumer.validate().is_err());
+
+        let mut consumer = KafkaConsumerConfig::default();
+        consumer.group_id = " ".to_string();
+        assert!(consumer.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_idempotence_without_acks_all() {
+        let mut producer = KafkaProducerConfig::default();
+        producer.enable_idempotence = Some(true);
+        producer.acks = Some("1".to_string());
+        let error = producer.validate().unwrap_err();
+        assert!(error.contains("acks"), "{}", error);
+
+        producer.acks = Some("all".to_string());
+        assert!(producer.validate().is_ok());
+        producer.acks = None;
+        assert!(producer.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_bootstrap_server() {
+        let mut producer = KafkaProducerConfig::default();
+        producer.base.bootstrap_servers = vec!["".to_string()];
+        assert!(producer.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_enum_values() {
+        let mut producer = KafkaProducerConfig::default();
+        producer.compression_type = Some("brotli".to_string());
+        let error = producer.validate().unwrap_err();
+        assert!(error.contains("brotli"), "{}", error);
+
+        let mut producer = KafkaProducerConfig::default();
+        producer.base.security_protocol = Some("tls".to_string());
+        let error = producer.validate().unwrap_err();
+        assert!(error.contains("tls"), "{}", error);
+
+        // 安全协议与 librdkafka 一样不区分大小写
+        producer.base = KafkaBaseConfig::sasl_ssl(["localhost:9092"], "PLAIN", "app", "secret");
+        producer.base.security_protocol = Some("sasl_ssl".to_string());
+        assert!(producer.validate().is_ok());
+
+        let mut consumer = KafkaConsumerConfig::default();
+        consumer.auto_offset_reset = Some("oldest".to_string());
+        let error = consumer.validate().unwrap_err();
+        assert!(error.contains("oldest"), "{}", error);
+    }
+
+    #[test]
+    fn test_security_presets() {
+        let config = KafkaBaseConfig::sasl_ssl(
+            ["broker1:9093", "broker2:9093"],
+            "SCRAM-SHA-512",
+            "app",
+            "secret",
+        )
+        .to_client_config()
+        .unwrap();
+        assert_eq!(
+            config.get("bootstrap.servers"),
+            Some("broker1:9093,broker2:9093")
+        );
+        assert_eq!(config.get("security.protocol"), Some("SASL_SSL"));
+        assert_eq!(config.get("sasl.mechanism"), Some("SCRAM-SHA-512"));
+        assert_eq!(config.get("sasl.username"), Some("app"));
+        assert_eq!(config.get("sasl.password"), Some("secret"));
+
+        let config = KafkaBaseConfig::plaintext(["localhost:9092"])
+            .to_client_config()
+            .unwrap();
+        assert_eq!(config.get("security.protocol"), Some("PLAINTEXT"));
+        assert_eq!(config.get("sasl.mechanism"), None);
+
+        let config = KafkaBaseConfig::ssl(["broker:9093"])
+            .to_client_config()
+            .unwrap();
+        assert_eq!(config.get("security.protocol"), Some("SSL"));
+    }
+
+    #[test]
+    fn test_sasl_protocol_requires_credentials() {
+        let mut base = KafkaBaseConfig::plaintext(["broker:9093"]);
+        base.security_protocol = Some("SASL_SSL".to_string());
+        let error = base.to_client_config().unwrap_err();
+        assert!(
+            matches!(&error, KafkaError::ConfigError(message) if message.contains("sasl_username")),
+            "{}",
+            error
+        );
+        assert!(base.validate().is_err());
+
+        // 缺少的字段都会列出，密码为空同样视为缺少
+        base.sasl_mechanism = Some("PLAIN".to_string());
+        base.sasl_username = Some("app".to_string());
+        base.sasl_password = Some(String::new());
+        let error = base.to_client_config().unwrap_err();
+        assert!(error.to_string().contains("sasl_password"), "{}", error);
+        assert!(!error.to_string().contains("sasl_username"), "{}", error);
+
+        // 生产者与消费者配置同样被拒绝
+        let producer = KafkaProducerConfig {
+            base: base.clone(),
+            ..KafkaProducerConfig::default()
+        };
+        assert!(producer.to_producer_config().is_err());
+        let consumer = KafkaConsumerConfig {
+            base,
+            ..KafkaConsumerConfig::default()
+        };
+        assert!(consumer.to_consumer_config().is_err());
+
+        let mut base = KafkaBaseConfig::ssl(["broker:9093"]);
+        base.ssl_certificate_location = Some("client.pem".to_string());
+        assert!(base.to_client_config().is_err());
+        base.ssl_key_location = Some("client.key".to_string());
+        assert!(base.to_client_config().is_ok());
+    }
 }
@@ -3,15 +3,21 @@
 //! 提供 Kafka 消息消费功能
 
 use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
-use rdkafka::message::{Message, OwnedMessage};
-use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::message::{Headers, Message, OwnedMessage};
+use rdkafka::topic_partition_list::{Offset, TopicPartitionList};
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tokio::time::timeout;
 
+#[cfg(feature = "kafka-avro")]
+use crate::kafka::kafka_avro::SchemaRegistryClient;
 use crate::kafka::kafka_config::KafkaConsumerConfig;
 use crate::kafka::kafka_error::{KafkaError, KafkaResult};
+use crate::kafka::kafka_metadata::{KafkaClusterMetadata, fetch_cluster_metadata};
 
 /// 消息处理函数类型
 pub type MessageHandler<T> = Box<dyn Fn(T) -> KafkaResult<()> + Send + Sync>;
@@ -20,17 +26,25 @@ pub type MessageHandler<T> = Box<dyn Fn(T) -> KafkaResult<()> + Send + Sync>;
 pub struct KafkaConsumer {
     consumer: StreamConsumer,
     config: KafkaConsumerConfig,
+    #[cfg(feature = "kafka-avro")]
+    schema_registry: Option<SchemaRegistryClient>,
 }
 
 impl KafkaConsumer {
     /// 创建新的 Kafka 消费者
     pub fn new(config: KafkaConsumerConfig) -> KafkaResult<Self> {
+        config.validate().map_err(KafkaError::ConfigError)?;
         let consumer_config = config.to_consumer_config()?;
         let consumer: StreamConsumer = consumer_config
             .create()
             .map_err(|e| KafkaError::ConsumerError(format!("创建消费者失败: {}", e)))?;
 
-        Ok(Self { consumer, config })
+        Ok(Self {
+            consumer,
+            #[cfg(feature = "kafka-avro")]
+            schema_registry: SchemaRegistryClient::from_config(&config.base),
+            config,
+        })
     }
 
     /// 订阅主题
@@ -42,6 +56,17 @@ impl KafkaConsumer {
         Ok(())
     }
 
+    /// 拉取集群元数据，`topic` 为 None 时返回所有主题，可用于订阅前检查主题是否存在
+    ///
+    /// 同步调用，最多阻塞 `timeout`
+    pub fn fetch_metadata(
+        &self,
+        topic: Option<&str>,
+        timeout: Duration,
+    ) -> KafkaResult<KafkaClusterMetadata> {
+        fetch_cluster_metadata(self.consumer.client(), topic, timeout)
+    }
+
     /// 订阅特定分区
     pub fn assign(&self, topic_partitions: &TopicPartitionList) -> KafkaResult<()> {
         self.consumer
@@ -74,6 +99,50 @@ impl KafkaConsumer {
         }
     }
 
+    /// 消费消息并按 JSON 反序列化负载（阻塞式）
+    pub async fn consume_json<T: DeserializeOwned>(&self) -> KafkaResult<T> {
+        let message = self.consume_message().await?;
+        deserialize_payload(&message)
+    }
+
+    /// 消费 Confluent 格式的 Avro 消息，按消息头中的 schema ID 从 Registry 获取 schema 并反序列化
+    ///
+    /// 需要配置 `schema_registry_url`
+    #[cfg(feature = "kafka-avro")]
+    pub async fn consume_avro<T: DeserializeOwned>(&self) -> KafkaResult<T> {
+        let registry = self.schema_registry.as_ref().ok_or_else(|| {
+            KafkaError::ConfigError("消费 Avro 消息需要配置 schema_registry_url".to_string())
+        })?;
+        let message = self.consume_message().await?;
+        let payload = message
+            .payload()
+            .ok_or_else(|| KafkaError::DeserializationError("消息负载为空".to_string()))?;
+
+        registry.deserialize(payload).await
+    }
+
+    /// 消费消息并按 JSON 反序列化负载（带超时，超时返回 None）
+    pub async fn consume_json_with_timeout<T: DeserializeOwned>(
+        &self,
+        timeout_duration: Duration,
+    ) -> KafkaResult<Option<T>> {
+        self.consume_message_with_timeout(timeout_duration)
+            .await?
+            .map(|message| deserialize_payload(&message))
+            .transpose()
+    }
+
+    /// 批量消费消息并按 JSON 反序列化，逐条返回结果（顺序与消息一致）
+    ///
+    /// 单条消息反序列化失败不影响其他消息，调用方可以把失败的消息转发到死信队列
+    pub async fn consume_json_batch<T: DeserializeOwned>(
+        &self,
+        max_messages: usize,
+    ) -> KafkaResult<Vec<KafkaResult<T>>> {
+        let messages = self.consume_batch(max_messages).await?;
+        Ok(deserialize_batch(&messages))
+    }
+
     /// 批量消费消息
     pub async fn consume_batch(&self, max_messages: usize) -> KafkaResult<Vec<OwnedMessage>> {
         let mut messages = Vec::new();
@@ -124,20 +193,24 @@ impl KafkaConsumer {
     }
 
     /// 提交单个消息的偏移量
-    pub fn commit_message(&self, _message: &OwnedMessage) -> KafkaResult<()> {
-        // 注意：在新版本的 rdkafka 中，commit_message 可能需要 BorrowedMessage
-        // 这里暂时返回成功，实际使用时需要根据具体版本调整
-        Ok(())
+    pub fn commit_message(&self, message: &OwnedMessage) -> KafkaResult<()> {
+        self.commit_messages(std::slice::from_ref(message))
     }
 
     /// 提交多个消息的偏移量
+    ///
+    /// 每个主题分区提交其中最大的偏移量 + 1，即下一条待消费消息的位置
     pub fn commit_messages(&self, messages: &[OwnedMessage]) -> KafkaResult<()> {
         if messages.is_empty() {
             return Ok(());
         }
 
-        let last_message = &messages[messages.len() - 1];
-        self.commit_message(last_message)
+        let tpl = build_commit_offsets(messages)?;
+        self.consumer
+            .commit(&tpl, CommitMode::Sync)
+            .map_err(|e| KafkaError::ConsumerError(format!("提交偏移量失败: {}", e)))?;
+
+        Ok(())
     }
 
     /// 手动提交偏移量
@@ -149,6 +222,81 @@ impl KafkaConsumer {
         Ok(())
     }
 
+    /// 将指定分区的消费位置移动到给定偏移量（分区需已分配）
+    pub fn seek(&self, topic: &str, partition: i32, offset: i64) -> KafkaResult<()> {
+        self.consumer
+            .seek(
+                topic,
+                partition,
+                Offset::Offset(offset),
+                self.seek_timeout(),
+            )
+            .map_err(|e| KafkaError::ConsumerError(format!("移动消费位置失败: {}", e)))
+    }
+
+    /// 将消息所在分区的消费位置回退到其中最早的消息，使这些消息在下次消费时重新投递
+    pub fn rewind(&self, messages: &[OwnedMessage]) -> KafkaResult<()> {
+        for ((topic, partition), offset) in earliest_offsets(messages) {
+            self.seek(topic, partition, offset)?;
+        }
+
+        Ok(())
+    }
+
+    /// 将给定分区的消费位置移动到最早的偏移量
+    pub fn seek_to_beginning(&self, partitions: &TopicPartitionList) -> KafkaResult<()> {
+        self.seek_partitions_to(partitions, Offset::Beginning)
+    }
+
+    /// 将给定分区的消费位置移动到最新的偏移量
+    pub fn seek_to_end(&self, partitions: &TopicPartitionList) -> KafkaResult<()> {
+        self.seek_partitions_to(partitions, Offset::End)
+    }
+
+    /// 获取已分配分区的当前消费位置（下一条待消费消息的偏移量）
+    pub fn position(&self) -> KafkaResult<TopicPartitionList> {
+        self.consumer
+            .position()
+            .map_err(|e| KafkaError::ConsumerError(format!("获取消费位置失败: {}", e)))
+    }
+
+    /// 将一组分区统一移动到指定位置
+    fn seek_partitions_to(
+        &self,
+        partitions: &TopicPartitionList,
+        offset: Offset,
+    ) -> KafkaResult<()> {
+        let mut tpl = TopicPartitionList::new();
+        for elem in partitions.elements() {
+            tpl.add_partition_offset(elem.topic(), elem.partition(), offset)
+                .map_err(|e| KafkaError::ConsumerError(format!("构建分区列表失败: {}", e)))?;
+        }
+
+        let result = self
+            .consumer
+            .seek_partitions(tpl, self.seek_timeout())
+            .map_err(|e| KafkaError::ConsumerError(format!("移动消费位置失败: {}", e)))?;
+
+        // 逐个分区检查移动结果
+        for elem in result.elements() {
+            if let Err(e) = elem.error() {
+                return Err(KafkaError::ConsumerError(format!(
+                    "移动分区 {}[{}] 消费位置失败: {}",
+                    elem.topic(),
+                    elem.partition(),
+                    e
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 移动消费位置的超时时间
+    fn seek_timeout(&self) -> Duration {
+        Duration::from_millis(self.config.base.request_timeout_ms.unwrap_or(30000))
+    }
+
     /// 获取消费者配置
     pub fn get_config(&self) -> &KafkaConsumerConfig {
         &self.config
@@ -176,6 +324,75 @@ impl KafkaConsumer {
     }
 }
 
+/// 根据消息构建待提交的偏移量列表
+///
+/// 同一主题分区只保留最大偏移量，提交位置为该偏移量 + 1
+pub(crate) fn build_commit_offsets(messages: &[OwnedMessage]) -> KafkaResult<TopicPartitionList> {
+    let mut latest: HashMap<(&str, i32), i64> = HashMap::new();
+    for message in messages {
+        let entry = latest
+            .entry((message.topic(), message.partition()))
+            .or_insert(message.offset());
+        *entry = (*entry).max(message.offset());
+    }
+
+    let mut tpl = TopicPartitionList::new();
+    for ((topic, partition), offset) in latest {
+        tpl.add_partition_offset(topic, partition, Offset::Offset(offset + 1))
+            .map_err(|e| KafkaError::ConsumerError(format!("构建提交偏移量失败: {}", e)))?;
+    }
+
+    Ok(tpl)
+}
+
+/// 每个主题分区中最早的消息偏移量
+fn earliest_offsets(messages: &[OwnedMessage]) -> HashMap<(&str, i32), i64> {
+    let mut earliest: HashMap<(&str, i32), i64> = HashMap::new();
+    for message in messages {
+        let entry = earliest
+            .entry((message.topic(), message.partition()))
+            .or_insert(message.offset());
+        *entry = (*entry).min(message.offset());
+    }
+    earliest
+}
+
+/// 读取消息头，转换为键到字节值的映射
+///
+/// 没有值的消息头以空字节数组表示，重复的键保留最后一个值
+pub fn message_headers<M: Message>(message: &M) -> HashMap<String, Vec<u8>> {
+    message
+        .headers()
+        .map(|headers| {
+            headers
+                .iter()
+                .map(|header| {
+                    (
+                        header.key.to_string(),
+                        header.value.map(<[u8]>::to_vec).unwrap_or_default(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 将消息负载按 JSON 反序列化为指定类型
+pub(crate) fn deserialize_payload<T: DeserializeOwned, M: Message>(message: &M) -> KafkaResult<T> {
+    let payload = message
+        .payload()
+        .ok_or_else(|| KafkaError::DeserializationError("消息负载为空".to_string()))?;
+
+    serde_json::from_slice(payload).map_err(|e| KafkaError::DeserializationError(e.to_string()))
+}
+
+/// 逐条反序列化消息负载，保留每条消息的结果
+pub(crate) fn deserialize_batch<T: DeserializeOwned>(
+    messages: &[OwnedMessage],
+) -> Vec<KafkaResult<T>> {
+    messages.iter().map(deserialize_payload).collect()
+}
+
 /// 高级 Kafka 消费者，支持消息处理函数
 pub struct AdvancedKafkaConsumer {
     consumer: StreamConsumer,
@@ -186,6 +403,7 @@ pub struct AdvancedKafkaConsumer {
 impl AdvancedKafkaConsumer {
     /// 创建新的高级 Kafka 消费者
     pub fn new(config: KafkaConsumerConfig) -> KafkaResult<Self> {
+        config.validate().map_err(KafkaError::ConfigError)?;
         let consumer_config = config.to_consumer_config()?;
         let consumer: StreamConsumer = consumer_config
             .create()
@@ -229,11 +447,27 @@ impl AdvancedKafkaConsumer {
         }
     }
 
-    /// 消费并反序列化消息
+    /// 消费并反序列化消息（阻塞式，按 JSON 解析消息负载）
     pub async fn consume_deserialized<T: DeserializeOwned>(&self) -> KafkaResult<Option<T>> {
-        // 注意：这个方法需要访问 consume_message_with_timeout，但它在 KafkaConsumer 中
-        // 这里暂时返回 None，实际使用时需要重新设计
-        Ok(None)
+        let message = self
+            .consumer
+            .recv()
+            .await
+            .map_err(|e| KafkaError::ReceiveError(format!("接收消息失败: {}", e)))?;
+
+        deserialize_payload(&message).map(Some)
+    }
+
+    /// 消费并反序列化消息（带超时，超时返回 None）
+    pub async fn consume_deserialized_with_timeout<T: DeserializeOwned>(
+        &self,
+        timeout_duration: Duration,
+    ) -> KafkaResult<Option<T>> {
+        match timeout(timeout_duration, self.consumer.recv()).await {
+            Ok(Ok(message)) => deserialize_payload(&message).map(Some),
+            Ok(Err(e)) => Err(KafkaError::ReceiveError(format!("接收消息失败: {}", e))),
+            Err(_) => Ok(None), // 超时
+        }
     }
 
     /// 获取消费者
@@ -242,10 +476,21 @@ impl AdvancedKafkaConsumer {
     }
 }
 
+/// 手动提交模式下处理失败后，重新投递前的等待时间
+const HANDLER_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// 手动提交模式下单条消息默认的最大重试次数
+const DEFAULT_MAX_HANDLER_RETRIES: u32 = 3;
+
+/// 死信处理函数类型，接收重试耗尽的消息及最后一次处理错误
+pub type DeadLetterHandler = Arc<dyn Fn(&OwnedMessage, &KafkaError) + Send + Sync>;
+
 /// 消费者组管理器
 pub struct ConsumerGroupManager {
-    consumers: Vec<KafkaConsumer>,
+    consumers: Vec<Arc<KafkaConsumer>>,
     config: KafkaConsumerConfig,
+    max_handler_retries: u32,
+    dead_letter: Option<DeadLetterHandler>,
 }
 
 impl ConsumerGroupManager {
@@ -261,23 +506,152 @@ impl ConsumerGroupManager {
                 i
             ));
 
-            consumers.push(KafkaConsumer::new(consumer_config)?);
+            consumers.push(Arc::new(KafkaConsumer::new(consumer_config)?));
         }
 
-        Ok(Self { consumers, config })
+        Ok(Self {
+            consumers,
+            config,
+            max_handler_retries: DEFAULT_MAX_HANDLER_RETRIES,
+            dead_letter: None,
+        })
+    }
+
+    /// 设置手动提交模式下单条消息处理失败后的最大重试次数
+    pub fn with_max_handler_retries(mut self, max_retries: u32) -> Self {
+        self.max_handler_retries = max_retries;
+        self
+    }
+
+    /// 设置死信处理函数，重试耗尽的消息会先交给它处理，再提交偏移量跳过
+    pub fn with_dead_letter_handler<D>(mut self, handler: D) -> Self
+    where
+        D: Fn(&OwnedMessage, &KafkaError) + Send + Sync + 'static,
+    {
+        self.dead_letter = Some(Arc::new(handler));
+        self
     }
 
     /// 启动所有消费者
+    ///
+    /// 仅订阅主题，消息需要调用方自行消费；如需并行消费请使用
+    /// [`start_all_with_handler`](Self::start_all_with_handler)
     pub async fn start_all(&self, topics: &[&str]) -> KafkaResult<()> {
         for consumer in &self.consumers {
             consumer.subscribe(topics)?;
         }
 
-        // 这里可以实现负载均衡逻辑
-        // 在实际应用中，每个消费者应该在单独的线程中运行
         Ok(())
     }
 
+    /// 订阅主题，并为每个消费者启动独立的 tokio 任务并行消费
+    ///
+    /// 同组消费者由 Kafka 分配不同分区，所有消费者共享同一个处理函数。
+    /// 关闭自动提交时，处理成功后会提交该消息的偏移量；处理失败则回退到该消息，
+    /// 短暂等待后重新投递，回退失败时停止该消费者的任务。同一条消息重试超过
+    /// [`with_max_handler_retries`](Self::with_max_handler_retries) 次后交给死信处理函数，
+    /// 并提交偏移量跳过该消息。提交和回退在阻塞线程池中执行，不会阻塞 tokio 工作线程
+    pub async fn start_all_with_handler<F>(
+        &self,
+        topics: &[&str],
+        handler: F,
+    ) -> KafkaResult<ConsumerGroupHandle>
+    where
+        F: Fn(OwnedMessage) -> KafkaResult<()> + Send + Sync + 'static,
+    {
+        self.start_all(topics).await?;
+
+        let handler = Arc::new(handler);
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let manual_commit = !self.config.enable_auto_commit.unwrap_or(true);
+        let max_retries = self.max_handler_retries;
+
+        let handles = self
+            .consumers
+            .iter()
+            .enumerate()
+            .map(|(index, consumer)| {
+                let consumer = consumer.clone();
+                let handler = handler.clone();
+                let dead_letter = self.dead_letter.clone();
+                let mut shutdown_rx = shutdown_rx.clone();
+
+                tokio::spawn(async move {
+                    let mut retries = HandlerRetries::default();
+                    loop {
+                        tokio::select! {
+                            _ = shutdown_rx.changed() => break,
+                            result = consumer.consume_message() => match result {
+                                Ok(message) => {
+                                    let pending = manual_commit.then(|| message.clone());
+                                    let result = handler(message);
+                                    let Some(message) = pending else {
+                                        if let Err(e) = result {
+                                            tracing::warn!("消费者 {} 处理消息失败: {}", index, e);
+                                        }
+                                        continue;
+                                    };
+                                    match result {
+                                        Ok(()) => {
+                                            retries.reset();
+                                            if let Err(e) = commit_in_background(&consumer, message).await {
+                                                tracing::warn!("消费者 {} 提交偏移量失败: {}", index, e);
+                                            }
+                                        }
+                                        Err(e) if retries.record_failure(&message) > max_retries => {
+                                            tracing::error!(
+                                                "消费者 {} 处理消息 {}[{}]@{} 重试 {} 次后仍失败，跳过: {}",
+                                                index,
+                                                message.topic(),
+                                                message.partition(),
+                                                message.offset(),
+                                                max_retries,
+                                                e
+                                            );
+                                            retries.reset();
+                                            if let Some(dead_letter) = &dead_letter {
+                                                dead_letter(&message, &e);
+                                            }
+                                            if let Err(e) = commit_in_background(&consumer, message).await {
+                                                tracing::warn!("消费者 {} 提交偏移量失败: {}", index, e);
+                                            }
+                                        }
+                                        Err(e) => {
+                                            tracing::warn!(
+                                                "消费者 {} 处理消息失败，回退后重新投递: {}",
+                                                index,
+                                                e
+                                            );
+                                            // 回退到失败的消息，避免后续提交越过它导致消息丢失
+                                            if let Err(e) = rewind_in_background(&consumer, message).await {
+                                                tracing::error!(
+                                                    "消费者 {} 回退消费位置失败，停止消费: {}",
+                                                    index,
+                                                    e
+                                                );
+                                                break;
+                                            }
+                                            tokio::select! {
+                                                _ = shutdown_rx.changed() => break,
+                                                _ = tokio::time::sleep(HANDLER_RETRY_BACKOFF) => {}
+                                            }
+                                        }
+                                    }
+                                }
+                                Err(e) => tracing::warn!("消费者 {} 接收消息失败: {}", index, e),
+                            },
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        Ok(ConsumerGroupHandle {
+            handles,
+            shutdown: shutdown_tx,
+        })
+    }
+
     /// 获取消费者数量
     pub fn consumer_count(&self) -> usize {
         self.consumers.len()
@@ -285,7 +659,92 @@ impl ConsumerGroupManager {
 
     /// 获取指定索引的消费者
     pub fn get_consumer(&self, index: usize) -> Option<&KafkaConsumer> {
-        self.consumers.get(index)
+        self.consumers.get(index).map(|consumer| consumer.as_ref())
+    }
+}
+
+/// 在阻塞线程池中同步提交消息的偏移量
+async fn commit_in_background(
+    consumer: &Arc<KafkaConsumer>,
+    message: OwnedMessage,
+) -> KafkaResult<()> {
+    let consumer = consumer.clone();
+    tokio::task::spawn_blocking(move || consumer.commit_message(&message))
+        .await
+        .map_err(|e| KafkaError::InternalError(format!("提交偏移量任务异常退出: {}", e)))?
+}
+
+/// 在阻塞线程池中将消费位置回退到该消息
+async fn rewind_in_background(
+    consumer: &Arc<KafkaConsumer>,
+    message: OwnedMessage,
+) -> KafkaResult<()> {
+    let consumer = consumer.clone();
+    tokio::task::spawn_blocking(move || consumer.rewind(std::slice::from_ref(&message)))
+        .await
+        .map_err(|e| KafkaError::InternalError(format!("回退消费位置任务异常退出: {}", e)))?
+}
+
+/// 记录当前消息连续处理失败的次数，消息变化时重新计数
+#[derive(Debug, Default)]
+struct HandlerRetries {
+    message: Option<(String, i32, i64)>,
+    failures: u32,
+}
+
+impl HandlerRetries {
+    /// 记录一次处理失败，返回该消息连续失败的次数
+    fn record_failure(&mut self, message: &OwnedMessage) -> u32 {
+        let key = (
+            message.topic().to_string(),
+            message.partition(),
+            message.offset(),
+        );
+        if self.message.as_ref() != Some(&key) {
+            self.message = Some(key);
+            self.failures = 0;
+        }
+        self.failures += 1;
+        self.failures
+    }
+
+    /// 消息处理成功或被跳过后清除计数
+    fn reset(&mut self) {
+        self.message = None;
+        self.failures = 0;
+    }
+}
+
+/// 消费者组运行句柄，用于停止并等待所有消费任务退出
+pub struct ConsumerGroupHandle {
+    handles: Vec<JoinHandle<()>>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl ConsumerGroupHandle {
+    /// 正在运行的消费任务数量
+    pub fn task_count(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// 通知所有消费任务停止，并等待其退出
+    pub async fn shutdown(self) -> KafkaResult<()> {
+        let _ = self.shutdown.send(true);
+
+        for handle in self.handles {
+            handle
+                .await
+                .map_err(|e| KafkaError::InternalError(format!("消费任务异常退出: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// 立即中止所有消费任务
+    pub fn abort(&self) {
+        for handle in &self.handles {
+            handle.abort();
+        }
     }
 }
 
@@ -299,6 +758,327 @@ mod tests {
         assert!(config.to_consumer_config().is_ok());
     }
 
+    fn test_message(topic: &str, partition: i32, offset: i64) -> OwnedMessage {
+        OwnedMessage::new(
+            Some(b"payload".to_vec()),
+            None,
+            topic.to_string(),
+            rdkafka::message::Timestamp::NotAvailable,
+            partition,
+            offset,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_build_commit_offsets_uses_next_offset() {
+        let tpl = build_commit_offsets(&[test_message("orders", 0, 41)]).unwrap();
+        let elem = tpl.find_partition("orders", 0).unwrap();
+        assert_eq!(elem.offset(), Offset::Offset(42));
+    }
+
+    #[test]
+    fn test_build_commit_offsets_keeps_latest_per_partition() {
+        let messages = vec![
+            test_message("orders", 0, 5),
+            test_message("orders", 0, 9),
+            test_message("orders", 0, 7),
+            test_message("orders", 1, 3),
+        ];
+        let tpl = build_commit_offsets(&messages).unwrap();
+        assert_eq!(tpl.count(), 2);
+        assert_eq!(
+            tpl.find_partition("orders", 0).unwrap().offset(),
+            Offset::Offset(10)
+        );
+        assert_eq!(
+            tpl.find_partition("orders", 1).unwrap().offset(),
+            Offset::Offset(4)
+        );
+    }
+
+    #[test]
+    fn test_earliest_offsets_per_partition() {
+        let messages = vec![
+            test_message("orders", 0, 9),
+            test_message("orders", 0, 5),
+            test_message("orders", 1, 3),
+        ];
+        let earliest = earliest_offsets(&messages);
+        assert_eq!(earliest.len(), 2);
+        assert_eq!(earliest[&("orders", 0)], 5);
+        assert_eq!(earliest[&("orders", 1)], 3);
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct UserEvent {
+        user_id: u64,
+        action: String,
+    }
+
+    #[test]
+    fn test_deserialize_payload() {
+        let mut message = test_message("users", 0, 0);
+        assert!(matches!(
+            deserialize_payload::<UserEvent, _>(&message),
+            Err(KafkaError::DeserializationError(_))
+        ));
+
+        message = OwnedMessage::new(
+            Some(br#"{"user_id":7,"action":"login"}"#.to_vec()),
+            None,
+            "users".to_string(),
+            rdkafka::message::Timestamp::NotAvailable,
+            0,
+            0,
+            None,
+        );
+        let event: UserEvent = deserialize_payload(&message).unwrap();
+        assert_eq!(event.user_id, 7);
+        assert_eq!(event.action, "login");
+    }
+
+    #[test]
+    fn test_deserialize_batch_reports_each_message() {
+        let json_message = |offset: i64, payload: &[u8]| {
+            OwnedMessage::new(
+                Some(payload.to_vec()),
+                None,
+                "users".to_string(),
+                rdkafka::message::Timestamp::NotAvailable,
+                0,
+                offset,
+                None,
+            )
+        };
+        let messages = vec![
+            json_message(0, br#"{"user_id":7,"action":"login"}"#),
+            json_message(1, b"not json"),
+        ];
+
+        let results = deserialize_batch::<UserEvent>(&messages);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().user_id, 7);
+        assert!(matches!(
+            results[1],
+            Err(KafkaError::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_message_headers_round_trip() {
+        let traceparent = b"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let headers = crate::kafka::kafka_producer::build_headers(&[
+            ("traceparent", traceparent),
+            ("content-type", b"application/json"),
+            ("schema-version", &[0x00, 0xff, 0x02]),
+        ]);
+        let message = OwnedMessage::new(
+            Some(b"{}".to_vec()),
+            None,
+            "traces".to_string(),
+            rdkafka::message::Timestamp::NotAvailable,
+            0,
+            0,
+            Some(headers),
+        );
+
+        let headers = message_headers(&message);
+        assert_eq!(headers.len(), 3);
+        assert_eq!(headers["traceparent"], traceparent.to_vec());
+        assert_eq!(headers["content-type"], b"application/json".to_vec());
+        assert_eq!(headers["schema-version"], vec![0x00, 0xff, 0x02]);
+
+        assert!(message_headers(&test_message("traces", 0, 0)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_consume_deserialized_round_trip() {
+        // 仅在设置了 KAFKA_BOOTSTRAP_SERVERS 时运行，需要实际的 Kafka 服务器
+        let Ok(servers) = std::env::var("KAFKA_BOOTSTRAP_SERVERS") else {
+            return;
+        };
+        let topic = "clamber-deserialize-test";
+
+        let mut producer_config = crate::kafka::KafkaProducerConfig::default();
+        producer_config.base.bootstrap_servers = vec![servers.clone()];
+        let producer = crate::kafka::KafkaProducer::new(producer_config).unwrap();
+
+        let mut consumer_config = KafkaConsumerConfig::default();
+        consumer_config.base.bootstrap_servers = vec![servers];
+        consumer_config.group_id = format!("clamber-deserialize-{}", std::process::id());
+        consumer_config.auto_offset_reset = Some("earliest".to_string());
+        let consumer = AdvancedKafkaConsumer::new(consumer_config).unwrap();
+        consumer.get_consumer().subscribe(&[topic]).unwrap();
+
+        let event = UserEvent {
+            user_id: 42,
+            action: "signup".to_string(),
+        };
+        producer.send_serialized(topic, None, &event).await.unwrap();
+
+        let received: Option<UserEvent> = consumer
+            .consume_deserialized_with_timeout(Duration::from_secs(30))
+            .await
+            .unwrap();
+        assert_eq!(received, Some(event));
+    }
+
+    #[tokio::test]
+    async fn test_consume_json_round_trip() {
+        // 仅在设置了 KAFKA_BOOTSTRAP_SERVERS 时运行，需要实际的 Kafka 服务器
+        let Ok(servers) = std::env::var("KAFKA_BOOTSTRAP_SERVERS") else {
+            return;
+        };
+        let topic = "clamber-consume-json-test";
+
+        let mut producer_config = crate::kafka::KafkaProducerConfig::default();
+        producer_config.base.bootstrap_servers = vec![servers.clone()];
+        let producer = crate::kafka::KafkaProducer::new(producer_config).unwrap();
+
+        let mut consumer_config = KafkaConsumerConfig::default();
+        consumer_config.base.bootstrap_servers = vec![servers];
+        consumer_config.group_id = format!("clamber-consume-json-{}", std::process::id());
+        consumer_config.auto_offset_reset = Some("earliest".to_string());
+        let consumer = KafkaConsumer::new(consumer_config).unwrap();
+        consumer.subscribe(&[topic]).unwrap();
+
+        let event = UserEvent {
+            user_id: 7,
+            action: "login".to_string(),
+        };
+        producer.send_serialized(topic, None, &event).await.unwrap();
+        producer
+            .send_message(topic, None, "not json")
+            .await
+            .unwrap();
+
+        let received: Option<UserEvent> = consumer
+            .consume_json_with_timeout(Duration::from_secs(30))
+            .await
+            .unwrap();
+        assert_eq!(received, Some(event));
+        assert!(matches!(
+            consumer.consume_json::<UserEvent>().await,
+            Err(KafkaError::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_seek_and_position_with_broker() {
+        // 仅在设置了 KAFKA_BOOTSTRAP_SERVERS 时运行，需要实际的 Kafka 服务器
+        let Ok(servers) = std::env::var("KAFKA_BOOTSTRAP_SERVERS") else {
+            return;
+        };
+        let mut config = KafkaConsumerConfig::default();
+        config.base.bootstrap_servers = vec![servers];
+        let consumer = KafkaConsumer::new(config).unwrap();
+
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition_offset("clamber-seek-test", 0, Offset::End)
+            .unwrap();
+        consumer.assign(&tpl).unwrap();
+
+        consumer.seek("clamber-seek-test", 0, 0).unwrap();
+        let position = consumer.position().unwrap();
+        assert_eq!(
+            position
+                .find_partition("clamber-seek-test", 0)
+                .unwrap()
+                .offset(),
+            Offset::Offset(0)
+        );
+
+        consumer.seek_to_beginning(&tpl).unwrap();
+        consumer.seek_to_end(&tpl).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_commit_message_with_broker() {
+        // 仅在设置了 KAFKA_BOOTSTRAP_SERVERS 时运行，需要实际的 Kafka 服务器
+        let Ok(servers) = std::env::var("KAFKA_BOOTSTRAP_SERVERS") else {
+            return;
+        };
+        let mut config = KafkaConsumerConfig::default();
+        config.base.bootstrap_servers = vec![servers];
+        config.enable_auto_commit = Some(false);
+        config.auto_offset_reset = Some("earliest".to_string());
+
+        let consumer = KafkaConsumer::new(config).unwrap();
+        consumer.subscribe(&["clamber-commit-test"]).unwrap();
+        if let Some(message) = consumer
+            .consume_message_with_timeout(Duration::from_secs(10))
+            .await
+            .unwrap()
+        {
+            consumer.commit_message(&message).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_consumer_group_parallel_consume() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // 仅在设置了 KAFKA_BOOTSTRAP_SERVERS 时运行，需要实际的 Kafka 服务器及多分区主题
+        let Ok(servers) = std::env::var("KAFKA_BOOTSTRAP_SERVERS") else {
+            return;
+        };
+        let topic = "clamber-group-test";
+
+        let mut producer_config = crate::kafka::KafkaProducerConfig::default();
+        producer_config.base.bootstrap_servers = vec![servers.clone()];
+        let producer = crate::kafka::KafkaProducer::new(producer_config).unwrap();
+
+        let mut config = KafkaConsumerConfig::default();
+        config.base.bootstrap_servers = vec![servers];
+        config.group_id = format!("clamber-group-{}", std::process::id());
+        config.auto_offset_reset = Some("latest".to_string());
+        let manager = ConsumerGroupManager::new(config, 2).unwrap();
+
+        let received = Arc::new(AtomicUsize::new(0));
+        let counter = received.clone();
+        let handle = manager
+            .start_all_with_handler(&[topic], move |_message| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(handle.task_count(), 2);
+
+        // 等待分区分配完成后再发送消息
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        for i in 0..10 {
+            let key = format!("key-{}", i);
+            producer
+                .send_message(topic, Some(&key), "payload")
+                .await
+                .unwrap();
+        }
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(30);
+        while received.load(Ordering::SeqCst) < 10 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        handle.shutdown().await.unwrap();
+        assert_eq!(received.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn test_handler_retries_count_per_message() {
+        let mut retries = HandlerRetries::default();
+        let first = test_message("orders", 0, 7);
+        assert_eq!(retries.record_failure(&first), 1);
+        assert_eq!(retries.record_failure(&first), 2);
+
+        // 换成另一条消息时重新计数
+        assert_eq!(retries.record_failure(&test_message("orders", 0, 8)), 1);
+        assert_eq!(retries.record_failure(&first), 1);
+
+        retries.reset();
+        assert_eq!(retries.record_failure(&first), 1);
+    }
+
     #[test]
     fn test_consumer_group_manager_creation() {
         let config = KafkaConsumerConfig::default();
@@ -2,6 +2,7 @@
 //!
 //! 提供统一的 Kafka 错误类型和处理机制
 
+use rdkafka::types::RDKafkaErrorCode;
 use thiserror::Error;
 
 /// Kafka 相关错误类型
@@ -11,7 +12,7 @@ pub enum KafkaError {
     #[error("Kafka配置错误: {0}")]
     ConfigError(String),
 
-    /// 生产者错误
+    /// 生产者错误，如消息过大、主题不存在、无权限等重试也不会成功的错误
     #[error("Kafka生产者错误: {0}")]
     ProducerError(String),
 
@@ -48,9 +49,75 @@ pub enum KafkaError {
     InternalError(String),
 }
 
+impl KafkaError {
+    /// 判断是否为可重试的错误（网络、超时、发送失败等瞬时错误）
+    ///
+    /// 配置、序列化以及消息过大、主题不存在、无权限等生产者错误重试也不会成功，应当立即失败
+    pub fn is_retriable(&self) -> bool {
+        matches!(
+            self,
+            KafkaError::SendError(_) | KafkaError::ConnectionError(_) | KafkaError::TimeoutError(_)
+        )
+    }
+
+    /// 判断是否为连接错误（broker 不可达、传输失败等），重建客户端可能恢复
+    pub fn is_connection_error(&self) -> bool {
+        matches!(self, KafkaError::ConnectionError(_))
+    }
+}
+
+/// broker 不可达或网络传输失败的错误码
+fn is_transport_error(code: RDKafkaErrorCode) -> bool {
+    matches!(
+        code,
+        RDKafkaErrorCode::BrokerTransportFailure
+            | RDKafkaErrorCode::AllBrokersDown
+            | RDKafkaErrorCode::Resolve
+            | RDKafkaErrorCode::NetworkException
+    )
+}
+
+/// 超时的错误码
+fn is_timeout_error(code: RDKafkaErrorCode) -> bool {
+    matches!(
+        code,
+        RDKafkaErrorCode::MessageTimedOut
+            | RDKafkaErrorCode::RequestTimedOut
+            | RDKafkaErrorCode::OperationTimedOut
+    )
+}
+
+/// 队列已满、分区 leader 切换、副本不足等稍后重试可能成功的错误码
+fn is_transient_production_error(code: RDKafkaErrorCode) -> bool {
+    matches!(
+        code,
+        RDKafkaErrorCode::QueueFull
+            | RDKafkaErrorCode::NotLeaderForPartition
+            | RDKafkaErrorCode::LeaderNotAvailable
+            | RDKafkaErrorCode::BrokerNotAvailable
+            | RDKafkaErrorCode::NotEnoughReplicas
+            | RDKafkaErrorCode::NotEnoughReplicasAfterAppend
+            | RDKafkaErrorCode::KafkaStorageError
+    )
+}
+
 impl From<rdkafka::error::KafkaError> for KafkaError {
     fn from(err: rdkafka::error::KafkaError) -> Self {
         match err {
+            rdkafka::error::KafkaError::MessageProduction(code)
+            | rdkafka::error::KafkaError::MetadataFetch(code)
+                if is_transport_error(code) =>
+            {
+                KafkaError::ConnectionError(format!("broker 不可达: {:?}", code))
+            }
+            rdkafka::error::KafkaError::MessageProduction(code) if is_timeout_error(code) => {
+                KafkaError::TimeoutError(format!("消息生产超时: {:?}", code))
+            }
+            rdkafka::error::KafkaError::MessageProduction(code)
+                if is_transient_production_error(code) =>
+            {
+                KafkaError::SendError(format!("消息生产暂时失败: {:?}", code))
+            }
             rdkafka::error::KafkaError::MessageProduction(code) => {
                 KafkaError::ProducerError(format!("消息生产错误: {:?}", code))
             }
@@ -77,3 +144,59 @@ impl From<std::time::SystemTimeError> for KafkaError {
 
 /// Kafka 结果类型
 pub type KafkaResult<T> = Result<T, KafkaError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retriable() {
+        assert!(KafkaError::SendError("broker down".to_string()).is_retriable());
+        assert!(KafkaError::TimeoutError("send".to_string()).is_retriable());
+        assert!(!KafkaError::SerializationError("bad json".to_string()).is_retriable());
+        assert!(!KafkaError::ConfigError("missing".to_string()).is_retriable());
+    }
+
+    #[test]
+    fn test_transport_errors_are_connection_errors() {
+        let error = KafkaError::from(rdkafka::error::KafkaError::MessageProduction(
+            RDKafkaErrorCode::AllBrokersDown,
+        ));
+        assert!(error.is_connection_error());
+        assert!(error.is_retriable());
+
+        let error = KafkaError::from(rdkafka::error::KafkaError::MetadataFetch(
+            RDKafkaErrorCode::BrokerTransportFailure,
+        ));
+        assert!(error.is_connection_error());
+
+        let error = KafkaError::from(rdkafka::error::KafkaError::MessageProduction(
+            RDKafkaErrorCode::MessageSizeTooLarge,
+        ));
+        assert!(matches!(error, KafkaError::ProducerError(_)));
+    }
+
+    #[test]
+    fn test_production_errors_classified_by_code() {
+        let production =
+            |code| KafkaError::from(rdkafka::error::KafkaError::MessageProduction(code));
+
+        for code in [
+            RDKafkaErrorCode::QueueFull,
+            RDKafkaErrorCode::MessageTimedOut,
+            RDKafkaErrorCode::RequestTimedOut,
+            RDKafkaErrorCode::NotLeaderForPartition,
+            RDKafkaErrorCode::NetworkException,
+        ] {
+            assert!(production(code).is_retriable(), "{:?}", code);
+        }
+        for code in [
+            RDKafkaErrorCode::MessageSizeTooLarge,
+            RDKafkaErrorCode::UnknownTopicOrPartition,
+            RDKafkaErrorCode::TopicAuthorizationFailed,
+            RDKafkaErrorCode::InvalidRecord,
+        ] {
+            assert!(!production(code).is_retriable(), "{:?}", code);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Kafka 集群元数据模块
+//!
+//! 查询集群中的 broker、主题及分区信息，可用于启动时检查依赖的主题是否存在
+
+use rdkafka::client::{Client, ClientContext};
+use rdkafka::metadata::Metadata;
+use rdkafka::types::RDKafkaErrorCode;
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::kafka::kafka_error::{KafkaError, KafkaResult};
+
+/// 集群元数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KafkaClusterMetadata {
+    /// 返回元数据的 broker ID
+    pub orig_broker_id: i32,
+    /// 集群中的 broker
+    pub brokers: Vec<KafkaBrokerMetadata>,
+    /// 主题列表，查询单个主题时只包含该主题
+    pub topics: Vec<KafkaTopicMetadata>,
+}
+
+/// broker 信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KafkaBrokerMetadata {
+    /// broker ID
+    pub id: i32,
+    /// 主机名
+    pub host: String,
+    /// 端口
+    pub port: i32,
+}
+
+/// 主题信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KafkaTopicMetadata {
+    /// 主题名称
+    pub name: String,
+    /// 分区 ID 列表
+    pub partitions: Vec<i32>,
+    /// broker 返回的主题错误（如主题不存在），正常时为 None
+    pub error: Option<String>,
+}
+
+impl KafkaTopicMetadata {
+    /// 分区数量
+    pub fn partition_count(&self) -> usize {
+        self.partitions.len()
+    }
+}
+
+impl KafkaClusterMetadata {
+    /// 按名称查找存在的主题，broker 报告错误的主题视为不存在
+    pub fn topic(&self, name: &str) -> Option<&KafkaTopicMetadata> {
+        self.topics
+            .iter()
+            .find(|topic| topic.name == name && topic.error.is_none())
+    }
+
+    /// 主题是否存在
+    pub fn has_topic(&self, name: &str) -> bool {
+        self.topic(name).is_some()
+    }
+
+    /// 查找主题，不存在时返回配置错误
+    pub fn require_topic(&self, name: &str) -> KafkaResult<&KafkaTopicMetadata> {
+        self.topic(name)
+            .ok_or_else(|| KafkaError::ConfigError(format!("主题 {} 不存在", name)))
+    }
+
+    /// 所有存在的主题名称
+    pub fn topic_names(&self) -> impl Iterator<Item = &str> {
+        self.topics
+            .iter()
+            .filter(|topic| topic.error.is_none())
+            .map(|topic| topic.name.as_str())
+    }
+}
+
+impl From<&Metadata> for KafkaClusterMetadata {
+    fn from(metadata: &Metadata) -> Self {
+        Self {
+            orig_broker_id: metadata.orig_broker_id(),
+            brokers: metadata
+                .brokers()
+                .iter()
+                .map(|broker| KafkaBrokerMetadata {
+                    id: broker.id(),
+                    host: broker.host().to_string(),
+                    port: broker.port(),
+                })
+                .collect(),
+            topics: metadata
+                .topics()
+                .iter()
+                .map(|topic| KafkaTopicMetadata {
+                    name: topic.name().to_string(),
+                    partitions: topic.partitions().iter().map(|p| p.id()).collect(),
+                    error: topic
+                        .error()
+                        .map(|e| format!("{:?}", RDKafkaErrorCode::from(e))),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 通过客户端拉取元数据（阻塞至多 `timeout`），`topic` 为 None 时返回所有主题
+pub(crate) fn fetch_cluster_metadata<C: ClientContext>(
+    client: &Client<C>,
+    topic: Option<&str>,
+    timeout: Duration,
+) -> KafkaResult<KafkaClusterMetadata> {
+    let metadata = client.fetch_metadata(topic, timeout)?;
+    Ok(KafkaClusterMetadata::from(&metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> KafkaClusterMetadata {
+        KafkaClusterMetadata {
+            orig_broker_id: 1,
+            brokers: vec![KafkaBrokerMetadata {
+                id: 1,
+                host: "localhost".to_string(),
+                port: 9092,
+            }],
+            topics: vec![
+                KafkaTopicMetadata {
+                    name: "user-events".to_string(),
+                    partitions: vec![0, 1, 2],
+                    error: None,
+                },
+                KafkaTopicMetadata {
+                    name: "missing".to_string(),
+                    partitions: vec![],
+                    error: Some("UnknownTopicOrPartition".to_string()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_topic_lookup() {
+        let metadata = sample();
+        assert_eq!(metadata.topic("user-events").unwrap().partition_count(), 3);
+        assert!(metadata.has_topic("user-events"));
+        // broker 报告错误的主题视为不存在
+        assert!(!metadata.has_topic("missing"));
+        assert!(!metadata.has_topic("other"));
+        assert_eq!(metadata.topic_names().collect::<Vec<_>>(), ["user-events"]);
+
+        let error = metadata.require_topic("missing").unwrap_err();
+        assert!(matches!(error, KafkaError::ConfigError(ref msg) if msg.contains("missing")));
+    }
+
+    #[test]
+    fn test_serialize_shape() {
+        let value = serde_json::to_value(sample()).unwrap();
+        assert_eq!(value["brokers"][0]["host"], "localhost");
+        assert_eq!(value["brokers"][0]["port"], 9092);
+        assert_eq!(value["topics"][0]["name"], "user-events");
+        assert_eq!(
+            value["topics"][0]["partitions"],
+            serde_json::json!([0, 1, 2])
+        );
+        assert!(value["topics"][0]["error"].is_null());
+    }
+
+    #[test]
+    fn test_fetch_metadata_live() {
+        use crate::kafka::{
+            KafkaConsumer, KafkaConsumerConfig, KafkaProducer, KafkaProducerConfig,
+        };
+
+        // 通过 KAFKA_BOOTSTRAP_SERVERS 指定测试 broker，未设置时跳过
+        let Ok(servers) = std::env::var("KAFKA_BOOTSTRAP_SERVERS") else {
+            return;
+        };
+        let timeout = Duration::from_secs(5);
+
+        let mut producer_config = KafkaProducerConfig::default();
+        producer_config.base.bootstrap_servers = vec![servers.clone()];
+        let producer = KafkaProducer::new(producer_config).unwrap();
+        let metadata = producer.fetch_metadata(None, timeout).unwrap();
+        assert!(!metadata.brokers.is_empty());
+        for topic in metadata.topics.iter().filter(|topic| topic.error.is_none()) {
+            assert!(topic.partition_count() > 0, "{:?}", topic);
+        }
+
+        let mut consumer_config = KafkaConsumerConfig::default();
+        consumer_config.base.bootstrap_servers = vec![servers];
+        consumer_config.group_id = "clamber-metadata-test".to_string();
+        let consumer = KafkaConsumer::new(consumer_config).unwrap();
+        let from_consumer = consumer.fetch_metadata(None, timeout).unwrap();
+        assert_eq!(from_consumer.brokers, metadata.brokers);
+    }
+}
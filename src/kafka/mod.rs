@@ -5,24 +5,34 @@
 //! - 生产者服务
 //! - 消费者服务
 //! - 错误处理
+//! - Avro / Schema Registry 序列化（`kafka-avro` feature）
 
 pub mod axum_integration;
+#[cfg(feature = "kafka-avro")]
+pub mod kafka_avro;
+pub mod kafka_channel;
 pub mod kafka_config;
 pub mod kafka_consumer;
 pub mod kafka_error;
+pub mod kafka_metadata;
 pub mod kafka_producer;
 
 // 重新导出主要类型
 pub use axum_integration::{
-    KafkaAppState, PollingConsumerService, create_default_kafka_app_state,
+    CommitStrategy, KafkaAppState, PollingConsumerService, create_default_kafka_app_state,
     create_kafka_app_state_from_config,
 };
+#[cfg(feature = "kafka-avro")]
+pub use kafka_avro::SchemaRegistryClient;
+pub use kafka_channel::ConsumerChannel;
 pub use kafka_config::{KafkaBaseConfig, KafkaConsumerConfig, KafkaProducerConfig};
 pub use kafka_consumer::{
-    AdvancedKafkaConsumer, ConsumerGroupManager, KafkaConsumer, MessageHandler,
+    AdvancedKafkaConsumer, ConsumerGroupHandle, ConsumerGroupManager, DeadLetterHandler,
+    KafkaConsumer, MessageHandler, message_headers,
 };
 pub use kafka_error::{KafkaError, KafkaResult};
-pub use kafka_producer::{KafkaProducer, TransactionalKafkaProducer};
+pub use kafka_metadata::{KafkaBrokerMetadata, KafkaClusterMetadata, KafkaTopicMetadata};
+pub use kafka_producer::{BatchMessage, KafkaProducer, TransactionalKafkaProducer};
 
 // 重新导出 rdkafka 相关类型
 pub use rdkafka::{
@@ -115,24 +125,12 @@ pub fn create_default_consumer(group_id: String) -> KafkaResult<KafkaConsumer> {
 
 /// 便捷函数：从配置文件创建生产者
 pub fn create_producer_from_config(config_path: &str) -> KafkaResult<KafkaProducer> {
-    let config_content = std::fs::read_to_string(config_path)
-        .map_err(|e| KafkaError::ConfigError(format!("读取配置文件失败: {}", e)))?;
-
-    let config: KafkaProducerConfig = serde_yaml::from_str(&config_content)
-        .map_err(|e| KafkaError::ConfigError(format!("解析配置文件失败: {}", e)))?;
-
-    KafkaProducer::new(config)
+    KafkaProducer::new(KafkaProducerConfig::from_yaml_file(config_path)?)
 }
 
 /// 便捷函数：从配置文件创建消费者
 pub fn create_consumer_from_config(config_path: &str) -> KafkaResult<KafkaConsumer> {
-    let config_content = std::fs::read_to_string(config_path)
-        .map_err(|e| KafkaError::ConfigError(format!("读取配置文件失败: {}", e)))?;
-
-    let config: KafkaConsumerConfig = serde_yaml::from_str(&config_content)
-        .map_err(|e| KafkaError::ConfigError(format!("解析配置文件失败: {}", e)))?;
-
-    KafkaConsumer::new(config)
+    KafkaConsumer::new(KafkaConsumerConfig::from_yaml_file(config_path)?)
 }
 
 #[cfg(test)]
@@ -7,7 +7,7 @@
 //! - Web 框架集成（基于 Axum）
 //! - 认证和授权
 //! - 统一错误处理
-//! - 配置管理
+//! - 配置管理（[`AppConfig`] 从单个 `app.yaml` 加载全部模块配置）
 //!
 //! ## Features
 //!
@@ -24,6 +24,9 @@
 //! clamber-web-core = { version = "0.1.1", features = ["database", "redis"] }
 //! ```
 
+pub mod app_config;
+pub mod env_interpolation;
+
 #[cfg(feature = "database")]
 pub mod database;
 
@@ -37,6 +40,9 @@ pub mod kafka;
 pub mod proxy;
 
 // 重新导出主要模块
+pub use app_config::{AppConfig, AppConfigError, AppConfigResult};
+pub use env_interpolation::expand_env_vars;
+
 #[cfg(feature = "database")]
 pub use database::*;
 
@@ -0,0 +1,177 @@
+//! 代理访问日志模块
+//!
+//! 在请求结束时通过 `tracing` 输出访问日志，包含请求方法、路径、匹配的 location、
+//! 选中的上游、响应状态和耗时
+
+use crate::proxy::load_balancer::UpstreamSelection;
+use crate::proxy::proxy_config::LogFormat;
+use crate::proxy::response_cache::CacheFill;
+use pingora::proxy::Session;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// 代理请求上下文，贯穿整个过滤器链
+#[derive(Debug)]
+pub struct ProxyCtx {
+    /// 请求开始时间
+    pub start: Instant,
+    /// 匹配的 location 路径
+    pub location: Option<String>,
+    /// 选中的上游服务器
+    pub upstream: Option<UpstreamSelection>,
+    /// 是否为允许转发的 WebSocket 升级请求
+    pub websocket: bool,
+    /// 未命中缓存、等待写入缓存的响应
+    pub(crate) cache: Option<CacheFill>,
+}
+
+impl ProxyCtx {
+    /// 创建上下文并记录开始时间
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            location: None,
+            upstream: None,
+            websocket: false,
+            cache: None,
+        }
+    }
+
+    /// 请求开始至今的耗时
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Default for ProxyCtx {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 一条访问日志
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessLogEntry {
+    /// 客户端地址
+    pub client: String,
+    /// 请求方法
+    pub method: String,
+    /// 请求路径（包含查询字符串）
+    pub path: String,
+    /// 匹配的 location
+    pub location: Option<String>,
+    /// 选中的上游服务器
+    pub upstream: Option<String>,
+    /// 响应状态码，未写出响应时为 0
+    pub status: u16,
+    /// 耗时
+    pub latency: Duration,
+}
+
+impl AccessLogEntry {
+    /// 从会话与上下文构建访问日志
+    pub fn from_session(session: &Session, ctx: &ProxyCtx) -> Self {
+        let request = session.req_header();
+        Self {
+            client: session
+                .client_addr()
+                .map(|addr| addr.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            method: request.method.to_string(),
+            path: request
+                .uri
+                .path_and_query()
+                .map(|pq| pq.to_string())
+                .unwrap_or_else(|| request.uri.path().to_string()),
+            location: ctx.location.clone(),
+            upstream: ctx.upstream.as_ref().map(|s| s.server.clone()),
+            status: session
+                .response_written()
+                .map(|response| response.status.as_u16())
+                .unwrap_or(0),
+            latency: ctx.elapsed(),
+        }
+    }
+
+    /// 按指定格式输出
+    pub fn format(&self, format: LogFormat) -> String {
+        let latency_ms = self.latency.as_secs_f64() * 1000.0;
+        match format {
+            LogFormat::Combined => format!(
+                "{} \"{} {}\" {} location={} upstream={} {:.3}ms",
+                self.client,
+                self.method,
+                self.path,
+                self.status,
+                self.location.as_deref().unwrap_or("-"),
+                self.upstream.as_deref().unwrap_or("-"),
+                latency_ms
+            ),
+            LogFormat::Json => serde_json::json!({
+                "client": self.client,
+                "method": self.method,
+                "path": self.path,
+                "location": self.location,
+                "upstream": self.upstream,
+                "status": self.status,
+                "latency_ms": latency_ms,
+            })
+            .to_string(),
+        }
+    }
+}
+
+/// 输出一条访问日志
+pub fn log_access(format: LogFormat, session: &Session, ctx: &ProxyCtx) {
+    let entry = AccessLogEntry::from_session(session, ctx);
+    info!(target: "clamber_web_core::proxy::access", "{}", entry.format(format));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> AccessLogEntry {
+        AccessLogEntry {
+            client: "127.0.0.1:50000".to_string(),
+            method: "GET".to_string(),
+            path: "/api/kafka/foo?x=1".to_string(),
+            location: Some("/api/kafka/".to_string()),
+            upstream: Some("127.0.0.1:3000".to_string()),
+            status: 200,
+            latency: Duration::from_micros(1500),
+        }
+    }
+
+    #[test]
+    fn test_ctx_captures_timing_across_phases() {
+        let mut ctx = ProxyCtx::new();
+        let start = ctx.start;
+
+        // 模拟过滤器链中的各阶段修改上下文
+        std::thread::sleep(Duration::from_millis(20));
+        ctx.location = Some("/api/".to_string());
+        std::thread::sleep(Duration::from_millis(10));
+
+        assert_eq!(ctx.start, start);
+        assert!(ctx.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn test_combined_format() {
+        assert_eq!(
+            entry().format(LogFormat::Combined),
+            "127.0.0.1:50000 \"GET /api/kafka/foo?x=1\" 200 location=/api/kafka/ upstream=127.0.0.1:3000 1.500ms"
+        );
+    }
+
+    #[test]
+    fn test_json_format() {
+        let line = entry().format(LogFormat::Json);
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["method"], "GET");
+        assert_eq!(value["status"], 200);
+        assert_eq!(value["upstream"], "127.0.0.1:3000");
+        assert_eq!(value["latency_ms"], 1.5);
+    }
+}
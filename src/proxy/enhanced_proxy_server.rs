@@ -3,16 +3,23 @@
 //! 支持路由到 Kafka API 和静态文件服务的增强代理服务器
 
 use crate::proxy::enhanced_proxy_service::EnhancedProxyService;
+use crate::proxy::health_check::HealthChecker;
 use crate::proxy::proxy_config::ProxyConfig;
+use crate::proxy::proxy_server::{ProxyShutdownHandle, add_listener, run_server};
+use crate::proxy::route_table::ProxyConfigHandle;
 use pingora::Result;
 use pingora::proxy::http_proxy_service;
 use pingora::server::Server;
+use pingora::services::background::background_service;
+use std::future::Future;
 use std::sync::Arc;
 
 /// 增强的代理服务器
 pub struct EnhancedProxyServer {
     config: Arc<ProxyConfig>,
     server: Server,
+    shutdown: ProxyShutdownHandle,
+    routes: ProxyConfigHandle,
 }
 
 impl EnhancedProxyServer {
@@ -20,24 +27,39 @@ impl EnhancedProxyServer {
     pub fn new(config: ProxyConfig) -> Result<Self> {
         let server = Server::new(None)?;
         Ok(Self {
+            routes: ProxyConfigHandle::new(config.clone()),
             config: Arc::new(config),
             server,
+            shutdown: ProxyShutdownHandle::default(),
         })
     }
 
-    /// 启动增强代理服务器
+    /// 启动增强代理服务器，阻塞直到调用 [`stop`](Self::stop) 或关闭句柄
     pub fn start(&mut self) -> Result<()> {
-        self.server.bootstrap();
+        self.run_until(std::future::pending())
+    }
 
+    /// 启动增强代理服务器，`shutdown` 完成时关闭并返回
+    pub fn run_until<F>(&mut self, shutdown: F) -> Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
         // 创建增强代理服务
-        let proxy_service = EnhancedProxyService::new((*self.config).clone());
+        let proxy_service = EnhancedProxyService::with_handle(self.routes.clone());
+        let health_checker = HealthChecker::for_routes(self.routes.clone());
         let mut service = http_proxy_service(&self.server.configuration, proxy_service);
 
-        // 关键修复：告诉服务监听指定的 TCP 地址
-        service.add_tcp(&self.config.listen);
+        // 按配置注册 TCP 或 TLS 监听，配置有误时在启动前直接返回错误
+        add_listener(&mut service, &self.config)?;
+        self.server.bootstrap();
+
         // 添加服务到服务器
         self.server.add_service(service);
 
+        // 启用了健康检查的上游由后台任务定期探测，热更新后的上游同样会被检查
+        self.server
+            .add_service(background_service("upstream health check", health_checker));
+
         println!("Enhanced proxy server starting on {}", self.config.listen);
         println!("Server name: {}", self.config.server_name);
         println!("SSL enabled: {}", self.config.ssl);
@@ -64,16 +86,31 @@ impl EnhancedProxyServer {
 
         // 启动服务器
         let server = std::mem::replace(&mut self.server, Server::new(None)?);
-        server.run(pingora::server::RunArgs::default());
+        run_server(server, &self.shutdown, shutdown);
         Ok(())
     }
 
     /// 停止增强代理服务器
-    pub fn stop(&mut self) {
-        println!("Stopping enhanced proxy server...");
+    pub fn stop(&self) {
+        self.shutdown.shutdown();
+    }
+
+    /// 获取关闭句柄，用于在其他线程中停止阻塞在 [`start`](Self::start) 的服务器
+    pub fn shutdown_handle(&self) -> ProxyShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// 获取配置句柄，用于在其他线程中热更新运行中服务器的上游与 location
+    pub fn config_handle(&self) -> ProxyConfigHandle {
+        self.routes.clone()
+    }
+
+    /// 校验并原子替换配置，下一个请求开始生效
+    pub fn reload_config(&self, config: ProxyConfig) -> std::result::Result<(), String> {
+        self.routes.reload_config(config)
     }
 
-    /// 获取配置
+    /// 获取创建时的配置，热更新后的配置通过 [`ProxyConfigHandle::config`] 获取
     pub fn get_config(&self) -> &ProxyConfig {
         &self.config
     }
@@ -1,100 +1,207 @@
 //! 增强的代理服务模块
 //!
-//! 支持路由到 Kafka API 和静态文件服务的增强代理实现
+//! 支持路由到 Kafka API 和静态文件服务的增强代理实现，
+//! 配置了 `cache_ttl_secs` 的 location 会缓存 GET 请求的 200 响应
 
+use crate::proxy::access_log::{ProxyCtx, log_access};
+use crate::proxy::header_rules::{apply_request_headers, apply_response_headers, remote_addr};
+use crate::proxy::ip_acl::respond_forbidden;
+use crate::proxy::load_balancer::LoadBalancer;
 use crate::proxy::proxy_config::{LocationConfig, LocationType, ProxyConfig};
-use crate::proxy::static_file_service::StaticFileService;
+use crate::proxy::rate_limit::respond_too_many_requests;
+use crate::proxy::response_cache::{
+    CacheFill, CachedResponse, MAX_CACHED_BODY_BYTES, ResponseCache, X_CACHE, cache_key,
+    is_cacheable,
+};
+use crate::proxy::route_table::{ProxyConfigHandle, RouteTable};
+use crate::proxy::static_file_service::StaticFileResponse;
+use crate::proxy::websocket::{forward_upgrade, is_websocket_upgrade, strip_upgrade};
 use async_trait::async_trait;
+use axum::body::Bytes;
 use pingora::Result;
 use pingora::http::{RequestHeader, ResponseHeader, StatusCode};
 use pingora::proxy::ProxyHttp;
 use pingora::proxy::Session;
 use pingora::upstreams::peer::HttpPeer;
-use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use tracing::error;
 
 /// 增强的代理服务实现
 pub struct EnhancedProxyService {
-    config: Arc<ProxyConfig>,
-    static_services: HashMap<String, StaticFileService>,
+    routes: ProxyConfigHandle,
+    cache: ResponseCache,
 }
 
 impl EnhancedProxyService {
     /// 创建新的增强代理服务
     pub fn new(config: ProxyConfig) -> Self {
-        let mut static_services = HashMap::new();
-
-        // 为每个静态文件位置创建静态文件服务
-        for location in &config.locations {
-            if let LocationType::Static = location.location_type {
-                if let Some(ref root) = location.root {
-                    static_services.insert(location.path.clone(), StaticFileService::new(root));
-                }
-            }
-        }
+        Self::with_handle(ProxyConfigHandle::new(config))
+    }
 
+    /// 使用共享的配置句柄创建服务，通过句柄热更新配置
+    pub fn with_handle(routes: ProxyConfigHandle) -> Self {
         Self {
-            config: Arc::new(config),
-            static_services,
+            routes,
+            cache: ResponseCache::default(),
         }
     }
 
-    /// 根据请求路径找到匹配的位置配置
-    fn find_location<'a>(&'a self, path: &str) -> Option<&'a LocationConfig> {
-        // 按路径长度降序排序，优先匹配更具体的路径
-        let mut locations: Vec<_> = self.config.locations.iter().collect();
-        locations.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
+    /// 获取配置句柄，可以在服务交给 pingora 后继续热更新配置
+    pub fn config_handle(&self) -> ProxyConfigHandle {
+        self.routes.clone()
+    }
 
-        for location in locations {
-            if path.starts_with(&location.path) {
-                return Some(location);
-            }
-        }
-        None
+    /// 校验并原子替换配置，下一个请求开始生效
+    pub fn reload_config(&self, config: ProxyConfig) -> std::result::Result<(), String> {
+        self.routes.reload_config(config)
     }
 
-    /// 获取上游服务器配置
-    fn get_upstream_config(
-        &self,
-        upstream_name: &str,
-    ) -> Option<&crate::proxy::proxy_config::UpstreamConfig> {
-        self.config.upstreams.get(upstream_name)
+    /// 获取负载均衡器，供健康检查等后台任务共享
+    pub fn load_balancer(&self) -> Arc<LoadBalancer> {
+        self.routes.load().load_balancer.clone()
+    }
+
+    /// 响应缓存，热更新配置后继续保留已缓存的响应直到过期
+    pub fn response_cache(&self) -> &ResponseCache {
+        &self.cache
     }
 
-    /// 选择上游服务器（简单的轮询实现）
-    fn select_upstream_server<'a>(
+    /// 配置了缓存的 location 命中缓存时直接响应并返回 true；
+    /// 未命中时记录缓存键，等待上游响应写入缓存
+    async fn serve_from_cache(
         &self,
-        upstream_config: &'a crate::proxy::proxy_config::UpstreamConfig,
-    ) -> Option<&'a String> {
-        // 这里可以实现更复杂的负载均衡策略
-        // 目前使用简单的轮询
-        upstream_config.servers.first()
+        session: &mut Session,
+        ctx: &mut ProxyCtx,
+        location: &LocationConfig,
+    ) -> Result<bool> {
+        let Some(ttl) = location.cache_ttl_secs.filter(|ttl| *ttl > 0) else {
+            return Ok(false);
+        };
+        if ctx.websocket {
+            return Ok(false);
+        }
+        let Some(key) = cache_key(session.req_header()) else {
+            return Ok(false);
+        };
+        let Some(cached) = self.cache.get(&key) else {
+            ctx.cache = Some(CacheFill::new(key, Duration::from_secs(ttl)));
+            return Ok(false);
+        };
+
+        ctx.location = Some(location.path.clone());
+        let mut header = cached.header;
+        header.remove_header("Transfer-Encoding");
+        header.insert_header("Content-Length", cached.body.len().to_string())?;
+        if let Some(headers) = &location.response_headers {
+            let client = remote_addr(session);
+            apply_response_headers(headers, &mut header, client.as_deref())?;
+        }
+        header.insert_header(X_CACHE, "HIT")?;
+        session
+            .write_response_header(Box::new(header), false)
+            .await?;
+        session.write_response_body(Some(cached.body), true).await?;
+        Ok(true)
+    }
+
+    /// 处理静态文件请求，返回要写回客户端的响应
+    async fn serve_static(
+        routes: &RouteTable,
+        location: &LocationConfig,
+        path: &str,
+    ) -> StaticFileResponse {
+        let Some(service) = routes.static_services.get(&location.path) else {
+            return StaticFileResponse::not_found();
+        };
+
+        // 去掉 location 前缀，剩余部分相对于 root 目录
+        let relative = path.strip_prefix(location.path.as_str()).unwrap_or(path);
+        match service.serve_file(relative).await {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Failed to serve static file {}: {}", path, e);
+                StaticFileResponse {
+                    status: 500,
+                    content_type: "text/plain",
+                    body: b"Internal Server Error".to_vec(),
+                }
+            }
+        }
     }
 }
 
 #[async_trait]
 impl ProxyHttp for EnhancedProxyService {
-    type CTX = ();
+    type CTX = ProxyCtx;
 
     fn new_ctx(&self) -> Self::CTX {
-        ()
+        ProxyCtx::new()
+    }
+
+    async fn request_filter(&self, session: &mut Session, ctx: &mut Self::CTX) -> Result<bool>
+    where
+        Self::CTX: Send + Sync,
+    {
+        let path = session.req_header().uri.path().to_string();
+        let routes = self.routes.load();
+        let Some(location) = routes.find_location(&path) else {
+            return Ok(false);
+        };
+        if routes.is_ip_denied(location, session) {
+            ctx.location = Some(location.path.clone());
+            respond_forbidden(session).await?;
+            return Ok(true);
+        }
+        if routes.is_rate_limited(location, session) {
+            ctx.location = Some(location.path.clone());
+            respond_too_many_requests(session).await?;
+            return Ok(true);
+        }
+        ctx.websocket = location.websocket && is_websocket_upgrade(session.req_header());
+        if !matches!(location.location_type, LocationType::Static) {
+            return self.serve_from_cache(session, ctx, location).await;
+        }
+
+        // 静态文件直接在此处响应，不再转发到上游
+        ctx.location = Some(location.path.clone());
+        let file = Self::serve_static(&routes, location, &path).await;
+        let status = StatusCode::from_u16(file.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut header = ResponseHeader::build(status, Some(2))?;
+        header.insert_header("Content-Type", file.content_type)?;
+        header.insert_header("Content-Length", file.body.len().to_string())?;
+        if let Some(headers) = &location.response_headers {
+            let client = remote_addr(session);
+            apply_response_headers(headers, &mut header, client.as_deref())?;
+        }
+        session
+            .write_response_header(Box::new(header), false)
+            .await?;
+        session
+            .write_response_body(Some(Bytes::from(file.body)), true)
+            .await?;
+
+        Ok(true)
     }
 
     async fn upstream_peer(
         &self,
         session: &mut Session,
-        _ctx: &mut Self::CTX,
+        ctx: &mut Self::CTX,
     ) -> Result<Box<HttpPeer>> {
         let path = session.req_header().uri.path();
+        let routes = self.routes.load();
 
         // 查找匹配的位置配置
-        let location = self.find_location(path).ok_or_else(|| {
+        let location = routes.find_location(path).ok_or_else(|| {
             pingora::Error::explain(
                 pingora::ErrorType::InternalError,
                 "No matching location found",
             )
         })?;
 
+        ctx.location = Some(location.path.clone());
+
         match location.location_type {
             LocationType::Proxy => {
                 // 代理到上游服务器
@@ -105,25 +212,16 @@ impl ProxyHttp for EnhancedProxyService {
                     )
                 })?;
 
-                let upstream_config = self.get_upstream_config(upstream_name).ok_or_else(|| {
-                    pingora::Error::explain(pingora::ErrorType::InternalError, "Upstream not found")
-                })?;
-
-                let server = self
-                    .select_upstream_server(upstream_config)
-                    .ok_or_else(|| {
-                        pingora::Error::explain(
-                            pingora::ErrorType::InternalError,
-                            "No servers in upstream",
-                        )
-                    })?;
-
-                let peer = HttpPeer::new(server, self.config.ssl, self.config.server_name.clone());
+                let (peer, selection) = routes.select_peer(upstream_name)?;
+                // 重试时会再次选择上游，先归还上一次的选择
+                if let Some(previous) = ctx.upstream.replace(selection) {
+                    routes.load_balancer.release(&previous);
+                }
                 Ok(Box::new(peer))
             }
             LocationType::Static => {
-                // 静态文件服务 - 返回一个虚拟的 peer
-                // 实际的文件服务在 response_filter 中处理
+                // 静态文件已在 request_filter 中直接响应，不会走到这里
+                // 返回一个虚拟的 peer 作为兜底
                 let peer = HttpPeer::new("127.0.0.1:1", false, "static".to_string());
                 Ok(Box::new(peer))
             }
@@ -134,37 +232,31 @@ impl ProxyHttp for EnhancedProxyService {
         &self,
         session: &mut Session,
         upstream_request: &mut RequestHeader,
-        _ctx: &mut Self::CTX,
+        ctx: &mut Self::CTX,
     ) -> Result<()> {
         let path = session.req_header().uri.path();
+        let routes = self.routes.load();
 
         // 查找匹配的位置配置
-        if let Some(location) = self.find_location(path) {
+        if let Some(location) = routes.find_location(path) {
             match location.location_type {
                 LocationType::Proxy => {
-                    // 修改请求路径，移除 location 前缀
-                    if let Some(proxy_pass) = &location.proxy_pass {
-                        if let Some(upstream_config) = self.get_upstream_config(proxy_pass) {
-                            if let Some(server) = self.select_upstream_server(upstream_config) {
-                                // 构建新的 URI
-                                let new_path = if path.len() > location.path.len() {
-                                    &path[location.path.len()..]
-                                } else {
-                                    "/"
-                                };
-
-                                // 解析服务器地址
-                                let server_parts: Vec<&str> = server.split(':').collect();
-                                let host = server_parts[0];
-                                let port = server_parts.get(1).unwrap_or(&"80");
-
-                                // 构建新的 URI
-                                let new_uri = format!("http://{}:{}{}", host, port, new_path);
-                                // 注意：这里需要根据实际的 Pingora API 来调整 URI 修改方式
-                                println!("Would proxy to: {}", new_uri);
-                            }
+                    // 修改请求路径，移除 location 前缀并保留查询字符串
+                    if location.proxy_pass.is_some() {
+                        if let Some(new_uri) = location.rewrite_uri(&upstream_request.uri) {
+                            upstream_request.set_uri(new_uri);
                         }
                     }
+                    if let Some(headers) = &location.request_headers {
+                        let client = remote_addr(session);
+                        apply_request_headers(headers, upstream_request, client.as_deref())?;
+                    }
+                    // 仅开启 websocket 的 location 转发升级请求，其余按普通 HTTP 请求转发
+                    if ctx.websocket {
+                        forward_upgrade(upstream_request)?;
+                    } else if is_websocket_upgrade(upstream_request) {
+                        strip_upgrade(upstream_request);
+                    }
                 }
                 LocationType::Static => {
                     // 静态文件请求不需要修改
@@ -175,4 +267,230 @@ impl ProxyHttp for EnhancedProxyService {
         println!("Proxying request to: {:?}", upstream_request.uri);
         Ok(())
     }
+
+    async fn response_filter(
+        &self,
+        session: &mut Session,
+        upstream_response: &mut ResponseHeader,
+        ctx: &mut Self::CTX,
+    ) -> Result<()>
+    where
+        Self::CTX: Send + Sync,
+    {
+        // 缓存未经 location 响应头改写的原始响应，命中时再按当前请求改写
+        if let Some(fill) = ctx.cache.as_mut() {
+            if is_cacheable(upstream_response) {
+                fill.header = Some(upstream_response.clone());
+            }
+        }
+
+        let routes = self.routes.load();
+        let headers = ctx
+            .location
+            .as_deref()
+            .and_then(|path| routes.config.location(path))
+            .and_then(|location| location.response_headers.as_ref());
+        if let Some(headers) = headers {
+            let client = remote_addr(session);
+            apply_response_headers(headers, upstream_response, client.as_deref())?;
+        }
+        if ctx.cache.is_some() {
+            upstream_response.insert_header(X_CACHE, "MISS")?;
+        }
+        Ok(())
+    }
+
+    fn response_body_filter(
+        &self,
+        _session: &mut Session,
+        body: &mut Option<Bytes>,
+        end_of_stream: bool,
+        ctx: &mut Self::CTX,
+    ) -> Result<Option<Duration>>
+    where
+        Self::CTX: Send + Sync,
+    {
+        let Some(fill) = ctx.cache.as_mut() else {
+            return Ok(None);
+        };
+        if fill.header.is_none() {
+            return Ok(None);
+        }
+        if let Some(chunk) = body {
+            if fill.body.len() + chunk.len() > MAX_CACHED_BODY_BYTES {
+                // 响应体过大，放弃缓存
+                fill.header = None;
+                fill.body = Vec::new();
+                return Ok(None);
+            }
+            fill.body.extend_from_slice(chunk);
+        }
+
+        if end_of_stream {
+            let Some(fill) = ctx.cache.take() else {
+                return Ok(None);
+            };
+            if let Some(header) = fill.header {
+                let response = CachedResponse {
+                    header,
+                    body: Bytes::from(fill.body),
+                };
+                self.cache.insert(fill.key, response, fill.ttl);
+            }
+        }
+        Ok(None)
+    }
+
+    async fn logging(&self, session: &mut Session, _e: Option<&pingora::Error>, ctx: &mut Self::CTX)
+    where
+        Self::CTX: Send + Sync,
+    {
+        // 请求结束，归还上游选择以更新活跃连接数
+        let routes = self.routes.load();
+        if let Some(selection) = ctx.upstream.as_ref() {
+            routes.load_balancer.release(selection);
+        }
+        log_access(routes.config.log_format, session, ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::proxy::enhanced_proxy_server::EnhancedProxyServer;
+    use crate::proxy::proxy_config::{
+        LocationConfig, LocationType, MatchType, ProxyConfig, UpstreamConfig,
+    };
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// 返回请求序号作为响应体的上游，记录收到的请求数
+    async fn counting_upstream() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut head = Vec::new();
+                let mut byte = [0u8; 1];
+                while !head.ends_with(b"\r\n\r\n") {
+                    if stream.read_exact(&mut byte).await.is_err() {
+                        break;
+                    }
+                    head.push(byte[0]);
+                }
+                let body = (counter.fetch_add(1, Ordering::SeqCst) + 1).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (addr, hits)
+    }
+
+    /// 发送一个 GET 请求，返回完整的响应文本
+    async fn get(listen: &str, path: &str) -> String {
+        let mut stream = TcpStream::connect(listen).await.unwrap();
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: cache.local\r\nConnection: close\r\n\r\n",
+            path
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_get_served_from_cache_until_expiry() {
+        let (upstream, hits) = counting_upstream().await;
+        let listen = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+
+        let config = ProxyConfig {
+            server_name: "cache.local".to_string(),
+            listen: listen.clone(),
+            ssl: false,
+            ssl_cert: None,
+            ssl_key: None,
+            upstreams: HashMap::from([(
+                "api".to_string(),
+                UpstreamConfig {
+                    servers: vec![upstream],
+                    health_check_interval_secs: 0,
+                    ..UpstreamConfig::default()
+                },
+            )]),
+            locations: vec![LocationConfig {
+                path: "/api/".to_string(),
+                location_type: LocationType::Proxy,
+                match_type: MatchType::Prefix,
+                proxy_pass: Some("api".to_string()),
+                root: None,
+                index: None,
+                request_headers: None,
+                response_headers: None,
+                rate_limit: None,
+                websocket: false,
+                cache_ttl_secs: Some(1),
+                allow: None,
+                deny: None,
+            }],
+            log_format: Default::default(),
+        };
+        std::thread::spawn(move || EnhancedProxyServer::new(config).unwrap().start());
+
+        // 等待代理开始监听
+        for _ in 0..50 {
+            if TcpStream::connect(&listen).await.is_ok() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        let first = get(&listen, "/api/items?page=1").await;
+        assert!(first.starts_with("HTTP/1.1 200"), "{}", first);
+        assert!(
+            first.to_ascii_lowercase().contains("x-cache: miss"),
+            "{}",
+            first
+        );
+        assert!(first.ends_with("\r\n\r\n1"), "{}", first);
+
+        let second = get(&listen, "/api/items?page=1").await;
+        assert!(
+            second.to_ascii_lowercase().contains("x-cache: hit"),
+            "{}",
+            second
+        );
+        assert!(second.ends_with("\r\n\r\n1"), "{}", second);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        // 查询字符串不同时使用不同的缓存键
+        let other = get(&listen, "/api/items?page=2").await;
+        assert!(other.ends_with("\r\n\r\n2"), "{}", other);
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let expired = get(&listen, "/api/items?page=1").await;
+        assert!(
+            expired.to_ascii_lowercase().contains("x-cache: miss"),
+            "{}",
+            expired
+        );
+        assert!(expired.ends_with("\r\n\r\n3"), "{}", expired);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
 }
@@ -0,0 +1,122 @@
+//! 代理头部改写模块
+//!
+//! 按 location 配置为上游请求和下游响应设置头部，类似 Nginx 的 `proxy_set_header` 与 `add_header`。
+//! 头部值支持变量 `$remote_addr`（客户端 IP），用于 `X-Forwarded-For` 时追加到已有值之后
+
+use pingora::Result;
+use pingora::http::{RequestHeader, ResponseHeader};
+use pingora::proxy::Session;
+use std::collections::HashMap;
+
+/// 客户端地址变量
+pub const REMOTE_ADDR: &str = "$remote_addr";
+
+/// X-Forwarded-For 头部名称
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// 获取客户端 IP（不含端口）
+pub fn remote_addr(session: &Session) -> Option<String> {
+    session.client_addr().map(|addr| match addr.as_inet() {
+        Some(inet) => inet.ip().to_string(),
+        None => addr.to_string(),
+    })
+}
+
+/// 替换头部值中的变量，客户端地址未知时替换为空字符串
+fn resolve_value(value: &str, remote_addr: Option<&str>) -> String {
+    value.replace(REMOTE_ADDR, remote_addr.unwrap_or_default())
+}
+
+/// 为发往上游的请求设置头部
+///
+/// `X-Forwarded-For` 已存在时（例如经过多级代理）将新值追加到末尾
+pub fn apply_request_headers(
+    headers: &HashMap<String, String>,
+    request: &mut RequestHeader,
+    remote_addr: Option<&str>,
+) -> Result<()> {
+    for (name, value) in headers {
+        let mut value = resolve_value(value, remote_addr);
+        if name.eq_ignore_ascii_case(X_FORWARDED_FOR) {
+            let existing = request
+                .headers
+                .get(X_FORWARDED_FOR)
+                .and_then(|existing| existing.to_str().ok());
+            if let Some(existing) = existing.filter(|existing| !existing.is_empty()) {
+                value = format!("{}, {}", existing, value);
+            }
+        }
+        request.insert_header(name.clone(), value)?;
+    }
+    Ok(())
+}
+
+/// 为返回给客户端的响应设置头部，已存在的同名头部会被覆盖
+pub fn apply_response_headers(
+    headers: &HashMap<String, String>,
+    response: &mut ResponseHeader,
+    remote_addr: Option<&str>,
+) -> Result<()> {
+    for (name, value) in headers {
+        response.insert_header(name.clone(), resolve_value(value, remote_addr))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_request_headers_applied_to_upstream_request() {
+        let config = headers(&[
+            ("X-Forwarded-For", REMOTE_ADDR),
+            ("X-Real-IP", REMOTE_ADDR),
+            ("X-Service", "kafka-api"),
+        ]);
+        let mut request = RequestHeader::build("GET", b"/foo", None).unwrap();
+
+        apply_request_headers(&config, &mut request, Some("10.0.0.7")).unwrap();
+
+        assert_eq!(request.headers["x-forwarded-for"], "10.0.0.7");
+        assert_eq!(request.headers["x-real-ip"], "10.0.0.7");
+        assert_eq!(request.headers["x-service"], "kafka-api");
+    }
+
+    #[test]
+    fn test_forwarded_for_appends_to_existing_chain() {
+        let config = headers(&[("X-Forwarded-For", REMOTE_ADDR)]);
+        let mut request = RequestHeader::build("GET", b"/foo", None).unwrap();
+        request
+            .insert_header("X-Forwarded-For", "203.0.113.1")
+            .unwrap();
+
+        apply_request_headers(&config, &mut request, Some("10.0.0.7")).unwrap();
+
+        assert_eq!(request.headers["x-forwarded-for"], "203.0.113.1, 10.0.0.7");
+    }
+
+    #[test]
+    fn test_response_headers_override_upstream_values() {
+        let config = headers(&[
+            ("Access-Control-Allow-Origin", "*"),
+            ("X-Frame-Options", "DENY"),
+        ]);
+        let mut response = ResponseHeader::build(200, None).unwrap();
+        response
+            .insert_header("X-Frame-Options", "SAMEORIGIN")
+            .unwrap();
+
+        apply_response_headers(&config, &mut response, None).unwrap();
+
+        assert_eq!(response.headers["access-control-allow-origin"], "*");
+        assert_eq!(response.headers["x-frame-options"], "DENY");
+    }
+}
@@ -0,0 +1,335 @@
+//! 上游健康检查模块
+//!
+//! 后台定期检查每个上游服务器：配置了 `health_check_path` 时发送 HTTP GET，
+//! 否则只检查 TCP 连接。检查结果写回 [`LoadBalancer`]，不健康的服务器不会被选中。
+//! 通过 [`HealthChecker::for_routes`] 创建的检查器每轮都从路由表读取当前的负载均衡器与上游列表，
+//! 配置热更新后新的上游同样会被检查
+
+use crate::proxy::load_balancer::LoadBalancer;
+use crate::proxy::proxy_config::UpstreamConfig;
+use crate::proxy::route_table::ProxyConfigHandle;
+use async_trait::async_trait;
+use pingora::server::ShutdownWatch;
+use pingora::services::background::BackgroundService;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tracing::{info, warn};
+
+/// 单次探测的超时时间
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 两轮检查之间的最长等待时间，用于及时发现配置热更新
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 健康检查的目标
+enum Targets {
+    /// 固定的负载均衡器与上游列表
+    Fixed {
+        load_balancer: Arc<LoadBalancer>,
+        upstreams: HashMap<String, UpstreamConfig>,
+    },
+    /// 每轮从路由表读取当前的负载均衡器与上游列表
+    Routes(ProxyConfigHandle),
+}
+
+/// 上游健康检查器
+pub struct HealthChecker {
+    targets: Targets,
+}
+
+impl HealthChecker {
+    /// 为固定的负载均衡器创建健康检查器，`health_check_interval_secs` 为 0 的上游不做检查
+    pub fn new(
+        load_balancer: Arc<LoadBalancer>,
+        upstreams: &HashMap<String, UpstreamConfig>,
+    ) -> Self {
+        Self {
+            targets: Targets::Fixed {
+                load_balancer,
+                upstreams: checked_upstreams(upstreams),
+            },
+        }
+    }
+
+    /// 创建跟随路由表的健康检查器，配置热更新替换负载均衡器后检查新的上游
+    pub fn for_routes(routes: ProxyConfigHandle) -> Self {
+        Self {
+            targets: Targets::Routes(routes),
+        }
+    }
+
+    /// 是否有需要检查的上游；跟随路由表时热更新可能新增上游，总是返回 true
+    pub fn is_enabled(&self) -> bool {
+        match &self.targets {
+            Targets::Fixed { upstreams, .. } => !upstreams.is_empty(),
+            Targets::Routes(_) => true,
+        }
+    }
+
+    /// 当前的负载均衡器与需要检查的上游
+    fn load(&self) -> (Arc<LoadBalancer>, HashMap<String, UpstreamConfig>) {
+        match &self.targets {
+            Targets::Fixed {
+                load_balancer,
+                upstreams,
+            } => (load_balancer.clone(), upstreams.clone()),
+            Targets::Routes(routes) => {
+                let routes = routes.load();
+                (
+                    routes.load_balancer.clone(),
+                    checked_upstreams(&routes.config.upstreams),
+                )
+            }
+        }
+    }
+
+    /// 检查一个上游的所有服务器
+    pub async fn check_upstream(&self, name: &str) {
+        let (load_balancer, upstreams) = self.load();
+        if let Some(config) = upstreams.get(name) {
+            check(&load_balancer, name, config).await;
+        }
+    }
+}
+
+#[async_trait]
+impl BackgroundService for HealthChecker {
+    async fn start(&self, mut shutdown: ShutdownWatch) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut next_due: HashMap<String, Instant> = HashMap::new();
+        let mut current: Option<Arc<LoadBalancer>> = None;
+
+        loop {
+            let (load_balancer, upstreams) = self.load();
+            // 上游变更后负载均衡器被替换，立即检查新的负载均衡器中的所有上游
+            if !current
+                .as_ref()
+                .is_some_and(|current| Arc::ptr_eq(current, &load_balancer))
+            {
+                next_due.clear();
+                current = Some(load_balancer.clone());
+            }
+            next_due.retain(|name, _| upstreams.contains_key(name));
+
+            let now = Instant::now();
+            for (name, config) in &upstreams {
+                let due = next_due.entry(name.clone()).or_insert(now);
+                if *due <= now {
+                    check(&load_balancer, name, config).await;
+                    let interval = Duration::from_secs(config.health_check_interval_secs);
+                    *due = Instant::now() + interval;
+                }
+            }
+
+            let poll = Instant::now() + RELOAD_POLL_INTERVAL;
+            let earliest = next_due.values().min().map_or(poll, |due| (*due).min(poll));
+            tokio::select! {
+                _ = shutdown.changed() => return,
+                _ = tokio::time::sleep_until(earliest.into()) => {}
+            }
+        }
+    }
+}
+
+/// 过滤出需要检查的上游，`health_check_interval_secs` 为 0 的上游不做检查
+fn checked_upstreams(
+    upstreams: &HashMap<String, UpstreamConfig>,
+) -> HashMap<String, UpstreamConfig> {
+    upstreams
+        .iter()
+        .filter(|(_, config)| config.health_check_interval_secs > 0)
+        .map(|(name, config)| (name.clone(), config.clone()))
+        .collect()
+}
+
+/// 检查一个上游的所有服务器并写回负载均衡器
+async fn check(load_balancer: &LoadBalancer, name: &str, config: &UpstreamConfig) {
+    for server in &config.servers {
+        let healthy = probe(server, config.health_check_path.as_deref()).await;
+        if load_balancer.report_health(name, server, healthy) {
+            if healthy {
+                info!("Upstream server recovered: {} ({})", server, name);
+            } else {
+                warn!("Upstream server marked unhealthy: {} ({})", server, name);
+            }
+        }
+    }
+}
+
+/// 探测服务器是否可用
+async fn probe(server: &str, path: Option<&str>) -> bool {
+    let Ok(Ok(mut stream)) = tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(server)).await
+    else {
+        return false;
+    };
+
+    let Some(path) = path else {
+        return true;
+    };
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, server
+    );
+    let exchange = async {
+        stream.write_all(request.as_bytes()).await?;
+        let mut buffer = [0u8; 64];
+        let n = stream.read(&mut buffer).await?;
+        Ok::<_, std::io::Error>(parse_status_code(&buffer[..n]))
+    };
+
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, exchange).await,
+        Ok(Ok(Some(status))) if (200..400).contains(&status)
+    )
+}
+
+/// 从响应状态行中解析状态码，例如 `HTTP/1.1 200 OK`
+fn parse_status_code(response: &[u8]) -> Option<u16> {
+    let line = std::str::from_utf8(response).ok()?.lines().next()?;
+    let mut parts = line.split_whitespace();
+    if !parts.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn upstreams(servers: Vec<String>, path: Option<&str>) -> HashMap<String, UpstreamConfig> {
+        let mut upstreams = HashMap::new();
+        upstreams.insert(
+            "backend".to_string(),
+            UpstreamConfig {
+                servers,
+                lb_strategy: "roundrobin".to_string(),
+                health_check_path: path.map(str::to_string),
+                health_check_interval_secs: 1,
+                unhealthy_threshold: 2,
+                upstream_keepalive_secs: None,
+                upstream_connect_timeout_secs: None,
+            },
+        );
+        upstreams
+    }
+
+    /// 获取一个当前没有监听的本地地址
+    async fn downed_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_parse_status_code() {
+        assert_eq!(parse_status_code(b"HTTP/1.1 200 OK\r\n"), Some(200));
+        assert_eq!(
+            parse_status_code(b"HTTP/1.0 503 Unavailable\r\n"),
+            Some(503)
+        );
+        assert_eq!(parse_status_code(b"garbage"), None);
+    }
+
+    #[tokio::test]
+    async fn test_downed_server_is_excluded() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let alive = listener.local_addr().unwrap().to_string();
+        let down = downed_server().await;
+
+        let config = upstreams(vec![alive.clone(), down.clone()], None);
+        let lb = Arc::new(LoadBalancer::new(&config));
+        let checker = HealthChecker::new(lb.clone(), &config);
+
+        checker.check_upstream("backend").await;
+        assert!(lb.is_healthy("backend", &down), "未达到阈值前仍视为健康");
+        checker.check_upstream("backend").await;
+        assert!(!lb.is_healthy("backend", &down));
+        assert!(lb.is_healthy("backend", &alive));
+
+        for _ in 0..4 {
+            let selection = lb.select("backend").unwrap();
+            assert_eq!(selection.server, alive);
+            lb.release(&selection);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_health_check_path() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 256];
+                let _ = stream.read(&mut buffer).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n")
+                    .await;
+            }
+        });
+
+        // TCP 可连接，但健康检查路径返回 503
+        let config = upstreams(vec![server.clone()], Some("/health"));
+        let lb = Arc::new(LoadBalancer::new(&config));
+        let checker = HealthChecker::new(lb.clone(), &config);
+        checker.check_upstream("backend").await;
+        checker.check_upstream("backend").await;
+
+        assert!(!lb.is_healthy("backend", &server));
+        assert!(lb.select("backend").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reloaded_upstreams_are_checked() {
+        use crate::proxy::proxy_config::{LogFormat, ProxyConfig};
+        use pingora::services::background::BackgroundService;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let alive = listener.local_addr().unwrap().to_string();
+        let down = downed_server().await;
+
+        let config = |server: &str| {
+            let mut upstreams = upstreams(vec![server.to_string()], None);
+            upstreams.get_mut("backend").unwrap().unhealthy_threshold = 1;
+            ProxyConfig {
+                server_name: "test".to_string(),
+                listen: "127.0.0.1:8080".to_string(),
+                ssl: false,
+                ssl_cert: None,
+                ssl_key: None,
+                upstreams,
+                locations: Vec::new(),
+                log_format: LogFormat::default(),
+            }
+        };
+        let handle = ProxyConfigHandle::new(config(&alive));
+        let checker = Arc::new(HealthChecker::for_routes(handle.clone()));
+        let (shutdown, shutdown_rx) = tokio::sync::watch::channel(false);
+        let task = tokio::spawn({
+            let checker = checker.clone();
+            async move { checker.start(shutdown_rx).await }
+        });
+
+        // 热更新后上游指向已关闭的端口，新的负载均衡器中的服务器被标记为不健康
+        handle.reload_config(config(&down)).unwrap();
+        let marked = async {
+            while handle.load().load_balancer.is_healthy("backend", &down) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), marked)
+            .await
+            .expect("热更新后的上游未被健康检查");
+        assert!(handle.load().load_balancer.select("backend").is_none());
+
+        shutdown.send(true).unwrap();
+        task.await.unwrap();
+    }
+}
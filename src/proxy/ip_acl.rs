@@ -0,0 +1,178 @@
+//! IP 访问控制模块
+//!
+//! 按 location 的 `allow`/`deny` 列表限制客户端地址，列表项为 CIDR（如 `10.0.0.0/8`）或单个 IP。
+//! 命中 `deny` 的地址总是被拒绝；配置了 `allow` 时，只有命中 `allow` 的地址可以访问，
+//! 被拒绝的请求返回 403
+
+use crate::proxy::proxy_config::{LocationConfig, ProxyConfig};
+use ipnet::IpNet;
+use pingora::Result;
+use pingora::http::ResponseHeader;
+use pingora::proxy::Session;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use tracing::warn;
+
+/// 单个 location 的 IP 访问控制列表
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpAcl {
+    /// 允许访问的地址段，为空时不限制
+    allow: Vec<IpNet>,
+    /// 拒绝访问的地址段，优先于 allow
+    deny: Vec<IpNet>,
+}
+
+impl IpAcl {
+    /// 解析 location 的 allow/deny 列表，任意一项无效时返回错误
+    pub fn new(location: &LocationConfig) -> std::result::Result<Self, String> {
+        let parse = |entries: &Option<Vec<String>>, field: &str| {
+            entries
+                .iter()
+                .flatten()
+                .map(|entry| {
+                    parse_net(entry).ok_or_else(|| {
+                        format!("location {} 的 {} 项 {} 无效", location.path, field, entry)
+                    })
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            allow: parse(&location.allow, "allow")?,
+            deny: parse(&location.deny, "deny")?,
+        })
+    }
+
+    /// 拒绝所有地址的访问控制列表
+    fn deny_all() -> Self {
+        let any: Vec<IpNet> = ["0.0.0.0/0", "::/0"]
+            .iter()
+            .filter_map(|net| net.parse().ok())
+            .collect();
+        // allow 非空使无法获取地址的客户端同样被拒绝
+        Self {
+            allow: any.clone(),
+            deny: any,
+        }
+    }
+
+    /// 是否未配置任何规则
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// 检查客户端地址是否允许访问，无法获取地址时只有未配置 allow 才允许
+    pub fn is_allowed(&self, ip: Option<IpAddr>) -> bool {
+        let Some(ip) = ip else {
+            return self.allow.is_empty();
+        };
+        // 双栈监听时 IPv4 客户端表现为 ::ffff:a.b.c.d
+        let ip = ip.to_canonical();
+        if self.deny.iter().any(|net| net.contains(&ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|net| net.contains(&ip))
+    }
+}
+
+/// 解析 CIDR 或单个 IP，单个 IP 视为 /32（IPv6 为 /128）
+fn parse_net(entry: &str) -> Option<IpNet> {
+    let entry = entry.trim();
+    entry
+        .parse::<IpNet>()
+        .ok()
+        .or_else(|| entry.parse::<IpAddr>().ok().map(IpNet::from))
+}
+
+/// 为配置了 allow/deny 的 location 创建访问控制列表，键为 location 路径
+///
+/// 列表无效时该 location 拒绝所有请求，避免放行本应被拦截的地址
+pub fn ip_acls(config: &ProxyConfig) -> HashMap<String, IpAcl> {
+    config
+        .locations
+        .iter()
+        .filter_map(|location| {
+            let acl = IpAcl::new(location).unwrap_or_else(|e| {
+                warn!("{}，已拒绝该 location 的所有请求", e);
+                IpAcl::deny_all()
+            });
+            (!acl.is_empty()).then(|| (location.path.clone(), acl))
+        })
+        .collect()
+}
+
+/// 向客户端返回 403 Forbidden
+pub async fn respond_forbidden(session: &mut Session) -> Result<()> {
+    let mut header = ResponseHeader::build(403, Some(1))?;
+    header.insert_header("Content-Length", "0")?;
+    session.write_response_header(Box::new(header), true).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::proxy_config::{LocationType, MatchType};
+
+    fn location(allow: &[&str], deny: &[&str]) -> LocationConfig {
+        let list = |entries: &[&str]| {
+            (!entries.is_empty()).then(|| entries.iter().map(|entry| entry.to_string()).collect())
+        };
+        LocationConfig {
+            path: "/admin/".to_string(),
+            location_type: LocationType::Proxy,
+            match_type: MatchType::Prefix,
+            proxy_pass: Some("api".to_string()),
+            root: None,
+            index: None,
+            request_headers: None,
+            response_headers: None,
+            rate_limit: None,
+            websocket: false,
+            cache_ttl_secs: None,
+            allow: list(allow),
+            deny: list(deny),
+        }
+    }
+
+    fn ip(addr: &str) -> Option<IpAddr> {
+        Some(addr.parse().unwrap())
+    }
+
+    #[test]
+    fn test_allow_list_restricts_to_internal_networks() {
+        let acl = IpAcl::new(&location(&["127.0.0.0/8", "10.0.0.0/8", "::1"], &[])).unwrap();
+
+        assert!(acl.is_allowed(ip("127.0.0.1")));
+        assert!(acl.is_allowed(ip("10.1.2.3")));
+        assert!(acl.is_allowed(ip("::1")));
+        assert!(acl.is_allowed(ip("::ffff:127.0.0.1")));
+        assert!(!acl.is_allowed(ip("203.0.113.5")));
+        assert!(!acl.is_allowed(None));
+    }
+
+    #[test]
+    fn test_deny_overrides_allow() {
+        let acl = IpAcl::new(&location(&["10.0.0.0/8"], &["10.0.0.13", "203.0.113.0/24"])).unwrap();
+
+        assert!(acl.is_allowed(ip("10.0.0.1")));
+        assert!(!acl.is_allowed(ip("10.0.0.13")));
+        assert!(!acl.is_allowed(ip("203.0.113.5")));
+
+        // 只配置 deny 时其余地址都允许
+        let acl = IpAcl::new(&location(&[], &["203.0.113.0/24"])).unwrap();
+        assert!(acl.is_allowed(ip("127.0.0.1")));
+        assert!(!acl.is_allowed(ip("203.0.113.5")));
+        assert!(acl.is_allowed(None));
+    }
+
+    #[test]
+    fn test_invalid_entry_denies_all() {
+        let location = location(&["10.0.0.0/33"], &[]);
+        let error = IpAcl::new(&location).unwrap_err();
+        assert!(error.contains("10.0.0.0/33"), "{}", error);
+
+        let acl = IpAcl::deny_all();
+        assert!(!acl.is_allowed(ip("127.0.0.1")));
+        assert!(!acl.is_allowed(ip("::1")));
+        assert!(!acl.is_allowed(None));
+    }
+}
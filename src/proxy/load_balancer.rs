@@ -0,0 +1,324 @@
+//! 负载均衡模块
+//!
+//! 根据上游配置的 `lb_strategy` 选择上游服务器，支持 `roundrobin`、`random`、`least_conn`
+
+use crate::proxy::proxy_config::UpstreamConfig;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+/// 负载均衡策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LbStrategy {
+    /// 轮询
+    RoundRobin,
+    /// 随机
+    Random,
+    /// 最少连接
+    LeastConn,
+}
+
+impl LbStrategy {
+    /// 根据配置名称解析策略，无法识别时使用轮询
+    pub fn from_name(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "random" => Self::Random,
+            "least_conn" | "leastconn" => Self::LeastConn,
+            _ => Self::RoundRobin,
+        }
+    }
+}
+
+/// 一次上游服务器选择的结果
+///
+/// 请求结束后需要通过 [`LoadBalancer::release`] 归还，以便最少连接策略统计活跃连接
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpstreamSelection {
+    /// 上游名称
+    pub upstream: String,
+    /// 选中的服务器地址
+    pub server: String,
+    index: usize,
+}
+
+/// 单个上游的服务器列表及其选择状态
+struct UpstreamPool {
+    servers: Vec<String>,
+    strategy: LbStrategy,
+    /// 轮询计数
+    next: AtomicUsize,
+    /// 每个服务器的活跃连接数
+    active: Vec<AtomicUsize>,
+    /// 每个服务器是否健康
+    healthy: Vec<AtomicBool>,
+    /// 每个服务器连续健康检查失败次数
+    failures: Vec<AtomicU32>,
+    /// 连续失败多少次后标记为不健康
+    unhealthy_threshold: u32,
+}
+
+impl UpstreamPool {
+    fn new(config: &UpstreamConfig) -> Self {
+        Self {
+            servers: config.servers.clone(),
+            strategy: LbStrategy::from_name(&config.lb_strategy),
+            next: AtomicUsize::new(0),
+            active: config.servers.iter().map(|_| AtomicUsize::new(0)).collect(),
+            healthy: config
+                .servers
+                .iter()
+                .map(|_| AtomicBool::new(true))
+                .collect(),
+            failures: config.servers.iter().map(|_| AtomicU32::new(0)).collect(),
+            unhealthy_threshold: config.unhealthy_threshold.max(1),
+        }
+    }
+
+    fn is_healthy(&self, index: usize) -> bool {
+        self.healthy[index].load(Ordering::Relaxed)
+    }
+
+    fn select(&self) -> Option<usize> {
+        let len = self.servers.len();
+        if len == 0 {
+            return None;
+        }
+
+        let turn = self.next.fetch_add(1, Ordering::Relaxed);
+        // 从轮询位置开始排列健康的服务器，跳过不健康的服务器
+        let mut candidates = (0..len)
+            .map(|offset| (turn + offset) % len)
+            .filter(|&i| self.is_healthy(i));
+        let index = match self.strategy {
+            LbStrategy::RoundRobin => candidates.next()?,
+            LbStrategy::Random => {
+                let candidates: Vec<usize> = candidates.collect();
+                if candidates.is_empty() {
+                    return None;
+                }
+                candidates[(RandomState::new().hash_one(turn) as usize) % candidates.len()]
+            }
+            // 活跃连接数相同时分散到不同服务器
+            LbStrategy::LeastConn => {
+                candidates.min_by_key(|&i| self.active[i].load(Ordering::Relaxed))?
+            }
+        };
+
+        self.active[index].fetch_add(1, Ordering::Relaxed);
+        Some(index)
+    }
+
+    /// 记录一次健康检查结果，返回健康状态是否发生变化
+    fn report_health(&self, index: usize, healthy: bool) -> bool {
+        if healthy {
+            self.failures[index].store(0, Ordering::Relaxed);
+            return !self.healthy[index].swap(true, Ordering::Relaxed);
+        }
+
+        let failures = self.failures[index].fetch_add(1, Ordering::Relaxed) + 1;
+        failures >= self.unhealthy_threshold && self.healthy[index].swap(false, Ordering::Relaxed)
+    }
+
+    fn release(&self, index: usize) {
+        if let Some(active) = self.active.get(index) {
+            let _ = active.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+        }
+    }
+}
+
+/// 负载均衡器，按上游名称分别维护选择状态
+pub struct LoadBalancer {
+    pools: HashMap<String, UpstreamPool>,
+}
+
+impl LoadBalancer {
+    /// 根据上游配置创建负载均衡器
+    pub fn new(upstreams: &HashMap<String, UpstreamConfig>) -> Self {
+        Self {
+            pools: upstreams
+                .iter()
+                .map(|(name, config)| (name.clone(), UpstreamPool::new(config)))
+                .collect(),
+        }
+    }
+
+    /// 为指定上游选择一个服务器
+    pub fn select(&self, upstream: &str) -> Option<UpstreamSelection> {
+        let pool = self.pools.get(upstream)?;
+        let index = pool.select()?;
+        Some(UpstreamSelection {
+            upstream: upstream.to_string(),
+            server: pool.servers[index].clone(),
+            index,
+        })
+    }
+
+    /// 请求结束后归还选择结果
+    ///
+    /// 配置热更新后选择结果可能来自旧的负载均衡器，服务器地址不一致时忽略
+    pub fn release(&self, selection: &UpstreamSelection) {
+        if let Some(pool) = self.pools.get(&selection.upstream) {
+            if pool.servers.get(selection.index) == Some(&selection.server) {
+                pool.release(selection.index);
+            }
+        }
+    }
+
+    /// 记录服务器的健康检查结果
+    ///
+    /// 连续失败达到 `unhealthy_threshold` 次后不再选择该服务器，检查成功一次即恢复。
+    /// 返回健康状态是否发生变化
+    pub fn report_health(&self, upstream: &str, server: &str, healthy: bool) -> bool {
+        self.pools
+            .get(upstream)
+            .and_then(|pool| {
+                let index = pool.servers.iter().position(|s| s == server)?;
+                Some(pool.report_health(index, healthy))
+            })
+            .unwrap_or(false)
+    }
+
+    /// 服务器当前是否健康，未知服务器视为不健康
+    pub fn is_healthy(&self, upstream: &str, server: &str) -> bool {
+        self.pools
+            .get(upstream)
+            .and_then(|pool| {
+                let index = pool.servers.iter().position(|s| s == server)?;
+                Some(pool.is_healthy(index))
+            })
+            .unwrap_or(false)
+    }
+
+    /// 获取上游使用的负载均衡策略
+    pub fn strategy(&self, upstream: &str) -> Option<LbStrategy> {
+        self.pools.get(upstream).map(|pool| pool.strategy)
+    }
+
+    /// 获取服务器当前的活跃连接数
+    pub fn active_connections(&self, upstream: &str, server: &str) -> usize {
+        self.pools
+            .get(upstream)
+            .and_then(|pool| {
+                let index = pool.servers.iter().position(|s| s == server)?;
+                Some(pool.active[index].load(Ordering::Relaxed))
+            })
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balancer(strategy: &str) -> LoadBalancer {
+        let mut upstreams = HashMap::new();
+        upstreams.insert(
+            "backend".to_string(),
+            UpstreamConfig {
+                servers: vec![
+                    "10.0.0.1:80".to_string(),
+                    "10.0.0.2:80".to_string(),
+                    "10.0.0.3:80".to_string(),
+                ],
+                lb_strategy: strategy.to_string(),
+                health_check_path: None,
+                health_check_interval_secs: 0,
+                unhealthy_threshold: 2,
+                upstream_keepalive_secs: None,
+                upstream_connect_timeout_secs: None,
+            },
+        );
+        LoadBalancer::new(&upstreams)
+    }
+
+    #[test]
+    fn test_strategy_from_name() {
+        assert_eq!(LbStrategy::from_name("roundrobin"), LbStrategy::RoundRobin);
+        assert_eq!(LbStrategy::from_name("random"), LbStrategy::Random);
+        assert_eq!(LbStrategy::from_name("least_conn"), LbStrategy::LeastConn);
+        assert_eq!(LbStrategy::from_name("unknown"), LbStrategy::RoundRobin);
+    }
+
+    #[test]
+    fn test_round_robin_cycles_through_all_servers() {
+        let lb = balancer("roundrobin");
+        let servers: Vec<String> = (0..6)
+            .map(|_| lb.select("backend").unwrap().server)
+            .collect();
+
+        assert_eq!(
+            servers,
+            vec![
+                "10.0.0.1:80",
+                "10.0.0.2:80",
+                "10.0.0.3:80",
+                "10.0.0.1:80",
+                "10.0.0.2:80",
+                "10.0.0.3:80",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_random_selects_configured_server() {
+        let lb = balancer("random");
+        for _ in 0..20 {
+            let selection = lb.select("backend").unwrap();
+            assert!(selection.server.starts_with("10.0.0."));
+        }
+    }
+
+    #[test]
+    fn test_least_conn_prefers_idle_server() {
+        let lb = balancer("least_conn");
+        let first = lb.select("backend").unwrap();
+        let second = lb.select("backend").unwrap();
+        let third = lb.select("backend").unwrap();
+        assert_ne!(first.server, second.server);
+        assert_ne!(second.server, third.server);
+        assert_ne!(first.server, third.server);
+
+        // 释放第二个服务器后，它成为唯一空闲的服务器
+        lb.release(&second);
+        assert_eq!(lb.active_connections("backend", &second.server), 0);
+        assert_eq!(lb.select("backend").unwrap().server, second.server);
+    }
+
+    #[test]
+    fn test_unhealthy_server_is_skipped_until_recovered() {
+        let lb = balancer("roundrobin");
+
+        // 达到阈值前仍然可用
+        assert!(!lb.report_health("backend", "10.0.0.2:80", false));
+        assert!(lb.is_healthy("backend", "10.0.0.2:80"));
+        assert!(lb.report_health("backend", "10.0.0.2:80", false));
+        assert!(!lb.is_healthy("backend", "10.0.0.2:80"));
+
+        for _ in 0..6 {
+            assert_ne!(lb.select("backend").unwrap().server, "10.0.0.2:80");
+        }
+
+        assert!(lb.report_health("backend", "10.0.0.2:80", true));
+        let servers: Vec<String> = (0..3)
+            .map(|_| lb.select("backend").unwrap().server)
+            .collect();
+        assert!(servers.contains(&"10.0.0.2:80".to_string()));
+    }
+
+    #[test]
+    fn test_no_healthy_servers() {
+        let lb = balancer("least_conn");
+        for server in ["10.0.0.1:80", "10.0.0.2:80", "10.0.0.3:80"] {
+            lb.report_health("backend", server, false);
+            lb.report_health("backend", server, false);
+        }
+        assert!(lb.select("backend").is_none());
+    }
+
+    #[test]
+    fn test_unknown_upstream() {
+        let lb = balancer("roundrobin");
+        assert!(lb.select("missing").is_none());
+        assert!(lb.strategy("missing").is_none());
+    }
+}
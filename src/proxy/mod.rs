@@ -4,22 +4,46 @@
 //! - HTTP/HTTPS 代理
 //! - 静态文件服务
 //! - 负载均衡
+//! - 上游健康检查
+//! - 访问日志
+//! - 请求/响应头改写
+//! - 按客户端 IP 限流
+//! - 按客户端 IP 的 allow/deny 访问控制
+//! - WebSocket 转发
+//! - GET 响应缓存
+//! - 上游与 location 配置热更新
 //! - SSL/TLS 支持
 
+pub mod access_log;
 pub mod enhanced_proxy_server;
 pub mod enhanced_proxy_service;
+pub mod header_rules;
+pub mod health_check;
+pub mod ip_acl;
+pub mod load_balancer;
 pub mod proxy_config;
 pub mod proxy_server;
 pub mod proxy_service;
+pub mod rate_limit;
+pub mod response_cache;
+pub mod route_table;
 pub mod simple_proxy_server;
 pub mod simple_proxy_service;
 pub mod static_file_service;
+pub mod websocket;
 
+pub use access_log::{AccessLogEntry, ProxyCtx};
 pub use enhanced_proxy_server::EnhancedProxyServer;
 pub use enhanced_proxy_service::EnhancedProxyService;
-pub use proxy_config::ProxyConfig;
-pub use proxy_server::ProxyServer;
+pub use health_check::HealthChecker;
+pub use ip_acl::IpAcl;
+pub use load_balancer::{LbStrategy, LoadBalancer, UpstreamSelection};
+pub use proxy_config::{LogFormat, ProxyConfig, RateLimitConfig};
+pub use proxy_server::{ProxyServer, ProxyShutdownHandle};
 pub use proxy_service::ProxyService;
+pub use rate_limit::RateLimiter;
+pub use response_cache::{CachedResponse, ResponseCache};
+pub use route_table::ProxyConfigHandle;
 pub use simple_proxy_server::SimpleProxyServer;
 pub use simple_proxy_service::SimpleProxyService;
-pub use static_file_service::StaticFileService;
+pub use static_file_service::{StaticFileResponse, StaticFileService};
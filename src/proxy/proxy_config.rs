@@ -2,8 +2,11 @@
 //!
 //! 定义代理服务器的配置结构，包括监听地址、上游服务器、SSL 配置等。
 
+use crate::proxy::ip_acl::IpAcl;
+use pingora::upstreams::peer::HttpPeer;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// 代理服务器配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,10 +32,14 @@ pub struct ProxyConfig {
 
     /// 位置配置
     pub locations: Vec<LocationConfig>,
+
+    /// 访问日志格式
+    #[serde(default)]
+    pub log_format: LogFormat,
 }
 
 /// 上游服务器配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UpstreamConfig {
     /// 服务器列表
     pub servers: Vec<String>,
@@ -40,6 +47,134 @@ pub struct UpstreamConfig {
     /// 负载均衡策略
     #[serde(default = "default_lb_strategy")]
     pub lb_strategy: String,
+
+    /// 健康检查路径，配置后使用 HTTP GET 检查，否则只检查 TCP 连接
+    #[serde(default)]
+    pub health_check_path: Option<String>,
+
+    /// 健康检查间隔（秒），0 表示不进行健康检查
+    #[serde(default = "default_health_check_interval")]
+    pub health_check_interval_secs: u64,
+
+    /// 连续失败多少次后标记为不健康
+    #[serde(default = "default_unhealthy_threshold")]
+    pub unhealthy_threshold: u32,
+
+    /// 上游连接空闲多久（秒）后关闭，在此之前放回连接池供后续请求复用，None 使用 pingora 默认值
+    #[serde(default)]
+    pub upstream_keepalive_secs: Option<u64>,
+
+    /// 建立上游连接的超时时间（秒），None 使用 pingora 默认值
+    #[serde(default)]
+    pub upstream_connect_timeout_secs: Option<u64>,
+}
+
+impl ProxyConfig {
+    /// 按路径查找 location 配置（精确匹配 location 路径）
+    pub fn location(&self, path: &str) -> Option<&LocationConfig> {
+        self.locations.iter().find(|location| location.path == path)
+    }
+
+    /// 启用 SSL 时返回证书与私钥路径，未启用时返回 None
+    ///
+    /// `ssl` 为 true 但缺少 `ssl_cert` 或 `ssl_key` 时返回错误，避免静默退化为明文监听
+    pub fn tls_paths(&self) -> pingora::Result<Option<(&str, &str)>> {
+        if !self.ssl {
+            return Ok(None);
+        }
+
+        let missing = |field: &str| {
+            pingora::Error::explain(
+                pingora::ErrorType::InternalError,
+                format!(
+                    "server {} enables ssl but `{}` is not configured",
+                    self.server_name, field
+                ),
+            )
+        };
+        let cert = self
+            .ssl_cert
+            .as_deref()
+            .ok_or_else(|| missing("ssl_cert"))?;
+        let key = self.ssl_key.as_deref().ok_or_else(|| missing("ssl_key"))?;
+        Ok(Some((cert, key)))
+    }
+
+    /// 验证配置的有效性
+    ///
+    /// 检查监听地址、SSL 证书配置、上游服务器列表、每个 location 引用的上游是否存在，
+    /// 以及 allow/deny 列表能否解析
+    pub fn validate(&self) -> Result<(), String> {
+        if self.listen.trim().is_empty() {
+            return Err("监听地址不能为空".to_string());
+        }
+        self.tls_paths().map_err(|e| e.to_string())?;
+
+        for (name, upstream) in &self.upstreams {
+            if upstream.servers.is_empty() {
+                return Err(format!("上游 {} 的服务器列表不能为空", name));
+            }
+            if upstream.upstream_connect_timeout_secs == Some(0) {
+                return Err(format!("上游 {} 的连接超时时间必须大于 0", name));
+            }
+        }
+
+        for location in &self.locations {
+            if location.match_type == MatchType::Regex {
+                regex::Regex::new(&location.path)
+                    .map_err(|e| format!("location {} 的正则表达式无效: {}", location.path, e))?;
+            }
+            IpAcl::new(location)?;
+            match location.location_type {
+                LocationType::Proxy => {
+                    let upstream = location
+                        .proxy_pass
+                        .as_deref()
+                        .ok_or_else(|| format!("location {} 未配置 proxy_pass", location.path))?;
+                    if !self.upstreams.contains_key(upstream) {
+                        return Err(format!(
+                            "location {} 引用的上游 {} 不存在",
+                            location.path, upstream
+                        ));
+                    }
+                }
+                LocationType::Static => {
+                    if location.root.is_none() {
+                        return Err(format!("静态文件 location {} 未配置 root", location.path));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for UpstreamConfig {
+    fn default() -> Self {
+        Self {
+            servers: Vec::new(),
+            lb_strategy: default_lb_strategy(),
+            health_check_path: None,
+            health_check_interval_secs: default_health_check_interval(),
+            unhealthy_threshold: default_unhealthy_threshold(),
+            upstream_keepalive_secs: None,
+            upstream_connect_timeout_secs: None,
+        }
+    }
+}
+
+impl UpstreamConfig {
+    /// 构建到 `server` 的连接参数，应用连接超时与连接池空闲时间
+    pub fn peer(&self, server: &str, tls: bool, sni: String) -> HttpPeer {
+        let mut peer = HttpPeer::new(server, tls, sni);
+        if let Some(secs) = self.upstream_connect_timeout_secs {
+            peer.options.connection_timeout = Some(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.upstream_keepalive_secs {
+            peer.options.idle_timeout = Some(Duration::from_secs(secs));
+        }
+        peer
+    }
 }
 
 /// 位置配置（类似 Nginx 的 location 块）
@@ -52,6 +187,10 @@ pub struct LocationConfig {
     #[serde(rename = "type")]
     pub location_type: LocationType,
 
+    /// 路径匹配方式，默认按前缀匹配；为 `regex` 时 `path` 是正则表达式
+    #[serde(default)]
+    pub match_type: MatchType,
+
     /// 代理目标（用于反向代理）
     pub proxy_pass: Option<String>,
 
@@ -60,6 +199,85 @@ pub struct LocationConfig {
 
     /// 索引文件
     pub index: Option<Vec<String>>,
+
+    /// 转发到上游时设置的请求头，值支持 `$remote_addr`
+    #[serde(default)]
+    pub request_headers: Option<HashMap<String, String>>,
+
+    /// 返回给客户端时设置的响应头，值支持 `$remote_addr`
+    #[serde(default)]
+    pub response_headers: Option<HashMap<String, String>>,
+
+    /// 按客户端 IP 限流
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+
+    /// 是否允许 WebSocket 升级并在客户端与上游之间双向转发
+    #[serde(default)]
+    pub websocket: bool,
+
+    /// 响应缓存时间（秒），配置后缓存上游对 GET 请求返回的 200 响应
+    #[serde(default)]
+    pub cache_ttl_secs: Option<u64>,
+
+    /// 允许访问的客户端地址（CIDR 或单个 IP），配置后其余地址返回 403
+    #[serde(default)]
+    pub allow: Option<Vec<String>>,
+
+    /// 拒绝访问的客户端地址（CIDR 或单个 IP），优先于 `allow`
+    #[serde(default)]
+    pub deny: Option<Vec<String>>,
+}
+
+/// location 路径匹配方式
+///
+/// 查找顺序：先查找精确匹配，再按配置顺序查找正则匹配，最后使用最长的前缀匹配
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchType {
+    /// 请求路径以 `path` 开头
+    #[default]
+    Prefix,
+    /// 请求路径与 `path` 完全相同
+    Exact,
+    /// 请求路径匹配正则表达式 `path`
+    Regex,
+}
+
+/// 限流配置（令牌桶）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// 每秒允许的请求数（令牌补充速率）
+    pub requests_per_second: f64,
+
+    /// 允许的突发请求数（令牌桶容量）
+    pub burst: u32,
+}
+
+impl LocationConfig {
+    /// 构建转发到上游的 URI：移除 location 路径前缀，并保留查询字符串
+    ///
+    /// 例如 location 为 `/api/kafka/` 时，`/api/kafka/foo?x=1` 转发为 `/foo?x=1`。
+    /// 正则匹配的 location 不移除前缀，按原路径转发
+    pub fn rewrite_uri(&self, uri: &http::Uri) -> Option<http::Uri> {
+        let path = uri.path();
+        let remaining = match self.match_type {
+            MatchType::Regex => path,
+            MatchType::Prefix | MatchType::Exact => {
+                path.strip_prefix(self.path.as_str()).unwrap_or(path)
+            }
+        };
+        let remaining = remaining.trim_start_matches('/');
+
+        let path_and_query = match uri.query() {
+            Some(query) => format!("/{}?{}", remaining, query),
+            None => format!("/{}", remaining),
+        };
+
+        let mut parts = uri.clone().into_parts();
+        parts.path_and_query = Some(path_and_query.parse().ok()?);
+        http::Uri::from_parts(parts).ok()
+    }
 }
 
 /// 位置类型
@@ -73,6 +291,159 @@ pub enum LocationType {
     Static,
 }
 
+/// 访问日志格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// 类似 Nginx combined 的单行文本
+    #[default]
+    Combined,
+
+    /// JSON 格式，便于日志系统采集
+    Json,
+}
+
 fn default_lb_strategy() -> String {
     "roundrobin".to_string()
 }
+
+fn default_health_check_interval() -> u64 {
+    10
+}
+
+fn default_unhealthy_threshold() -> u32 {
+    3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxy_location(path: &str) -> LocationConfig {
+        LocationConfig {
+            path: path.to_string(),
+            location_type: LocationType::Proxy,
+            match_type: MatchType::Prefix,
+            proxy_pass: Some("kafka_api".to_string()),
+            root: None,
+            index: None,
+            request_headers: None,
+            response_headers: None,
+            rate_limit: None,
+            websocket: false,
+            cache_ttl_secs: None,
+            allow: None,
+            deny: None,
+        }
+    }
+
+    #[test]
+    fn test_rewrite_uri_strips_prefix_and_keeps_query() {
+        let location = proxy_location("/api/kafka/");
+        let uri: http::Uri = "/api/kafka/foo?x=1".parse().unwrap();
+        assert_eq!(location.rewrite_uri(&uri).unwrap(), "/foo?x=1");
+    }
+
+    #[test]
+    fn test_rewrite_uri_edge_cases() {
+        let location = proxy_location("/api/kafka");
+        let uri: http::Uri = "/api/kafka/foo/bar".parse().unwrap();
+        assert_eq!(location.rewrite_uri(&uri).unwrap(), "/foo/bar");
+
+        let uri: http::Uri = "/api/kafka".parse().unwrap();
+        assert_eq!(location.rewrite_uri(&uri).unwrap(), "/");
+    }
+
+    #[test]
+    fn test_validate_checks_upstream_references() {
+        let mut config = ProxyConfig {
+            server_name: "test".to_string(),
+            listen: "0.0.0.0:8080".to_string(),
+            ssl: false,
+            ssl_cert: None,
+            ssl_key: None,
+            upstreams: HashMap::from([(
+                "kafka_api".to_string(),
+                UpstreamConfig {
+                    servers: vec!["127.0.0.1:3000".to_string()],
+                    ..UpstreamConfig::default()
+                },
+            )]),
+            locations: vec![proxy_location("/api/kafka/")],
+            log_format: LogFormat::default(),
+        };
+        assert!(config.validate().is_ok());
+
+        config.locations[0].proxy_pass = Some("missing".to_string());
+        assert!(config.validate().is_err());
+
+        config.locations[0].proxy_pass = Some("kafka_api".to_string());
+        config.locations[0].deny = Some(vec!["203.0.113.0/24".to_string(), "bogus".to_string()]);
+        let error = config.validate().unwrap_err();
+        assert!(error.contains("bogus"), "{}", error);
+
+        config.locations[0].deny = None;
+        config.ssl = true;
+        assert!(config.validate().is_err());
+
+        let location: LocationConfig = serde_yaml::from_str(
+            "path: /admin/\ntype: proxy\nproxy_pass: kafka_api\nallow: [\"10.0.0.0/8\"]\n",
+        )
+        .unwrap();
+        assert_eq!(location.allow, Some(vec!["10.0.0.0/8".to_string()]));
+        assert_eq!(location.deny, None);
+    }
+
+    #[test]
+    fn test_peer_applies_timeouts() {
+        let upstream = UpstreamConfig {
+            servers: vec!["127.0.0.1:3000".to_string()],
+            upstream_keepalive_secs: Some(60),
+            upstream_connect_timeout_secs: Some(2),
+            ..UpstreamConfig::default()
+        };
+        let peer = upstream.peer("127.0.0.1:3000", false, "test".to_string());
+        assert_eq!(
+            peer.options.connection_timeout,
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(peer.options.idle_timeout, Some(Duration::from_secs(60)));
+
+        let peer = UpstreamConfig::default().peer("127.0.0.1:3000", false, "test".to_string());
+        assert_eq!(peer.options.connection_timeout, None);
+
+        let upstream: UpstreamConfig = serde_yaml::from_str(
+            "servers: [\"127.0.0.1:3000\"]\nupstream_keepalive_secs: 30\nupstream_connect_timeout_secs: 3\n",
+        )
+        .unwrap();
+        assert_eq!(upstream.upstream_keepalive_secs, Some(30));
+        assert_eq!(upstream.upstream_connect_timeout_secs, Some(3));
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_regex() {
+        let mut location = proxy_location("^/users/(\\d+$");
+        location.match_type = MatchType::Regex;
+        let config = ProxyConfig {
+            server_name: "test".to_string(),
+            listen: "0.0.0.0:8080".to_string(),
+            ssl: false,
+            ssl_cert: None,
+            ssl_key: None,
+            upstreams: HashMap::from([(
+                "kafka_api".to_string(),
+                UpstreamConfig {
+                    servers: vec!["127.0.0.1:3000".to_string()],
+                    ..UpstreamConfig::default()
+                },
+            )]),
+            locations: vec![location],
+            log_format: LogFormat::default(),
+        };
+        let error = config.validate().unwrap_err();
+        assert!(error.contains("正则表达式"), "{}", error);
+
+        let match_type: MatchType = serde_yaml::from_str("exact").unwrap();
+        assert_eq!(match_type, MatchType::Exact);
+    }
+}
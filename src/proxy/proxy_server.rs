@@ -1,18 +1,95 @@
 //! 代理服务器模块
 //!
-//! 负责启动和管理代理服务器实例
+//! 负责启动和管理代理服务器实例。
+//! `start` 会阻塞当前线程直到通过 [`ProxyShutdownHandle`] 关闭，
+//! `run_until` 则在传入的 Future 完成时关闭，便于在配置变更后用新配置重新启动
 
+use crate::proxy::health_check::HealthChecker;
 use crate::proxy::proxy_config::ProxyConfig;
 use crate::proxy::proxy_service::ProxyService;
+use async_trait::async_trait;
 use pingora::Result;
 use pingora::proxy::http_proxy_service;
-use pingora::server::Server;
-use std::sync::Arc;
+use pingora::server::{RunArgs, Server, ShutdownSignal, ShutdownSignalWatch};
+use pingora::services::background::background_service;
+use pingora::services::listening::Service;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// 按配置为服务注册监听地址：启用 SSL 时注册 TLS 监听，否则注册 TCP 监听
+pub(crate) fn add_listener<A>(service: &mut Service<A>, config: &ProxyConfig) -> Result<()> {
+    match config.tls_paths()? {
+        Some((cert, key)) => service.add_tls(&config.listen, cert, key)?,
+        None => service.add_tcp(&config.listen),
+    }
+    Ok(())
+}
+
+/// 代理服务器关闭句柄，可以克隆到其他线程中触发关闭
+///
+/// 在服务器启动前调用 [`shutdown`](Self::shutdown) 时，下一次启动会立即返回
+#[derive(Debug, Clone, Default)]
+pub struct ProxyShutdownHandle {
+    notify: Arc<Notify>,
+}
+
+impl ProxyShutdownHandle {
+    /// 触发关闭，正在运行的服务器停止接受连接并中断进行中的请求后返回
+    pub fn shutdown(&self) {
+        self.notify.notify_one();
+    }
+
+    /// 等待关闭信号
+    async fn notified(&self) {
+        self.notify.notified().await;
+    }
+}
+
+/// 将 Future 适配为 pingora 的关闭信号
+struct FutureShutdownSignal {
+    future: Mutex<Option<Pin<Box<dyn Future<Output = ()> + Send>>>>,
+}
+
+#[async_trait]
+impl ShutdownSignalWatch for FutureShutdownSignal {
+    async fn recv(&self) -> ShutdownSignal {
+        let future = self.future.lock().unwrap().take();
+        match future {
+            Some(future) => future.await,
+            None => std::future::pending().await,
+        }
+        // pingora 的优雅关闭默认会等待数分钟，这里立即退出以便调用方尽快重新启动
+        ShutdownSignal::FastShutdown
+    }
+}
+
+/// 运行服务器，`shutdown` 完成或通过 `handle` 触发关闭时返回
+pub(crate) fn run_server<F>(server: Server, handle: &ProxyShutdownHandle, shutdown: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let handle = handle.clone();
+    let shutdown = async move {
+        tokio::select! {
+            _ = shutdown => {}
+            _ = handle.notified() => {}
+        }
+    };
+    let signal = FutureShutdownSignal {
+        future: Mutex::new(Some(Box::pin(shutdown))),
+    };
+    server.run(RunArgs {
+        shutdown_signal: Box::new(signal),
+    });
+}
 
 /// 代理服务器
 pub struct ProxyServer {
     config: Arc<ProxyConfig>,
     server: Server,
+    shutdown: ProxyShutdownHandle,
 }
 
 impl ProxyServer {
@@ -22,29 +99,129 @@ impl ProxyServer {
         Ok(Self {
             config: Arc::new(config),
             server,
+            shutdown: ProxyShutdownHandle::default(),
         })
     }
 
-    /// 启动代理服务器
+    /// 启动代理服务器，阻塞直到调用 [`stop`](Self::stop) 或关闭句柄
     pub fn start(&mut self) -> Result<()> {
-        self.server.bootstrap();
+        self.run_until(std::future::pending())
+    }
 
+    /// 启动代理服务器，`shutdown` 完成时关闭并返回
+    pub fn run_until<F>(&mut self, shutdown: F) -> Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
         // 创建代理服务
         let proxy_service = ProxyService::new((*self.config).clone());
-        let service = http_proxy_service(&self.server.configuration, proxy_service);
+        let health_checker =
+            HealthChecker::new(proxy_service.load_balancer(), &self.config.upstreams);
+        let mut service = http_proxy_service(&self.server.configuration, proxy_service);
+
+        // 监听配置有误时在启动前直接返回错误
+        add_listener(&mut service, &self.config)?;
+        self.server.bootstrap();
 
         // 添加服务到服务器
         self.server.add_service(service);
 
+        // 启用了健康检查的上游由后台任务定期探测
+        if health_checker.is_enabled() {
+            self.server
+                .add_service(background_service("upstream health check", health_checker));
+        }
+
         // 启动服务器
         let server = std::mem::replace(&mut self.server, Server::new(None)?);
-        server.run(pingora::server::RunArgs::default());
+        run_server(server, &self.shutdown, shutdown);
         Ok(())
     }
 
     /// 停止代理服务器
-    pub fn stop(&mut self) {
-        // 在实际实现中，这里需要添加优雅关闭的逻辑
-        println!("Stopping proxy server...");
+    pub fn stop(&self) {
+        self.shutdown.shutdown();
+    }
+
+    /// 获取关闭句柄，用于在其他线程中停止阻塞在 [`start`](Self::start) 的服务器
+    pub fn shutdown_handle(&self) -> ProxyShutdownHandle {
+        self.shutdown.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    fn plain_config() -> ProxyConfig {
+        ProxyConfig {
+            ssl: false,
+            ssl_cert: None,
+            ssl_key: None,
+            ..ssl_config(None, None)
+        }
+    }
+
+    fn ssl_config(ssl_cert: Option<&str>, ssl_key: Option<&str>) -> ProxyConfig {
+        ProxyConfig {
+            server_name: "tls.local".to_string(),
+            listen: "127.0.0.1:0".to_string(),
+            ssl: true,
+            ssl_cert: ssl_cert.map(str::to_string),
+            ssl_key: ssl_key.map(str::to_string),
+            upstreams: HashMap::new(),
+            locations: Vec::new(),
+            log_format: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_ssl_without_cert_fails_to_start() {
+        let mut server = ProxyServer::new(ssl_config(None, Some("server.key"))).unwrap();
+        let err = server.start().unwrap_err();
+        assert!(err.to_string().contains("`ssl_cert` is not configured"));
+
+        let mut server = ProxyServer::new(ssl_config(Some("server.crt"), None)).unwrap();
+        let err = server.start().unwrap_err();
+        assert!(err.to_string().contains("`ssl_key` is not configured"));
+    }
+
+    #[test]
+    fn test_ssl_with_unreadable_cert_fails_to_start() {
+        let config = ssl_config(
+            Some("/nonexistent/clamber/server.crt"),
+            Some("/nonexistent/clamber/server.key"),
+        );
+        let mut server = ProxyServer::new(config).unwrap();
+        assert!(server.start().is_err());
+    }
+
+    #[test]
+    fn test_run_until_returns_when_shutdown_resolves() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            let _ = tx.send(());
+        });
+
+        let started = Instant::now();
+        let mut server = ProxyServer::new(plain_config()).unwrap();
+        server
+            .run_until(async move {
+                let _ = rx.await;
+            })
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_stop_before_start_returns_immediately() {
+        let mut server = ProxyServer::new(plain_config()).unwrap();
+        server.shutdown_handle().shutdown();
+        let started = Instant::now();
+        server.start().unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
     }
 }
@@ -2,38 +2,56 @@
 //!
 //! 实现基于 Pingora 的反向代理服务
 
+use crate::proxy::access_log::{ProxyCtx, log_access};
+use crate::proxy::health_check::HealthChecker;
+use crate::proxy::load_balancer::LoadBalancer;
 use crate::proxy::proxy_config::ProxyConfig;
+use crate::proxy::proxy_server::add_listener;
 use async_trait::async_trait;
 use pingora::Result;
+use pingora::http::RequestHeader;
 use pingora::proxy::ProxyHttp;
 use pingora::proxy::http_proxy_service;
 use pingora::server::Server;
+use pingora::services::background::background_service;
 use pingora::upstreams::peer::HttpPeer;
-use pingora::http::RequestHeader;
 use std::sync::Arc;
 
 /// 代理服务实现
 pub struct ProxyService {
     config: Arc<ProxyConfig>,
+    load_balancer: Arc<LoadBalancer>,
 }
 
 impl ProxyService {
     /// 创建新的代理服务
     pub fn new(config: ProxyConfig) -> Self {
         Self {
+            load_balancer: Arc::new(LoadBalancer::new(&config.upstreams)),
             config: Arc::new(config),
         }
     }
 
+    /// 获取负载均衡器，供健康检查等后台任务共享
+    pub fn load_balancer(&self) -> Arc<LoadBalancer> {
+        self.load_balancer.clone()
+    }
+
     /// 启动代理服务
     pub fn start(&self) -> Result<()> {
         let mut server = Server::new(None)?;
-        server.bootstrap();
 
         // http_proxy_service expects an owned service, not a reference
         let owned_service = ProxyService::new((*self.config).clone());
-        let service = http_proxy_service(&server.configuration, owned_service);
+        let health_checker =
+            HealthChecker::new(owned_service.load_balancer(), &self.config.upstreams);
+        let mut service = http_proxy_service(&server.configuration, owned_service);
+        add_listener(&mut service, &self.config)?;
+        server.bootstrap();
         server.add_service(service);
+        if health_checker.is_enabled() {
+            server.add_service(background_service("upstream health check", health_checker));
+        }
 
         // 启动服务器
         server.run(pingora::server::RunArgs::default());
@@ -43,40 +61,37 @@ impl ProxyService {
 
 #[async_trait]
 impl ProxyHttp for ProxyService {
-    type CTX = ();
+    type CTX = ProxyCtx;
     fn new_ctx(&self) -> Self::CTX {
-        ()
+        ProxyCtx::new()
     }
 
     async fn upstream_peer(
         &self,
         _session: &mut pingora::proxy::Session,
-        _ctx: &mut Self::CTX,
+        ctx: &mut Self::CTX,
     ) -> Result<Box<HttpPeer>> {
-        // 简单实现：选择第一个上游服务器
-        // 实际实现中需要根据配置和负载均衡策略选择合适的上游服务器
-        let upstream = self
-            .config
-            .upstreams
-            .values()
-            .next()
-            .ok_or_else(|| pingora::Error::err::<()>(pingora::ErrorType::InternalError))
-            .map_err(|_| {
-                pingora::Error::explain(
-                    pingora::ErrorType::InternalError,
-                    "No upstream servers configured",
-                )
-            })?;
+        // 选择第一个上游，并按其负载均衡策略选择服务器
+        let (upstream_name, upstream) = self.config.upstreams.iter().next().ok_or_else(|| {
+            pingora::Error::explain(
+                pingora::ErrorType::InternalError,
+                "No upstream servers configured",
+            )
+        })?;
 
-        let server = upstream
-            .servers
-            .first()
-            .ok_or_else(|| pingora::Error::err::<()>(pingora::ErrorType::InternalError))
-            .map_err(|_| {
-                pingora::Error::explain(pingora::ErrorType::InternalError, "No servers in upstream")
-            })?;
+        let selection = self.load_balancer.select(upstream_name).ok_or_else(|| {
+            pingora::Error::explain(pingora::ErrorType::InternalError, "No servers in upstream")
+        })?;
 
-        let peer = HttpPeer::new(server, self.config.ssl, self.config.server_name.clone());
+        let peer = upstream.peer(
+            &selection.server,
+            self.config.ssl,
+            self.config.server_name.clone(),
+        );
+        // 重试时会再次选择上游，先归还上一次的选择
+        if let Some(previous) = ctx.upstream.replace(selection) {
+            self.load_balancer.release(&previous);
+        }
         Ok(Box::new(peer))
     }
 
@@ -91,4 +106,19 @@ impl ProxyHttp for ProxyService {
         println!("Proxying request to: {:?}", upstream_request.uri);
         Ok(())
     }
+
+    async fn logging(
+        &self,
+        session: &mut pingora::proxy::Session,
+        _e: Option<&pingora::Error>,
+        ctx: &mut Self::CTX,
+    ) where
+        Self::CTX: Send + Sync,
+    {
+        // 请求结束，归还上游选择以更新活跃连接数
+        if let Some(selection) = ctx.upstream.as_ref() {
+            self.load_balancer.release(selection);
+        }
+        log_access(self.config.log_format, session, ctx);
+    }
 }
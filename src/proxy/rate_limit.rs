@@ -0,0 +1,221 @@
+//! 限流模块
+//!
+//! 按客户端 IP 的令牌桶限流：每个 IP 拥有容量为 `burst` 的令牌桶，
+//! 令牌以 `requests_per_second` 的速率补充，令牌耗尽时返回 429
+
+use crate::proxy::proxy_config::{ProxyConfig, RateLimitConfig};
+use dashmap::DashMap;
+use pingora::Result;
+use pingora::http::ResponseHeader;
+use pingora::proxy::Session;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 清理过期令牌桶的间隔
+const REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 单个客户端的令牌桶
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// 当前令牌数
+    tokens: f64,
+    /// 上次补充令牌的时间
+    last: Instant,
+}
+
+/// 令牌桶限流器
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// 每秒补充的令牌数
+    rate: f64,
+    /// 令牌桶容量
+    burst: f64,
+    /// 各客户端的令牌桶
+    buckets: DashMap<IpAddr, Bucket>,
+    /// 上次清理时间
+    last_reap: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// 根据配置创建限流器，`burst` 至少为 1
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            rate: config.requests_per_second.max(f64::MIN_POSITIVE),
+            burst: config.burst.max(1) as f64,
+            buckets: DashMap::new(),
+            last_reap: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// 为客户端消耗一个令牌，令牌不足时返回 false
+    pub fn check(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        self.reap_if_due(now);
+        self.check_at(ip, now)
+    }
+
+    /// 在指定时间点为客户端消耗一个令牌
+    fn check_at(&self, ip: IpAddr, now: Instant) -> bool {
+        let mut bucket = self.buckets.entry(ip).or_insert(Bucket {
+            tokens: self.burst,
+            last: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.last = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 令牌桶在指定时间点是否已补满，补满的桶与新建的桶等价，可以安全移除
+    fn is_full_at(&self, bucket: &Bucket, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+        bucket.tokens + elapsed * self.rate >= self.burst
+    }
+
+    /// 距上次清理超过 [`REAP_INTERVAL`] 时清理过期令牌桶
+    fn reap_if_due(&self, now: Instant) {
+        let Ok(mut last_reap) = self.last_reap.try_lock() else {
+            // 其他请求正在清理
+            return;
+        };
+        if now.saturating_duration_since(*last_reap) < REAP_INTERVAL {
+            return;
+        }
+        *last_reap = now;
+        drop(last_reap);
+        self.reap(now);
+    }
+
+    /// 移除令牌已补满的令牌桶，限制内存占用；未补满的桶保留，避免客户端借此重置限流
+    fn reap(&self, now: Instant) {
+        self.buckets
+            .retain(|_, bucket| !self.is_full_at(bucket, now));
+    }
+
+    /// 当前跟踪的客户端数量
+    pub fn tracked_clients(&self) -> usize {
+        self.buckets.len()
+    }
+}
+
+/// 为配置了限流的 location 创建限流器，键为 location 路径
+pub fn rate_limiters(config: &ProxyConfig) -> HashMap<String, RateLimiter> {
+    config
+        .locations
+        .iter()
+        .filter_map(|location| {
+            let rate_limit = location.rate_limit.as_ref()?;
+            Some((location.path.clone(), RateLimiter::new(rate_limit)))
+        })
+        .collect()
+}
+
+/// 获取客户端 IP，非 TCP 连接（如 Unix Socket）时返回 None
+pub fn client_ip(session: &Session) -> Option<IpAddr> {
+    session
+        .client_addr()
+        .and_then(|addr| addr.as_inet())
+        .map(|addr| addr.ip())
+}
+
+/// 向客户端返回 429 Too Many Requests
+pub async fn respond_too_many_requests(session: &mut Session) -> Result<()> {
+    let mut header = ResponseHeader::build(429, Some(2))?;
+    header.insert_header("Retry-After", "1")?;
+    header.insert_header("Content-Length", "0")?;
+    session.write_response_header(Box::new(header), true).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_second: f64, burst: u32) -> RateLimiter {
+        RateLimiter::new(&RateLimitConfig {
+            requests_per_second,
+            burst,
+        })
+    }
+
+    #[test]
+    fn test_requests_beyond_burst_are_limited() {
+        let limiter = limiter(1.0, 5);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let now = Instant::now();
+
+        let allowed: Vec<bool> = (0..8).map(|_| limiter.check_at(ip, now)).collect();
+        assert_eq!(allowed, [true, true, true, true, true, false, false, false]);
+
+        // 其他客户端不受影响
+        assert!(limiter.check_at("10.0.0.2".parse().unwrap(), now));
+    }
+
+    #[test]
+    fn test_tokens_refill_over_time() {
+        let limiter = limiter(2.0, 2);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let now = Instant::now();
+
+        assert!(limiter.check_at(ip, now));
+        assert!(limiter.check_at(ip, now));
+        assert!(!limiter.check_at(ip, now));
+
+        // 每秒补充 2 个令牌，500ms 后补充 1 个
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.check_at(ip, later));
+        assert!(!limiter.check_at(ip, later));
+
+        // 补充不会超过桶容量
+        let much_later = later + Duration::from_secs(10);
+        assert!(limiter.check_at(ip, much_later));
+        assert!(limiter.check_at(ip, much_later));
+        assert!(!limiter.check_at(ip, much_later));
+    }
+
+    #[test]
+    fn test_reap_removes_idle_buckets() {
+        let limiter = limiter(10.0, 5);
+        let now = Instant::now();
+        limiter.check_at("10.0.0.1".parse().unwrap(), now);
+        limiter.check_at("10.0.0.2".parse().unwrap(), now + Duration::from_secs(1));
+        assert_eq!(limiter.tracked_clients(), 2);
+
+        // 容量 5、速率 10/s，消耗 1 个令牌后 100ms 补满
+        limiter.reap(now + Duration::from_millis(1050));
+        assert_eq!(limiter.tracked_clients(), 1);
+        limiter.reap(now + Duration::from_secs(2));
+        assert_eq!(limiter.tracked_clients(), 0);
+    }
+
+    #[test]
+    fn test_reap_keeps_partially_refilled_buckets_at_slow_rate() {
+        // 每 10 秒补充 1 个令牌，从空到满需要 100 秒，超过清理间隔
+        let limiter = limiter(0.1, 10);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert!(limiter.check_at(ip, now));
+        }
+        assert!(!limiter.check_at(ip, now));
+
+        // 空闲超过清理间隔后只补充了 9 个令牌，桶仍然保留，不会被重建为满桶
+        let later = now + REAP_INTERVAL + Duration::from_secs(30);
+        limiter.reap(later);
+        assert_eq!(limiter.tracked_clients(), 1);
+        let allowed = (0..10).filter(|_| limiter.check_at(ip, later)).count();
+        assert_eq!(allowed, 9);
+
+        // 补满后才被移除
+        limiter.reap(later + Duration::from_secs(100));
+        assert_eq!(limiter.tracked_clients(), 0);
+    }
+}
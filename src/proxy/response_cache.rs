@@ -0,0 +1,326 @@
+//! 响应缓存模块
+//!
+//! 内存中的 LRU 缓存，按请求方法、路径与查询字符串缓存上游响应。
+//! 缓存由所有客户端共享，因此只缓存不带 `Authorization`/`Cookie` 的 GET 请求的 200 响应；
+//! 上游响应声明 `Cache-Control: no-store/private/no-cache`、设置 Cookie 或带有 `Vary` 时不缓存
+
+use axum::body::Bytes;
+use pingora::http::{RequestHeader, ResponseHeader};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 标记缓存命中情况的响应头，值为 `HIT` 或 `MISS`
+pub const X_CACHE: &str = "X-Cache";
+
+/// 默认最多缓存的响应数量
+pub const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+/// 单个响应体的大小上限，超过时不缓存
+pub const MAX_CACHED_BODY_BYTES: usize = 1024 * 1024;
+
+/// 缓存的响应
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    /// 响应头
+    pub header: ResponseHeader,
+    /// 响应体
+    pub body: Bytes,
+}
+
+/// 缓存条目
+#[derive(Debug)]
+struct CacheEntry {
+    response: CachedResponse,
+    expires_at: Instant,
+    /// 最近一次访问的序号，用于 LRU 淘汰
+    tick: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    /// 访问序号 -> 缓存键，序号最小的是最久未访问的条目
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl CacheState {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.tick);
+        }
+    }
+}
+
+/// LRU 响应缓存
+#[derive(Debug)]
+pub struct ResponseCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl ResponseCache {
+    /// 创建最多缓存 `capacity` 个响应的缓存，`capacity` 至少为 1
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// 查找未过期的响应，过期条目会被移除
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.get_at(key, Instant::now())
+    }
+
+    fn get_at(&self, key: &str, now: Instant) -> Option<CachedResponse> {
+        let mut state = self.state.lock().unwrap();
+        let expired = now >= state.entries.get(key)?.expires_at;
+        if expired {
+            state.remove(key);
+            return None;
+        }
+
+        let tick = state.next_tick();
+        let entry = state.entries.get_mut(key)?;
+        let previous = std::mem::replace(&mut entry.tick, tick);
+        let response = entry.response.clone();
+        state.recency.remove(&previous);
+        state.recency.insert(tick, key.to_string());
+        Some(response)
+    }
+
+    /// 缓存响应 `ttl` 时长，超出容量时淘汰最久未访问的条目
+    pub fn insert(&self, key: String, response: CachedResponse, ttl: Duration) {
+        self.insert_at(key, response, ttl, Instant::now());
+    }
+
+    fn insert_at(&self, key: String, response: CachedResponse, ttl: Duration, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        state.remove(&key);
+        while state.entries.len() >= self.capacity {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+
+        let tick = state.next_tick();
+        state.recency.insert(tick, key.clone());
+        state.entries.insert(
+            key,
+            CacheEntry {
+                response,
+                expires_at: now + ttl,
+                tick,
+            },
+        );
+    }
+
+    /// 当前缓存的响应数量（包括尚未清理的过期条目）
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// 缓存是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+/// 正在从上游读取、等待写入缓存的响应
+#[derive(Debug)]
+pub(crate) struct CacheFill {
+    /// 缓存键
+    pub(crate) key: String,
+    /// 缓存时间
+    pub(crate) ttl: Duration,
+    /// 可缓存时保存的响应头，为 None 表示本次响应不缓存
+    pub(crate) header: Option<ResponseHeader>,
+    /// 已读取的响应体
+    pub(crate) body: Vec<u8>,
+}
+
+impl CacheFill {
+    pub(crate) fn new(key: String, ttl: Duration) -> Self {
+        Self {
+            key,
+            ttl,
+            header: None,
+            body: Vec::new(),
+        }
+    }
+}
+
+/// 请求对应的缓存键（方法 + 路径 + 查询字符串）
+///
+/// 非 GET 请求以及携带 `Authorization` 或 `Cookie` 的请求返回 None，避免按用户区分的响应被其他客户端读到
+pub(crate) fn cache_key(request: &RequestHeader) -> Option<String> {
+    if request.method != http::Method::GET {
+        return None;
+    }
+    let headers = &request.headers;
+    if headers.contains_key(http::header::AUTHORIZATION)
+        || headers.contains_key(http::header::COOKIE)
+    {
+        return None;
+    }
+    let path_and_query = request
+        .uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+    Some(format!("{} {}", request.method, path_and_query))
+}
+
+/// 上游响应是否可以放入共享缓存
+///
+/// 状态码须为 200，且未声明 `Cache-Control: no-store`、`private` 或 `no-cache`，没有 `Set-Cookie`；
+/// 缓存键不包含请求头，因此带有 `Vary` 的响应也不缓存
+pub(crate) fn is_cacheable(response: &ResponseHeader) -> bool {
+    if response.status != http::StatusCode::OK {
+        return false;
+    }
+    let headers = &response.headers;
+    if headers.contains_key(http::header::SET_COOKIE) || headers.contains_key(http::header::VARY) {
+        return false;
+    }
+    let forbidden = headers
+        .get_all(http::header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.trim())
+        // private="Set-Cookie" 等带参数的形式同样视为禁止共享缓存
+        .map(|directive| directive.split('=').next().unwrap_or_default().trim())
+        .any(|directive| {
+            ["no-store", "private", "no-cache"]
+                .iter()
+                .any(|name| directive.eq_ignore_ascii_case(name))
+        });
+    !forbidden
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &'static str) -> CachedResponse {
+        CachedResponse {
+            header: ResponseHeader::build(200, None).unwrap(),
+            body: Bytes::from_static(body.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_expired_entries_are_dropped() {
+        let cache = ResponseCache::new(4);
+        let now = Instant::now();
+        cache.insert_at(
+            "GET /a".to_string(),
+            response("a"),
+            Duration::from_secs(5),
+            now,
+        );
+
+        let hit = cache
+            .get_at("GET /a", now + Duration::from_secs(4))
+            .unwrap();
+        assert_eq!(hit.body, "a");
+        assert!(
+            cache
+                .get_at("GET /a", now + Duration::from_secs(5))
+                .is_none()
+        );
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = ResponseCache::new(2);
+        let ttl = Duration::from_secs(60);
+        cache.insert("GET /a".to_string(), response("a"), ttl);
+        cache.insert("GET /b".to_string(), response("b"), ttl);
+        // 访问 /a 后，/b 成为最久未访问的条目
+        assert!(cache.get("GET /a").is_some());
+        cache.insert("GET /c".to_string(), response("c"), ttl);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("GET /a").is_some());
+        assert!(cache.get("GET /b").is_none());
+        assert!(cache.get("GET /c").is_some());
+    }
+
+    #[test]
+    fn test_cache_key_includes_query_and_skips_non_get() {
+        let request = RequestHeader::build("GET", b"/api/users?page=2", None).unwrap();
+        assert_eq!(cache_key(&request).unwrap(), "GET /api/users?page=2");
+
+        let request = RequestHeader::build("POST", b"/api/users", None).unwrap();
+        assert!(cache_key(&request).is_none());
+    }
+
+    #[test]
+    fn test_requests_with_credentials_are_not_cached() {
+        for (name, value) in [("Authorization", "Bearer token"), ("Cookie", "session=abc")] {
+            let mut request = RequestHeader::build("GET", b"/api/me", None).unwrap();
+            request.insert_header(name, value).unwrap();
+            assert!(cache_key(&request).is_none(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_is_cacheable() {
+        let mut header = ResponseHeader::build(200, None).unwrap();
+        assert!(is_cacheable(&header));
+        header
+            .insert_header("Cache-Control", "private, No-Store")
+            .unwrap();
+        assert!(!is_cacheable(&header));
+
+        let header = ResponseHeader::build(404, None).unwrap();
+        assert!(!is_cacheable(&header));
+
+        let mut header = ResponseHeader::build(200, None).unwrap();
+        header
+            .insert_header("Cache-Control", "public, max-age=60")
+            .unwrap();
+        assert!(is_cacheable(&header));
+    }
+
+    #[test]
+    fn test_per_user_responses_are_not_cacheable() {
+        for cache_control in ["private", "no-cache", "private=\"Set-Cookie\", max-age=60"] {
+            let mut header = ResponseHeader::build(200, None).unwrap();
+            header
+                .insert_header("Cache-Control", cache_control)
+                .unwrap();
+            assert!(!is_cacheable(&header), "{}", cache_control);
+        }
+
+        let mut header = ResponseHeader::build(200, None).unwrap();
+        header.insert_header("Set-Cookie", "session=abc").unwrap();
+        assert!(!is_cacheable(&header));
+    }
+
+    #[test]
+    fn test_vary_responses_are_not_cacheable() {
+        for vary in ["*", "Accept-Language"] {
+            let mut header = ResponseHeader::build(200, None).unwrap();
+            header.insert_header("Vary", vary).unwrap();
+            assert!(!is_cacheable(&header), "{}", vary);
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! 可热更新的路由表模块
+//!
+//! [`RouteTable`] 保存代理配置及由其派生的静态文件服务、限流器、IP 访问控制列表和负载均衡器，
+//! 通过 [`ProxyConfigHandle::reload_config`] 原子替换：进行中的请求继续使用旧路由表，
+//! 下一个请求开始使用新路由表，已建立的连接不会断开
+
+use crate::proxy::ip_acl::{IpAcl, ip_acls};
+use crate::proxy::load_balancer::{LoadBalancer, UpstreamSelection};
+use crate::proxy::proxy_config::{
+    LocationConfig, LocationType, MatchType, ProxyConfig, UpstreamConfig,
+};
+use crate::proxy::rate_limit::{RateLimiter, client_ip, rate_limiters};
+use crate::proxy::static_file_service::StaticFileService;
+use arc_swap::ArcSwap;
+use pingora::proxy::Session;
+use pingora::upstreams::peer::HttpPeer;
+use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+/// 某一版本配置对应的路由状态
+pub(crate) struct RouteTable {
+    /// 代理配置
+    pub(crate) config: ProxyConfig,
+    /// 正则匹配的 location 下标及编译后的正则表达式，按配置顺序排列
+    regexes: Vec<(usize, Regex)>,
+    /// 按 location 路径索引的静态文件服务
+    pub(crate) static_services: HashMap<String, StaticFileService>,
+    /// 按 location 路径索引的限流器
+    rate_limiters: HashMap<String, RateLimiter>,
+    /// 按 location 路径索引的 IP 访问控制列表
+    ip_acls: HashMap<String, IpAcl>,
+    /// 负载均衡器
+    pub(crate) load_balancer: Arc<LoadBalancer>,
+}
+
+impl RouteTable {
+    /// 根据配置创建路由表
+    ///
+    /// 上游配置与 `previous` 相同时复用原负载均衡器，保留健康状态与活跃连接数
+    fn new(config: ProxyConfig, previous: Option<&RouteTable>) -> Self {
+        let mut static_services = HashMap::new();
+        for location in &config.locations {
+            if let LocationType::Static = location.location_type {
+                if let Some(ref root) = location.root {
+                    static_services.insert(location.path.clone(), StaticFileService::new(root));
+                }
+            }
+        }
+
+        let regexes = config
+            .locations
+            .iter()
+            .enumerate()
+            .filter(|(_, location)| location.match_type == MatchType::Regex)
+            .filter_map(|(index, location)| match Regex::new(&location.path) {
+                Ok(regex) => Some((index, regex)),
+                Err(e) => {
+                    warn!("location {} 的正则表达式无效，已忽略: {}", location.path, e);
+                    None
+                }
+            })
+            .collect();
+
+        let load_balancer = match previous {
+            Some(previous) if previous.config.upstreams == config.upstreams => {
+                previous.load_balancer.clone()
+            }
+            _ => Arc::new(LoadBalancer::new(&config.upstreams)),
+        };
+
+        Self {
+            regexes,
+            rate_limiters: rate_limiters(&config),
+            ip_acls: ip_acls(&config),
+            static_services,
+            load_balancer,
+            config,
+        }
+    }
+
+    /// 根据请求路径找到匹配的位置配置
+    ///
+    /// 先查找精确匹配，再按配置顺序查找正则匹配，最后选择最长的前缀匹配
+    pub(crate) fn find_location(&self, path: &str) -> Option<&LocationConfig> {
+        let locations = &self.config.locations;
+        let exact = locations
+            .iter()
+            .find(|location| location.match_type == MatchType::Exact && location.path == path);
+        if exact.is_some() {
+            return exact;
+        }
+
+        let regex = self
+            .regexes
+            .iter()
+            .find(|(_, regex)| regex.is_match(path))
+            .map(|(index, _)| &locations[*index]);
+        if regex.is_some() {
+            return regex;
+        }
+
+        // 长度相同时取配置中靠前的 location
+        locations
+            .iter()
+            .filter(|location| {
+                location.match_type == MatchType::Prefix && path.starts_with(&location.path)
+            })
+            .min_by_key(|location| Reverse(location.path.len()))
+    }
+
+    /// 获取上游服务器配置
+    pub(crate) fn upstream_config(&self, upstream_name: &str) -> Option<&UpstreamConfig> {
+        self.config.upstreams.get(upstream_name)
+    }
+
+    /// 按 location 的 allow/deny 列表检查客户端是否被拒绝访问
+    pub(crate) fn is_ip_denied(&self, location: &LocationConfig, session: &Session) -> bool {
+        let Some(acl) = self.ip_acls.get(&location.path) else {
+            return false;
+        };
+        !acl.is_allowed(client_ip(session))
+    }
+
+    /// 按 location 的限流配置检查客户端是否超出限制
+    pub(crate) fn is_rate_limited(&self, location: &LocationConfig, session: &Session) -> bool {
+        let Some(limiter) = self.rate_limiters.get(&location.path) else {
+            return false;
+        };
+        client_ip(session).is_some_and(|ip| !limiter.check(ip))
+    }
+
+    /// 按上游配置的负载均衡策略选择服务器
+    pub(crate) fn select_upstream_server(&self, upstream_name: &str) -> Option<UpstreamSelection> {
+        self.load_balancer.select(upstream_name)
+    }
+
+    /// 选择上游服务器，并按上游的连接超时与连接池空闲时间构建连接参数
+    pub(crate) fn select_peer(
+        &self,
+        upstream_name: &str,
+    ) -> pingora::Result<(HttpPeer, UpstreamSelection)> {
+        let upstream = self.upstream_config(upstream_name).ok_or_else(|| {
+            pingora::Error::explain(pingora::ErrorType::InternalError, "Upstream not found")
+        })?;
+        let selection = self.select_upstream_server(upstream_name).ok_or_else(|| {
+            pingora::Error::explain(pingora::ErrorType::InternalError, "No servers in upstream")
+        })?;
+        let peer = upstream.peer(
+            &selection.server,
+            self.config.ssl,
+            self.config.server_name.clone(),
+        );
+        Ok((peer, selection))
+    }
+}
+
+/// 代理配置热更新句柄，克隆后与代理服务共享同一份路由表
+///
+/// 监听地址与 SSL 配置在启动时绑定，热更新只能修改上游与 location；
+/// 健康检查通过 [`HealthChecker::for_routes`](crate::proxy::HealthChecker::for_routes) 跟随当前路由表
+#[derive(Clone)]
+pub struct ProxyConfigHandle {
+    routes: Arc<ArcSwap<RouteTable>>,
+}
+
+impl ProxyConfigHandle {
+    /// 使用初始配置创建句柄
+    pub fn new(config: ProxyConfig) -> Self {
+        Self {
+            routes: Arc::new(ArcSwap::from_pointee(RouteTable::new(config, None))),
+        }
+    }
+
+    /// 当前生效的配置
+    pub fn config(&self) -> ProxyConfig {
+        self.routes.load().config.clone()
+    }
+
+    /// 校验并原子替换配置，校验失败时保留原配置
+    ///
+    /// location 引用未定义的上游、修改监听地址或 SSL 配置时返回错误
+    pub fn reload_config(&self, config: ProxyConfig) -> Result<(), String> {
+        config.validate()?;
+
+        let current = self.routes.load_full();
+        if config.listen != current.config.listen
+            || config.ssl != current.config.ssl
+            || config.ssl_cert != current.config.ssl_cert
+            || config.ssl_key != current.config.ssl_key
+        {
+            return Err("监听地址与 SSL 配置不支持热更新，请重新启动代理服务器".to_string());
+        }
+
+        self.routes
+            .store(Arc::new(RouteTable::new(config, Some(&current))));
+        Ok(())
+    }
+
+    /// 获取当前路由表，请求处理期间持有同一份路由表
+    pub(crate) fn load(&self) -> Arc<RouteTable> {
+        self.routes.load_full()
+    }
+}
+
+impl std::fmt::Debug for ProxyConfigHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyConfigHandle")
+            .field("config", &self.routes.load().config)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::proxy_config::LogFormat;
+
+    fn proxy_location(path: &str, upstream: &str) -> LocationConfig {
+        LocationConfig {
+            path: path.to_string(),
+            location_type: LocationType::Proxy,
+            match_type: MatchType::Prefix,
+            proxy_pass: Some(upstream.to_string()),
+            root: None,
+            index: None,
+            request_headers: None,
+            response_headers: None,
+            rate_limit: None,
+            websocket: false,
+            cache_ttl_secs: None,
+            allow: None,
+            deny: None,
+        }
+    }
+
+    fn upstream(server: &str) -> UpstreamConfig {
+        UpstreamConfig {
+            servers: vec![server.to_string()],
+            ..UpstreamConfig::default()
+        }
+    }
+
+    fn config() -> ProxyConfig {
+        ProxyConfig {
+            server_name: "test".to_string(),
+            listen: "127.0.0.1:8080".to_string(),
+            ssl: false,
+            ssl_cert: None,
+            ssl_key: None,
+            upstreams: HashMap::from([("api".to_string(), upstream("127.0.0.1:3000"))]),
+            locations: vec![proxy_location("/api/", "api")],
+            log_format: LogFormat::default(),
+        }
+    }
+
+    #[test]
+    fn test_reload_routes_new_location() {
+        let handle = ProxyConfigHandle::new(config());
+        let before = handle.load();
+        assert!(before.find_location("/orders/1").is_none());
+
+        let mut new_config = config();
+        new_config
+            .upstreams
+            .insert("orders".to_string(), upstream("127.0.0.1:4000"));
+        new_config
+            .locations
+            .push(proxy_location("/orders/", "orders"));
+        handle.reload_config(new_config).unwrap();
+
+        let routes = handle.load();
+        let location = routes.find_location("/orders/1").unwrap();
+        assert_eq!(location.proxy_pass.as_deref(), Some("orders"));
+        let selection = routes.select_upstream_server("orders").unwrap();
+        assert_eq!(selection.server, "127.0.0.1:4000");
+
+        // 已取得的旧路由表不受影响
+        assert!(before.find_location("/orders/1").is_none());
+    }
+
+    #[test]
+    fn test_exact_match_avoids_prefix_false_positive() {
+        let mut exact = proxy_location("/api", "api");
+        exact.match_type = MatchType::Exact;
+        let mut config = config();
+        config.locations = vec![exact];
+        let routes = ProxyConfigHandle::new(config).load();
+
+        assert_eq!(routes.find_location("/api").unwrap().path, "/api");
+        assert!(routes.find_location("/apixyz").is_none());
+        assert!(routes.find_location("/api/users").is_none());
+    }
+
+    #[test]
+    fn test_regex_match() {
+        let mut users = proxy_location("^/users/\\d+$", "api");
+        users.match_type = MatchType::Regex;
+        let mut config = config();
+        config.locations.push(users);
+        let routes = ProxyConfigHandle::new(config).load();
+
+        let location = routes.find_location("/users/42").unwrap();
+        assert_eq!(location.match_type, MatchType::Regex);
+        assert!(routes.find_location("/users/abc").is_none());
+        assert!(routes.find_location("/users/42/orders").is_none());
+        assert_eq!(routes.find_location("/api/users").unwrap().path, "/api/");
+    }
+
+    #[test]
+    fn test_match_priority() {
+        let mut exact = proxy_location("/api/health", "api");
+        exact.match_type = MatchType::Exact;
+        let mut regex = proxy_location("^/api/v\\d+/", "api");
+        regex.match_type = MatchType::Regex;
+        let mut config = config();
+        config.locations.push(proxy_location("/api/v1/", "api"));
+        config.locations.push(regex);
+        config.locations.push(exact);
+        let routes = ProxyConfigHandle::new(config).load();
+
+        assert_eq!(
+            routes.find_location("/api/health").unwrap().path,
+            "/api/health"
+        );
+        assert_eq!(
+            routes.find_location("/api/v1/users").unwrap().match_type,
+            MatchType::Regex
+        );
+        assert_eq!(routes.find_location("/api/other").unwrap().path, "/api/");
+    }
+
+    #[test]
+    fn test_select_peer_applies_upstream_timeouts() {
+        let mut config = config();
+        let api = config.upstreams.get_mut("api").unwrap();
+        api.upstream_connect_timeout_secs = Some(3);
+        api.upstream_keepalive_secs = Some(90);
+        let routes = ProxyConfigHandle::new(config).load();
+
+        let (peer, selection) = routes.select_peer("api").unwrap();
+        assert_eq!(selection.server, "127.0.0.1:3000");
+        assert_eq!(
+            peer.options.connection_timeout,
+            Some(std::time::Duration::from_secs(3))
+        );
+        assert_eq!(
+            peer.options.idle_timeout,
+            Some(std::time::Duration::from_secs(90))
+        );
+        routes.load_balancer.release(&selection);
+
+        assert!(routes.select_peer("missing").is_err());
+    }
+
+    #[test]
+    fn test_reload_rejects_undefined_upstream() {
+        let handle = ProxyConfigHandle::new(config());
+
+        let mut new_config = config();
+        new_config
+            .locations
+            .push(proxy_location("/orders/", "orders"));
+        let error = handle.reload_config(new_config).unwrap_err();
+        assert!(error.contains("orders"), "{}", error);
+        assert!(handle.load().find_location("/orders/1").is_none());
+    }
+
+    #[test]
+    fn test_reload_rejects_listen_change() {
+        let handle = ProxyConfigHandle::new(config());
+        let mut new_config = config();
+        new_config.listen = "127.0.0.1:9090".to_string();
+        assert!(handle.reload_config(new_config).is_err());
+        assert_eq!(handle.config().listen, "127.0.0.1:8080");
+    }
+
+    #[test]
+    fn test_reload_keeps_load_balancer_when_upstreams_unchanged() {
+        let handle = ProxyConfigHandle::new(config());
+        let before = handle.load().load_balancer.clone();
+
+        let mut new_config = config();
+        new_config.locations.push(proxy_location("/v2/", "api"));
+        handle.reload_config(new_config).unwrap();
+        assert!(Arc::ptr_eq(&before, &handle.load().load_balancer));
+
+        let mut new_config = config();
+        new_config.upstreams = HashMap::from([("api".to_string(), upstream("127.0.0.1:3001"))]);
+        handle.reload_config(new_config).unwrap();
+        assert!(!Arc::ptr_eq(&before, &handle.load().load_balancer));
+    }
+}
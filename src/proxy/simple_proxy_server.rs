@@ -2,17 +2,24 @@
 //!
 //! 支持路由到 Kafka API 的简化代理服务器
 
+use crate::proxy::health_check::HealthChecker;
 use crate::proxy::proxy_config::ProxyConfig;
+use crate::proxy::proxy_server::{ProxyShutdownHandle, add_listener, run_server};
+use crate::proxy::route_table::ProxyConfigHandle;
 use crate::proxy::simple_proxy_service::SimpleProxyService;
 use pingora::Result;
 use pingora::proxy::http_proxy_service;
 use pingora::server::Server;
+use pingora::services::background::background_service;
+use std::future::Future;
 use std::sync::Arc;
 
 /// 简化的代理服务器
 pub struct SimpleProxyServer {
     config: Arc<ProxyConfig>,
     server: Server,
+    shutdown: ProxyShutdownHandle,
+    routes: ProxyConfigHandle,
 }
 
 impl SimpleProxyServer {
@@ -20,25 +27,39 @@ impl SimpleProxyServer {
     pub fn new(config: ProxyConfig) -> Result<Self> {
         let server = Server::new(None)?;
         Ok(Self {
+            routes: ProxyConfigHandle::new(config.clone()),
             config: Arc::new(config),
             server,
+            shutdown: ProxyShutdownHandle::default(),
         })
     }
 
-    /// 启动简化代理服务器
+    /// 启动简化代理服务器，阻塞直到调用 [`stop`](Self::stop) 或关闭句柄
     pub fn start(&mut self) -> Result<()> {
-        self.server.bootstrap();
+        self.run_until(std::future::pending())
+    }
 
+    /// 启动简化代理服务器，`shutdown` 完成时关闭并返回
+    pub fn run_until<F>(&mut self, shutdown: F) -> Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
         // 创建简化代理服务
-        let proxy_service = SimpleProxyService::new((*self.config).clone());
+        let proxy_service = SimpleProxyService::with_handle(self.routes.clone());
+        let health_checker = HealthChecker::for_routes(self.routes.clone());
         let mut service = http_proxy_service(&self.server.configuration, proxy_service);
 
-        // 关键修复：告诉服务监听指定的 TCP 地址
-        service.add_tcp(&self.config.listen);
+        // 按配置注册 TCP 或 TLS 监听，配置有误时在启动前直接返回错误
+        add_listener(&mut service, &self.config)?;
+        self.server.bootstrap();
 
         // 添加服务到服务器
         self.server.add_service(service);
 
+        // 启用了健康检查的上游由后台任务定期探测，热更新后的上游同样会被检查
+        self.server
+            .add_service(background_service("upstream health check", health_checker));
+
         println!("Simple proxy server starting on {}", self.config.listen);
         println!("Server name: {}", self.config.server_name);
         println!("SSL enabled: {}", self.config.ssl);
@@ -65,16 +86,31 @@ impl SimpleProxyServer {
 
         // 启动服务器
         let server = std::mem::replace(&mut self.server, Server::new(None)?);
-        server.run(pingora::server::RunArgs::default());
+        run_server(server, &self.shutdown, shutdown);
         Ok(())
     }
 
     /// 停止简化代理服务器
-    pub fn stop(&mut self) {
-        println!("Stopping simple proxy server...");
+    pub fn stop(&self) {
+        self.shutdown.shutdown();
+    }
+
+    /// 获取关闭句柄，用于在其他线程中停止阻塞在 [`start`](Self::start) 的服务器
+    pub fn shutdown_handle(&self) -> ProxyShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// 获取配置句柄，用于在其他线程中热更新运行中服务器的上游与 location
+    pub fn config_handle(&self) -> ProxyConfigHandle {
+        self.routes.clone()
+    }
+
+    /// 校验并原子替换配置，下一个请求开始生效
+    pub fn reload_config(&self, config: ProxyConfig) -> std::result::Result<(), String> {
+        self.routes.reload_config(config)
     }
 
-    /// 获取配置
+    /// 获取创建时的配置，热更新后的配置通过 [`ProxyConfigHandle::config`] 获取
     pub fn get_config(&self) -> &ProxyConfig {
         &self.config
     }
@@ -2,10 +2,17 @@
 //!
 //! 支持路由到 Kafka API 的简化代理实现
 
-use crate::proxy::proxy_config::{LocationConfig, LocationType, ProxyConfig};
+use crate::proxy::access_log::{ProxyCtx, log_access};
+use crate::proxy::header_rules::{apply_request_headers, apply_response_headers, remote_addr};
+use crate::proxy::ip_acl::respond_forbidden;
+use crate::proxy::load_balancer::LoadBalancer;
+use crate::proxy::proxy_config::{LocationType, ProxyConfig};
+use crate::proxy::rate_limit::respond_too_many_requests;
+use crate::proxy::route_table::ProxyConfigHandle;
+use crate::proxy::websocket::{forward_upgrade, is_websocket_upgrade, strip_upgrade};
 use async_trait::async_trait;
 use pingora::Result;
-use pingora::http::RequestHeader;
+use pingora::http::{RequestHeader, ResponseHeader};
 use pingora::proxy::ProxyHttp;
 use pingora::proxy::Session;
 use pingora::upstreams::peer::HttpPeer;
@@ -13,73 +20,84 @@ use std::sync::Arc;
 
 /// 简化的代理服务实现
 pub struct SimpleProxyService {
-    config: Arc<ProxyConfig>,
+    routes: ProxyConfigHandle,
 }
 
 impl SimpleProxyService {
     /// 创建新的简化代理服务
     pub fn new(config: ProxyConfig) -> Self {
-        Self {
-            config: Arc::new(config),
-        }
+        Self::with_handle(ProxyConfigHandle::new(config))
     }
 
-    /// 根据请求路径找到匹配的位置配置
-    fn find_location(&self, path: &str) -> Option<&LocationConfig> {
-        // 按路径长度降序排序，优先匹配更具体的路径
-        let mut locations: Vec<_> = self.config.locations.iter().collect();
-        locations.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
+    /// 使用共享的配置句柄创建服务，通过句柄热更新配置
+    pub fn with_handle(routes: ProxyConfigHandle) -> Self {
+        Self { routes }
+    }
 
-        for location in locations {
-            if path.starts_with(&location.path) {
-                return Some(location);
-            }
-        }
-        None
+    /// 获取配置句柄，可以在服务交给 pingora 后继续热更新配置
+    pub fn config_handle(&self) -> ProxyConfigHandle {
+        self.routes.clone()
     }
 
-    /// 获取上游服务器配置
-    fn get_upstream_config(
-        &self,
-        upstream_name: &str,
-    ) -> Option<&crate::proxy::proxy_config::UpstreamConfig> {
-        self.config.upstreams.get(upstream_name)
+    /// 校验并原子替换配置，下一个请求开始生效
+    pub fn reload_config(&self, config: ProxyConfig) -> std::result::Result<(), String> {
+        self.routes.reload_config(config)
     }
 
-    /// 选择上游服务器（简单的轮询实现）
-    fn select_upstream_server<'a>(
-        &self,
-        upstream_config: &'a crate::proxy::proxy_config::UpstreamConfig,
-    ) -> Option<&'a String> {
-        // 这里可以实现更复杂的负载均衡策略
-        // 目前使用简单的轮询
-        upstream_config.servers.first()
+    /// 获取负载均衡器，供健康检查等后台任务共享
+    pub fn load_balancer(&self) -> Arc<LoadBalancer> {
+        self.routes.load().load_balancer.clone()
     }
 }
 
 #[async_trait]
 impl ProxyHttp for SimpleProxyService {
-    type CTX = ();
+    type CTX = ProxyCtx;
 
     fn new_ctx(&self) -> Self::CTX {
-        ()
+        ProxyCtx::new()
+    }
+
+    async fn request_filter(&self, session: &mut Session, ctx: &mut Self::CTX) -> Result<bool>
+    where
+        Self::CTX: Send + Sync,
+    {
+        let routes = self.routes.load();
+        let Some(location) = routes.find_location(session.req_header().uri.path()) else {
+            return Ok(false);
+        };
+        if routes.is_ip_denied(location, session) {
+            ctx.location = Some(location.path.clone());
+            respond_forbidden(session).await?;
+            return Ok(true);
+        }
+        if routes.is_rate_limited(location, session) {
+            ctx.location = Some(location.path.clone());
+            respond_too_many_requests(session).await?;
+            return Ok(true);
+        }
+        ctx.websocket = location.websocket && is_websocket_upgrade(session.req_header());
+        Ok(false)
     }
 
     async fn upstream_peer(
         &self,
         session: &mut Session,
-        _ctx: &mut Self::CTX,
+        ctx: &mut Self::CTX,
     ) -> Result<Box<HttpPeer>> {
         let path = session.req_header().uri.path();
+        let routes = self.routes.load();
 
         // 查找匹配的位置配置
-        let location = self.find_location(path).ok_or_else(|| {
+        let location = routes.find_location(path).ok_or_else(|| {
             pingora::Error::explain(
                 pingora::ErrorType::InternalError,
                 "No matching location found",
             )
         })?;
 
+        ctx.location = Some(location.path.clone());
+
         match location.location_type {
             LocationType::Proxy => {
                 // 代理到上游服务器
@@ -90,20 +108,11 @@ impl ProxyHttp for SimpleProxyService {
                     )
                 })?;
 
-                let upstream_config = self.get_upstream_config(upstream_name).ok_or_else(|| {
-                    pingora::Error::explain(pingora::ErrorType::InternalError, "Upstream not found")
-                })?;
-
-                let server = self
-                    .select_upstream_server(upstream_config)
-                    .ok_or_else(|| {
-                        pingora::Error::explain(
-                            pingora::ErrorType::InternalError,
-                            "No servers in upstream",
-                        )
-                    })?;
-
-                let peer = HttpPeer::new(server, self.config.ssl, self.config.server_name.clone());
+                let (peer, selection) = routes.select_peer(upstream_name)?;
+                // 重试时会再次选择上游，先归还上一次的选择
+                if let Some(previous) = ctx.upstream.replace(selection) {
+                    routes.load_balancer.release(&previous);
+                }
                 Ok(Box::new(peer))
             }
             LocationType::Static => {
@@ -119,38 +128,31 @@ impl ProxyHttp for SimpleProxyService {
         &self,
         session: &mut Session,
         upstream_request: &mut RequestHeader,
-        _ctx: &mut Self::CTX,
+        ctx: &mut Self::CTX,
     ) -> Result<()> {
         let path = session.req_header().uri.path();
+        let routes = self.routes.load();
 
         // 查找匹配的位置配置
-        if let Some(location) = self.find_location(path) {
+        if let Some(location) = routes.find_location(path) {
             match location.location_type {
                 LocationType::Proxy => {
                     // 修改请求路径，移除 location 前缀
-                    if let Some(_proxy_pass) = &location.proxy_pass {
-                        // 移除 location 前缀
-                        let new_path = path.strip_prefix(&location.path).unwrap_or(path);
-
-                        // 保留原始请求的查询字符串
-                        let new_path_and_query = if let Some(query) = session.req_header().uri.query() {
-                            format!("/{}?{}", new_path, query)
-                        } else {
-                            format!("/{}", new_path)
-                        };
-
-                        // 解析为 PathAndQuery
-                        if let Ok(path_and_query) = new_path_and_query.parse() {
-                            // 获取当前 URI 的 parts
-                            let mut parts = upstream_request.uri.clone().into_parts();
-                            // 替换 path_and_query
-                            parts.path_and_query = Some(path_and_query);
-                            // 从 parts 构建新的 URI
-                            if let Ok(new_uri) = http::Uri::from_parts(parts) {
-                                upstream_request.set_uri(new_uri);
-                            }
+                    if location.proxy_pass.is_some() {
+                        if let Some(new_uri) = location.rewrite_uri(&upstream_request.uri) {
+                            upstream_request.set_uri(new_uri);
                         }
                     }
+                    if let Some(headers) = &location.request_headers {
+                        let client = remote_addr(session);
+                        apply_request_headers(headers, upstream_request, client.as_deref())?;
+                    }
+                    // 仅开启 websocket 的 location 转发升级请求，其余按普通 HTTP 请求转发
+                    if ctx.websocket {
+                        forward_upgrade(upstream_request)?;
+                    } else if is_websocket_upgrade(upstream_request) {
+                        strip_upgrade(upstream_request);
+                    }
                 }
                 LocationType::Static => {
                     // 静态文件请求
@@ -162,4 +164,38 @@ impl ProxyHttp for SimpleProxyService {
         println!("Proxying request to: {:?}", upstream_request.uri);
         Ok(())
     }
+
+    async fn response_filter(
+        &self,
+        session: &mut Session,
+        upstream_response: &mut ResponseHeader,
+        ctx: &mut Self::CTX,
+    ) -> Result<()>
+    where
+        Self::CTX: Send + Sync,
+    {
+        let routes = self.routes.load();
+        let headers = ctx
+            .location
+            .as_deref()
+            .and_then(|path| routes.config.location(path))
+            .and_then(|location| location.response_headers.as_ref());
+        if let Some(headers) = headers {
+            let client = remote_addr(session);
+            apply_response_headers(headers, upstream_response, client.as_deref())?;
+        }
+        Ok(())
+    }
+
+    async fn logging(&self, session: &mut Session, _e: Option<&pingora::Error>, ctx: &mut Self::CTX)
+    where
+        Self::CTX: Send + Sync,
+    {
+        // 请求结束，归还上游选择以更新活跃连接数
+        let routes = self.routes.load();
+        if let Some(selection) = ctx.upstream.as_ref() {
+            routes.load_balancer.release(selection);
+        }
+        log_access(routes.config.log_format, session, ctx);
+    }
 }
@@ -2,8 +2,8 @@
 //!
 //! 提供静态文件服务功能，类似 Nginx 的静态文件服务
 
-use std::io::Result;
-use std::path::{Path, PathBuf};
+use std::io::{ErrorKind, Result};
+use std::path::{Component, Path, PathBuf};
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 
@@ -12,6 +12,37 @@ pub struct StaticFileService {
     root: PathBuf,
 }
 
+/// 静态文件响应
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticFileResponse {
+    /// HTTP 状态码
+    pub status: u16,
+    /// 内容类型
+    pub content_type: &'static str,
+    /// 响应体
+    pub body: Vec<u8>,
+}
+
+impl StaticFileResponse {
+    /// 文件不存在
+    pub fn not_found() -> Self {
+        Self {
+            status: 404,
+            content_type: "text/plain",
+            body: b"Not Found".to_vec(),
+        }
+    }
+
+    /// 禁止访问（路径越界）
+    pub fn forbidden() -> Self {
+        Self {
+            status: 403,
+            content_type: "text/plain",
+            body: b"Forbidden".to_vec(),
+        }
+    }
+}
+
 impl StaticFileService {
     /// 创建新的静态文件服务
     pub fn new(root: &str) -> Self {
@@ -21,49 +52,60 @@ impl StaticFileService {
     }
 
     /// 处理静态文件请求
-    pub async fn serve_file(&self, path: &str) -> Result<Vec<u8>> {
+    ///
+    /// 文件不存在时返回 404，路径包含 `..` 等越界访问时返回 403
+    pub async fn serve_file(&self, path: &str) -> Result<StaticFileResponse> {
         // 防止路径遍历攻击
-        let full_path = self.sanitize_path(path)?;
+        let Some(full_path) = self.sanitize_path(path) else {
+            return Ok(StaticFileResponse::forbidden());
+        };
 
         // 检查文件是否存在
-        if !full_path.exists() || !full_path.is_file() {
-            // 简化处理：直接返回文本内容，避免依赖未解析的 http 类型
-            return Ok(b"Not Found".to_vec());
+        if !full_path.is_file() {
+            return Ok(StaticFileResponse::not_found());
         }
 
         // 读取文件内容
-        let mut file = File::open(&full_path).await?;
+        let mut file = match File::open(&full_path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Ok(StaticFileResponse::not_found());
+            }
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+                return Ok(StaticFileResponse::forbidden());
+            }
+            Err(e) => return Err(e),
+        };
         let mut buffer = Vec::new();
         file.read_to_end(&mut buffer).await?;
 
-        // 简化处理：返回文件字节内容
-        Ok(buffer)
+        Ok(StaticFileResponse {
+            status: 200,
+            content_type: self.guess_content_type(&full_path),
+            body: buffer,
+        })
     }
 
     /// 清理路径，防止路径遍历攻击
-    fn sanitize_path(&self, path: &str) -> Result<PathBuf> {
+    ///
+    /// 路径中出现 `..` 时返回 None
+    fn sanitize_path(&self, path: &str) -> Option<PathBuf> {
         // 移除查询参数和片段
         let clean_path = path.split('?').next().unwrap_or(path);
         let clean_path = clean_path.split('#').next().unwrap_or(clean_path);
 
-        // 解析路径
-        let path = Path::new(clean_path);
-
         // 规范化路径
         let mut full_path = self.root.clone();
-        for component in path.components() {
+        for component in Path::new(clean_path).components() {
             match component {
-                std::path::Component::Normal(part) => {
-                    full_path.push(part);
-                }
-                _ => {
-                    // 忽略其他组件（如 .. 或 .）
-                    continue;
-                }
+                Component::Normal(part) => full_path.push(part),
+                Component::ParentDir => return None,
+                // 忽略根目录和 `.`
+                _ => continue,
             }
         }
 
-        Ok(full_path)
+        Some(full_path)
     }
 
     /// 根据文件扩展名猜测内容类型
@@ -85,3 +127,55 @@ impl StaticFileService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 创建临时静态文件目录
+    fn temp_root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("clamber-static-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[tokio::test]
+    async fn test_serve_existing_html_file() {
+        let root = temp_root("html");
+        std::fs::write(root.join("index.html"), "<h1>hello</h1>").unwrap();
+        let service = StaticFileService::new(root.to_str().unwrap());
+
+        let response = service.serve_file("/index.html?v=1").await.unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.content_type, "text/html");
+        assert_eq!(response.body, b"<h1>hello</h1>");
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_serve_missing_file() {
+        let root = temp_root("missing");
+        let service = StaticFileService::new(root.to_str().unwrap());
+
+        let response = service.serve_file("/missing.css").await.unwrap();
+        assert_eq!(response, StaticFileResponse::not_found());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reject_path_traversal() {
+        let root = temp_root("traversal");
+        let public = root.join("public");
+        std::fs::create_dir_all(&public).unwrap();
+        std::fs::write(root.join("secret.txt"), "secret").unwrap();
+        let service = StaticFileService::new(public.to_str().unwrap());
+
+        let response = service.serve_file("/../secret.txt").await.unwrap();
+        assert_eq!(response, StaticFileResponse::forbidden());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,211 @@
+//! WebSocket 转发模块
+//!
+//! 识别 `Connection: Upgrade` + `Upgrade: websocket` 请求。location 开启 `websocket` 时
+//! 保留升级头部转发给上游，上游返回 101 后由 Pingora 在客户端与上游之间双向转发数据；
+//! 未开启时移除升级头部，按普通 HTTP 请求转发（与 Nginx 默认行为一致）
+
+use pingora::Result;
+use pingora::http::RequestHeader;
+
+/// 判断是否为 WebSocket 升级请求
+pub fn is_websocket_upgrade(request: &RequestHeader) -> bool {
+    let header_has_token = |name: &str, token: &str| {
+        request.headers.get_all(name).iter().any(|value| {
+            value.to_str().is_ok_and(|value| {
+                value
+                    .split(',')
+                    .any(|item| item.trim().eq_ignore_ascii_case(token))
+            })
+        })
+    };
+    header_has_token("connection", "upgrade") && header_has_token("upgrade", "websocket")
+}
+
+/// 为发往上游的请求设置升级头部，确保上游收到完整的升级请求
+pub fn forward_upgrade(upstream_request: &mut RequestHeader) -> Result<()> {
+    upstream_request.insert_header("Upgrade", "websocket")?;
+    upstream_request.insert_header("Connection", "Upgrade")?;
+    Ok(())
+}
+
+/// 移除升级相关头部，按普通 HTTP 请求转发
+pub fn strip_upgrade(upstream_request: &mut RequestHeader) {
+    upstream_request.remove_header("Upgrade");
+    upstream_request.remove_header("Connection");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::proxy_config::{
+        LocationConfig, LocationType, MatchType, ProxyConfig, UpstreamConfig,
+    };
+    use crate::proxy::simple_proxy_server::SimpleProxyServer;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// RFC 6455 中的示例握手密钥及对应的 Accept 值
+    const HANDSHAKE_KEY: &str = "dGhlIHNhbXBsZSBub25jZQ==";
+    const HANDSHAKE_ACCEPT: &str = "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=";
+
+    fn upgrade_request(connection: &str, upgrade: Option<&str>) -> RequestHeader {
+        let mut request = RequestHeader::build("GET", b"/ws", None).unwrap();
+        request.insert_header("Connection", connection).unwrap();
+        if let Some(upgrade) = upgrade {
+            request.insert_header("Upgrade", upgrade).unwrap();
+        }
+        request
+    }
+
+    #[test]
+    fn test_detect_websocket_upgrade() {
+        assert!(is_websocket_upgrade(&upgrade_request(
+            "Upgrade",
+            Some("websocket")
+        )));
+        assert!(is_websocket_upgrade(&upgrade_request(
+            "keep-alive, Upgrade",
+            Some("WebSocket")
+        )));
+        assert!(!is_websocket_upgrade(&upgrade_request("keep-alive", None)));
+        assert!(!is_websocket_upgrade(&upgrade_request(
+            "Upgrade",
+            Some("h2c")
+        )));
+    }
+
+    #[test]
+    fn test_strip_upgrade_headers() {
+        let mut request = upgrade_request("Upgrade", Some("websocket"));
+        strip_upgrade(&mut request);
+        assert!(!is_websocket_upgrade(&request));
+        assert!(request.headers.get("upgrade").is_none());
+    }
+
+    /// 读取直到 HTTP 头部结束
+    async fn read_head(stream: &mut TcpStream) -> String {
+        let mut head = Vec::new();
+        let mut byte = [0u8; 1];
+        while !head.ends_with(b"\r\n\r\n") {
+            stream.read_exact(&mut byte).await.unwrap();
+            head.push(byte[0]);
+        }
+        String::from_utf8(head).unwrap()
+    }
+
+    /// 最简单的 WebSocket 回显上游：完成握手后原样返回收到的字节
+    async fn echo_upstream() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let head = read_head(&mut stream).await.to_ascii_lowercase();
+            assert!(head.starts_with("get /echo "));
+            assert!(head.contains("upgrade: websocket"));
+
+            let response = format!(
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                HANDSHAKE_ACCEPT
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+
+            let mut buf = [0u8; 1024];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                stream.write_all(&buf[..n]).await.unwrap();
+            }
+        });
+        addr
+    }
+
+    /// 获取一个当前没有被占用的本地地址
+    async fn free_addr() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_websocket_tunnel_through_proxy() {
+        let upstream = echo_upstream().await;
+        let listen = free_addr().await;
+
+        let mut upstreams = HashMap::new();
+        upstreams.insert(
+            "echo".to_string(),
+            UpstreamConfig {
+                servers: vec![upstream],
+                lb_strategy: "roundrobin".to_string(),
+                health_check_path: None,
+                health_check_interval_secs: 0,
+                unhealthy_threshold: 3,
+                upstream_keepalive_secs: None,
+                upstream_connect_timeout_secs: None,
+            },
+        );
+        let config = ProxyConfig {
+            server_name: "ws.local".to_string(),
+            listen: listen.clone(),
+            ssl: false,
+            ssl_cert: None,
+            ssl_key: None,
+            upstreams,
+            locations: vec![LocationConfig {
+                path: "/ws/".to_string(),
+                location_type: LocationType::Proxy,
+                match_type: MatchType::Prefix,
+                proxy_pass: Some("echo".to_string()),
+                root: None,
+                index: None,
+                request_headers: None,
+                response_headers: None,
+                rate_limit: None,
+                websocket: true,
+                cache_ttl_secs: None,
+                allow: None,
+                deny: None,
+            }],
+            log_format: Default::default(),
+        };
+        std::thread::spawn(move || SimpleProxyServer::new(config).unwrap().start());
+
+        // 等待代理开始监听
+        let mut client = None;
+        for _ in 0..50 {
+            if let Ok(stream) = TcpStream::connect(&listen).await {
+                client = Some(stream);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let mut client = client.expect("proxy did not start listening");
+
+        let handshake = format!(
+            "GET /ws/echo HTTP/1.1\r\nHost: ws.local\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            HANDSHAKE_KEY
+        );
+        client.write_all(handshake.as_bytes()).await.unwrap();
+        let head = read_head(&mut client).await;
+        assert!(head.starts_with("HTTP/1.1 101"), "{}", head);
+        assert!(head.contains(HANDSHAKE_ACCEPT));
+
+        // 客户端发送带掩码的文本帧 "hello"，上游原样回显
+        let mask = [0x37, 0xfa, 0x21, 0x3d];
+        let mut frame = vec![0x81, 0x80 | 5];
+        frame.extend_from_slice(&mask);
+        frame.extend(b"hello".iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        for _ in 0..2 {
+            client.write_all(&frame).await.unwrap();
+            let mut echoed = vec![0u8; frame.len()];
+            tokio::time::timeout(Duration::from_secs(5), client.read_exact(&mut echoed))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(echoed, frame);
+        }
+    }
+}
@@ -0,0 +1,439 @@
+//! Redis 幂等键中间件
+//!
+//! 基于 `Idempotency-Key` 请求头的 Axum 中间件：首次请求通过 SET NX 占用幂等键并在处理完成后
+//! 保存响应，有效期内相同键的重复请求直接返回保存的响应，不再执行处理函数
+
+use crate::redis::{RedisConnection, RedisError, RedisResult};
+use axum::body::{Body, HttpBody, to_bytes};
+use axum::extract::{Request, State};
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// 重放响应时附加的响应头
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// 幂等中间件配置
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    /// 幂等键请求头名称
+    pub header_name: String,
+    /// Redis 键前缀
+    pub key_prefix: String,
+    /// 响应保存时长
+    pub ttl: Duration,
+    /// 处理中状态的占用时长，超过后视为处理方已失败
+    pub in_flight_ttl: Duration,
+    /// 相同键的请求仍在处理中时，是否等待其完成后重放响应（否则直接返回 409）
+    pub wait_for_in_flight: bool,
+    /// 等待处理中请求完成的最长时间
+    pub wait_timeout: Duration,
+    /// 可保存的最大响应体字节数，超过或长度未知的响应原样返回但不保存
+    pub max_body_bytes: usize,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            header_name: "Idempotency-Key".to_string(),
+            key_prefix: "idempotency:".to_string(),
+            ttl: Duration::from_secs(24 * 60 * 60),
+            in_flight_ttl: Duration::from_secs(30),
+            wait_for_in_flight: false,
+            wait_timeout: Duration::from_secs(10),
+            max_body_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// 幂等中间件状态，配合 `axum::middleware::from_fn_with_state` 使用
+#[derive(Clone)]
+pub struct IdempotencyState {
+    connection: RedisConnection,
+    config: Arc<IdempotencyConfig>,
+}
+
+impl IdempotencyState {
+    /// 创建幂等中间件状态
+    pub fn new(connection: RedisConnection, config: IdempotencyConfig) -> Self {
+        Self {
+            connection,
+            config: Arc::new(config),
+        }
+    }
+}
+
+/// 幂等键记录
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum IdempotencyRecord {
+    /// 请求处理中
+    InFlight,
+    /// 请求已完成，保存了响应
+    Completed(StoredResponse),
+    /// 请求已完成，但响应过大或无法读取而未保存
+    NotStored,
+}
+
+/// 保存的响应
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl StoredResponse {
+    /// 还原为 HTTP 响应
+    fn into_response(self, replayed: bool) -> Response {
+        let mut response = Response::new(Body::from(self.body));
+        *response.status_mut() =
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        let headers = response.headers_mut();
+        for (name, value) in self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(name.as_str()),
+                HeaderValue::try_from(value.as_str()),
+            ) {
+                headers.append(name, value);
+            }
+        }
+
+        if replayed {
+            headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+        }
+
+        response
+    }
+}
+
+/// 幂等键中间件
+///
+/// 未携带幂等键的请求直接放行；Redis 不可用时记录警告并直接处理请求。
+/// 5xx 响应不会被保存，客户端可以使用相同的幂等键重试；响应体超过
+/// [`IdempotencyConfig::max_body_bytes`] 时原样返回，相同键的重复请求返回 409 而不会再次执行
+pub async fn idempotency_middleware(
+    State(state): State<IdempotencyState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = request
+        .headers()
+        .get(state.config.header_name.as_str())
+        .and_then(|value| value.to_str().ok())
+        .map(|value| format!("{}{}", state.config.key_prefix, value))
+    else {
+        return next.run(request).await;
+    };
+
+    let mut conn = state.connection.clone();
+    match claim(&mut conn, &key, state.config.in_flight_ttl).await {
+        Ok(true) => {}
+        Ok(false) => return replay_or_conflict(&mut conn, &key, &state.config).await,
+        Err(e) => {
+            warn!("幂等键检查失败，直接处理请求: {}", e);
+            return next.run(request).await;
+        }
+    }
+
+    let response = next.run(request).await;
+    store_response(&mut conn, &key, response, &state.config).await
+}
+
+/// 通过 SET NX 占用幂等键
+async fn claim(conn: &mut RedisConnection, key: &str, ttl: Duration) -> RedisResult<bool> {
+    let record = serde_json::to_string(&IdempotencyRecord::InFlight)
+        .map_err(|e| RedisError::serialization(e.to_string()))?;
+    let reply: Option<String> = conn
+        .query(
+            redis::cmd("SET")
+                .arg(conn.prefixed_key(key))
+                .arg(record)
+                .arg("NX")
+                .arg("PX")
+                .arg(ttl.as_millis() as u64),
+        )
+        .await?;
+    Ok(reply.is_some())
+}
+
+/// 保存处理结果并返回响应
+async fn store_response(
+    conn: &mut RedisConnection,
+    key: &str,
+    response: Response,
+    config: &IdempotencyConfig,
+) -> Response {
+    let (parts, body) = response.into_parts();
+    if parts.status.is_server_error() {
+        release(conn, key).await;
+        return Response::from_parts(parts, body);
+    }
+
+    // 处理函数已执行，无法保存响应时仍标记为已完成，避免重试时重复执行
+    if !fits_limit(&body, config.max_body_bytes) {
+        warn!("响应体过大或长度未知，不保存幂等响应 {}", key);
+        mark_not_stored(conn, key, config).await;
+        return Response::from_parts(parts, body);
+    }
+    let body = match to_bytes(body, config.max_body_bytes).await {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("读取响应体失败，不保存幂等响应 {}: {}", key, e);
+            mark_not_stored(conn, key, config).await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        headers: parts
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect(),
+        body: body.to_vec(),
+    };
+
+    if let Err(e) = conn
+        .set_json_ex(key, &IdempotencyRecord::Completed(stored), config.ttl)
+        .await
+    {
+        warn!("保存幂等响应失败 {}: {}", key, e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// 响应体长度是否已知且不超过上限
+fn fits_limit(body: &Body, max_body_bytes: usize) -> bool {
+    body.size_hint()
+        .upper()
+        .is_some_and(|upper| upper <= max_body_bytes as u64)
+}
+
+/// 标记请求已完成但未保存响应
+async fn mark_not_stored(conn: &mut RedisConnection, key: &str, config: &IdempotencyConfig) {
+    if let Err(e) = conn
+        .set_json_ex(key, &IdempotencyRecord::NotStored, config.ttl)
+        .await
+    {
+        warn!("标记幂等键失败 {}: {}", key, e);
+    }
+}
+
+/// 重放已保存的响应，请求仍在处理中时按配置等待或返回 409
+async fn replay_or_conflict(
+    conn: &mut RedisConnection,
+    key: &str,
+    config: &IdempotencyConfig,
+) -> Response {
+    let deadline = Instant::now() + config.wait_timeout;
+
+    loop {
+        match conn.get_json::<IdempotencyRecord>(key).await {
+            Ok(Some(IdempotencyRecord::Completed(stored))) => return stored.into_response(true),
+            Ok(Some(IdempotencyRecord::NotStored)) => {
+                return (StatusCode::CONFLICT, "相同幂等键的请求已处理，响应未保存")
+                    .into_response();
+            }
+            Ok(Some(IdempotencyRecord::InFlight)) | Ok(None) => {}
+            Err(e) => {
+                warn!("读取幂等记录失败 {}: {}", key, e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+
+        if !config.wait_for_in_flight || Instant::now() >= deadline {
+            return (StatusCode::CONFLICT, "相同幂等键的请求正在处理中").into_response();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// 释放幂等键，允许客户端重试
+async fn release(conn: &mut RedisConnection, key: &str) {
+    if let Err(e) = conn.del(key).await {
+        warn!("释放幂等键失败 {}: {}", key, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+    use axum::Router;
+    use axum::routing::post;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    fn unique_key() -> String {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        format!("test-{}-{}", std::process::id(), nanos)
+    }
+
+    fn payment_router(state: IdempotencyState, calls: Arc<AtomicUsize>, delay: Duration) -> Router {
+        Router::new()
+            .route(
+                "/payments",
+                post(move || {
+                    let calls = calls.clone();
+                    async move {
+                        let count = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                        tokio::time::sleep(delay).await;
+                        (StatusCode::CREATED, format!("payment-{}", count))
+                    }
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                state,
+                idempotency_middleware,
+            ))
+    }
+
+    fn payment_request(key: &str) -> Request {
+        axum::http::Request::post("/payments")
+            .header("Idempotency-Key", key)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_stored_response_round_trip() {
+        let stored = StoredResponse {
+            status: 201,
+            headers: vec![("content-type".to_string(), "text/plain".to_string())],
+            body: b"ok".to_vec(),
+        };
+        let json = serde_json::to_string(&IdempotencyRecord::Completed(stored)).unwrap();
+        let IdempotencyRecord::Completed(stored) = serde_json::from_str(&json).unwrap() else {
+            panic!("应为已完成记录");
+        };
+
+        let response = stored.into_response(true);
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()["content-type"], "text/plain");
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+    }
+
+    #[test]
+    fn test_fits_limit() {
+        assert!(fits_limit(&Body::from("ok"), 2));
+        assert!(!fits_limit(&Body::from("too long"), 2));
+        assert!(fits_limit(&Body::empty(), 0));
+
+        // 流式响应长度未知，不保存
+        let stream = Body::from("streamed").into_data_stream();
+        assert!(!fits_limit(&Body::from_stream(stream), usize::MAX));
+    }
+
+    #[tokio::test]
+    async fn test_handler_runs_once_for_duplicate_requests() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let calls = Arc::new(AtomicUsize::new(0));
+        let state = IdempotencyState::new(conn, IdempotencyConfig::default());
+        let app = payment_router(state, calls.clone(), Duration::ZERO);
+        let key = unique_key();
+
+        for i in 0..3 {
+            let response = app.clone().oneshot(payment_request(&key)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            assert_eq!(
+                response.headers().contains_key(IDEMPOTENT_REPLAYED_HEADER),
+                i > 0
+            );
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(&body[..], b"payment-1");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 不携带幂等键的请求不受影响
+        let request = axum::http::Request::post("/payments")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_duplicate_conflict_or_wait() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        // 默认不等待：处理中的重复请求返回 409
+        let state = IdempotencyState::new(conn.clone(), IdempotencyConfig::default());
+        let app = payment_router(state, calls.clone(), Duration::from_millis(300));
+        let key = unique_key();
+        let first = tokio::spawn(app.clone().oneshot(payment_request(&key)));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let duplicate = app.oneshot(payment_request(&key)).await.unwrap();
+        assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+        assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::CREATED);
+
+        // 开启等待：处理中的重复请求等待完成后重放
+        let config = IdempotencyConfig {
+            wait_for_in_flight: true,
+            ..IdempotencyConfig::default()
+        };
+        let app = payment_router(
+            IdempotencyState::new(conn, config),
+            calls.clone(),
+            Duration::from_millis(300),
+        );
+        let key = unique_key();
+        let first = tokio::spawn(app.clone().oneshot(payment_request(&key)));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let duplicate = app.oneshot(payment_request(&key)).await.unwrap();
+        assert_eq!(duplicate.status(), StatusCode::CREATED);
+        assert!(duplicate.headers().contains_key(IDEMPOTENT_REPLAYED_HEADER));
+        first.await.unwrap().unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_response_is_returned_but_not_stored() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = IdempotencyConfig {
+            max_body_bytes: 4,
+            ..IdempotencyConfig::default()
+        };
+        let app = payment_router(
+            IdempotencyState::new(conn, config),
+            calls.clone(),
+            Duration::ZERO,
+        );
+        let key = unique_key();
+
+        // 首次请求返回处理函数的真实响应
+        let response = app.clone().oneshot(payment_request(&key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"payment-1");
+
+        // 重复请求不会再次执行处理函数
+        let duplicate = app.oneshot(payment_request(&key)).await.unwrap();
+        assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
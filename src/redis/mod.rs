@@ -3,18 +3,90 @@
 //! 提供基于 Redis 的缓存连接管理、配置和工具函数
 //! 集成 clamber-core 的配置管理功能
 
+pub mod idempotency;
+pub mod redis_axum;
+pub mod redis_bitmap;
+pub mod redis_cache;
+pub mod redis_compression;
 pub mod redis_config;
+pub mod redis_config_builder;
 pub mod redis_connection;
 pub mod redis_error;
+pub mod redis_events;
+pub mod redis_geo;
+pub mod redis_health;
+pub mod redis_info;
+pub mod redis_lock;
+pub mod redis_manager;
+pub mod redis_metrics;
+pub mod redis_migration;
+pub mod redis_pool;
+pub mod redis_queue;
+pub mod redis_retry;
+pub mod redis_script;
+pub mod redis_tracing;
+pub mod redis_transaction;
+pub mod redis_value_type;
+pub mod token_revocation;
 
 // 重新导出主要组件
+pub use idempotency::{IdempotencyConfig, IdempotencyState, idempotency_middleware};
+pub use redis_axum::{
+    RedisAppState, create_redis_app_state_from_config, create_redis_app_state_from_url,
+};
+pub use redis_bitmap::{BitOperation, BitRange};
+#[cfg(feature = "redis-bincode")]
+pub use redis_cache::BincodeSerializer;
+#[cfg(feature = "redis-msgpack")]
+pub use redis_cache::MessagePackSerializer;
+pub use redis_cache::{CacheSerializer, JsonSerializer, RedisCache, RedisCacheStats};
+pub use redis_compression::CompressionCodec;
 pub use redis_config::RedisConfig;
+pub use redis_config_builder::RedisConfigBuilder;
 pub use redis_connection::{RedisConnection, RedisConnectionStats, RedisHealthStatus};
 pub use redis_error::{RedisError, RedisResult};
+pub use redis_events::{ConnectionEvent, ConnectionEventKind};
+pub use redis_geo::{GeoCoordinates, GeoMember, GeoUnit};
+pub use redis_health::{RedisHealthMonitor, RedisHealthMonitorConfig};
+pub use redis_info::{KeyspaceInfo, RedisServerInfo, ReplicationRole, SlowlogEntry};
+pub use redis_lock::RedisLock;
+pub use redis_manager::{RedisManager, parse_redis_configs};
+pub use redis_metrics::{
+    CommandMetricsSnapshot, LatencyBucket, LatencySnapshot, RedisMetrics, RedisMetricsSnapshot,
+};
+pub use redis_migration::{ConflictPolicy, MigrationProgress, migrate_keys};
+pub use redis_pool::{PooledConnection, RedisPool, RedisPoolStrategy};
+pub use redis_queue::{Delivery, RedisQueue, RedisQueueConfig};
+pub use redis_retry::RetryPolicy;
+pub use redis_script::{FromRedisScriptValue, RedisScript};
+pub use redis_tracing::KeyLogging;
+pub use redis_transaction::{RedisTransaction, TransactionQueue};
+pub use redis_value_type::RedisValueType;
+pub use token_revocation::{
+    RevocationClaims, TokenRevocationConfig, TokenRevocationStore, token_revocation_middleware,
+};
 
 // 便利函数
 pub use redis_connection::{
     // 用于 Axum AppState 的便利版本
     create_redis_connection_from_config,
+    create_redis_connection_from_env,
     create_redis_connection_from_url,
 };
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::{RedisConfig, RedisConnection};
+
+    /// 连接测试用 Redis（通过 REDIS_URL 指定），不可用时返回 None 以跳过测试
+    pub(crate) async fn connect_test_redis() -> Option<RedisConnection> {
+        let config = RedisConfig {
+            url: std::env::var("REDIS_URL")
+                .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string()),
+            connection_timeout_secs: 2,
+            retry_count: 0,
+            ..RedisConfig::default()
+        };
+        RedisConnection::new(config).await.ok()
+    }
+}
@@ -0,0 +1,180 @@
+//! Axum 集成模块
+//!
+//! 为 axum 项目提供 Redis 的 AppState 集成。[`RedisConnection`] 内部是多路复用连接，
+//! 克隆开销很小，[`RedisAppState`] 直接持有连接而不需要加锁；
+//! 处理函数也可以通过 `State<RedisConnection>` 直接取得连接
+
+use crate::redis::redis_config::RedisConfig;
+use crate::redis::redis_config_builder::RedisConfigBuilder;
+use crate::redis::redis_connection::RedisConnection;
+use crate::redis::redis_error::RedisResult;
+use axum::extract::FromRef;
+use std::time::Duration;
+
+/// Axum 应用的 Redis 状态
+#[derive(Clone)]
+pub struct RedisAppState {
+    /// Redis 连接
+    pub connection: RedisConnection,
+}
+
+impl RedisAppState {
+    /// 创建新的 Redis AppState
+    pub async fn new(config: RedisConfig) -> RedisResult<Self> {
+        Ok(Self::from_connection(RedisConnection::new(config).await?))
+    }
+
+    /// 使用已建立的连接创建 AppState
+    pub fn from_connection(connection: RedisConnection) -> Self {
+        Self { connection }
+    }
+
+    /// 获取连接的克隆，用于调用 AppState 未封装的命令
+    pub fn connection(&self) -> RedisConnection {
+        self.connection.clone()
+    }
+
+    /// 获取键的值，键不存在时返回 None
+    pub async fn get(&self, key: &str) -> RedisResult<Option<String>> {
+        self.connection().get_builtin(key).await
+    }
+
+    /// 设置键值对
+    pub async fn set(&self, key: &str, value: &str) -> RedisResult<()> {
+        self.connection().set_builtin(key, value).await
+    }
+
+    /// 设置键值对，并在 `ttl` 后过期
+    pub async fn set_ex(&self, key: &str, value: &str, ttl: Duration) -> RedisResult<()> {
+        let mut connection = self.connection();
+        let key = connection.prefixed_key(key);
+        connection
+            .query(
+                redis::cmd("SET")
+                    .arg(key)
+                    .arg(value)
+                    .arg("PX")
+                    .arg(ttl.as_millis() as u64),
+            )
+            .await
+    }
+
+    /// 删除键，返回键是否存在
+    pub async fn del(&self, key: &str) -> RedisResult<bool> {
+        Ok(self.connection().del(key).await? > 0)
+    }
+}
+
+impl FromRef<RedisAppState> for RedisConnection {
+    fn from_ref(state: &RedisAppState) -> Self {
+        state.connection()
+    }
+}
+
+/// 便捷函数：从 URL 创建 Redis AppState
+pub async fn create_redis_app_state_from_url(redis_url: &str) -> RedisResult<RedisAppState> {
+    Ok(RedisAppState::from_connection(
+        RedisConnection::from_url(redis_url).await?,
+    ))
+}
+
+/// 便捷函数：从配置文件创建 Redis AppState
+pub async fn create_redis_app_state_from_config(config_path: &str) -> RedisResult<RedisAppState> {
+    RedisAppState::new(RedisConfigBuilder::from_yaml_file(config_path)?.build()?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+    use axum::Router;
+    use axum::body::{Body, to_bytes};
+    use axum::extract::{Path, State};
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    fn unique_key() -> String {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        format!("test-app-state-{}-{}", std::process::id(), nanos)
+    }
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let Some(connection) = connect_test_redis().await else {
+            return;
+        };
+        let state = RedisAppState::from_connection(connection);
+        let key = unique_key();
+
+        assert_eq!(state.get(&key).await.unwrap(), None);
+        state.set(&key, "value").await.unwrap();
+        assert_eq!(state.get(&key).await.unwrap().as_deref(), Some("value"));
+        assert!(state.del(&key).await.unwrap());
+        assert!(!state.del(&key).await.unwrap());
+
+        state
+            .set_ex(&key, "short", Duration::from_millis(100))
+            .await
+            .unwrap();
+        assert_eq!(state.get(&key).await.unwrap().as_deref(), Some("short"));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(state.get(&key).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_handlers_extract_state() {
+        let Some(connection) = connect_test_redis().await else {
+            return;
+        };
+        let key = unique_key();
+
+        async fn get_value(
+            State(state): State<RedisAppState>,
+            Path(key): Path<String>,
+        ) -> Result<String, StatusCode> {
+            state
+                .get(&key)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)
+        }
+
+        async fn put_value(
+            State(mut connection): State<RedisConnection>,
+            Path(key): Path<String>,
+            value: String,
+        ) -> StatusCode {
+            match connection.set_builtin(key, value).await {
+                Ok(()) => StatusCode::NO_CONTENT,
+                Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            }
+        }
+
+        let app = Router::new()
+            .route("/cache/{key}", get(get_value).put(put_value))
+            .with_state(RedisAppState::from_connection(connection));
+        let uri = format!("/cache/{}", key);
+
+        let response = app
+            .clone()
+            .oneshot(Request::put(&uri).body(Body::from("hello")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app
+            .oneshot(Request::get(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, "hello");
+
+        let mut connection = connect_test_redis().await.unwrap();
+        connection.del(&key).await.unwrap();
+    }
+}
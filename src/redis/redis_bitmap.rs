@@ -0,0 +1,187 @@
+//! Redis 位图模块
+//!
+//! 提供 SETBIT / GETBIT / BITCOUNT / BITOP，适用于日活统计等按位记录的场景，
+//! 例如以用户 ID 为偏移量，每天一个键记录当天是否活跃
+
+use crate::redis::{RedisConnection, RedisResult};
+use redis::ToRedisArgs;
+use serde::{Deserialize, Serialize};
+
+/// 位运算类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitOperation {
+    /// 按位与
+    And,
+    /// 按位或
+    Or,
+    /// 按位异或
+    Xor,
+}
+
+impl BitOperation {
+    /// Redis 命令中使用的运算参数
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            BitOperation::And => "AND",
+            BitOperation::Or => "OR",
+            BitOperation::Xor => "XOR",
+        }
+    }
+}
+
+/// BITCOUNT 的统计范围（闭区间，支持负数下标）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitRange {
+    /// 按字节下标统计
+    Bytes(i64, i64),
+    /// 按位下标统计，需要 Redis 7.0 及以上版本
+    Bits(i64, i64),
+}
+
+impl BitRange {
+    /// 追加范围参数
+    fn append_args(&self, cmd: &mut redis::Cmd) {
+        match *self {
+            BitRange::Bytes(start, end) => {
+                cmd.arg(start).arg(end);
+            }
+            BitRange::Bits(start, end) => {
+                cmd.arg(start).arg(end).arg("BIT");
+            }
+        }
+    }
+}
+
+impl RedisConnection {
+    /// 位图：设置指定偏移量的位，返回该位原来的值
+    pub async fn setbit<K>(&mut self, key: K, offset: u64, value: bool) -> RedisResult<bool>
+    where
+        K: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("SETBIT");
+        cmd.arg(self.prefixed(key)).arg(offset).arg(u8::from(value));
+        let previous: u8 = self.query(&cmd).await?;
+        Ok(previous == 1)
+    }
+
+    /// 位图：获取指定偏移量的位，键不存在或超出长度时为 false
+    pub async fn getbit<K>(&mut self, key: K, offset: u64) -> RedisResult<bool>
+    where
+        K: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("GETBIT");
+        cmd.arg(self.prefixed(key)).arg(offset);
+        let bit: u8 = self.query(&cmd).await?;
+        Ok(bit == 1)
+    }
+
+    /// 位图：统计值为 1 的位数，`range` 为 None 时统计整个键
+    pub async fn bitcount<K>(&mut self, key: K, range: Option<BitRange>) -> RedisResult<u64>
+    where
+        K: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("BITCOUNT");
+        cmd.arg(self.prefixed(key));
+        if let Some(range) = range {
+            range.append_args(&mut cmd);
+        }
+        self.query(&cmd).await
+    }
+
+    /// 位图：对多个键做位运算并写入目标键，返回目标键的字节长度
+    pub async fn bitop<D, K>(
+        &mut self,
+        operation: BitOperation,
+        dest: D,
+        sources: K,
+    ) -> RedisResult<u64>
+    where
+        D: ToRedisArgs,
+        K: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("BITOP");
+        cmd.arg(operation.as_arg())
+            .arg(self.prefixed(dest))
+            .arg(self.prefixed(sources));
+        self.query(&cmd).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+
+    #[test]
+    fn test_operation_args() {
+        assert_eq!(BitOperation::And.as_arg(), "AND");
+        assert_eq!(BitOperation::Or.as_arg(), "OR");
+        assert_eq!(BitOperation::Xor.as_arg(), "XOR");
+    }
+
+    #[test]
+    fn test_range_args() {
+        let mut cmd = redis::cmd("BITCOUNT");
+        BitRange::Bits(5, 30).append_args(&mut cmd);
+        assert_eq!(cmd.args_iter().count(), 4);
+
+        let mut cmd = redis::cmd("BITCOUNT");
+        BitRange::Bytes(0, -1).append_args(&mut cmd);
+        assert_eq!(cmd.args_iter().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_daily_active_users() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let (monday, tuesday, week) = (
+            "clamber:test:dau:monday",
+            "clamber:test:dau:tuesday",
+            "clamber:test:dau:week",
+        );
+        conn.del(&[monday, tuesday, week]).await.unwrap();
+
+        for user in [1, 7, 8, 100, 1000] {
+            assert!(!conn.setbit(monday, user, true).await.unwrap());
+        }
+        for user in [7, 9, 1000, 5000] {
+            conn.setbit(tuesday, user, true).await.unwrap();
+        }
+        // 重复设置返回原值
+        assert!(conn.setbit(monday, 7, true).await.unwrap());
+
+        assert!(conn.getbit(monday, 100).await.unwrap());
+        assert!(!conn.getbit(monday, 101).await.unwrap());
+        assert!(!conn.getbit("clamber:test:dau:missing", 1).await.unwrap());
+
+        assert_eq!(conn.bitcount(monday, None).await.unwrap(), 5);
+        assert_eq!(conn.bitcount(tuesday, None).await.unwrap(), 4);
+        // 第 0 字节包含偏移量 0-7，即用户 1 和 7
+        assert_eq!(
+            conn.bitcount(monday, Some(BitRange::Bytes(0, 0)))
+                .await
+                .unwrap(),
+            2
+        );
+
+        conn.bitop(BitOperation::Or, week, &[monday, tuesday])
+            .await
+            .unwrap();
+        assert_eq!(conn.bitcount(week, None).await.unwrap(), 7);
+
+        conn.bitop(BitOperation::And, week, &[monday, tuesday])
+            .await
+            .unwrap();
+        assert_eq!(conn.bitcount(week, None).await.unwrap(), 2);
+
+        // Redis 7.0 以下不支持 BIT 范围，跳过该断言
+        if let Ok(count) = conn.bitcount(monday, Some(BitRange::Bits(0, 8))).await {
+            assert_eq!(count, 3);
+        }
+
+        conn.del(&[monday, tuesday, week]).await.unwrap();
+    }
+}
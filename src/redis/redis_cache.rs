@@ -0,0 +1,337 @@
+//! Redis 对象缓存模块
+//!
+//! [`RedisCache`] 绑定键命名空间、过期时间与序列化方式，统一缓存键格式为
+//! `{namespace}:{id}`（位于连接键前缀之后），并记录命中与未命中次数。
+//! 默认使用 JSON 序列化，启用 `redis-bincode` / `redis-msgpack` feature 后可选 bincode 或 MessagePack
+
+use crate::redis::redis_compression::decompress_optional;
+use crate::redis::{RedisConnection, RedisError, RedisResult};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// 缓存值的序列化方式
+pub trait CacheSerializer: Send + Sync {
+    /// 序列化为字节
+    fn serialize<T: Serialize>(&self, value: &T) -> RedisResult<Vec<u8>>;
+
+    /// 从字节反序列化
+    fn deserialize<T: DeserializeOwned>(&self, raw: &[u8]) -> RedisResult<T>;
+}
+
+/// JSON 序列化（默认）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonSerializer;
+
+impl CacheSerializer for JsonSerializer {
+    fn serialize<T: Serialize>(&self, value: &T) -> RedisResult<Vec<u8>> {
+        serde_json::to_vec(value).map_err(|e| RedisError::serialization(e.to_string()))
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, raw: &[u8]) -> RedisResult<T> {
+        serde_json::from_slice(raw).map_err(|e| RedisError::deserialization(e.to_string()))
+    }
+}
+
+/// bincode 序列化，体积更小、速度更快，但不同结构体版本之间不兼容
+#[cfg(feature = "redis-bincode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BincodeSerializer;
+
+#[cfg(feature = "redis-bincode")]
+impl CacheSerializer for BincodeSerializer {
+    fn serialize<T: Serialize>(&self, value: &T) -> RedisResult<Vec<u8>> {
+        bincode::serialize(value).map_err(|e| RedisError::serialization(e.to_string()))
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, raw: &[u8]) -> RedisResult<T> {
+        bincode::deserialize(raw).map_err(|e| RedisError::deserialization(e.to_string()))
+    }
+}
+
+/// MessagePack 序列化，按字段名编码，可以兼容新增的可选字段
+#[cfg(feature = "redis-msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePackSerializer;
+
+#[cfg(feature = "redis-msgpack")]
+impl CacheSerializer for MessagePackSerializer {
+    fn serialize<T: Serialize>(&self, value: &T) -> RedisResult<Vec<u8>> {
+        rmp_serde::to_vec_named(value).map_err(|e| RedisError::serialization(e.to_string()))
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, raw: &[u8]) -> RedisResult<T> {
+        rmp_serde::from_slice(raw).map_err(|e| RedisError::deserialization(e.to_string()))
+    }
+}
+
+/// 缓存命中统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RedisCacheStats {
+    /// 命中次数
+    pub hits: u64,
+    /// 未命中次数
+    pub misses: u64,
+}
+
+impl RedisCacheStats {
+    /// 命中率，尚无请求时为 0
+    pub fn hit_ratio(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// 命中计数器，克隆的缓存共享同一份计数
+#[derive(Debug, Default)]
+struct HitCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl HitCounters {
+    fn record(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// 类型化的对象缓存
+///
+/// ```ignore
+/// let mut users: RedisCache<User> = RedisCache::new(&conn, "users", Duration::from_secs(300));
+/// users.put("42", &user).await?;
+/// let cached = users.get("42").await?;
+/// ```
+pub struct RedisCache<T, S = JsonSerializer> {
+    /// 追加了命名空间前缀的连接
+    connection: RedisConnection,
+    /// 过期时间，为 0 时不过期
+    ttl: Duration,
+    serializer: S,
+    counters: Arc<HitCounters>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+// 手动实现 Clone，避免要求缓存的类型 T 实现 Clone
+impl<T, S: Clone> Clone for RedisCache<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            connection: self.connection.clone(),
+            ttl: self.ttl,
+            serializer: self.serializer.clone(),
+            counters: self.counters.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> RedisCache<T, JsonSerializer>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// 创建使用 JSON 序列化的缓存，键格式为 `{namespace}:{id}`
+    pub fn new(connection: &RedisConnection, namespace: &str, ttl: Duration) -> Self {
+        Self::with_serializer(connection, namespace, ttl, JsonSerializer)
+    }
+}
+
+impl<T, S> RedisCache<T, S>
+where
+    T: Serialize + DeserializeOwned,
+    S: CacheSerializer,
+{
+    /// 创建使用指定序列化方式的缓存
+    pub fn with_serializer(
+        connection: &RedisConnection,
+        namespace: &str,
+        ttl: Duration,
+        serializer: S,
+    ) -> Self {
+        Self {
+            connection: connection.with_prefix(&format!("{}:", namespace)),
+            ttl,
+            serializer,
+            counters: Arc::new(HitCounters::default()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// 读取缓存，不存在时返回 None
+    pub async fn get(&mut self, id: &str) -> RedisResult<Option<T>> {
+        let raw = self.connection.get_bytes(id).await?;
+        self.counters.record(raw.is_some());
+        raw.map(|raw| self.serializer.deserialize(&raw)).transpose()
+    }
+
+    /// 批量读取缓存，结果与 `ids` 一一对应
+    pub async fn get_many(&mut self, ids: &[&str]) -> RedisResult<Vec<Option<T>>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut cmd = redis::cmd("MGET");
+        for id in ids {
+            cmd.arg(self.connection.prefixed_key(id));
+        }
+        let values: Vec<Option<Vec<u8>>> = self.connection.query(&cmd).await?;
+
+        values
+            .into_iter()
+            .map(|raw| {
+                self.counters.record(raw.is_some());
+                decompress_optional(raw)?
+                    .map(|raw| self.serializer.deserialize(&raw))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    /// 写入缓存并设置过期时间
+    pub async fn put(&mut self, id: &str, value: &T) -> RedisResult<()> {
+        let raw = self.serializer.serialize(value)?;
+        if self.ttl.is_zero() {
+            return self.connection.set_bytes(id, &raw).await;
+        }
+        let raw = self.connection.compress_value(&raw)?.unwrap_or(raw);
+
+        self.connection
+            .query(
+                redis::cmd("SET")
+                    .arg(self.connection.prefixed_key(id))
+                    .arg(raw)
+                    .arg("PX")
+                    .arg(self.ttl.as_millis() as u64),
+            )
+            .await
+    }
+
+    /// 删除缓存，返回是否存在
+    pub async fn invalidate(&mut self, id: &str) -> RedisResult<bool> {
+        Ok(self.connection.del(id).await? > 0)
+    }
+
+    /// 获取命中统计
+    pub fn stats(&self) -> RedisCacheStats {
+        RedisCacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 缓存的过期时间
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Profile {
+        id: u64,
+        name: String,
+        tags: Vec<String>,
+        scores: BTreeMap<String, f64>,
+        manager: Option<Box<Profile>>,
+    }
+
+    fn profile() -> Profile {
+        Profile {
+            id: 42,
+            name: "张三".to_string(),
+            tags: vec!["admin".to_string(), "ops".to_string()],
+            scores: BTreeMap::from([("q1".to_string(), 0.5), ("q2".to_string(), 1.25)]),
+            manager: Some(Box::new(Profile {
+                id: 1,
+                name: "李四".to_string(),
+                tags: Vec::new(),
+                scores: BTreeMap::new(),
+                manager: None,
+            })),
+        }
+    }
+
+    fn round_trip<S: CacheSerializer>(serializer: S) {
+        let raw = serializer.serialize(&profile()).unwrap();
+        let decoded: Profile = serializer.deserialize(&raw).unwrap();
+        assert_eq!(decoded, profile());
+        assert!(serializer.deserialize::<Profile>(b"\xff\x00").is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        round_trip(JsonSerializer);
+    }
+
+    #[cfg(feature = "redis-bincode")]
+    #[test]
+    fn test_bincode_round_trip() {
+        round_trip(BincodeSerializer);
+    }
+
+    #[cfg(feature = "redis-msgpack")]
+    #[test]
+    fn test_msgpack_round_trip() {
+        round_trip(MessagePackSerializer);
+    }
+
+    #[test]
+    fn test_hit_ratio() {
+        assert_eq!(RedisCacheStats::default().hit_ratio(), 0.0);
+        let stats = RedisCacheStats { hits: 3, misses: 1 };
+        assert_eq!(stats.hit_ratio(), 0.75);
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit_and_miss() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let mut cache: RedisCache<Profile> =
+            RedisCache::new(&conn, "test_cache:profiles", Duration::from_secs(60));
+        cache.invalidate("42").await.unwrap();
+
+        assert_eq!(cache.get("42").await.unwrap(), None);
+        cache.put("42", &profile()).await.unwrap();
+        assert_eq!(cache.get("42").await.unwrap(), Some(profile()));
+
+        // 键格式为 {namespace}:{id}
+        let mut raw = conn.clone();
+        assert!(raw.exists_builtin("test_cache:profiles:42").await.unwrap());
+
+        let many = cache.get_many(&["42", "missing"]).await.unwrap();
+        assert_eq!(many, vec![Some(profile()), None]);
+
+        assert!(cache.invalidate("42").await.unwrap());
+        assert_eq!(cache.get("42").await.unwrap(), None);
+        assert_eq!(cache.stats(), RedisCacheStats { hits: 2, misses: 3 });
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl_expiry() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let mut cache: RedisCache<Profile> =
+            RedisCache::new(&conn, "test_cache:expiring", Duration::from_millis(100));
+
+        cache.put("42", &profile()).await.unwrap();
+        assert!(cache.get("42").await.unwrap().is_some());
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(cache.get("42").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,272 @@
+//! Redis 值压缩模块
+//!
+//! 超过阈值的值在写入前压缩，并在开头加上 3 字节的头部（2 字节魔数 + 1 字节编码标识）；
+//! 读取时根据头部透明解压，没有头部的旧值原样返回。
+//! 未压缩但恰好以魔数开头的二进制值会加上编码标识为 0 的头部，读取时去掉头部，保证二进制值原样往返。
+//! gzip 与 zstd 分别由 `redis-gzip`、`redis-zstd` feature 提供，默认不引入压缩依赖
+
+use crate::redis::{RedisError, RedisResult};
+use serde::{Deserialize, Serialize};
+
+/// 压缩头部魔数，0xC1 不会出现在合法的 UTF-8 中，因此不会与 JSON 等文本值冲突
+const MAGIC: [u8; 2] = [0xC1, 0x5A];
+
+/// 压缩头部长度：魔数 + 编码标识
+const HEADER_LEN: usize = MAGIC.len() + 1;
+
+/// 压缩编码
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionCodec {
+    /// 不压缩
+    #[default]
+    None,
+    /// gzip（需要 `redis-gzip` feature）
+    Gzip,
+    /// zstd（需要 `redis-zstd` feature）
+    Zstd,
+}
+
+impl CompressionCodec {
+    /// 编码名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// 提供该编码的 cargo feature 名称
+    pub fn feature(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some("redis-gzip"),
+            Self::Zstd => Some("redis-zstd"),
+        }
+    }
+
+    /// 当前构建是否支持该编码
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            Self::None => true,
+            Self::Gzip => cfg!(feature = "redis-gzip"),
+            Self::Zstd => cfg!(feature = "redis-zstd"),
+        }
+    }
+
+    /// 写入头部的编码标识
+    fn id(&self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Gzip => 1,
+            Self::Zstd => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::None),
+            1 => Some(Self::Gzip),
+            2 => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CompressionCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 长度超过 `threshold` 字节时按 `codec` 压缩并加上头部，无需改写原值时返回 None
+///
+/// 不压缩的值若以魔数开头，会加上编码标识为 0 的头部，避免读取时被误认为压缩值
+pub(crate) fn compress(
+    codec: CompressionCodec,
+    threshold: usize,
+    raw: &[u8],
+) -> RedisResult<Option<Vec<u8>>> {
+    if codec == CompressionCodec::None || raw.len() <= threshold {
+        if !raw.starts_with(&MAGIC) {
+            return Ok(None);
+        }
+        let mut output = Vec::with_capacity(HEADER_LEN + raw.len());
+        output.extend_from_slice(&MAGIC);
+        output.push(CompressionCodec::None.id());
+        output.extend_from_slice(raw);
+        return Ok(Some(output));
+    }
+
+    let mut output = Vec::with_capacity(HEADER_LEN + raw.len() / 2);
+    output.extend_from_slice(&MAGIC);
+    output.push(codec.id());
+    encode(codec, raw, &mut output)?;
+    Ok(Some(output))
+}
+
+/// 带有压缩头部时解压，否则视为未压缩的旧值原样返回
+pub(crate) fn decompress(raw: Vec<u8>) -> RedisResult<Vec<u8>> {
+    if !raw.starts_with(&MAGIC) {
+        return Ok(raw);
+    }
+
+    let Some(&id) = raw.get(MAGIC.len()) else {
+        return Err(RedisError::deserialization("压缩头部不完整"));
+    };
+    let codec = CompressionCodec::from_id(id)
+        .ok_or_else(|| RedisError::deserialization(format!("未知的压缩编码标识: {}", id)))?;
+    if codec == CompressionCodec::None {
+        return Ok(raw[HEADER_LEN..].to_vec());
+    }
+    decode(codec, &raw[HEADER_LEN..])
+}
+
+/// 读取可选值并解压
+pub(crate) fn decompress_optional(raw: Option<Vec<u8>>) -> RedisResult<Option<Vec<u8>>> {
+    raw.map(decompress).transpose()
+}
+
+fn encode(codec: CompressionCodec, raw: &[u8], output: &mut Vec<u8>) -> RedisResult<()> {
+    match codec {
+        #[cfg(feature = "redis-gzip")]
+        CompressionCodec::Gzip => {
+            use std::io::Write;
+
+            let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+            let error =
+                |e: std::io::Error| RedisError::serialization(format!("gzip 压缩失败: {}", e));
+            encoder.write_all(raw).map_err(error)?;
+            encoder.finish().map_err(error)?;
+            Ok(())
+        }
+        #[cfg(feature = "redis-zstd")]
+        CompressionCodec::Zstd => zstd::stream::copy_encode(raw, output, 0)
+            .map_err(|e| RedisError::serialization(format!("zstd 压缩失败: {}", e))),
+        other => Err(RedisError::serialization(not_enabled(other))),
+    }
+}
+
+fn decode(codec: CompressionCodec, data: &[u8]) -> RedisResult<Vec<u8>> {
+    match codec {
+        #[cfg(feature = "redis-gzip")]
+        CompressionCodec::Gzip => {
+            use std::io::Read;
+
+            let mut output = Vec::new();
+            flate2::read::GzDecoder::new(data)
+                .read_to_end(&mut output)
+                .map_err(|e| RedisError::deserialization(format!("gzip 解压失败: {}", e)))?;
+            Ok(output)
+        }
+        #[cfg(feature = "redis-zstd")]
+        CompressionCodec::Zstd => zstd::stream::decode_all(data)
+            .map_err(|e| RedisError::deserialization(format!("zstd 解压失败: {}", e))),
+        other => Err(RedisError::deserialization(not_enabled(other))),
+    }
+}
+
+fn not_enabled(codec: CompressionCodec) -> String {
+    match codec.feature() {
+        Some(feature) => format!(
+            "当前构建未启用 {} 压缩，请开启 `{}` feature",
+            codec, feature
+        ),
+        None => format!("{} 不是有效的压缩编码", codec),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codec_none_never_compresses() {
+        let raw = vec![b'a'; 4096];
+        assert_eq!(compress(CompressionCodec::None, 0, &raw).unwrap(), None);
+    }
+
+    #[test]
+    fn test_legacy_values_pass_through() {
+        let legacy = br#"{"id":1,"name":"clamber"}"#.to_vec();
+        assert_eq!(decompress(legacy.clone()).unwrap(), legacy);
+        assert_eq!(decompress(Vec::new()).unwrap(), Vec::<u8>::new());
+        assert_eq!(decompress_optional(None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_raw_value_starting_with_magic_round_trips() {
+        let raw = vec![MAGIC[0], MAGIC[1], 0xFF, 0x00, 0x01];
+        for codec in [CompressionCodec::None, CompressionCodec::Gzip] {
+            // 未压缩（未启用压缩或未超过阈值）时加上标识为 0 的头部
+            let stored = compress(codec, 1024, &raw).unwrap().unwrap();
+            assert_eq!(&stored[..HEADER_LEN], &[MAGIC[0], MAGIC[1], 0]);
+            assert_eq!(decompress(stored).unwrap(), raw);
+        }
+
+        // 仅有魔数的值同样可以往返
+        let stored = compress(CompressionCodec::None, 0, &MAGIC)
+            .unwrap()
+            .unwrap();
+        assert_eq!(decompress(stored).unwrap(), MAGIC.to_vec());
+    }
+
+    #[test]
+    fn test_corrupted_header_is_deserialization_error() {
+        for raw in [MAGIC.to_vec(), vec![0xC1, 0x5A, 0xFF, 1, 2, 3]] {
+            assert!(matches!(
+                decompress(raw),
+                Err(RedisError::Deserialization { .. })
+            ));
+        }
+    }
+
+    #[cfg(feature = "redis-gzip")]
+    #[test]
+    fn test_gzip_threshold_boundaries() {
+        assert_round_trip(CompressionCodec::Gzip);
+    }
+
+    #[cfg(feature = "redis-zstd")]
+    #[test]
+    fn test_zstd_threshold_boundaries() {
+        assert_round_trip(CompressionCodec::Zstd);
+    }
+
+    #[cfg(any(feature = "redis-gzip", feature = "redis-zstd"))]
+    fn assert_round_trip(codec: CompressionCodec) {
+        let threshold = 1024;
+
+        // 恰好等于阈值时不压缩
+        let raw = vec![b'x'; threshold];
+        assert_eq!(compress(codec, threshold, &raw).unwrap(), None);
+
+        // 超过阈值一个字节时压缩并带有头部
+        let raw = vec![b'x'; threshold + 1];
+        let stored = compress(codec, threshold, &raw).unwrap().unwrap();
+        assert_eq!(&stored[..HEADER_LEN], &[MAGIC[0], MAGIC[1], codec.id()]);
+        assert!(stored.len() < raw.len());
+        assert_eq!(decompress(stored.clone()).unwrap(), raw);
+
+        // 头部完整但数据损坏
+        let mut corrupted = stored[..HEADER_LEN].to_vec();
+        corrupted.extend_from_slice(b"not compressed");
+        assert!(matches!(
+            decompress(corrupted),
+            Err(RedisError::Deserialization { .. })
+        ));
+    }
+
+    #[cfg(not(feature = "redis-gzip"))]
+    #[test]
+    fn test_disabled_codec_is_rejected() {
+        assert!(!CompressionCodec::Gzip.is_enabled());
+        let raw = vec![b'x'; 16];
+        assert!(compress(CompressionCodec::Gzip, 0, &raw).is_err());
+        assert!(matches!(
+            decompress(vec![MAGIC[0], MAGIC[1], 1, 0]),
+            Err(RedisError::Deserialization { .. })
+        ));
+    }
+}
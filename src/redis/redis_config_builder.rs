@@ -0,0 +1,521 @@
+//! Redis 配置构建器模块
+//!
+//! 通过链式调用构造 [`RedisConfig`]，新增配置字段时不会破坏已有代码。
+//! 支持先从 YAML 文件或环境变量加载基础配置，再覆盖个别字段
+
+use crate::env_interpolation::expand_env_vars;
+use crate::redis::{
+    CompressionCodec, KeyLogging, RedisConfig, RedisError, RedisPoolStrategy, RedisResult,
+    RetryPolicy,
+};
+use std::path::Path;
+use std::str::FromStr;
+
+/// 默认的环境变量前缀
+pub const DEFAULT_ENV_PREFIX: &str = "REDIS";
+
+/// Redis 配置构建器
+///
+/// ```ignore
+/// let config = RedisConfig::builder()
+///     .url("redis://localhost:6379")
+///     .database(2)
+///     .connection_timeout_secs(10)
+///     .pool_size(8)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RedisConfigBuilder {
+    config: RedisConfig,
+}
+
+impl RedisConfig {
+    /// 创建以默认配置为基础的构建器
+    pub fn builder() -> RedisConfigBuilder {
+        RedisConfigBuilder::new()
+    }
+
+    /// 从 `REDIS_` 前缀的环境变量加载并校验配置
+    ///
+    /// 例如 `REDIS_URL`、`REDIS_DATABASE`（或 `REDIS_DATABASE_INDEX`）、
+    /// `REDIS_CONNECTION_TIMEOUT_SECS`、`REDIS_RETRY_COUNT`，未设置的字段使用默认值
+    pub fn from_env() -> RedisResult<Self> {
+        RedisConfigBuilder::from_env()?.build()
+    }
+}
+
+impl RedisConfigBuilder {
+    /// 创建以默认配置为基础的构建器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 以已有配置为基础创建构建器
+    pub fn from_config(config: RedisConfig) -> Self {
+        Self { config }
+    }
+
+    /// 以 YAML 字符串中的配置为基础创建构建器，支持 `${ENV_VAR}` 占位符
+    pub fn from_yaml_str(yaml: &str) -> RedisResult<Self> {
+        let yaml = expand_env_vars(yaml).map_err(RedisError::config)?;
+        let config = serde_yaml::from_str(&yaml)
+            .map_err(|e| RedisError::config(format!("解析配置失败: {}", e)))?;
+        Ok(Self { config })
+    }
+
+    /// 以 YAML 文件中的配置为基础创建构建器
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> RedisResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            RedisError::config(format!("读取配置文件 {} 失败: {}", path.display(), e))
+        })?;
+        Self::from_yaml_str(&content)
+    }
+
+    /// 以 `REDIS_` 前缀的环境变量为基础创建构建器，见 [`RedisConfigBuilder::from_env_with_prefix`]
+    pub fn from_env() -> RedisResult<Self> {
+        Self::from_env_with_prefix(DEFAULT_ENV_PREFIX)
+    }
+
+    /// 以环境变量为基础创建构建器
+    ///
+    /// 变量名为 `{prefix}_` 加上大写的字段名，例如 `REDIS_URL`、`REDIS_DATABASE_INDEX`、
+    /// `REDIS_POOL_SIZE`，未设置的字段使用默认值
+    pub fn from_env_with_prefix(prefix: &str) -> RedisResult<Self> {
+        Self::new().apply_env(prefix, |name| std::env::var(name).ok())
+    }
+
+    /// 使用指定的查找函数读取环境变量并覆盖对应字段
+    pub(crate) fn apply_env(
+        mut self,
+        prefix: &str,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> RedisResult<Self> {
+        let var =
+            |field: &str| lookup(&env_name(prefix, field)).map(|value| value.trim().to_string());
+        let config = &mut self.config;
+
+        if let Some(url) = var("url") {
+            config.url = url;
+        }
+        // `{prefix}_DATABASE` 作为 `{prefix}_DATABASE_INDEX` 的简写
+        let database_index = match parse_env(&var, prefix, "database_index")? {
+            Some(value) => Some(value),
+            None => parse_env(&var, prefix, "database")?,
+        };
+        if let Some(value) = database_index {
+            config.database_index = value;
+        }
+        if let Some(username) = var("username") {
+            config.username = Some(username);
+        }
+        if let Some(password) = var("password") {
+            config.password = Some(password);
+        }
+        if let Some(value) = parse_env(&var, prefix, "connection_timeout_secs")? {
+            config.connection_timeout_secs = value;
+        }
+        if let Some(value) = parse_env(&var, prefix, "response_timeout_secs")? {
+            config.response_timeout_secs = value;
+        }
+        if let Some(value) = parse_env(&var, prefix, "retry_count")? {
+            config.retry_count = value;
+        }
+        if let Some(value) = parse_env(&var, prefix, "retry_factor_ms")? {
+            config.retry_factor_ms = value;
+        }
+        if let Some(value) = parse_env(&var, prefix, "max_retry_delay_ms")? {
+            config.max_retry_delay_ms = value;
+        }
+        if let Some(value) = parse_env(&var, prefix, "enable_tls")? {
+            config.enable_tls = value;
+        }
+        if let Some(path) = var("ca_cert_path") {
+            config.ca_cert_path = Some(path);
+        }
+        if let Some(path) = var("client_cert_path") {
+            config.client_cert_path = Some(path);
+        }
+        if let Some(path) = var("client_key_path") {
+            config.client_key_path = Some(path);
+        }
+        if let Some(value) = parse_env(&var, prefix, "insecure_skip_verify")? {
+            config.insecure_skip_verify = value;
+        }
+        if let Some(prefix) = var("key_prefix") {
+            config.key_prefix = Some(prefix);
+        }
+        if let Some(value) = parse_env(&var, prefix, "pool_size")? {
+            config.pool_size = value;
+        }
+        if let Some(value) = var("pool_strategy") {
+            config.pool_strategy = serde_yaml::from_str(&value).map_err(|_| {
+                RedisError::config(format!(
+                    "环境变量 {} 的值 {} 无效",
+                    env_name(prefix, "pool_strategy"),
+                    value
+                ))
+            })?;
+        }
+        if let Some(value) = parse_env(&var, prefix, "enable_metrics")? {
+            config.enable_metrics = value;
+        }
+        if let Some(value) = var("compression") {
+            config.compression = serde_yaml::from_str(&value).map_err(|_| {
+                RedisError::config(format!(
+                    "环境变量 {} 的值 {} 无效",
+                    env_name(prefix, "compression"),
+                    value
+                ))
+            })?;
+        }
+        if let Some(value) = parse_env(&var, prefix, "compression_threshold_bytes")? {
+            config.compression_threshold_bytes = value;
+        }
+        if let Some(value) = var("key_logging") {
+            config.key_logging = serde_yaml::from_str(&value).map_err(|_| {
+                RedisError::config(format!(
+                    "环境变量 {} 的值 {} 无效",
+                    env_name(prefix, "key_logging"),
+                    value
+                ))
+            })?;
+        }
+        if let Some(value) = parse_env(&var, prefix, "slow_command_threshold_ms")? {
+            config.slow_command_threshold_ms = value;
+        }
+        if let Some(value) = parse_env(&var, prefix, "stale_ttl_ratio")? {
+            config.stale_ttl_ratio = value;
+        }
+        if let Some(value) = parse_env(&var, prefix, "max_background_refreshes")? {
+            config.max_background_refreshes = value;
+        }
+
+        Ok(self)
+    }
+
+    /// 设置 Redis 连接 URL
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.config.url = url.into();
+        self
+    }
+
+    /// 设置数据库索引
+    pub fn database(mut self, database_index: u8) -> Self {
+        self.config.database_index = database_index;
+        self
+    }
+
+    /// 设置 ACL 用户名与密码
+    pub fn credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.config.username = Some(username.into());
+        self.config.password = Some(password.into());
+        self
+    }
+
+    /// 设置密码（使用默认用户）
+    pub fn password(mut self, password: impl Into<String>) -> Self {
+        self.config.password = Some(password.into());
+        self
+    }
+
+    /// 设置连接超时时间（秒）
+    pub fn connection_timeout_secs(mut self, secs: u64) -> Self {
+        self.config.connection_timeout_secs = secs;
+        self
+    }
+
+    /// 设置响应超时时间（秒）
+    pub fn response_timeout_secs(mut self, secs: u64) -> Self {
+        self.config.response_timeout_secs = secs;
+        self
+    }
+
+    /// 设置连接重试次数、延迟因子与最大延迟（毫秒）
+    pub fn retries(mut self, count: usize, factor_ms: u64, max_delay_ms: u64) -> Self {
+        self.config.retry_count = count;
+        self.config.retry_factor_ms = factor_ms;
+        self.config.max_retry_delay_ms = max_delay_ms;
+        self
+    }
+
+    /// 设置命令级重试策略
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.config.retry_policy = policy;
+        self
+    }
+
+    /// 启用或关闭 TLS
+    pub fn tls(mut self, enable: bool) -> Self {
+        self.config.enable_tls = enable;
+        self
+    }
+
+    /// 设置自定义 CA 证书路径，同时启用 TLS
+    pub fn ca_cert_path(mut self, path: impl Into<String>) -> Self {
+        self.config.enable_tls = true;
+        self.config.ca_cert_path = Some(path.into());
+        self
+    }
+
+    /// 设置客户端证书与私钥路径，同时启用 TLS
+    pub fn client_cert(
+        mut self,
+        cert_path: impl Into<String>,
+        key_path: impl Into<String>,
+    ) -> Self {
+        self.config.enable_tls = true;
+        self.config.client_cert_path = Some(cert_path.into());
+        self.config.client_key_path = Some(key_path.into());
+        self
+    }
+
+    /// 跳过服务端证书校验（仅用于内部集群）
+    pub fn insecure_skip_verify(mut self, skip: bool) -> Self {
+        self.config.insecure_skip_verify = skip;
+        self
+    }
+
+    /// 设置键前缀
+    pub fn key_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.key_prefix = Some(prefix.into());
+        self
+    }
+
+    /// 设置连接池大小
+    pub fn pool_size(mut self, size: usize) -> Self {
+        self.config.pool_size = size;
+        self
+    }
+
+    /// 设置连接池分发策略
+    pub fn pool_strategy(mut self, strategy: RedisPoolStrategy) -> Self {
+        self.config.pool_strategy = strategy;
+        self
+    }
+
+    /// 启用或关闭命令指标
+    pub fn metrics(mut self, enable: bool) -> Self {
+        self.config.enable_metrics = enable;
+        self
+    }
+
+    /// 设置值压缩编码与压缩阈值（字节）
+    pub fn compression(mut self, codec: CompressionCodec, threshold_bytes: usize) -> Self {
+        self.config.compression = codec;
+        self.config.compression_threshold_bytes = threshold_bytes;
+        self
+    }
+
+    /// 设置命令追踪中键名的记录方式与慢命令阈值（毫秒，0 表示不检测）
+    pub fn tracing(mut self, key_logging: KeyLogging, slow_command_threshold_ms: u64) -> Self {
+        self.config.key_logging = key_logging;
+        self.config.slow_command_threshold_ms = slow_command_threshold_ms;
+        self
+    }
+
+    /// 设置 stale-while-revalidate 缓存的软过期比例与后台刷新并发上限
+    pub fn stale_while_revalidate(
+        mut self,
+        stale_ttl_ratio: f64,
+        max_background_refreshes: usize,
+    ) -> Self {
+        self.config.stale_ttl_ratio = stale_ttl_ratio;
+        self.config.max_background_refreshes = max_background_refreshes;
+        self
+    }
+
+    /// 校验并返回配置
+    pub fn build(self) -> RedisResult<RedisConfig> {
+        self.config.validate().map_err(RedisError::config)?;
+        Ok(self.config)
+    }
+}
+
+/// 字段对应的环境变量名，例如 `REDIS_POOL_SIZE`
+fn env_name(prefix: &str, field: &str) -> String {
+    format!("{}_{}", prefix, field.to_ascii_uppercase())
+}
+
+/// 读取并解析环境变量，值无效时返回包含变量名的配置错误
+fn parse_env<T: FromStr>(
+    var: &impl Fn(&str) -> Option<String>,
+    prefix: &str,
+    field: &str,
+) -> RedisResult<Option<T>> {
+    var(field)
+        .map(|value| {
+            value.parse().map_err(|_| {
+                RedisError::config(format!(
+                    "环境变量 {} 的值 {} 无效",
+                    env_name(prefix, field),
+                    value
+                ))
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_builder_sets_fields() {
+        let config = RedisConfig::builder()
+            .url("redis://cache:6379")
+            .database(2)
+            .connection_timeout_secs(10)
+            .response_timeout_secs(3)
+            .retries(3, 200, 5000)
+            .key_prefix("svc:dev:")
+            .pool_size(8)
+            .pool_strategy(RedisPoolStrategy::LeastInFlight)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.url, "redis://cache:6379");
+        assert_eq!(config.database_index, 2);
+        assert_eq!(config.connection_timeout_secs, 10);
+        assert_eq!(config.response_timeout_secs, 3);
+        assert_eq!(config.retry_count, 3);
+        assert_eq!(config.retry_factor_ms, 200);
+        assert_eq!(config.max_retry_delay_ms, 5000);
+        assert_eq!(config.key_prefix.as_deref(), Some("svc:dev:"));
+        assert_eq!(config.pool_size, 8);
+        assert_eq!(config.pool_strategy, RedisPoolStrategy::LeastInFlight);
+    }
+
+    #[test]
+    fn test_build_runs_validation() {
+        assert!(RedisConfig::builder().pool_size(0).build().is_err());
+        assert!(
+            RedisConfig::builder()
+                .url("redis://localhost:6379/1")
+                .database(2)
+                .build()
+                .unwrap_err()
+                .is_config_error()
+        );
+        // 客户端证书需要同时配置证书与私钥
+        let config = RedisConfig::builder()
+            .client_cert("client.pem", "client.key")
+            .build()
+            .unwrap();
+        assert!(config.enable_tls);
+        // 未启用对应 feature 的压缩编码无法通过校验
+        #[cfg(not(feature = "redis-zstd"))]
+        assert!(
+            RedisConfig::builder()
+                .compression(CompressionCodec::Zstd, 1024)
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_yaml_base_with_overrides() {
+        let yaml = "url: redis://cache:6379\npool_size: 4\nkey_prefix: \"svc:\"\n";
+        let config = RedisConfigBuilder::from_yaml_str(yaml)
+            .unwrap()
+            .pool_size(16)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.url, "redis://cache:6379");
+        assert_eq!(config.pool_size, 16);
+        assert_eq!(config.key_prefix.as_deref(), Some("svc:"));
+
+        assert!(RedisConfigBuilder::from_yaml_str("pool_size: [").is_err());
+        assert!(RedisConfigBuilder::from_yaml_file("/nonexistent/redis.yaml").is_err());
+    }
+
+    #[test]
+    fn test_env_base_with_overrides() {
+        let lookup = env(&[
+            ("APP_REDIS_URL", "redis://cache:6379"),
+            ("APP_REDIS_DATABASE_INDEX", "3"),
+            ("APP_REDIS_POOL_SIZE", "4"),
+            ("APP_REDIS_POOL_STRATEGY", "least_in_flight"),
+            ("APP_REDIS_ENABLE_METRICS", "true"),
+            ("APP_REDIS_COMPRESSION_THRESHOLD_BYTES", "2048"),
+            ("APP_REDIS_KEY_LOGGING", "off"),
+            ("APP_REDIS_STALE_TTL_RATIO", "0.5"),
+        ]);
+        let config = RedisConfigBuilder::new()
+            .apply_env("APP_REDIS", lookup)
+            .unwrap()
+            .pool_size(2)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.url, "redis://cache:6379");
+        assert_eq!(config.database_index, 3);
+        assert_eq!(config.pool_size, 2);
+        assert_eq!(config.pool_strategy, RedisPoolStrategy::LeastInFlight);
+        assert!(config.enable_metrics);
+        assert_eq!(config.compression_threshold_bytes, 2048);
+        assert_eq!(config.key_logging, KeyLogging::Off);
+        assert_eq!(config.stale_ttl_ratio, 0.5);
+        // 未设置的字段保持默认值
+        assert_eq!(
+            config.connection_timeout_secs,
+            RedisConfig::default().connection_timeout_secs
+        );
+    }
+
+    /// 修改进程环境变量的测试需要串行执行
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn test_config_from_process_env() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        // REDIS_URL 由集成测试使用，这里不做修改
+        let vars = [
+            ("REDIS_DATABASE", "4"),
+            ("REDIS_CONNECTION_TIMEOUT_SECS", "7"),
+            ("REDIS_RETRY_COUNT", "2"),
+        ];
+        for (name, value) in vars {
+            unsafe { std::env::set_var(name, value) };
+        }
+        let result = RedisConfig::from_env();
+        for (name, _) in vars {
+            unsafe { std::env::remove_var(name) };
+        }
+
+        let config = result.unwrap();
+        assert_eq!(config.database_index, 4);
+        assert_eq!(config.connection_timeout_secs, 7);
+        assert_eq!(config.retry_count, 2);
+        assert_eq!(
+            config.response_timeout_secs,
+            RedisConfig::default().response_timeout_secs
+        );
+
+        unsafe { std::env::set_var("REDIS_RETRY_COUNT", "-1") };
+        let result = RedisConfig::from_env();
+        unsafe { std::env::remove_var("REDIS_RETRY_COUNT") };
+        let error = result.unwrap_err();
+        assert!(error.is_config_error());
+        assert!(error.to_string().contains("REDIS_RETRY_COUNT"));
+    }
+
+    #[test]
+    fn test_env_invalid_value() {
+        let error = RedisConfigBuilder::new()
+            .apply_env("REDIS", env(&[("REDIS_POOL_SIZE", "many")]))
+            .unwrap_err();
+        assert!(error.is_config_error());
+        assert!(error.to_string().contains("REDIS_POOL_SIZE"));
+    }
+}
@@ -2,19 +2,129 @@
 //!
 //! 提供 Redis 连接的封装和扩展功能，支持连接池和基本操作
 
-use crate::redis::{RedisConfig, RedisError, RedisResult};
+use crate::redis::redis_compression::{compress, decompress_optional};
+use crate::redis::redis_events::ConnectionEvents;
+use crate::redis::redis_retry::is_idempotent_command;
+use crate::redis::redis_tracing::CommandTracer;
+use crate::redis::{
+    ConnectionEvent, FromRedisScriptValue, RedisConfig, RedisError, RedisMetrics,
+    RedisMetricsSnapshot, RedisResult, RedisValueType, RetryPolicy, TransactionQueue,
+};
+use chrono::{DateTime, Utc};
 use redis::{
-    AsyncCommands, Client, ToRedisArgs,
-    aio::{ConnectionManager, ConnectionManagerConfig},
+    Client, ClientTlsConfig, ExistenceCheck, FromRedisValue, SetOptions, TlsCertificates,
+    ToRedisArgs,
+    aio::{ConnectionManager, ConnectionManagerConfig, MultiplexedConnection},
 };
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
-use tracing::{error, info, warn};
+use tokio::sync::{Semaphore, broadcast};
+use tracing::{Instrument, error, info, warn};
 
 /// Redis 连接封装
 #[derive(Clone)]
 pub struct RedisConnection {
     /// Redis 连接管理器
     manager: ConnectionManager,
+    /// Redis 客户端，用于为事务等需要独占连接的操作建立新连接
+    client: Client,
+    /// 创建连接时使用的配置
+    config: RedisConfig,
+    /// 运行时命令计数器（克隆的连接共享同一份计数）
+    counters: Arc<CommandCounters>,
+    /// 命令级重试策略
+    retry_policy: RetryPolicy,
+    /// 单条命令的超时时间，覆盖连接层的 `response_timeout_secs`
+    command_timeout: Option<Duration>,
+    /// stale-while-revalidate 后台刷新许可（克隆的连接共享同一份许可）
+    refresh_permits: Arc<Semaphore>,
+}
+
+/// 命令执行计数器
+#[derive(Debug, Default)]
+struct CommandCounters {
+    /// 已执行命令总数
+    commands: AtomicU64,
+    /// 因连接断开触发的重连次数
+    reconnects: AtomicU64,
+    /// 命令指标（配置 enable_metrics 时启用）
+    metrics: Option<RedisMetrics>,
+    /// 连接生命周期事件
+    events: ConnectionEvents,
+    /// 命令追踪设置
+    tracer: CommandTracer,
+}
+
+impl CommandCounters {
+    /// 根据配置创建计数器，按需启用命令指标
+    fn new(config: &RedisConfig) -> Self {
+        Self {
+            metrics: config.enable_metrics.then(RedisMetrics::new),
+            tracer: CommandTracer::new(config.key_logging, config.slow_command_threshold_ms),
+            ..Default::default()
+        }
+    }
+
+    /// 执行命令并记录结果，启用指标时同时记录命令类型与延迟
+    ///
+    /// 命令在 `redis.command` span 中执行，`key` 为 [`CommandTracer::key_field`] 格式化后的键名。
+    /// 指定 `timeout` 时超时未完成的命令返回以命令名称命名的超时错误
+    async fn observe<T, F>(
+        &self,
+        command: &str,
+        key: Option<String>,
+        timeout: Option<Duration>,
+        future: F,
+    ) -> RedisResult<T>
+    where
+        F: Future<Output = redis::RedisResult<T>>,
+    {
+        let span = self.tracer.span(command, key.as_deref());
+        let future = future.instrument(span.clone());
+        let start = Instant::now();
+        let result = match timeout {
+            Some(limit) => match tokio::time::timeout(limit, future).await {
+                Ok(result) => self.track(result),
+                Err(_) => {
+                    self.commands.fetch_add(1, Ordering::Relaxed);
+                    Err(RedisError::timeout(command))
+                }
+            },
+            None => self.track(future.await),
+        };
+        let elapsed = start.elapsed();
+        self.tracer
+            .finish(&span, command, key.as_deref(), elapsed, result.is_err());
+        if let Some(metrics) = &self.metrics {
+            metrics.record(command, elapsed, result.as_ref().err());
+        }
+        result
+    }
+
+    /// 记录一次命令执行结果，并转换为模块错误类型
+    ///
+    /// 连接断开或 IO 错误时 ConnectionManager 会在后台重连，因此计入重连次数
+    fn track<T>(&self, result: redis::RedisResult<T>) -> RedisResult<T> {
+        self.commands.fetch_add(1, Ordering::Relaxed);
+        match result {
+            Ok(value) => {
+                self.events.succeeded();
+                Ok(value)
+            }
+            Err(e) => {
+                if e.is_connection_dropped() || e.is_io_error() {
+                    self.reconnects.fetch_add(1, Ordering::Relaxed);
+                    self.events.disconnected(&e);
+                }
+                Err(RedisError::from(e))
+            }
+        }
+    }
 }
 
 impl RedisConnection {
@@ -26,10 +136,7 @@ impl RedisConnection {
         info!("正在连接 Redis: {}", mask_redis_url(&config.url));
 
         // 创建 Redis 客户端
-        let client = Client::open(config.build_url()).map_err(|e| {
-            error!("Redis 客户端创建失败: {}", e);
-            RedisError::connection(format!("客户端创建失败: {}", e))
-        })?;
+        let client = Self::build_client(&config)?;
 
         // 创建 ConnectionManagerConfig 并应用自定义配置
         let mut manager_config = ConnectionManagerConfig::new()
@@ -54,7 +161,7 @@ impl RedisConnection {
         }
 
         // 使用自定义配置创建连接管理器
-        let manager = ConnectionManager::new_with_config(client, manager_config)
+        let manager = ConnectionManager::new_with_config(client.clone(), manager_config)
             .await
             .map_err(|e| {
                 error!("Redis 连接管理器创建失败: {}", e);
@@ -68,7 +175,49 @@ impl RedisConnection {
 
         info!("Redis 连接成功建立");
 
-        Ok(Self { manager })
+        Ok(Self {
+            manager,
+            client,
+            retry_policy: config.retry_policy.clone(),
+            counters: Arc::new(CommandCounters::new(&config)),
+            refresh_permits: Arc::new(Semaphore::new(config.max_background_refreshes)),
+            config,
+            command_timeout: None,
+        })
+    }
+
+    /// 根据配置创建 Redis 客户端，启用 TLS 且配置了证书时加载证书文件
+    fn build_client(config: &RedisConfig) -> RedisResult<Client> {
+        let result = if config.enable_tls && config.has_custom_certificates() {
+            let root_cert = config
+                .ca_cert_path
+                .as_deref()
+                .map(|path| read_certificate(path, "CA 证书"))
+                .transpose()?;
+
+            let client_tls = match (&config.client_cert_path, &config.client_key_path) {
+                (Some(cert_path), Some(key_path)) => Some(ClientTlsConfig {
+                    client_cert: read_certificate(cert_path, "客户端证书")?,
+                    client_key: read_certificate(key_path, "客户端私钥")?,
+                }),
+                _ => None,
+            };
+
+            Client::build_with_tls(
+                config.build_url(),
+                TlsCertificates {
+                    client_tls,
+                    root_cert,
+                },
+            )
+        } else {
+            Client::open(config.build_url())
+        };
+
+        result.map_err(|e| {
+            error!("Redis 客户端创建失败: {}", e);
+            RedisError::connection(format!("客户端创建失败: {}", e))
+        })
     }
 
     /// 从 Redis URL 字符串创建连接（最常用）
@@ -82,21 +231,221 @@ impl RedisConnection {
     pub async fn ping(&mut self) -> RedisResult<()> {
         let start = Instant::now();
 
-        redis::cmd("PING")
-            .query_async::<String>(&mut self.manager)
-            .await
-            .map_err(|e| {
-                warn!("Redis 连接测试失败: {}", e);
-                RedisError::connection(format!("连接测试失败: {}", e))
-            })?;
+        let result = self
+            .counters
+            .observe(
+                "PING",
+                None,
+                self.command_timeout,
+                redis::cmd("PING").query_async::<String>(&mut self.manager),
+            )
+            .await;
+        result.map_err(|e| {
+            warn!("Redis 连接测试失败: {}", e);
+            RedisError::connection(format!("连接测试失败: {}", e))
+        })?;
 
         let elapsed = start.elapsed();
         info!("Redis 连接测试成功，耗时: {:?}", elapsed);
         Ok(())
     }
 
+    /// 执行一次健康检查（PING），返回健康状态及响应时间，不输出日志
+    pub async fn health_check(&mut self) -> RedisHealthStatus {
+        let start = Instant::now();
+        let result = self
+            .counters
+            .observe(
+                "PING",
+                None,
+                self.command_timeout,
+                redis::cmd("PING").query_async::<String>(&mut self.manager),
+            )
+            .await;
+        let response_time_ms = start.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(_) => RedisHealthStatus {
+                is_healthy: true,
+                response_time_ms,
+                message: "PONG".to_string(),
+            },
+            Err(e) => RedisHealthStatus {
+                is_healthy: false,
+                response_time_ms,
+                message: e.to_string(),
+            },
+        }
+    }
+
+    /// 订阅连接生命周期事件（断开、重连、关闭），克隆的连接共享同一个事件源
+    ///
+    /// ```ignore
+    /// let mut events = conn.subscribe_events();
+    /// tokio::spawn(async move {
+    ///     while let Ok(event) = events.recv().await {
+    ///         if event.kind == ConnectionEventKind::Reconnected {
+    ///             // 故障切换后重新预热缓存
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.counters.events.subscribe()
+    }
+
+    /// 关闭连接，向订阅方发布关闭事件
+    ///
+    /// 克隆的连接共享底层连接管理器，所有克隆都被丢弃后底层连接才会断开
+    pub fn close(self) {
+        self.counters.events.closed();
+        info!("Redis 连接已关闭");
+    }
+
+    /// 执行任意 Redis 命令，并计入命令统计
+    ///
+    /// 遇到暂时性错误时按连接的重试策略重试，见 [`RedisConnection::with_retry`]。
+    /// 连接断开或超时后只重试 GET、HGETALL 等只读命令，其余命令可能已经执行，
+    /// 调用方确认命令可以安全重复执行时使用 [`RedisConnection::query_idempotent`]
+    pub async fn query<T: FromRedisValue>(&mut self, cmd: &redis::Cmd) -> RedisResult<T> {
+        let idempotent = is_idempotent_command(&command_name(cmd));
+        self.execute(cmd, None, idempotent).await
+    }
+
+    /// 执行重复执行时数据与返回值都不变的命令（如不带 NX/GET 的 SET），
+    /// 连接断开或超时后同样按重试策略重试
+    pub async fn query_idempotent<T: FromRedisValue>(
+        &mut self,
+        cmd: &redis::Cmd,
+    ) -> RedisResult<T> {
+        self.execute(cmd, None, true).await
+    }
+
+    /// 执行命令并计入命令统计，按连接的重试策略重试，所有命令方法都经由此处执行
+    ///
+    /// `idempotent` 表示命令重复执行时数据与返回值都不变；为 false 时连接断开、超时等
+    /// 无法确定命令是否已执行的错误不会重试，见 [`RedisError::is_retriable_for`]
+    async fn execute<T: FromRedisValue>(
+        &self,
+        cmd: &redis::Cmd,
+        key: Option<String>,
+        idempotent: bool,
+    ) -> RedisResult<T> {
+        let command = command_name(cmd);
+        self.retry_policy
+            .execute_with(idempotent, || {
+                let mut manager = self.manager.clone();
+                self.counters
+                    .observe(&command, key.clone(), self.command_timeout, async move {
+                        cmd.query_async(&mut manager).await
+                    })
+            })
+            .await
+    }
+
+    /// 通过 MULTI/EXEC 原子执行 `build` 排队的命令，返回各命令的结果
+    ///
+    /// 命令在共享的连接管理器上执行，不支持 WATCH 乐观锁，需要时使用
+    /// [`RedisConnection::watch_transaction`]；[`TransactionQueue`] 排队的键会加上当前连接的键前缀，
+    /// `build` 返回错误时不执行任何命令
+    pub async fn multi_exec<T, F>(&self, build: F) -> RedisResult<T>
+    where
+        T: FromRedisValue,
+        F: FnOnce(&mut TransactionQueue) -> RedisResult<()>,
+    {
+        let mut queue = TransactionQueue::new(self);
+        build(&mut queue)?;
+        let mut manager = self.manager.clone();
+        self.observe("EXEC", queue.pipeline().query_async(&mut manager))
+            .await
+    }
+
+    /// 按追踪设置格式化键名，作为命令 span 的 `db.redis.key` 字段
+    fn trace_key<K: ToRedisArgs>(&self, key: &K) -> Option<String> {
+        let tracer = &self.counters.tracer;
+        if !tracer.records_keys() {
+            return None;
+        }
+        tracer.key_field(&key.to_redis_args())
+    }
+
+    /// 执行命令并计入命令统计（不重试），供事务等使用独占连接的操作复用
+    pub(crate) async fn observe<T, F>(&self, command: &str, future: F) -> RedisResult<T>
+    where
+        F: Future<Output = redis::RedisResult<T>>,
+    {
+        self.counters
+            .observe(command, None, self.command_timeout, future)
+            .await
+    }
+
+    /// 建立一条不与其他调用方共享的新连接
+    ///
+    /// WATCH 状态绑定在连接上，共享的 ConnectionManager 上会被其他命令干扰
+    pub(crate) async fn dedicated_connection(&self) -> RedisResult<MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| RedisError::connection(format!("独占连接创建失败: {}", e)))
+    }
+
+    /// 派生一个使用指定重试策略的连接，共享底层连接和命令统计
+    ///
+    /// 重试策略作用于所有命令方法；连接断开或超时后只重试重复执行结果不变的命令，
+    /// INCR、LPUSH 等命令只在服务端拒绝执行（LOADING、BUSY、TRYAGAIN）时重试
+    pub fn with_retry(&self, policy: RetryPolicy) -> Self {
+        let mut retrying = self.clone();
+        retrying.retry_policy = policy;
+        retrying
+    }
+
+    /// 派生一个为每条命令设置超时时间的连接，共享底层连接和命令统计
+    ///
+    /// 超时的命令返回 [`RedisError::Timeout`]，不影响之后的命令。ConnectionManager 的
+    /// `response_timeout_secs` 仍然生效，需要比它更长的超时（如 BRPOP、大范围 SCAN）时应将其设为 0
+    ///
+    /// ```ignore
+    /// let value = conn.with_timeout(Duration::from_millis(50)).get_builtin("key").await?;
+    /// ```
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let mut limited = self.clone();
+        limited.command_timeout = Some(timeout);
+        limited
+    }
+
+    /// 当前连接的单条命令超时时间
+    pub fn command_timeout(&self) -> Option<Duration> {
+        self.command_timeout
+    }
+
+    /// 当前连接使用的重试策略
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// 执行临时 Lua 脚本（EVAL），适用于只执行一次的脚本
+    ///
+    /// 需要重复执行的脚本请使用 [`RedisScript`](crate::redis::RedisScript)，以便通过 EVALSHA 复用已加载的脚本
+    pub async fn eval_script<T, K, A>(
+        &mut self,
+        source: &str,
+        keys: &[K],
+        args: &[A],
+    ) -> RedisResult<T>
+    where
+        T: FromRedisScriptValue,
+        K: ToRedisArgs,
+        A: ToRedisArgs,
+    {
+        let keys = self.prefixed(keys);
+        let mut cmd = redis::cmd("EVAL");
+        cmd.arg(source).arg(keys.len()).arg(&keys).arg(args);
+        let value: redis::Value = self.query(&cmd).await?;
+        T::from_script_value(value)
+    }
+
     // =============================================================================
-    // 使用 AsyncCommands trait 内置方法的示例（推荐）
+    // 常用命令
     // =============================================================================
 
     /// 设置键值对 - 使用内置方法
@@ -105,8 +454,13 @@ impl RedisConnection {
         K: ToRedisArgs + Send + Sync,
         V: ToRedisArgs + Send + Sync,
     {
-        // 使用 AsyncCommands trait 的内置 set 方法
-        self.manager.set(key, value).await.map_err(RedisError::from)
+        let key = self.prefixed(key);
+        self.execute(
+            redis::cmd("SET").arg(&key).arg(value),
+            self.trace_key(&key),
+            true,
+        )
+        .await
     }
 
     /// 获取键的值 - 使用内置方法
@@ -114,8 +468,42 @@ impl RedisConnection {
     where
         K: ToRedisArgs + Send + Sync,
     {
-        // 使用 AsyncCommands trait 的内置 get 方法
-        self.manager.get(key).await.map_err(RedisError::from)
+        let key = self.prefixed(key);
+        let result = self
+            .execute(redis::cmd("GET").arg(&key), self.trace_key(&key), true)
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::String)
+            .await
+    }
+
+    /// 以原始字节设置键值，适用于 bincode/protobuf 等二进制数据
+    ///
+    /// 配置了 `compression` 时，超过 `compression_threshold_bytes` 的值会被压缩后写入
+    pub async fn set_bytes<K>(&mut self, key: K, value: &[u8]) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let compressed = self.compress_value(value)?;
+        let value = compressed.as_deref().unwrap_or(value);
+        let key = self.prefixed(key);
+        self.execute(
+            redis::cmd("SET").arg(&key).arg(value),
+            self.trace_key(&key),
+            true,
+        )
+        .await
+    }
+
+    /// 以原始字节获取键的值，不做 UTF-8 校验，压缩过的值会被透明解压
+    pub async fn get_bytes<K>(&mut self, key: K) -> RedisResult<Option<Vec<u8>>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let raw = self
+            .execute(redis::cmd("GET").arg(&key), self.trace_key(&key), true)
+            .await?;
+        decompress_optional(raw)
     }
 
     /// 检查键是否存在 - 使用内置方法
@@ -123,8 +511,150 @@ impl RedisConnection {
     where
         K: ToRedisArgs + Send + Sync,
     {
-        // 使用 AsyncCommands trait 的内置 exists 方法
-        self.manager.exists(key).await.map_err(RedisError::from)
+        let key = self.prefixed(key);
+        self.execute(redis::cmd("EXISTS").arg(&key), self.trace_key(&key), true)
+            .await
+    }
+
+    /// 删除键，返回实际删除的数量
+    pub async fn del<K>(&mut self, key: K) -> RedisResult<i64>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        self.execute(redis::cmd("DEL").arg(&key), self.trace_key(&key), false)
+            .await
+    }
+
+    /// 仅当键不存在时设置（SETNX），返回是否设置成功
+    pub async fn set_nx<K, V>(&mut self, key: K, value: V) -> RedisResult<bool>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        self.execute(
+            redis::cmd("SETNX").arg(&key).arg(value),
+            self.trace_key(&key),
+            false,
+        )
+        .await
+    }
+
+    /// 仅当键已存在时设置（SET XX），返回是否设置成功
+    pub async fn set_xx<K, V>(&mut self, key: K, value: V) -> RedisResult<bool>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let options = SetOptions::default().conditional_set(ExistenceCheck::XX);
+        let reply: Option<String> = self
+            .execute(
+                redis::cmd("SET").arg(&key).arg(value).arg(options),
+                self.trace_key(&key),
+                true,
+            )
+            .await?;
+        Ok(reply.is_some())
+    }
+
+    /// 设置新值并返回旧值（GETSET），键不存在时返回 None
+    pub async fn getset<K, V>(&mut self, key: K, value: V) -> RedisResult<Option<String>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        self.execute(
+            redis::cmd("GETSET").arg(&key).arg(value),
+            self.trace_key(&key),
+            false,
+        )
+        .await
+    }
+
+    /// 获取值并原子删除键（GETDEL），适用于一次性令牌
+    pub async fn getdel<K>(&mut self, key: K) -> RedisResult<Option<String>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        self.execute(redis::cmd("GETDEL").arg(&key), self.trace_key(&key), false)
+            .await
+    }
+
+    /// 设置键在指定时间点过期（EXPIREAT，秒级精度），返回键是否存在
+    ///
+    /// 时间点已过去时键会被立即删除
+    pub async fn expire_at<K>(&mut self, key: K, at: DateTime<Utc>) -> RedisResult<bool>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        self.execute(
+            redis::cmd("EXPIREAT").arg(&key).arg(at.timestamp()),
+            self.trace_key(&key),
+            true,
+        )
+        .await
+    }
+
+    /// 设置键在指定时间点过期（PEXPIREAT，毫秒级精度），返回键是否存在
+    ///
+    /// 时间点已过去时键会被立即删除
+    pub async fn pexpire_at<K>(&mut self, key: K, at: DateTime<Utc>) -> RedisResult<bool>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        self.execute(
+            redis::cmd("PEXPIREAT").arg(&key).arg(at.timestamp_millis()),
+            self.trace_key(&key),
+            true,
+        )
+        .await
+    }
+
+    /// 获取键的剩余存活时间（PTTL），键不存在或未设置过期时间时返回 None
+    pub async fn ttl_remaining<K>(&mut self, key: K) -> RedisResult<Option<chrono::Duration>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let millis: i64 = self
+            .execute(redis::cmd("PTTL").arg(&key), self.trace_key(&key), true)
+            .await?;
+        // -2 表示键不存在，-1 表示键没有过期时间
+        Ok((millis >= 0).then(|| chrono::Duration::milliseconds(millis)))
+    }
+
+    /// 按模式扫描键（SCAN MATCH），模式与返回的键均不包含键前缀
+    pub async fn scan_match(&mut self, pattern: &str) -> RedisResult<Vec<String>> {
+        let pattern = self.prefixed_key(pattern);
+        let mut cursor: u64 = 0;
+        let mut keys = Vec::new();
+
+        loop {
+            let (next, batch): (u64, Vec<String>) = self
+                .query(
+                    redis::cmd("SCAN")
+                        .arg(cursor)
+                        .arg("MATCH")
+                        .arg(&pattern)
+                        .arg("COUNT")
+                        .arg(100),
+                )
+                .await?;
+            keys.extend(batch.into_iter().map(|key| self.strip_prefix(key)));
+
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+
+        Ok(keys)
     }
 
     /// 列表操作：左侧推入
@@ -133,10 +663,16 @@ impl RedisConnection {
         K: ToRedisArgs + Send + Sync,
         V: ToRedisArgs + Send + Sync,
     {
-        self.manager
-            .lpush(key, value)
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("LPUSH").arg(&key).arg(value),
+                self.trace_key(&key),
+                false,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::List)
             .await
-            .map_err(RedisError::from)
     }
 
     /// 列表操作：右侧弹出
@@ -144,7 +680,154 @@ impl RedisConnection {
     where
         K: ToRedisArgs + Send + Sync,
     {
-        self.manager.rpop(key, None).await.map_err(RedisError::from)
+        let key = self.prefixed(key);
+        let result = self
+            .execute(redis::cmd("RPOP").arg(&key), self.trace_key(&key), false)
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::List)
+            .await
+    }
+
+    /// 列表操作：右侧弹出原始字节
+    pub async fn rpop_bytes<K>(&mut self, key: K) -> RedisResult<Option<Vec<u8>>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(redis::cmd("RPOP").arg(&key), self.trace_key(&key), false)
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::List)
+            .await
+    }
+
+    /// 列表操作：右侧推入
+    pub async fn rpush<K, V>(&mut self, key: K, value: V) -> RedisResult<i64>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("RPUSH").arg(&key).arg(value),
+                self.trace_key(&key),
+                false,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::List)
+            .await
+    }
+
+    /// 列表操作：获取区间元素（闭区间，支持负数下标）
+    pub async fn lrange<K>(&mut self, key: K, start: isize, stop: isize) -> RedisResult<Vec<String>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("LRANGE").arg(&key).arg(start).arg(stop),
+                self.trace_key(&key),
+                true,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::List)
+            .await
+    }
+
+    /// 列表操作：获取列表长度
+    pub async fn llen<K>(&mut self, key: K) -> RedisResult<i64>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(redis::cmd("LLEN").arg(&key), self.trace_key(&key), true)
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::List)
+            .await
+    }
+
+    /// 列表操作：移除与 value 相等的元素，返回移除数量
+    ///
+    /// count > 0 从头部开始移除 count 个，count < 0 从尾部开始移除，count = 0 移除全部
+    pub async fn lrem<K, V>(&mut self, key: K, count: isize, value: V) -> RedisResult<i64>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("LREM").arg(&key).arg(count).arg(value),
+                self.trace_key(&key),
+                false,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::List)
+            .await
+    }
+
+    /// 列表操作：阻塞式右侧弹出，返回 (键, 值)，超时返回 None
+    ///
+    /// 可传入多个键，按顺序检查第一个非空列表。阻塞期间不影响同一连接上的其他命令，
+    /// 见 [`RedisConnection::blpop`]
+    pub async fn brpop<K>(
+        &mut self,
+        key: K,
+        timeout: Duration,
+    ) -> RedisResult<Option<(String, String)>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        self.blocking_pop("BRPOP", self.prefixed(key), timeout)
+            .await
+    }
+
+    /// 列表操作：阻塞式左侧弹出，返回 (键, 值)，超时返回 None
+    ///
+    /// 共享的 ConnectionManager 是多路复用连接，阻塞命令会让排在其后的所有命令一起等待，
+    /// 因此阻塞式弹出在独占连接上执行；设置了命令超时时，超时时间在阻塞时间基础上顺延
+    pub async fn blpop<K>(
+        &mut self,
+        key: K,
+        timeout: Duration,
+    ) -> RedisResult<Option<(String, String)>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        self.blocking_pop("BLPOP", self.prefixed(key), timeout)
+            .await
+    }
+
+    /// 在独占连接上执行 BLPOP/BRPOP
+    async fn blocking_pop(
+        &self,
+        command: &str,
+        key: Vec<Vec<u8>>,
+        timeout: Duration,
+    ) -> RedisResult<Option<(String, String)>> {
+        // 弹出不可重复执行，只在服务端拒绝执行时重试
+        let key = &key;
+        let popped: Option<(String, String)> = self
+            .retry_policy
+            .execute_with(false, || async move {
+                let mut connection = self.dedicated_connection().await?;
+                self.counters
+                    .observe(
+                        command,
+                        self.trace_key(key),
+                        self.command_timeout.map(|limit| limit + timeout),
+                        redis::cmd(command)
+                            .arg(key)
+                            .arg(timeout.as_secs_f64())
+                            .query_async(&mut connection),
+                    )
+                    .await
+            })
+            .await?;
+        Ok(popped.map(|(key, value)| (self.strip_prefix(key), value)))
     }
 
     /// 哈希操作：设置字段
@@ -154,10 +837,16 @@ impl RedisConnection {
         F: ToRedisArgs + Send + Sync,
         V: ToRedisArgs + Send + Sync,
     {
-        self.manager
-            .hset(key, field, value)
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("HSET").arg(&key).arg(field).arg(value),
+                self.trace_key(&key),
+                false,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::Hash)
             .await
-            .map_err(RedisError::from)
     }
 
     /// 哈希操作：获取字段
@@ -166,72 +855,839 @@ impl RedisConnection {
         K: ToRedisArgs + Send + Sync,
         F: ToRedisArgs + Send + Sync,
     {
-        self.manager
-            .hget(key, field)
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("HGET").arg(&key).arg(field),
+                self.trace_key(&key),
+                true,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::Hash)
             .await
-            .map_err(RedisError::from)
     }
 
-    /// 获取连接池统计信息
-    pub fn get_pool_stats(&self) -> RedisConnectionStats {
-        RedisConnectionStats {
-            max_connections: 10, // ConnectionManager 默认最大连接数
-            min_connections: 0,  // ConnectionManager 默认最小连接数
-            connect_timeout: 30, // ConnectionManager 默认连接超时（秒）
-            read_timeout: 5,     // ConnectionManager 默认读取超时（秒）
-            write_timeout: 5,    // ConnectionManager 默认写入超时（秒）
-        }
+    /// 哈希操作：以原始字节获取字段
+    pub async fn hget_bytes<K, F>(&mut self, key: K, field: F) -> RedisResult<Option<Vec<u8>>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        F: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("HGET").arg(&key).arg(field),
+                self.trace_key(&key),
+                true,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::Hash)
+            .await
     }
-}
-
-/// 便利函数：从 URL 创建连接（最常用）
-pub async fn create_redis_connection_from_url(redis_url: &str) -> RedisResult<RedisConnection> {
-    RedisConnection::from_url(redis_url).await
-}
-
-/// 便利函数：从配置对象创建连接
-pub async fn create_redis_connection_from_config(
-    config: RedisConfig,
-) -> RedisResult<RedisConnection> {
-    RedisConnection::new(config).await
-}
 
-/// 连接统计信息
-#[derive(Debug, Clone)]
-pub struct RedisConnectionStats {
-    pub max_connections: u32,
-    pub min_connections: u32,
-    pub connect_timeout: u64,
-    pub read_timeout: u64,
-    pub write_timeout: u64,
-}
+    /// 哈希操作：获取所有字段及值
+    pub async fn hgetall<K>(&mut self, key: K) -> RedisResult<HashMap<String, String>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(redis::cmd("HGETALL").arg(&key), self.trace_key(&key), true)
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::Hash)
+            .await
+    }
 
-/// Redis 健康状态
-#[derive(Debug, Clone)]
-pub struct RedisHealthStatus {
-    pub is_healthy: bool,
-    pub response_time_ms: u64,
-    pub message: String,
-}
+    /// 哈希操作：一次设置多个字段
+    pub async fn hset_multiple<K, F, V>(&mut self, key: K, items: &[(F, V)]) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+        F: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("HSET").arg(&key).arg(items),
+                self.trace_key(&key),
+                true,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::Hash)
+            .await
+    }
 
-/// 屏蔽 Redis URL 中的敏感信息
-pub fn mask_redis_url(url: &str) -> String {
-    // 简单地屏蔽可能的密码部分
-    if let Some(at_pos) = url.find('@') {
-        if let Some(colon_pos) = url[..at_pos].rfind(':') {
-            if let Some(slash_pos) = url[..colon_pos].rfind('/') {
-                let before = &url[..slash_pos + 1];
-                let after = &url[at_pos..];
-                return format!("{}***:***{}", before, after);
-            }
-        }
+    /// 哈希操作：删除一个或多个字段，返回实际删除的数量
+    pub async fn hdel<K, F>(&mut self, key: K, fields: F) -> RedisResult<i64>
+    where
+        K: ToRedisArgs + Send + Sync,
+        F: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("HDEL").arg(&key).arg(fields),
+                self.trace_key(&key),
+                false,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::Hash)
+            .await
     }
-    url.to_string()
-}
+
+    /// 哈希操作：获取所有字段名
+    pub async fn hkeys<K>(&mut self, key: K) -> RedisResult<Vec<String>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(redis::cmd("HKEYS").arg(&key), self.trace_key(&key), true)
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::Hash)
+            .await
+    }
+
+    /// 哈希操作：获取所有字段值
+    pub async fn hvals<K>(&mut self, key: K) -> RedisResult<Vec<String>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(redis::cmd("HVALS").arg(&key), self.trace_key(&key), true)
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::Hash)
+            .await
+    }
+
+    /// 哈希操作：获取字段数量
+    pub async fn hlen<K>(&mut self, key: K) -> RedisResult<usize>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(redis::cmd("HLEN").arg(&key), self.trace_key(&key), true)
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::Hash)
+            .await
+    }
+
+    /// 哈希操作：获取字段并按 JSON 反序列化
+    pub async fn hget_json<K, F, T>(&mut self, key: K, field: F) -> RedisResult<Option<T>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        F: ToRedisArgs + Send + Sync,
+        T: DeserializeOwned,
+    {
+        decode_json(self.hget_bytes(key, field).await?)
+    }
+
+    /// 哈希操作：将结构体的每个顶层字段按 JSON 序列化后写入哈希
+    pub async fn hset_json_multiple<K, T>(&mut self, key: K, value: &T) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+        T: Serialize,
+    {
+        let fields = struct_to_hash_fields(value)?;
+        self.hset_multiple(key, &fields).await
+    }
+
+    /// 哈希操作：读取整个哈希并还原为结构体，哈希不存在时返回 None
+    pub async fn hgetall_json<K, T>(&mut self, key: K) -> RedisResult<Option<T>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        T: DeserializeOwned,
+    {
+        let fields = self.hgetall(key).await?;
+        if fields.is_empty() {
+            return Ok(None);
+        }
+        hash_fields_to_struct(fields).map(Some)
+    }
+
+    /// 有序集合操作：添加成员及分数
+    pub async fn zadd<K, M>(&mut self, key: K, member: M, score: f64) -> RedisResult<i64>
+    where
+        K: ToRedisArgs + Send + Sync,
+        M: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("ZADD").arg(&key).arg(score).arg(member),
+                self.trace_key(&key),
+                false,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::ZSet)
+            .await
+    }
+
+    /// 有序集合操作：按分数升序获取区间成员及分数
+    pub async fn zrange_withscores<K>(
+        &mut self,
+        key: K,
+        start: isize,
+        stop: isize,
+    ) -> RedisResult<Vec<(String, f64)>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("ZRANGE")
+                    .arg(&key)
+                    .arg(start)
+                    .arg(stop)
+                    .arg("WITHSCORES"),
+                self.trace_key(&key),
+                true,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::ZSet)
+            .await
+    }
+
+    /// 有序集合操作：按分数降序获取区间成员及分数
+    pub async fn zrevrange_withscores<K>(
+        &mut self,
+        key: K,
+        start: isize,
+        stop: isize,
+    ) -> RedisResult<Vec<(String, f64)>>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("ZREVRANGE")
+                    .arg(&key)
+                    .arg(start)
+                    .arg(stop)
+                    .arg("WITHSCORES"),
+                self.trace_key(&key),
+                true,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::ZSet)
+            .await
+    }
+
+    /// 有序集合操作：获取成员分数
+    pub async fn zscore<K, M>(&mut self, key: K, member: M) -> RedisResult<Option<f64>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        M: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("ZSCORE").arg(&key).arg(member),
+                self.trace_key(&key),
+                true,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::ZSet)
+            .await
+    }
+
+    /// 有序集合操作：获取成员排名（按分数升序，从 0 开始）
+    pub async fn zrank<K, M>(&mut self, key: K, member: M) -> RedisResult<Option<u64>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        M: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        let result = self
+            .execute(
+                redis::cmd("ZRANK").arg(&key).arg(member),
+                self.trace_key(&key),
+                true,
+            )
+            .await;
+        self.explain_wrong_type(result, &key, RedisValueType::ZSet)
+            .await
+    }
+
+    /// HyperLogLog：添加元素，返回基数估计是否发生变化
+    pub async fn pfadd<K, E>(&mut self, key: K, elements: E) -> RedisResult<bool>
+    where
+        K: ToRedisArgs + Send + Sync,
+        E: ToRedisArgs + Send + Sync,
+    {
+        let key = self.prefixed(key);
+        self.execute(
+            redis::cmd("PFADD").arg(&key).arg(elements),
+            self.trace_key(&key),
+            false,
+        )
+        .await
+    }
+
+    /// HyperLogLog：获取一个或多个键合并后的基数估计（标准误差约 0.81%）
+    pub async fn pfcount<K>(&mut self, keys: K) -> RedisResult<u64>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let keys = self.prefixed(keys);
+        self.execute(
+            redis::cmd("PFCOUNT").arg(&keys),
+            self.trace_key(&keys),
+            true,
+        )
+        .await
+    }
+
+    /// HyperLogLog：将多个键合并到目标键
+    pub async fn pfmerge<D, K>(&mut self, dest: D, sources: K) -> RedisResult<()>
+    where
+        D: ToRedisArgs + Send + Sync,
+        K: ToRedisArgs + Send + Sync,
+    {
+        let dest = self.prefixed(dest);
+        let sources = self.prefixed(sources);
+        self.execute(
+            redis::cmd("PFMERGE").arg(&dest).arg(&sources),
+            self.trace_key(&dest),
+            true,
+        )
+        .await
+    }
+
+    // =============================================================================
+    // JSON 缓存操作
+    // =============================================================================
+
+    /// 读取 JSON 缓存并反序列化，键不存在时返回 None
+    pub async fn get_json<T: DeserializeOwned>(&mut self, key: &str) -> RedisResult<Option<T>> {
+        decode_json(self.get_bytes(key).await?)
+    }
+
+    /// 序列化为 JSON 并写入缓存，同时设置过期时间
+    pub async fn set_json_ex<T: Serialize>(
+        &mut self,
+        key: &str,
+        value: &T,
+        ttl: Duration,
+    ) -> RedisResult<()> {
+        let raw = self.encode_json_value(value)?;
+        self.query_idempotent(
+            redis::cmd("SET")
+                .arg(self.prefixed_key(key))
+                .arg(raw)
+                .arg("PX")
+                .arg(ttl.as_millis() as u64),
+        )
+        .await
+    }
+
+    /// 序列化为 JSON 后仅当键不存在时写入，返回是否写入成功
+    pub async fn set_nx_json<T: Serialize>(&mut self, key: &str, value: &T) -> RedisResult<bool> {
+        let raw = self.encode_json_value(value)?;
+        self.set_nx(key, raw).await
+    }
+
+    /// 序列化为 JSON 后仅当键已存在时写入，返回是否写入成功
+    pub async fn set_xx_json<T: Serialize>(&mut self, key: &str, value: &T) -> RedisResult<bool> {
+        let raw = self.encode_json_value(value)?;
+        self.set_xx(key, raw).await
+    }
+
+    /// 写入新的 JSON 值并返回反序列化后的旧值
+    pub async fn getset_json<T>(&mut self, key: &str, value: &T) -> RedisResult<Option<T>>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let raw = self.encode_json_value(value)?;
+        let key = self.prefixed_key(key);
+        let old = self
+            .execute(
+                redis::cmd("GETSET").arg(&key).arg(raw),
+                self.trace_key(&key),
+                false,
+            )
+            .await?;
+        decode_json(old)
+    }
+
+    /// 获取 JSON 值并原子删除键
+    pub async fn getdel_json<T: DeserializeOwned>(&mut self, key: &str) -> RedisResult<Option<T>> {
+        let key = self.prefixed_key(key);
+        let raw = self
+            .execute(redis::cmd("GETDEL").arg(&key), self.trace_key(&key), false)
+            .await?;
+        decode_json(raw)
+    }
+
+    /// 读取缓存，未命中时调用 loader 计算结果并写回缓存
+    pub async fn get_or_set_json<T, F, Fut>(
+        &mut self,
+        key: &str,
+        ttl: Duration,
+        loader: F,
+    ) -> RedisResult<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        if let Some(value) = self.get_json(key).await? {
+            return Ok(value);
+        }
+
+        let value = loader().await;
+        self.set_json_ex(key, &value, ttl).await?;
+        Ok(value)
+    }
+
+    /// 读取缓存，未命中时调用 loader 计算结果并写回缓存，通过短期 NX 锁防止缓存击穿
+    ///
+    /// 同一时刻只有获得 `{key}:lock` 锁的调用方执行 loader，其余调用方轮询等待缓存写入；
+    /// 等待超过 `lock_ttl` 仍未命中时（例如持锁方异常退出）自行执行 loader
+    pub async fn get_or_set_json_with_lock<T, F, Fut>(
+        &mut self,
+        key: &str,
+        ttl: Duration,
+        lock_ttl: Duration,
+        loader: F,
+    ) -> RedisResult<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        if let Some(value) = self.get_json(key).await? {
+            return Ok(value);
+        }
+
+        let lock_key = format!("{}:lock", key);
+        let token = lock_token();
+        let deadline = Instant::now() + lock_ttl;
+
+        while Instant::now() < deadline {
+            if self
+                .acquire_lock(&lock_key, &token, lock_ttl.as_millis() as u64)
+                .await?
+            {
+                // 获得锁后再次检查缓存，避免重复加载
+                let result = match self.get_json(key).await {
+                    Ok(Some(value)) => Ok(value),
+                    Ok(None) => {
+                        let value = loader().await;
+                        self.set_json_ex(key, &value, ttl).await.map(|_| value)
+                    }
+                    Err(e) => Err(e),
+                };
+                self.release_lock(&lock_key, &token).await?;
+                return result;
+            }
+
+            tokio::time::sleep(LOCK_RETRY_INTERVAL).await;
+            if let Some(value) = self.get_json(key).await? {
+                return Ok(value);
+            }
+        }
+
+        warn!("等待缓存锁超时，直接加载: {}", key);
+        let value = loader().await;
+        self.set_json_ex(key, &value, ttl).await?;
+        Ok(value)
+    }
+
+    /// 读取缓存，软过期后立即返回旧值并在后台刷新（stale-while-revalidate）
+    ///
+    /// 值与软过期时间一起写入缓存：Redis TTL 为 `ttl`，软过期时间为 `ttl * stale_ttl_ratio`。
+    /// 软过期后只有获得 `{key}:refresh` 锁的调用方会在后台执行 loader，且同时执行的后台刷新
+    /// 不超过 `max_background_refreshes` 个，因此直到硬过期前调用方都不会遇到未命中；
+    /// 键不存在时同步执行 loader。写入的值带有软过期时间，不要与 `get_json` 共用同一个键
+    pub async fn get_or_set_json_swr<T, F, Fut>(
+        &mut self,
+        key: &str,
+        ttl: Duration,
+        loader: F,
+    ) -> RedisResult<T>
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        match self.get_json::<StaleEntry<T>>(key).await? {
+            Some(entry) if !entry.is_stale(unix_millis()) => return Ok(entry.value),
+            Some(entry) => {
+                // 刷新失败不影响返回旧值
+                if let Err(e) = self.spawn_refresh(key, ttl, loader).await {
+                    warn!("启动缓存后台刷新失败: {}: {}", key, e);
+                }
+                return Ok(entry.value);
+            }
+            None => {}
+        }
+
+        let value = loader().await;
+        let raw = self.encode_stale_entry(&value, ttl)?;
+        self.set_stale_entry(key, raw, ttl).await?;
+        Ok(value)
+    }
+
+    /// 获得刷新许可与 `{key}:refresh` 锁后在后台执行 loader 并写回缓存
+    ///
+    /// 锁的有效期为软过期到硬过期之间的时长，刷新任务异常退出时锁也会在值硬过期前释放
+    async fn spawn_refresh<T, F, Fut>(
+        &mut self,
+        key: &str,
+        ttl: Duration,
+        loader: F,
+    ) -> RedisResult<()>
+    where
+        T: Serialize + Send + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let Ok(permit) = self.refresh_permits.clone().try_acquire_owned() else {
+            return Ok(());
+        };
+
+        let lock_key = format!("{}:refresh", key);
+        let token = lock_token();
+        let lock_ttl = ttl
+            .saturating_sub(self.fresh_ttl(ttl))
+            .max(Duration::from_millis(1));
+        if !self
+            .acquire_lock(&lock_key, &token, lock_ttl.as_millis() as u64)
+            .await?
+        {
+            return Ok(());
+        }
+
+        let mut connection = self.clone();
+        let key = key.to_string();
+        tokio::spawn(async move {
+            let _permit = permit;
+            let value = loader().await;
+            // 先完成序列化，避免跨 await 持有 &T 而要求 T: Sync
+            let written = match connection.encode_stale_entry(&value, ttl) {
+                Ok(raw) => connection.set_stale_entry(&key, raw, ttl).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = written {
+                warn!("缓存后台刷新失败: {}: {}", key, e);
+            }
+            if let Err(e) = connection.release_lock(&lock_key, &token).await {
+                warn!("释放缓存刷新锁失败: {}: {}", lock_key, e);
+            }
+        });
+        Ok(())
+    }
+
+    /// 将值与软过期时间一起序列化
+    fn encode_stale_entry<T: Serialize>(&self, value: &T, ttl: Duration) -> RedisResult<Vec<u8>> {
+        self.encode_json_value(&StaleEntry {
+            value,
+            fresh_until_ms: unix_millis() + self.fresh_ttl(ttl).as_millis() as u64,
+        })
+    }
+
+    /// 写入序列化后的缓存值并设置硬过期时间
+    async fn set_stale_entry(&mut self, key: &str, raw: Vec<u8>, ttl: Duration) -> RedisResult<()> {
+        self.query_idempotent(
+            redis::cmd("SET")
+                .arg(self.prefixed_key(key))
+                .arg(raw)
+                .arg("PX")
+                .arg(ttl.as_millis() as u64),
+        )
+        .await
+    }
+
+    /// 按 `stale_ttl_ratio` 计算软过期时长
+    fn fresh_ttl(&self, ttl: Duration) -> Duration {
+        ttl.mul_f64(self.config.stale_ttl_ratio)
+    }
+
+    // =============================================================================
+    // 键前缀
+    // =============================================================================
+
+    /// 派生一个追加了键前缀的连接（共享底层连接），例如 `svc:` + `users:` 得到 `svc:users:`
+    pub fn with_prefix(&self, extra: &str) -> Self {
+        let mut scoped = self.clone();
+        scoped.config.key_prefix = Some(self.prefixed_key(extra));
+        scoped
+    }
+
+    /// 获取当前键前缀
+    pub fn key_prefix(&self) -> Option<&str> {
+        self.config.key_prefix.as_deref()
+    }
+
+    /// 为键加上前缀，用于通过 [`query`](Self::query) 执行自定义命令时手动处理键
+    pub fn prefixed_key(&self, key: &str) -> String {
+        match self.key_prefix() {
+            Some(prefix) => format!("{}{}", prefix, key),
+            None => key.to_string(),
+        }
+    }
+
+    /// 为键参数加上前缀，支持单个键或多个键
+    pub(crate) fn prefixed<K: ToRedisArgs>(&self, key: K) -> Vec<Vec<u8>> {
+        apply_key_prefix(self.key_prefix(), key)
+    }
+
+    /// 移除键上的前缀
+    fn strip_prefix(&self, key: String) -> String {
+        match self.key_prefix() {
+            Some(prefix) => key.strip_prefix(prefix).map(str::to_string).unwrap_or(key),
+            None => key,
+        }
+    }
+
+    /// 获取连接池统计信息
+    pub fn get_pool_stats(&self) -> RedisConnectionStats {
+        RedisConnectionStats::from_config(
+            &self.config,
+            self.counters.commands.load(Ordering::Relaxed),
+            self.counters.reconnects.load(Ordering::Relaxed),
+        )
+    }
+
+    /// 获取命令指标快照，未启用 enable_metrics 时返回空快照
+    pub fn get_metrics(&self) -> RedisMetricsSnapshot {
+        self.counters
+            .metrics
+            .as_ref()
+            .map(RedisMetrics::snapshot)
+            .unwrap_or_default()
+    }
+
+    /// 获取创建连接时使用的配置
+    pub fn get_config(&self) -> &RedisConfig {
+        &self.config
+    }
+
+    /// 按配置压缩待写入的值，未超过阈值或未启用压缩时返回 None
+    pub(crate) fn compress_value(&self, raw: &[u8]) -> RedisResult<Option<Vec<u8>>> {
+        compress(
+            self.config.compression,
+            self.config.compression_threshold_bytes,
+            raw,
+        )
+    }
+
+    /// 序列化为 JSON 并按配置压缩
+    pub(crate) fn encode_json_value<T: Serialize>(&self, value: &T) -> RedisResult<Vec<u8>> {
+        let raw = encode_json(value)?;
+        Ok(self.compress_value(&raw)?.unwrap_or(raw))
+    }
+}
+
+/// 序列化为 JSON 字节
+fn encode_json<T: Serialize>(value: &T) -> RedisResult<Vec<u8>> {
+    serde_json::to_vec(value).map_err(|e| RedisError::serialization(e.to_string()))
+}
+
+/// 解压并反序列化可选的 JSON 字节
+pub(crate) fn decode_json<T: DeserializeOwned>(raw: Option<Vec<u8>>) -> RedisResult<Option<T>> {
+    decompress_optional(raw)?
+        .map(|raw| {
+            serde_json::from_slice(&raw).map_err(|e| RedisError::deserialization(e.to_string()))
+        })
+        .transpose()
+}
+
+/// stale-while-revalidate 缓存值，携带软过期时间（Unix 毫秒时间戳）
+#[derive(Serialize, Deserialize)]
+struct StaleEntry<T> {
+    value: T,
+    fresh_until_ms: u64,
+}
+
+impl<T> StaleEntry<T> {
+    /// 是否已过软过期时间
+    fn is_stale(&self, now_ms: u64) -> bool {
+        now_ms >= self.fresh_until_ms
+    }
+}
+
+/// 当前 Unix 毫秒时间戳
+fn unix_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// 从命令中取出命令名称，用于指标统计
+pub(crate) fn command_name(cmd: &redis::Cmd) -> String {
+    match cmd.args_iter().next() {
+        Some(redis::Arg::Simple(name)) => String::from_utf8_lossy(name).to_ascii_uppercase(),
+        _ => "UNKNOWN".to_string(),
+    }
+}
+
+/// 便利函数：从 URL 创建连接（最常用）
+pub async fn create_redis_connection_from_url(redis_url: &str) -> RedisResult<RedisConnection> {
+    RedisConnection::from_url(redis_url).await
+}
+
+/// 便利函数：从配置对象创建连接
+pub async fn create_redis_connection_from_config(
+    config: RedisConfig,
+) -> RedisResult<RedisConnection> {
+    RedisConnection::new(config).await
+}
+
+/// 便利函数：从 `REDIS_` 前缀的环境变量创建连接，见 [`RedisConfig::from_env`]
+pub async fn create_redis_connection_from_env() -> RedisResult<RedisConnection> {
+    RedisConnection::new(RedisConfig::from_env()?).await
+}
+
+/// 连接统计信息
+#[derive(Debug, Clone)]
+pub struct RedisConnectionStats {
+    /// 最大连接数（RedisConnection 复用单个多路复用连接，RedisPool 为连接池大小）
+    pub max_connections: u32,
+    /// 最小连接数
+    pub min_connections: u32,
+    /// 连接超时（秒），0 表示不限制
+    pub connect_timeout: u64,
+    /// 读取超时（秒），0 表示不限制
+    pub read_timeout: u64,
+    /// 写入超时（秒），0 表示不限制
+    pub write_timeout: u64,
+    /// 重连重试次数
+    pub retry_count: usize,
+    /// 重试延迟因子（毫秒）
+    pub retry_factor_ms: u64,
+    /// 每次重连重试前的等待时间（毫秒）
+    pub retry_delays_ms: Vec<u64>,
+    /// 已执行命令总数
+    pub total_commands: u64,
+    /// 因连接断开触发的重连次数
+    pub total_reconnects: u64,
+    /// 连接池大小（单个 RedisConnection 为 1）
+    pub pool_size: usize,
+    /// 每个连接当前的在途请求数
+    pub in_flight: Vec<usize>,
+}
+
+impl RedisConnectionStats {
+    /// 根据配置与运行时计数构建统计信息
+    pub fn from_config(config: &RedisConfig, total_commands: u64, total_reconnects: u64) -> Self {
+        Self {
+            max_connections: 1,
+            min_connections: 1,
+            connect_timeout: config.connection_timeout_secs,
+            read_timeout: config.response_timeout_secs,
+            write_timeout: config.response_timeout_secs,
+            retry_count: config.retry_count,
+            retry_factor_ms: config.retry_factor_ms,
+            retry_delays_ms: config.retry_delays_ms(),
+            total_commands,
+            total_reconnects,
+            pool_size: 1,
+            in_flight: vec![0],
+        }
+    }
+}
+
+/// Redis 健康状态
+#[derive(Debug, Clone, Serialize)]
+pub struct RedisHealthStatus {
+    /// 是否健康
+    pub is_healthy: bool,
+    /// 最近一次检查的响应时间（毫秒）
+    pub response_time_ms: u64,
+    /// 检查结果说明，失败时为错误信息
+    pub message: String,
+}
+
+/// 为键参数的每一项加上前缀
+fn apply_key_prefix<K: ToRedisArgs>(prefix: Option<&str>, key: K) -> Vec<Vec<u8>> {
+    let args = key.to_redis_args();
+    match prefix {
+        Some(prefix) => args
+            .into_iter()
+            .map(|arg| [prefix.as_bytes(), &arg].concat())
+            .collect(),
+        None => args,
+    }
+}
+
+/// 将结构体拆分为哈希字段，每个字段值均以 JSON 文本保存
+fn struct_to_hash_fields<T: Serialize>(value: &T) -> RedisResult<Vec<(String, String)>> {
+    match serde_json::to_value(value).map_err(|e| RedisError::serialization(e.to_string()))? {
+        serde_json::Value::Object(map) => Ok(map
+            .into_iter()
+            .map(|(field, value)| (field, value.to_string()))
+            .collect()),
+        other => Err(RedisError::serialization(format!(
+            "只有结构体或映射可以保存为哈希，实际为: {}",
+            other
+        ))),
+    }
+}
+
+/// 将以 JSON 文本保存的哈希字段还原为结构体
+fn hash_fields_to_struct<T: DeserializeOwned>(fields: HashMap<String, String>) -> RedisResult<T> {
+    let map = fields
+        .into_iter()
+        .map(|(field, raw)| {
+            serde_json::from_str(&raw)
+                .map(|value| (field.clone(), value))
+                .map_err(|e| RedisError::deserialization(format!("字段 {} 解析失败: {}", field, e)))
+        })
+        .collect::<RedisResult<serde_json::Map<String, serde_json::Value>>>()?;
+
+    serde_json::from_value(serde_json::Value::Object(map))
+        .map_err(|e| RedisError::deserialization(e.to_string()))
+}
+
+/// 获取缓存锁失败后的轮询间隔
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 生成唯一令牌，用于区分不同的持锁方或队列消息
+pub(crate) fn lock_token() -> String {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "{}-{}-{}",
+        std::process::id(),
+        nanos,
+        SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// 读取 TLS 证书文件
+fn read_certificate(path: &str, name: &str) -> RedisResult<Vec<u8>> {
+    std::fs::read(path).map_err(|e| RedisError::config(format!("读取{}失败 {}: {}", name, path, e)))
+}
+
+/// 屏蔽 Redis URL 中的敏感信息
+pub fn mask_redis_url(url: &str) -> String {
+    // 简单地屏蔽可能的密码部分
+    if let Some(at_pos) = url.find('@') {
+        if let Some(colon_pos) = url[..at_pos].rfind(':') {
+            if let Some(slash_pos) = url[..colon_pos].rfind('/') {
+                let before = &url[..slash_pos + 1];
+                let after = &url[at_pos..];
+                return format!("{}***:***{}", before, after);
+            }
+        }
+    }
+    url.to_string()
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::redis::test_support::connect_test_redis;
 
     #[test]
     fn test_mask_redis_url() {
@@ -240,4 +1696,723 @@ mod tests {
         assert!(masked.contains("***"));
         assert!(!masked.contains("password"));
     }
+
+    #[test]
+    fn test_pool_stats_mirror_config() {
+        let config = RedisConfig {
+            url: "redis://localhost:6379".to_string(),
+            database_index: 0,
+            connection_timeout_secs: 10,
+            response_timeout_secs: 3,
+            retry_count: 3,
+            retry_factor_ms: 200,
+            max_retry_delay_ms: 5000,
+            ..RedisConfig::default()
+        };
+
+        let stats = RedisConnectionStats::from_config(&config, 42, 2);
+        assert_eq!(stats.connect_timeout, 10);
+        assert_eq!(stats.read_timeout, 3);
+        assert_eq!(stats.write_timeout, 3);
+        assert_eq!(stats.retry_count, 3);
+        assert_eq!(stats.retry_factor_ms, 200);
+        assert_eq!(stats.retry_delays_ms, vec![400, 800, 1600]);
+        assert_eq!(stats.total_commands, 42);
+        assert_eq!(stats.total_reconnects, 2);
+    }
+
+    #[test]
+    fn test_counters_track_results() {
+        let counters = CommandCounters::default();
+        assert!(counters.track(Ok::<_, redis::RedisError>(1)).is_ok());
+
+        let dropped = redis::RedisError::from(std::io::Error::new(
+            std::io::ErrorKind::BrokenPipe,
+            "broken pipe",
+        ));
+        assert!(counters.track::<()>(Err(dropped)).is_err());
+
+        assert_eq!(counters.commands.load(Ordering::Relaxed), 2);
+        assert_eq!(counters.reconnects.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_observe_timeout_does_not_affect_later_calls() {
+        let counters = CommandCounters::new(&RedisConfig {
+            enable_metrics: true,
+            ..RedisConfig::default()
+        });
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok::<_, redis::RedisError>("late")
+        };
+        let error = counters
+            .observe("BRPOP", None, Some(Duration::from_millis(20)), slow)
+            .await
+            .unwrap_err();
+        assert!(error.is_timeout_error());
+        assert_eq!(error.to_string(), "操作超时: BRPOP");
+
+        let fast = async { Ok::<_, redis::RedisError>("ok") };
+        let value = counters
+            .observe("GET", None, Some(Duration::from_millis(20)), fast)
+            .await
+            .unwrap();
+        assert_eq!(value, "ok");
+
+        let snapshot = counters.metrics.as_ref().unwrap().snapshot();
+        assert_eq!(snapshot.commands["BRPOP"].errors, 1);
+        assert_eq!(snapshot.errors["Timeout"], 1);
+        assert_eq!(counters.commands.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_observe_records_tracing_span() {
+        use crate::redis::KeyLogging;
+        use crate::redis::redis_tracing::test_support::CapturingLayer;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let layer = CapturingLayer::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(layer.clone()));
+        let counters = CommandCounters::new(&RedisConfig {
+            key_logging: KeyLogging::Full,
+            slow_command_threshold_ms: 10,
+            ..RedisConfig::default()
+        });
+
+        let key = counters.tracer.key_field(&[b"svc:user:1".to_vec()]);
+        let fast = async { Ok::<_, redis::RedisError>("ok") };
+        counters.observe("GET", key, None, fast).await.unwrap();
+
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            Ok::<_, redis::RedisError>("ok")
+        };
+        counters.observe("HGETALL", None, None, slow).await.unwrap();
+
+        let spans = layer.spans();
+        let get = spans
+            .iter()
+            .find(|span| span["db.operation"] == "GET")
+            .unwrap();
+        assert_eq!(get["name"], "redis.command");
+        assert_eq!(get["db.system"], "redis");
+        assert_eq!(get["db.redis.key"], "svc:user:1");
+        assert_eq!(get["otel.status_code"], "OK");
+        assert!(get.contains_key("duration_ms"));
+
+        let hgetall = spans
+            .iter()
+            .find(|span| span["db.operation"] == "HGETALL")
+            .unwrap();
+        assert!(!hgetall.contains_key("db.redis.key"));
+
+        let slow_events: Vec<_> = layer
+            .events()
+            .into_iter()
+            .filter(|event| event["level"] == "WARN")
+            .collect();
+        assert_eq!(slow_events.len(), 1);
+        assert_eq!(slow_events[0]["db.operation"], "HGETALL");
+        assert_eq!(slow_events[0]["threshold_ms"], "10");
+    }
+
+    #[tokio::test]
+    async fn test_with_timeout_live() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:timeout:empty";
+        conn.del(key).await.unwrap();
+
+        let mut limited = conn.with_timeout(Duration::from_millis(100));
+        assert_eq!(limited.command_timeout(), Some(Duration::from_millis(100)));
+        assert_eq!(conn.command_timeout(), None);
+
+        let error = limited
+            .blpop(key, Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert!(error.is_timeout_error());
+
+        // 超时后同一连接上的命令仍然正常
+        limited.set_builtin(key, "ok").await.unwrap();
+        assert_eq!(
+            limited.get_builtin(key).await.unwrap(),
+            Some("ok".to_string())
+        );
+        conn.del(key).await.unwrap();
+    }
+
+    #[test]
+    fn test_counters_publish_connection_events() {
+        use crate::redis::ConnectionEventKind;
+
+        let counters = CommandCounters::default();
+        let mut events = counters.events.subscribe();
+
+        let dropped = redis::RedisError::from(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "connection reset",
+        ));
+        assert!(counters.track::<()>(Err(dropped)).is_err());
+        assert!(counters.track(Ok::<_, redis::RedisError>(())).is_ok());
+
+        match events.try_recv().unwrap().kind {
+            ConnectionEventKind::Disconnected { error } => {
+                assert!(error.contains("connection reset"))
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert_eq!(
+            events.try_recv().unwrap().kind,
+            ConnectionEventKind::Reconnected
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_lock_token_unique() {
+        assert_ne!(lock_token(), lock_token());
+    }
+
+    #[test]
+    fn test_apply_key_prefix() {
+        assert_eq!(apply_key_prefix(None, "user:1"), vec![b"user:1".to_vec()]);
+        assert_eq!(
+            apply_key_prefix(Some("svc:prod:"), "user:1"),
+            vec![b"svc:prod:user:1".to_vec()]
+        );
+        assert_eq!(
+            apply_key_prefix(Some("svc:"), &["a", "b"][..]),
+            vec![b"svc:a".to_vec(), b"svc:b".to_vec()]
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct UserProfile {
+        id: u64,
+        username: String,
+        email: String,
+        age: u32,
+        score: f64,
+        active: bool,
+        city: String,
+        country: String,
+        tags: Vec<String>,
+        nickname: Option<String>,
+    }
+
+    fn sample_profile() -> UserProfile {
+        UserProfile {
+            id: 1001,
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            age: 30,
+            score: 98.5,
+            active: true,
+            city: "Hangzhou".to_string(),
+            country: "CN".to_string(),
+            tags: vec!["vip".to_string(), "beta".to_string()],
+            nickname: None,
+        }
+    }
+
+    #[test]
+    fn test_struct_hash_fields_round_trip() {
+        let profile = sample_profile();
+        let fields = struct_to_hash_fields(&profile).unwrap();
+        assert_eq!(fields.len(), 10);
+        assert!(fields.contains(&("username".to_string(), "\"alice\"".to_string())));
+        assert!(struct_to_hash_fields(&42).is_err());
+
+        let restored: UserProfile = hash_fields_to_struct(fields.into_iter().collect()).unwrap();
+        assert_eq!(restored, profile);
+    }
+
+    #[tokio::test]
+    async fn test_hash_bulk_round_trip() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:hash:profile";
+        conn.del(key).await.unwrap();
+
+        let profile = sample_profile();
+        let fields: Vec<(String, String)> = vec![
+            ("id".to_string(), profile.id.to_string()),
+            ("username".to_string(), profile.username.clone()),
+            ("email".to_string(), profile.email.clone()),
+            ("age".to_string(), profile.age.to_string()),
+            ("score".to_string(), profile.score.to_string()),
+            ("active".to_string(), profile.active.to_string()),
+            ("city".to_string(), profile.city.clone()),
+            ("country".to_string(), profile.country.clone()),
+            ("tags".to_string(), profile.tags.join(",")),
+            ("nickname".to_string(), String::new()),
+        ];
+        conn.hset_multiple(key, &fields).await.unwrap();
+
+        let stored = conn.hgetall(key).await.unwrap();
+        assert_eq!(stored, fields.iter().cloned().collect::<HashMap<_, _>>());
+        assert_eq!(conn.hlen(key).await.unwrap(), 10);
+        assert_eq!(conn.hkeys(key).await.unwrap().len(), 10);
+        assert_eq!(conn.hvals(key).await.unwrap().len(), 10);
+
+        assert_eq!(conn.hdel(key, "nickname").await.unwrap(), 1);
+        assert_eq!(conn.hdel(key, &["city", "country"][..]).await.unwrap(), 2);
+        assert_eq!(conn.hlen(key).await.unwrap(), 7);
+
+        // 结构体按字段 JSON 存储
+        conn.del(key).await.unwrap();
+        conn.hset_json_multiple(key, &profile).await.unwrap();
+        let restored: Option<UserProfile> = conn.hgetall_json(key).await.unwrap();
+        assert_eq!(restored, Some(profile));
+        let tags: Option<Vec<String>> = conn.hget_json(key, "tags").await.unwrap();
+        assert_eq!(tags, Some(vec!["vip".to_string(), "beta".to_string()]));
+
+        conn.del(key).await.unwrap();
+        let missing: Option<UserProfile> = conn.hgetall_json(key).await.unwrap();
+        assert_eq!(missing, None);
+    }
+
+    #[tokio::test]
+    async fn test_brpop_wakes_on_push() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let mut conn = conn.with_prefix("clamber:test:queue:");
+        let key = "jobs";
+        conn.del(key).await.unwrap();
+
+        // 空列表超时返回 None
+        assert_eq!(conn.brpop(key, Duration::from_secs(1)).await.unwrap(), None);
+
+        let mut consumer = conn.clone();
+        let waiter = tokio::spawn(async move { consumer.brpop(key, Duration::from_secs(5)).await });
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        conn.rpush(key, "job-1").await.unwrap();
+
+        let popped = waiter.await.unwrap().unwrap();
+        assert_eq!(popped, Some(("jobs".to_string(), "job-1".to_string())));
+
+        conn.rpush(key, "a").await.unwrap();
+        conn.rpush(key, "b").await.unwrap();
+        conn.rpush(key, "a").await.unwrap();
+        assert_eq!(conn.llen(key).await.unwrap(), 3);
+        assert_eq!(conn.lrange(key, 0, -1).await.unwrap(), vec!["a", "b", "a"]);
+        assert_eq!(conn.lrem(key, 0, "a").await.unwrap(), 2);
+        assert_eq!(
+            conn.blpop(key, Duration::from_secs(1)).await.unwrap(),
+            Some(("jobs".to_string(), "b".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blpop_does_not_block_shared_connection() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let mut conn = conn.with_prefix("clamber:test:blocking:");
+        let key = "jobs";
+        conn.del(key).await.unwrap();
+
+        let mut consumer = conn.clone();
+        let waiter = tokio::spawn(async move { consumer.blpop(key, Duration::from_secs(5)).await });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // 消费者阻塞期间，共享连接上的命令仍然立即返回
+        let started = Instant::now();
+        conn.ping().await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(!waiter.is_finished());
+
+        conn.rpush(key, "job-1").await.unwrap();
+        let popped = waiter.await.unwrap().unwrap();
+        assert_eq!(popped, Some(("jobs".to_string(), "job-1".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_rpush_llen_lrange_order() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:list:order";
+        conn.del(key).await.unwrap();
+
+        for item in ["first", "second", "third", "fourth"] {
+            conn.rpush(key, item).await.unwrap();
+        }
+        assert_eq!(conn.llen(key).await.unwrap(), 4);
+        assert_eq!(
+            conn.lrange(key, 0, -1).await.unwrap(),
+            vec!["first", "second", "third", "fourth"]
+        );
+        assert_eq!(
+            conn.lrange(key, 1, 2).await.unwrap(),
+            vec!["second", "third"]
+        );
+
+        conn.del(key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_key_prefix_isolation() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let mut orders = conn.with_prefix("clamber:test:orders:");
+        let mut billing = conn.with_prefix("clamber:test:billing:");
+        assert_eq!(orders.key_prefix(), Some("clamber:test:orders:"));
+
+        orders.set_builtin("item:1", "book").await.unwrap();
+        billing.set_builtin("item:2", "invoice").await.unwrap();
+
+        assert_eq!(
+            orders.get_builtin("item:1").await.unwrap(),
+            Some("book".to_string())
+        );
+        assert!(!billing.exists_builtin("item:1").await.unwrap());
+        assert!(!orders.exists_builtin("item:2").await.unwrap());
+
+        assert_eq!(orders.scan_match("item:*").await.unwrap(), vec!["item:1"]);
+        assert_eq!(billing.scan_match("item:*").await.unwrap(), vec!["item:2"]);
+
+        assert_eq!(orders.del("item:1").await.unwrap(), 1);
+        assert_eq!(billing.del("item:2").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_set_json_loader_runs_once() {
+        use std::sync::atomic::AtomicUsize;
+
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:cache:get_or_set";
+        let _: () = conn.query(redis::cmd("DEL").arg(key)).await.unwrap();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut handles = Vec::new();
+        for _ in 0..8 {
+            let mut conn = conn.clone();
+            let calls = calls.clone();
+            handles.push(tokio::spawn(async move {
+                conn.get_or_set_json_with_lock(
+                    key,
+                    Duration::from_secs(30),
+                    Duration::from_secs(5),
+                    || async move {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                        vec![1u32, 2, 3]
+                    },
+                )
+                .await
+                .unwrap()
+            }));
+        }
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), vec![1, 2, 3]);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 缓存命中时不执行 loader
+        let value: Vec<u32> = conn
+            .get_or_set_json(key, Duration::from_secs(30), || async {
+                panic!("缓存命中时不应执行 loader")
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_stale_entry_freshness() {
+        let entry = StaleEntry {
+            value: 1,
+            fresh_until_ms: 1_000,
+        };
+        assert!(!entry.is_stale(999));
+        assert!(entry.is_stale(1_000));
+    }
+
+    #[tokio::test]
+    async fn test_get_or_set_json_swr_single_refresh() {
+        use std::sync::atomic::AtomicUsize;
+
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        // 软过期 400ms，硬过期 2s
+        let config = RedisConfig {
+            stale_ttl_ratio: 0.2,
+            ..conn.get_config().clone()
+        };
+        let Ok(mut conn) = RedisConnection::new(config).await else {
+            return;
+        };
+        let key = "clamber:test:cache:swr";
+        let ttl = Duration::from_secs(2);
+        conn.del(&[key.to_string(), format!("{}:refresh", key)])
+            .await
+            .unwrap();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let loader = |calls: Arc<AtomicUsize>| {
+            move || async move {
+                let version = calls.fetch_add(1, Ordering::SeqCst) as u32 + 1;
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                version
+            }
+        };
+
+        // 未命中时同步加载
+        let first: u32 = conn
+            .get_or_set_json_swr(key, ttl, loader(calls.clone()))
+            .await
+            .unwrap();
+        assert_eq!(first, 1);
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // 软过期后并发读取：全部立即拿到旧值，只触发一次后台刷新
+        let mut handles = Vec::new();
+        for _ in 0..8 {
+            let mut conn = conn.clone();
+            let loader = loader(calls.clone());
+            handles.push(tokio::spawn(async move {
+                let started = Instant::now();
+                let value: u32 = conn.get_or_set_json_swr(key, ttl, loader).await.unwrap();
+                (value, started.elapsed())
+            }));
+        }
+        for handle in handles {
+            let (value, elapsed) = handle.await.unwrap();
+            assert_eq!(value, 1);
+            assert!(elapsed < Duration::from_millis(300), "{:?}", elapsed);
+        }
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        let refreshed: u32 = conn
+            .get_or_set_json_swr(key, ttl, || async { panic!("刷新后不应再次加载") })
+            .await
+            .unwrap();
+        assert_eq!(refreshed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_pfcount_within_error_margin() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let (day1, day2, week) = (
+            "clamber:test:uv:day1",
+            "clamber:test:uv:day2",
+            "clamber:test:uv:week",
+        );
+        conn.del(&[day1, day2, week]).await.unwrap();
+
+        let visitors: Vec<String> = (0..1000).map(|i| format!("user-{}", i)).collect();
+        assert!(conn.pfadd(day1, &visitors[..600]).await.unwrap());
+        assert!(conn.pfadd(day2, &visitors[400..]).await.unwrap());
+        // 重复添加不会改变基数
+        assert!(!conn.pfadd(day1, &visitors[..10]).await.unwrap());
+
+        // HyperLogLog 存在约 0.81% 的标准误差，这里允许 3%
+        let within = |count: u64, expected: f64| (count as f64 - expected).abs() <= expected * 0.03;
+        assert!(within(conn.pfcount(day1).await.unwrap(), 600.0));
+        assert!(within(conn.pfcount(&[day1, day2]).await.unwrap(), 1000.0));
+
+        conn.pfmerge(week, &[day1, day2]).await.unwrap();
+        assert!(within(conn.pfcount(week).await.unwrap(), 1000.0));
+
+        conn.del(&[day1, day2, week]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zset_leaderboard_order() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:zset:leaderboard";
+        let _: () = conn.query(redis::cmd("DEL").arg(key)).await.unwrap();
+
+        conn.zadd(key, "alice", 10.0).await.unwrap();
+        conn.zadd(key, "bob", 30.0).await.unwrap();
+        conn.zadd(key, "carol", 20.0).await.unwrap();
+
+        let top = conn.zrevrange_withscores(key, 0, -1).await.unwrap();
+        assert_eq!(
+            top,
+            vec![
+                ("bob".to_string(), 30.0),
+                ("carol".to_string(), 20.0),
+                ("alice".to_string(), 10.0),
+            ]
+        );
+
+        let asc = conn.zrange_withscores(key, 0, 0).await.unwrap();
+        assert_eq!(asc, vec![("alice".to_string(), 10.0)]);
+        assert_eq!(conn.zscore(key, "carol").await.unwrap(), Some(20.0));
+        assert_eq!(conn.zrank(key, "bob").await.unwrap(), Some(2));
+        assert_eq!(conn.zrank(key, "nobody").await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_command_name() {
+        assert_eq!(command_name(redis::cmd("set").arg("k").arg("v")), "SET");
+        assert_eq!(command_name(&redis::Cmd::new()), "UNKNOWN");
+    }
+
+    #[tokio::test]
+    async fn test_metrics_snapshot_counts_commands() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let config = RedisConfig {
+            enable_metrics: true,
+            ..conn.get_config().clone()
+        };
+        let mut conn = RedisConnection::new(config).await.unwrap();
+        let key = "clamber:test:metrics";
+
+        conn.set_builtin(key, "1").await.unwrap();
+        conn.set_builtin(key, "2").await.unwrap();
+        conn.set_builtin(key, "3").await.unwrap();
+        conn.get_builtin(key).await.unwrap();
+        conn.get_builtin(key).await.unwrap();
+        // 对字符串键执行哈希命令，触发 WRONGTYPE 错误
+        assert!(conn.hget(key, "field").await.is_err());
+        conn.del(key).await.unwrap();
+
+        let snapshot = conn.get_metrics();
+        assert_eq!(snapshot.command_count("SET"), 3);
+        assert_eq!(snapshot.command_count("GET"), 2);
+        assert_eq!(snapshot.command_count("HGET"), 1);
+        assert_eq!(snapshot.command_count("DEL"), 1);
+        assert_eq!(snapshot.commands["HGET"].errors, 1);
+        assert_eq!(snapshot.errors["Redis"], 1);
+        // WRONGTYPE 后会额外执行一次 TYPE 查询实际类型
+        assert_eq!(snapshot.command_count("TYPE"), 1);
+        assert_eq!(snapshot.total_commands, 8);
+        assert_eq!(snapshot.commands["SET"].latency.count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_atomic_string_primitives() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:atomic";
+        conn.del(key).await.unwrap();
+
+        // SET XX 对不存在的键不生效
+        assert!(!conn.set_xx(key, "x").await.unwrap());
+        assert!(conn.set_nx(key, "first").await.unwrap());
+        assert!(!conn.set_nx(key, "second").await.unwrap());
+        assert_eq!(
+            conn.get_builtin(key).await.unwrap(),
+            Some("first".to_string())
+        );
+
+        assert!(conn.set_xx(key, "updated").await.unwrap());
+        assert_eq!(
+            conn.getset(key, "swapped").await.unwrap(),
+            Some("updated".to_string())
+        );
+
+        assert_eq!(conn.getdel(key).await.unwrap(), Some("swapped".to_string()));
+        assert!(!conn.exists_builtin(key).await.unwrap());
+        assert_eq!(conn.getdel(key).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_atomic_json_primitives() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:atomic:json";
+        conn.del(key).await.unwrap();
+
+        let token = vec!["one-shot".to_string()];
+        assert!(conn.set_nx_json(key, &token).await.unwrap());
+        assert!(!conn.set_nx_json(key, &token).await.unwrap());
+
+        let replaced = vec!["replaced".to_string()];
+        assert_eq!(conn.getset_json(key, &replaced).await.unwrap(), Some(token));
+        assert!(conn.set_xx_json(key, &replaced).await.unwrap());
+        assert_eq!(
+            conn.getdel_json::<Vec<String>>(key).await.unwrap(),
+            Some(replaced)
+        );
+        assert!(!conn.exists_builtin(key).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_absolute_expiry() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:expire_at";
+        conn.set_builtin(key, "until-soon").await.unwrap();
+        assert_eq!(conn.ttl_remaining(key).await.unwrap(), None);
+
+        let at = Utc::now() + chrono::Duration::seconds(2);
+        assert!(conn.pexpire_at(key, at).await.unwrap());
+        let ttl = conn.ttl_remaining(key).await.unwrap().unwrap();
+        assert!(ttl > chrono::Duration::zero() && ttl <= chrono::Duration::seconds(2));
+
+        tokio::time::sleep(Duration::from_millis(2200)).await;
+        assert!(!conn.exists_builtin(key).await.unwrap());
+        assert_eq!(conn.ttl_remaining(key).await.unwrap(), None);
+        assert!(!conn.expire_at(key, at).await.unwrap());
+
+        // 过去的时间点会立即删除键
+        conn.set_builtin(key, "stale").await.unwrap();
+        let past = Utc::now() - chrono::Duration::seconds(10);
+        assert!(conn.expire_at(key, past).await.unwrap());
+        assert!(!conn.exists_builtin(key).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_binary_values_round_trip() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:bytes";
+        let list_key = "clamber:test:bytes:list";
+        let hash_key = "clamber:test:bytes:hash";
+        conn.del(&[key, list_key, hash_key]).await.unwrap();
+
+        // 包含 0x00 与非法 UTF-8 字节 0xFF
+        let blob: Vec<u8> = vec![0x00, 0xFF, 0x10, 0x00, 0xFE, 0xFF];
+        conn.set_bytes(key, &blob).await.unwrap();
+        assert_eq!(conn.get_bytes(key).await.unwrap(), Some(blob.clone()));
+
+        // 以压缩头部魔数开头的原始值不会被误认为压缩值
+        let magic_blob: Vec<u8> = vec![0xC1, 0x5A, 0x07, 0x00, 0xFF];
+        conn.set_bytes(key, &magic_blob).await.unwrap();
+        assert_eq!(conn.get_bytes(key).await.unwrap(), Some(magic_blob));
+        assert_eq!(
+            conn.get_bytes("clamber:test:bytes:missing").await.unwrap(),
+            None
+        );
+
+        conn.rpush(list_key, blob.as_slice()).await.unwrap();
+        assert_eq!(conn.rpop_bytes(list_key).await.unwrap(), Some(blob.clone()));
+
+        conn.hset(hash_key, "payload", blob.as_slice())
+            .await
+            .unwrap();
+        assert_eq!(
+            conn.hget_bytes(hash_key, "payload").await.unwrap(),
+            Some(blob)
+        );
+
+        conn.del(&[key, list_key, hash_key]).await.unwrap();
+    }
 }
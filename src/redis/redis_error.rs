@@ -43,6 +43,10 @@ pub enum RedisError {
     #[error("操作超时: {operation}")]
     Timeout { operation: String },
 
+    /// 事务被中止（WATCH 的键被并发修改）
+    #[error("事务已中止: {message}")]
+    TransactionAborted { message: String },
+
     /// 核心库错误
     #[error("核心库错误: {0}")]
     Core(#[from] clamber_core::ClamberError),
@@ -104,6 +108,30 @@ impl RedisError {
         }
     }
 
+    /// 创建事务中止错误
+    pub fn transaction_aborted(message: impl Into<String>) -> Self {
+        Self::TransactionAborted {
+            message: message.into(),
+        }
+    }
+
+    /// 错误类型名称，用于指标统计
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            RedisError::Redis(_) => "Redis",
+            RedisError::Connection { .. } => "Connection",
+            RedisError::Config { .. } => "Config",
+            RedisError::Pool { .. } => "Pool",
+            RedisError::Serialization { .. } => "Serialization",
+            RedisError::Deserialization { .. } => "Deserialization",
+            RedisError::KeyNotFound { .. } => "KeyNotFound",
+            RedisError::TypeMismatch { .. } => "TypeMismatch",
+            RedisError::Timeout { .. } => "Timeout",
+            RedisError::TransactionAborted { .. } => "TransactionAborted",
+            RedisError::Core(_) => "Core",
+        }
+    }
+
     /// 判断是否为连接错误
     pub fn is_connection_error(&self) -> bool {
         matches!(self, RedisError::Connection { .. } | RedisError::Redis(_))
@@ -136,6 +164,21 @@ impl RedisError {
     pub fn is_timeout_error(&self) -> bool {
         matches!(self, RedisError::Timeout { .. })
     }
+
+    /// 判断是否为事务中止错误
+    pub fn is_transaction_aborted(&self) -> bool {
+        matches!(self, RedisError::TransactionAborted { .. })
+    }
+
+    /// 判断是否为服务端返回的 WRONGTYPE 错误（对键执行了不匹配其类型的命令）
+    pub fn is_wrong_type(&self) -> bool {
+        match self {
+            RedisError::Redis(e) => {
+                e.code() == Some("WRONGTYPE") || e.to_string().contains("WRONGTYPE")
+            }
+            _ => false,
+        }
+    }
 }
 
 /// Redis 操作结果类型
@@ -165,6 +208,16 @@ mod tests {
         assert_eq!(error.to_string(), "类型转换错误: expected string, got hash");
     }
 
+    #[test]
+    fn test_wrong_type() {
+        let error = RedisError::from(redis::RedisError::from((
+            redis::ErrorKind::TypeError,
+            "WRONGTYPE Operation against a key holding the wrong kind of value",
+        )));
+        assert!(error.is_wrong_type());
+        assert!(!RedisError::type_mismatch("hash", "string").is_wrong_type());
+    }
+
     #[test]
     fn test_timeout() {
         let error = RedisError::timeout("GET operation");
@@ -178,4 +231,12 @@ mod tests {
         assert!(error.is_serialization_error());
         assert_eq!(error.to_string(), "序列化错误: JSON parsing failed");
     }
+
+    #[test]
+    fn test_transaction_aborted() {
+        let error = RedisError::transaction_aborted("counter");
+        assert!(error.is_transaction_aborted());
+        assert_eq!(error.kind_name(), "TransactionAborted");
+        assert_eq!(error.to_string(), "事务已中止: counter");
+    }
 }
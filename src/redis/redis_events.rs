@@ -0,0 +1,149 @@
+//! Redis 连接事件模块
+//!
+//! 通过 `tokio::sync::broadcast` 通道发布连接生命周期事件，应用可以订阅后记录日志，
+//! 或在故障切换后重新预热缓存。断开事件只在连接状态发生变化时发布一次，
+//! 之后第一条执行成功的命令会发布重连事件
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::broadcast;
+
+/// 事件通道容量，订阅方处理过慢时会丢失最早的事件
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// 连接事件类型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConnectionEventKind {
+    /// 命令因连接断开或 IO 错误失败，连接管理器将在后台重连
+    Disconnected {
+        /// 触发断开的错误信息
+        error: String,
+    },
+    /// 断开后命令再次执行成功
+    Reconnected,
+    /// 连接被主动关闭
+    Closed,
+}
+
+/// 连接事件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConnectionEvent {
+    /// 事件类型
+    #[serde(flatten)]
+    pub kind: ConnectionEventKind,
+    /// 事件发生时间
+    pub timestamp: DateTime<Utc>,
+}
+
+/// 连接事件源，克隆的连接共享同一个事件源
+#[derive(Debug)]
+pub(crate) struct ConnectionEvents {
+    sender: broadcast::Sender<ConnectionEvent>,
+    /// 当前是否处于断开状态
+    disconnected: AtomicBool,
+}
+
+impl Default for ConnectionEvents {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            disconnected: AtomicBool::new(false),
+        }
+    }
+}
+
+impl ConnectionEvents {
+    /// 订阅后续发布的事件
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 记录一次连接断开，已处于断开状态时不重复发布
+    pub(crate) fn disconnected(&self, error: &impl ToString) {
+        if !self.disconnected.swap(true, Ordering::AcqRel) {
+            self.publish(ConnectionEventKind::Disconnected {
+                error: error.to_string(),
+            });
+        }
+    }
+
+    /// 记录一次命令成功，此前处于断开状态时发布重连事件
+    pub(crate) fn succeeded(&self) {
+        if self.disconnected.load(Ordering::Acquire)
+            && self.disconnected.swap(false, Ordering::AcqRel)
+        {
+            self.publish(ConnectionEventKind::Reconnected);
+        }
+    }
+
+    /// 记录连接关闭
+    pub(crate) fn closed(&self) {
+        self.publish(ConnectionEventKind::Closed);
+    }
+
+    /// 发布事件，没有订阅方时直接丢弃
+    fn publish(&self, kind: ConnectionEventKind) {
+        let _ = self.sender.send(ConnectionEvent {
+            kind,
+            timestamp: Utc::now(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribers_receive_ordered_events() {
+        let events = ConnectionEvents::default();
+        let mut first = events.subscribe();
+        let mut second = events.subscribe();
+
+        // 未断开时的成功命令不产生事件
+        events.succeeded();
+        events.disconnected(&"broken pipe");
+        // 重复的断开错误只发布一次
+        events.disconnected(&"connection refused");
+        events.succeeded();
+        events.succeeded();
+        events.closed();
+
+        for receiver in [&mut first, &mut second] {
+            let received: Vec<ConnectionEvent> =
+                std::iter::from_fn(|| receiver.try_recv().ok()).collect();
+            let kinds: Vec<_> = received.iter().map(|event| event.kind.clone()).collect();
+            assert_eq!(
+                kinds,
+                vec![
+                    ConnectionEventKind::Disconnected {
+                        error: "broken pipe".to_string()
+                    },
+                    ConnectionEventKind::Reconnected,
+                    ConnectionEventKind::Closed,
+                ]
+            );
+            assert!(
+                received
+                    .windows(2)
+                    .all(|pair| pair[0].timestamp <= pair[1].timestamp)
+            );
+        }
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = ConnectionEvent {
+            kind: ConnectionEventKind::Disconnected {
+                error: "timeout".to_string(),
+            },
+            timestamp: Utc::now(),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "disconnected");
+        assert_eq!(json["error"], "timeout");
+        assert!(json["timestamp"].is_string());
+    }
+}
@@ -0,0 +1,218 @@
+//! Redis GEO 模块
+//!
+//! 提供地理位置的写入、距离计算和半径查询（GEOSEARCH），查询结果转换为 [`GeoMember`]
+
+use crate::redis::{RedisConnection, RedisResult};
+use redis::ToRedisArgs;
+use serde::{Deserialize, Serialize};
+
+/// 距离单位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeoUnit {
+    /// 米
+    #[default]
+    Meters,
+    /// 千米
+    Kilometers,
+    /// 英里
+    Miles,
+    /// 英尺
+    Feet,
+}
+
+impl GeoUnit {
+    /// Redis 命令中使用的单位参数
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            GeoUnit::Meters => "m",
+            GeoUnit::Kilometers => "km",
+            GeoUnit::Miles => "mi",
+            GeoUnit::Feet => "ft",
+        }
+    }
+}
+
+/// 经纬度坐标
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoCoordinates {
+    /// 经度
+    pub longitude: f64,
+    /// 纬度
+    pub latitude: f64,
+}
+
+/// GEO 查询结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoMember {
+    /// 成员名称
+    pub member: String,
+    /// 与查询中心的距离，单位与查询时指定的单位一致
+    pub distance: f64,
+    /// 成员坐标
+    pub coordinates: GeoCoordinates,
+}
+
+impl RedisConnection {
+    /// GEO：添加成员坐标，`members` 为 `(经度, 纬度, 成员)`，返回新增成员数量
+    pub async fn geoadd<K, M>(&mut self, key: K, members: &[(f64, f64, M)]) -> RedisResult<i64>
+    where
+        K: ToRedisArgs,
+        M: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("GEOADD");
+        cmd.arg(self.prefixed(key));
+        for (longitude, latitude, member) in members {
+            cmd.arg(longitude).arg(latitude).arg(member);
+        }
+        self.query(&cmd).await
+    }
+
+    /// GEO：计算两个成员之间的距离，任一成员不存在时返回 None
+    pub async fn geodist<K, M1, M2>(
+        &mut self,
+        key: K,
+        member1: M1,
+        member2: M2,
+        unit: GeoUnit,
+    ) -> RedisResult<Option<f64>>
+    where
+        K: ToRedisArgs,
+        M1: ToRedisArgs,
+        M2: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("GEODIST");
+        cmd.arg(self.prefixed(key))
+            .arg(member1)
+            .arg(member2)
+            .arg(unit.as_arg());
+        self.query(&cmd).await
+    }
+
+    /// GEO：查询以指定成员为中心、半径范围内的成员，按距离由近到远排序
+    pub async fn geosearch_by_member<K, M>(
+        &mut self,
+        key: K,
+        member: M,
+        radius: f64,
+        unit: GeoUnit,
+    ) -> RedisResult<Vec<GeoMember>>
+    where
+        K: ToRedisArgs,
+        M: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("GEOSEARCH");
+        cmd.arg(self.prefixed(key)).arg("FROMMEMBER").arg(member);
+        self.geosearch(cmd, radius, unit).await
+    }
+
+    /// GEO：查询以指定经纬度为中心、半径范围内的成员，按距离由近到远排序
+    pub async fn geosearch_by_lonlat<K>(
+        &mut self,
+        key: K,
+        longitude: f64,
+        latitude: f64,
+        radius: f64,
+        unit: GeoUnit,
+    ) -> RedisResult<Vec<GeoMember>>
+    where
+        K: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("GEOSEARCH");
+        cmd.arg(self.prefixed(key))
+            .arg("FROMLONLAT")
+            .arg(longitude)
+            .arg(latitude);
+        self.geosearch(cmd, radius, unit).await
+    }
+
+    /// 追加半径、排序及返回字段参数并执行 GEOSEARCH
+    async fn geosearch(
+        &mut self,
+        mut cmd: redis::Cmd,
+        radius: f64,
+        unit: GeoUnit,
+    ) -> RedisResult<Vec<GeoMember>> {
+        cmd.arg("BYRADIUS")
+            .arg(radius)
+            .arg(unit.as_arg())
+            .arg("ASC")
+            .arg("WITHCOORD")
+            .arg("WITHDIST");
+        let rows: Vec<(String, f64, (f64, f64))> = self.query(&cmd).await?;
+        Ok(rows
+            .into_iter()
+            .map(|(member, distance, (longitude, latitude))| GeoMember {
+                member,
+                distance,
+                coordinates: GeoCoordinates {
+                    longitude,
+                    latitude,
+                },
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+
+    #[test]
+    fn test_unit_args() {
+        assert_eq!(GeoUnit::default().as_arg(), "m");
+        assert_eq!(GeoUnit::Kilometers.as_arg(), "km");
+        assert_eq!(GeoUnit::Miles.as_arg(), "mi");
+        assert_eq!(GeoUnit::Feet.as_arg(), "ft");
+    }
+
+    #[tokio::test]
+    async fn test_geosearch_within_radius() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:geo:stores";
+        conn.del(key).await.unwrap();
+
+        let stores = [
+            (116.397_128, 39.916_527, "tiananmen"),
+            (116.403_963, 39.915_119, "wangfujing"),
+            (116.326_836, 39.984_094, "zhongguancun"),
+            (121.473_701, 31.230_416, "shanghai"),
+        ];
+        assert_eq!(conn.geoadd(key, &stores).await.unwrap(), 4);
+
+        let distance = conn
+            .geodist(key, "tiananmen", "shanghai", GeoUnit::Kilometers)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!((1000.0..1100.0).contains(&distance));
+        assert_eq!(
+            conn.geodist(key, "tiananmen", "missing", GeoUnit::Meters)
+                .await
+                .unwrap(),
+            None
+        );
+
+        let nearby = conn
+            .geosearch_by_member(key, "tiananmen", 2.0, GeoUnit::Kilometers)
+            .await
+            .unwrap();
+        let members: Vec<_> = nearby.iter().map(|m| m.member.as_str()).collect();
+        assert_eq!(members, ["tiananmen", "wangfujing"]);
+        assert_eq!(nearby[0].distance, 0.0);
+        assert!((nearby[0].coordinates.longitude - 116.397_128).abs() < 1e-4);
+
+        let around = conn
+            .geosearch_by_lonlat(key, 116.36, 39.95, 15.0, GeoUnit::Kilometers)
+            .await
+            .unwrap();
+        assert_eq!(around.len(), 3);
+        assert!(around.iter().all(|m| m.member != "shanghai"));
+        assert!(around.windows(2).all(|w| w[0].distance <= w[1].distance));
+
+        conn.del(key).await.unwrap();
+    }
+}
@@ -0,0 +1,201 @@
+//! Redis 健康监控模块
+//!
+//! 在后台定期 PING Redis，并通过 `tokio::sync::watch` 通道发布最新的健康状态，
+//! 就绪探针等场景可以直接读取状态，无需在每个请求中执行 PING。
+//! 连续失败达到阈值后才标记为不健康，避免网络抖动导致状态频繁切换
+
+use crate::redis::{RedisConnection, RedisHealthStatus};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// 健康监控配置
+#[derive(Debug, Clone)]
+pub struct RedisHealthMonitorConfig {
+    /// 检查间隔
+    pub interval: Duration,
+    /// 连续失败多少次后标记为不健康
+    pub failure_threshold: u32,
+}
+
+impl Default for RedisHealthMonitorConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            failure_threshold: 3,
+        }
+    }
+}
+
+/// Redis 健康监控
+///
+/// 通过 [`RedisHealthMonitor::start`] 启动后台任务，监控对象被丢弃时后台任务随之停止
+pub struct RedisHealthMonitor {
+    receiver: watch::Receiver<RedisHealthStatus>,
+    task: JoinHandle<()>,
+}
+
+impl RedisHealthMonitor {
+    /// 启动后台健康检查任务，首次检查立即执行
+    pub fn start(mut connection: RedisConnection, config: RedisHealthMonitorConfig) -> Self {
+        let (sender, receiver) = watch::channel(RedisHealthStatus {
+            is_healthy: false,
+            response_time_ms: 0,
+            message: "尚未完成首次检查".to_string(),
+        });
+
+        let task = tokio::spawn(async move {
+            let mut tracker = HealthTracker::new(config.failure_threshold);
+            let mut ticker = tokio::time::interval(config.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                let status = tracker.observe(connection.health_check().await);
+                // 所有接收端都已关闭时没有必要继续检查
+                if sender.send(status).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { receiver, task }
+    }
+
+    /// 订阅健康状态，可放入 axum 的应用状态中供就绪探针读取
+    pub fn subscribe(&self) -> watch::Receiver<RedisHealthStatus> {
+        self.receiver.clone()
+    }
+
+    /// 获取最新的健康状态
+    pub fn status(&self) -> RedisHealthStatus {
+        self.receiver.borrow().clone()
+    }
+
+    /// Redis 当前是否健康
+    pub fn is_healthy(&self) -> bool {
+        self.receiver.borrow().is_healthy
+    }
+}
+
+impl Drop for RedisHealthMonitor {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 健康状态去抖：连续失败达到阈值才判定为不健康，一次成功即恢复
+#[derive(Debug)]
+struct HealthTracker {
+    failure_threshold: u32,
+    consecutive_failures: u32,
+    healthy: Option<bool>,
+}
+
+impl HealthTracker {
+    fn new(failure_threshold: u32) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            consecutive_failures: 0,
+            healthy: None,
+        }
+    }
+
+    /// 记录一次检查结果，返回去抖后的状态，状态变化时输出日志
+    fn observe(&mut self, mut status: RedisHealthStatus) -> RedisHealthStatus {
+        let healthy = if status.is_healthy {
+            self.consecutive_failures = 0;
+            true
+        } else {
+            self.consecutive_failures += 1;
+            // 首次检查失败时直接判定为不健康，之后需要连续失败达到阈值
+            match self.healthy {
+                Some(true) => self.consecutive_failures < self.failure_threshold,
+                _ => false,
+            }
+        };
+
+        match (self.healthy, healthy) {
+            (Some(false) | None, true) => info!("Redis 健康状态: 健康"),
+            (Some(true) | None, false) => {
+                warn!("Redis 健康状态: 不健康（{}）", status.message)
+            }
+            _ => {}
+        }
+
+        self.healthy = Some(healthy);
+        status.is_healthy = healthy;
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+
+    fn check(is_healthy: bool) -> RedisHealthStatus {
+        RedisHealthStatus {
+            is_healthy,
+            response_time_ms: 1,
+            message: if is_healthy {
+                "PONG"
+            } else {
+                "connection refused"
+            }
+            .to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tracker_debounces_failures() {
+        let mut tracker = HealthTracker::new(3);
+        assert!(tracker.observe(check(true)).is_healthy);
+
+        // 未达到阈值前保持健康，失败信息仍然透出
+        let status = tracker.observe(check(false));
+        assert!(status.is_healthy);
+        assert_eq!(status.message, "connection refused");
+        assert!(tracker.observe(check(false)).is_healthy);
+        assert!(!tracker.observe(check(false)).is_healthy);
+        assert!(!tracker.observe(check(false)).is_healthy);
+
+        // 一次成功即恢复，并重新计数
+        assert!(tracker.observe(check(true)).is_healthy);
+        assert!(tracker.observe(check(false)).is_healthy);
+        assert!(tracker.observe(check(true)).is_healthy);
+    }
+
+    #[test]
+    fn test_tracker_first_failure_is_unhealthy() {
+        let mut tracker = HealthTracker::new(3);
+        assert!(!tracker.observe(check(false)).is_healthy);
+        assert!(tracker.observe(check(true)).is_healthy);
+    }
+
+    #[tokio::test]
+    async fn test_monitor_publishes_status() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let monitor = RedisHealthMonitor::start(
+            conn,
+            RedisHealthMonitorConfig {
+                interval: Duration::from_millis(50),
+                failure_threshold: 2,
+            },
+        );
+        let mut receiver = monitor.subscribe();
+
+        tokio::time::timeout(
+            Duration::from_secs(2),
+            receiver.wait_for(|status| status.is_healthy),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(monitor.is_healthy());
+        assert_eq!(monitor.status().message, "PONG");
+    }
+}
@@ -0,0 +1,370 @@
+//! Redis 服务端信息模块
+//!
+//! 将 INFO 命令的文本输出解析为 [`RedisServerInfo`]，将 SLOWLOG GET 的结果解析为 [`SlowlogEntry`]，
+//! 供管理后台直接使用。解析时忽略未知字段，缺失的字段使用默认值，以兼容不同版本的 Redis
+
+use crate::redis::{RedisConnection, RedisError, RedisResult};
+use chrono::{DateTime, Utc};
+use redis::Value;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// 复制角色
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplicationRole {
+    /// 主节点
+    Master,
+    /// 副本节点（INFO 中为 `slave`）
+    Replica,
+    /// 未知角色
+    #[default]
+    Unknown,
+}
+
+/// 单个逻辑库的键统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct KeyspaceInfo {
+    /// 键数量
+    pub keys: u64,
+    /// 设置了过期时间的键数量
+    pub expires: u64,
+    /// 平均剩余存活时间（毫秒）
+    pub avg_ttl_ms: u64,
+}
+
+/// INFO 命令的解析结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RedisServerInfo {
+    /// 服务端版本
+    pub version: String,
+    /// 运行模式（standalone / cluster / sentinel）
+    pub mode: Option<String>,
+    /// 运行时长（秒）
+    pub uptime_secs: u64,
+    /// 已连接的客户端数
+    pub connected_clients: u64,
+    /// 已使用内存（字节）
+    pub used_memory: u64,
+    /// 复制角色
+    pub role: ReplicationRole,
+    /// 已连接的副本数（仅主节点）
+    pub connected_replicas: u64,
+    /// 各逻辑库的键统计，键为库索引
+    pub keyspace: BTreeMap<u32, KeyspaceInfo>,
+    /// 全部原始字段，包含未单独解析的字段
+    pub fields: HashMap<String, String>,
+}
+
+impl RedisServerInfo {
+    /// 解析 INFO 命令的文本输出
+    pub fn parse(text: &str) -> Self {
+        let mut info = Self::default();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+
+            if let Some(index) = name.strip_prefix("db").and_then(|i| i.parse().ok()) {
+                info.keyspace.insert(index, parse_keyspace(value));
+            }
+            info.fields.insert(name.to_string(), value.to_string());
+        }
+
+        let number = |name: &str| {
+            info.fields
+                .get(name)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default()
+        };
+        info.uptime_secs = number("uptime_in_seconds");
+        info.connected_clients = number("connected_clients");
+        info.used_memory = number("used_memory");
+        info.connected_replicas = number("connected_slaves");
+        info.version = info
+            .fields
+            .get("redis_version")
+            .cloned()
+            .unwrap_or_default();
+        info.mode = info.fields.get("redis_mode").cloned();
+        info.role = match info.fields.get("role").map(String::as_str) {
+            Some("master") => ReplicationRole::Master,
+            Some("slave" | "replica") => ReplicationRole::Replica,
+            _ => ReplicationRole::Unknown,
+        };
+        info
+    }
+
+    /// 获取原始字段
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+
+    /// 所有逻辑库的键总数
+    pub fn total_keys(&self) -> u64 {
+        self.keyspace.values().map(|db| db.keys).sum()
+    }
+}
+
+/// 解析 `keys=1,expires=0,avg_ttl=0` 格式的键统计
+fn parse_keyspace(value: &str) -> KeyspaceInfo {
+    let mut keyspace = KeyspaceInfo::default();
+    for pair in value.split(',') {
+        let Some((name, count)) = pair.split_once('=') else {
+            continue;
+        };
+        let count = count.parse().unwrap_or_default();
+        match name {
+            "keys" => keyspace.keys = count,
+            "expires" => keyspace.expires = count,
+            "avg_ttl" => keyspace.avg_ttl_ms = count,
+            _ => {}
+        }
+    }
+    keyspace
+}
+
+/// 慢查询日志条目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SlowlogEntry {
+    /// 条目 ID
+    pub id: i64,
+    /// 命令开始执行的时间
+    pub timestamp: DateTime<Utc>,
+    /// 执行耗时
+    pub duration: Duration,
+    /// 命令及参数（服务端可能截断过长的参数）
+    pub command: Vec<String>,
+    /// 客户端地址（Redis 4.0 起提供）
+    pub client_addr: Option<String>,
+    /// 客户端名称（Redis 4.0 起提供）
+    pub client_name: Option<String>,
+}
+
+impl SlowlogEntry {
+    /// 从 SLOWLOG GET 返回的单个条目解析
+    pub fn from_value(value: &Value) -> RedisResult<Self> {
+        let Value::Array(fields) = value else {
+            return Err(slowlog_mismatch(value));
+        };
+        if fields.len() < 4 {
+            return Err(slowlog_mismatch(value));
+        }
+
+        let int = |value: &Value| match value {
+            Value::Int(n) => Ok(*n),
+            other => Err(slowlog_mismatch(other)),
+        };
+        let command = match &fields[3] {
+            Value::Array(args) => args.iter().map(value_to_string).collect(),
+            other => return Err(slowlog_mismatch(other)),
+        };
+        let timestamp = int(&fields[1])?;
+
+        Ok(Self {
+            id: int(&fields[0])?,
+            timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_default(),
+            duration: Duration::from_micros(int(&fields[2])?.max(0) as u64),
+            command,
+            client_addr: fields.get(4).map(value_to_string),
+            client_name: fields
+                .get(5)
+                .map(value_to_string)
+                .filter(|name| !name.is_empty()),
+        })
+    }
+}
+
+/// 将字符串类型的返回值转换为文本
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::BulkString(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        Value::SimpleString(text) => text.clone(),
+        Value::Int(n) => n.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+fn slowlog_mismatch(value: &Value) -> RedisError {
+    RedisError::type_mismatch("SlowlogEntry", format!("{:?}", value))
+}
+
+impl RedisConnection {
+    /// 获取并解析 INFO 命令的输出
+    pub async fn server_info(&mut self) -> RedisResult<RedisServerInfo> {
+        let text: String = self.query(&redis::cmd("INFO")).await?;
+        Ok(RedisServerInfo::parse(&text))
+    }
+
+    /// 获取最近的 `count` 条慢查询日志，按时间由新到旧排列
+    pub async fn slowlog(&mut self, count: usize) -> RedisResult<Vec<SlowlogEntry>> {
+        let value: Value = self
+            .query(redis::cmd("SLOWLOG").arg("GET").arg(count))
+            .await?;
+        match &value {
+            Value::Array(entries) => entries.iter().map(SlowlogEntry::from_value).collect(),
+            other => Err(slowlog_mismatch(other)),
+        }
+    }
+
+    /// 清空慢查询日志
+    pub async fn slowlog_reset(&mut self) -> RedisResult<()> {
+        self.query(redis::cmd("SLOWLOG").arg("RESET")).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+
+    /// Redis 6.2 主节点的 INFO 输出（节选）
+    const INFO_REDIS_6: &str = "# Server\r\n\
+        redis_version:6.2.14\r\n\
+        redis_git_sha1:00000000\r\n\
+        redis_mode:standalone\r\n\
+        os:Linux 5.15.0-1051-azure x86_64\r\n\
+        uptime_in_seconds:86523\r\n\
+        \r\n\
+        # Clients\r\n\
+        connected_clients:12\r\n\
+        blocked_clients:0\r\n\
+        \r\n\
+        # Memory\r\n\
+        used_memory:1048576\r\n\
+        used_memory_human:1.00M\r\n\
+        \r\n\
+        # Replication\r\n\
+        role:master\r\n\
+        connected_slaves:1\r\n\
+        slave0:ip=10.0.0.2,port=6379,state=online,offset=1234,lag=0\r\n\
+        \r\n\
+        # Keyspace\r\n\
+        db0:keys=120,expires=30,avg_ttl=54000\r\n\
+        db3:keys=5,expires=0,avg_ttl=0\r\n";
+
+    /// Redis 7.2 副本节点的 INFO 输出（节选），包含新增字段
+    const INFO_REDIS_7: &str = "# Server\r\n\
+        redis_version:7.2.4\r\n\
+        redis_mode:standalone\r\n\
+        listener0:name=tcp,bind=*,bind=-::*,port=6379\r\n\
+        uptime_in_seconds:42\r\n\
+        \r\n\
+        # Clients\r\n\
+        connected_clients:3\r\n\
+        pubsub_clients:0\r\n\
+        \r\n\
+        # Memory\r\n\
+        used_memory:2097152\r\n\
+        \r\n\
+        # Replication\r\n\
+        role:slave\r\n\
+        master_host:10.0.0.1\r\n\
+        master_link_status:up\r\n\
+        connected_slaves:0\r\n\
+        \r\n\
+        # Keyspace\r\n\
+        db0:keys=7,expires=2,avg_ttl=1000,subexpiry=0\r\n";
+
+    #[test]
+    fn test_parse_redis_6_info() {
+        let info = RedisServerInfo::parse(INFO_REDIS_6);
+        assert_eq!(info.version, "6.2.14");
+        assert_eq!(info.mode.as_deref(), Some("standalone"));
+        assert_eq!(info.uptime_secs, 86523);
+        assert_eq!(info.connected_clients, 12);
+        assert_eq!(info.used_memory, 1_048_576);
+        assert_eq!(info.role, ReplicationRole::Master);
+        assert_eq!(info.connected_replicas, 1);
+        assert_eq!(
+            info.keyspace[&0],
+            KeyspaceInfo {
+                keys: 120,
+                expires: 30,
+                avg_ttl_ms: 54000
+            }
+        );
+        assert_eq!(info.keyspace[&3].keys, 5);
+        assert_eq!(info.total_keys(), 125);
+        assert_eq!(info.field("used_memory_human"), Some("1.00M"));
+    }
+
+    #[test]
+    fn test_parse_redis_7_info() {
+        let info = RedisServerInfo::parse(INFO_REDIS_7);
+        assert_eq!(info.version, "7.2.4");
+        assert_eq!(info.role, ReplicationRole::Replica);
+        assert_eq!(info.connected_clients, 3);
+        assert_eq!(info.keyspace.len(), 1);
+        assert_eq!(info.keyspace[&0].expires, 2);
+        assert_eq!(info.field("master_link_status"), Some("up"));
+    }
+
+    #[test]
+    fn test_parse_tolerates_garbage() {
+        let info = RedisServerInfo::parse("garbage\r\nconnected_clients:many\r\ndbx:keys=1\r\n");
+        assert_eq!(
+            info,
+            RedisServerInfo {
+                fields: info.fields.clone(),
+                ..RedisServerInfo::default()
+            }
+        );
+        assert_eq!(info.role, ReplicationRole::Unknown);
+    }
+
+    fn bulk(text: &str) -> Value {
+        Value::BulkString(text.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_parse_slowlog_entry() {
+        let entry = SlowlogEntry::from_value(&Value::Array(vec![
+            Value::Int(14),
+            Value::Int(1_700_000_000),
+            Value::Int(15_230),
+            Value::Array(vec![bulk("KEYS"), bulk("*")]),
+            bulk("127.0.0.1:58212"),
+            bulk(""),
+        ]))
+        .unwrap();
+        assert_eq!(entry.id, 14);
+        assert_eq!(entry.timestamp.timestamp(), 1_700_000_000);
+        assert_eq!(entry.duration, Duration::from_micros(15_230));
+        assert_eq!(entry.command, ["KEYS", "*"]);
+        assert_eq!(entry.client_addr.as_deref(), Some("127.0.0.1:58212"));
+        assert_eq!(entry.client_name, None);
+
+        // Redis 4.0 之前的条目只有 4 个字段
+        let legacy = SlowlogEntry::from_value(&Value::Array(vec![
+            Value::Int(1),
+            Value::Int(1_600_000_000),
+            Value::Int(10),
+            Value::Array(vec![bulk("DEBUG"), bulk("SLEEP"), bulk("1")]),
+        ]))
+        .unwrap();
+        assert_eq!(legacy.client_addr, None);
+
+        assert!(SlowlogEntry::from_value(&Value::Int(1)).is_err());
+        assert!(SlowlogEntry::from_value(&Value::Array(vec![Value::Int(1)])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_server_info_and_slowlog_live() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let info = conn.server_info().await.unwrap();
+        assert!(!info.version.is_empty());
+        assert!(info.connected_clients >= 1);
+        assert!(info.used_memory > 0);
+
+        let entries = conn.slowlog(5).await.unwrap();
+        assert!(entries.len() <= 5);
+    }
+}
@@ -0,0 +1,164 @@
+//! Redis 分布式锁模块
+//!
+//! 基于 `SET key token NX PX ttl` 获取锁，释放时通过 Lua 脚本比较令牌后删除，
+//! 避免误删其他持有方在锁过期后重新获得的锁。[`RedisLock`] 在离开作用域时自动释放
+
+use crate::redis::redis_connection::lock_token;
+use crate::redis::{RedisConnection, RedisResult, RedisScript};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tracing::warn;
+
+/// 释放锁脚本：仅当锁的值与调用方令牌一致时删除
+static RELEASE_LOCK_SCRIPT: LazyLock<RedisScript> = LazyLock::new(|| {
+    RedisScript::new(
+        "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) end return 0",
+    )
+});
+
+impl RedisConnection {
+    /// 以 `SET key token NX PX ttl_ms` 获取锁，返回是否获取成功
+    pub async fn acquire_lock(&mut self, key: &str, token: &str, ttl_ms: u64) -> RedisResult<bool> {
+        let reply: Option<String> = self
+            .query(
+                redis::cmd("SET")
+                    .arg(self.prefixed_key(key))
+                    .arg(token)
+                    .arg("NX")
+                    .arg("PX")
+                    .arg(ttl_ms),
+            )
+            .await?;
+        Ok(reply.is_some())
+    }
+
+    /// 释放锁，仅当锁的值与 `token` 一致时删除，返回是否释放
+    pub async fn release_lock(&mut self, key: &str, token: &str) -> RedisResult<bool> {
+        let deleted: i64 = RELEASE_LOCK_SCRIPT.exec(self, &[key], &[token]).await?;
+        Ok(deleted == 1)
+    }
+
+    /// 使用随机令牌获取锁，锁已被其他持有方占用时返回 None
+    pub async fn lock(&self, key: &str, ttl: Duration) -> RedisResult<Option<RedisLock>> {
+        let mut connection = self.clone();
+        let token = lock_token();
+        if !connection
+            .acquire_lock(key, &token, ttl.as_millis() as u64)
+            .await?
+        {
+            return Ok(None);
+        }
+        Ok(Some(RedisLock {
+            connection,
+            key: key.to_string(),
+            token,
+            released: false,
+        }))
+    }
+}
+
+/// 分布式锁守卫
+///
+/// 调用 [`release`](Self::release) 显式释放；未释放时在 drop 中启动后台任务释放，
+/// 不在 Tokio 运行时中 drop 时只能等待锁自然过期
+pub struct RedisLock {
+    connection: RedisConnection,
+    key: String,
+    token: String,
+    released: bool,
+}
+
+impl RedisLock {
+    /// 锁的键（不含连接的键前缀）
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// 持有方令牌
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// 释放锁，返回锁是否仍由当前持有方持有（已过期时返回 false）
+    pub async fn release(mut self) -> RedisResult<bool> {
+        self.released = true;
+        self.connection.release_lock(&self.key, &self.token).await
+    }
+}
+
+impl Drop for RedisLock {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+
+        let mut connection = self.connection.clone();
+        let key = std::mem::take(&mut self.key);
+        let token = std::mem::take(&mut self.token);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = connection.release_lock(&key, &token).await {
+                        warn!("释放分布式锁失败: {}: {}", key, e);
+                    }
+                });
+            }
+            Err(_) => warn!("不在 Tokio 运行时中，分布式锁 {} 将在过期后释放", key),
+        }
+    }
+}
+
+impl std::fmt::Debug for RedisLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisLock")
+            .field("key", &self.key)
+            .field("released", &self.released)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::redis::test_support::connect_test_redis;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_contended_acquisition() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:lock:contended";
+        conn.del(key).await.unwrap();
+
+        assert!(conn.acquire_lock(key, "owner-a", 5_000).await.unwrap());
+        assert!(!conn.acquire_lock(key, "owner-b", 5_000).await.unwrap());
+
+        // 错误的令牌不能释放锁
+        assert!(!conn.release_lock(key, "owner-b").await.unwrap());
+        assert!(!conn.acquire_lock(key, "owner-b", 5_000).await.unwrap());
+
+        assert!(conn.release_lock(key, "owner-a").await.unwrap());
+        assert!(conn.acquire_lock(key, "owner-b", 5_000).await.unwrap());
+        assert!(conn.release_lock(key, "owner-b").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_guard_releases_on_drop() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:lock:guard";
+        conn.del(key).await.unwrap();
+        let ttl = Duration::from_secs(5);
+
+        let guard = conn.lock(key, ttl).await.unwrap().unwrap();
+        assert!(conn.lock(key, ttl).await.unwrap().is_none());
+        drop(guard);
+
+        // drop 在后台释放，稍等片刻后可以重新获取
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let guard = conn.lock(key, ttl).await.unwrap().unwrap();
+        assert!(guard.release().await.unwrap());
+        assert!(!conn.exists_builtin(key).await.unwrap());
+    }
+}
@@ -0,0 +1,232 @@
+//! 多 Redis 连接管理模块
+//!
+//! 从一个 YAML 文件加载 `名称 -> RedisConfig` 的映射，启动时建立全部连接并按名称取用。
+//! [`RedisManager`] 内部使用 `Arc` 共享连接表，克隆开销很小，可以直接放进 axum 的 State
+
+use crate::env_interpolation::expand_env_vars;
+use crate::redis::{RedisConfig, RedisConnection, RedisError, RedisHealthStatus, RedisResult};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tracing::info;
+
+/// 解析 YAML 中的命名 Redis 配置，支持 `${ENV_VAR}` 占位符
+///
+/// ```yaml
+/// cache:
+///   url: redis://127.0.0.1:6379
+///   database_index: 0
+/// queue:
+///   url: redis://127.0.0.1:6379
+///   database_index: 1
+/// ```
+pub fn parse_redis_configs(yaml: &str) -> RedisResult<BTreeMap<String, RedisConfig>> {
+    let yaml = expand_env_vars(yaml).map_err(RedisError::config)?;
+    let configs: BTreeMap<String, RedisConfig> = serde_yaml::from_str(&yaml)
+        .map_err(|e| RedisError::config(format!("解析配置失败: {}", e)))?;
+    if configs.is_empty() {
+        return Err(RedisError::config("至少需要配置一个 Redis 连接"));
+    }
+
+    let errors: Vec<String> = configs
+        .iter()
+        .filter_map(|(name, config)| {
+            config
+                .validate()
+                .err()
+                .map(|message| format!("{}: {}", name, message))
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(RedisError::config(errors.join("; ")));
+    }
+    Ok(configs)
+}
+
+/// 命名 Redis 连接管理器
+#[derive(Clone)]
+pub struct RedisManager {
+    connections: Arc<BTreeMap<String, RedisConnection>>,
+}
+
+impl RedisManager {
+    /// 并发建立所有连接，任一连接失败时返回包含全部失败名称及原因的错误
+    pub async fn new(configs: BTreeMap<String, RedisConfig>) -> RedisResult<Self> {
+        if configs.is_empty() {
+            return Err(RedisError::config("至少需要配置一个 Redis 连接"));
+        }
+
+        let mut tasks = JoinSet::new();
+        for (name, config) in configs {
+            tasks.spawn(async move { (name, RedisConnection::new(config).await) });
+        }
+
+        let mut connections = BTreeMap::new();
+        let mut errors = BTreeMap::new();
+        while let Some(joined) = tasks.join_next().await {
+            let (name, result) =
+                joined.map_err(|e| RedisError::connection(format!("连接任务异常退出: {}", e)))?;
+            match result {
+                Ok(connection) => {
+                    connections.insert(name, connection);
+                }
+                Err(e) => {
+                    errors.insert(name, e.to_string());
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            let details: Vec<String> = errors
+                .iter()
+                .map(|(name, message)| format!("{}: {}", name, message))
+                .collect();
+            return Err(RedisError::connection(format!(
+                "{} 个 Redis 连接建立失败: {}",
+                errors.len(),
+                details.join("; ")
+            )));
+        }
+
+        info!("已建立 {} 个命名 Redis 连接", connections.len());
+        Ok(Self::from_connections(connections))
+    }
+
+    /// 从 YAML 字符串加载配置并建立所有连接
+    pub async fn from_yaml_str(yaml: &str) -> RedisResult<Self> {
+        Self::new(parse_redis_configs(yaml)?).await
+    }
+
+    /// 从 YAML 文件加载配置并建立所有连接
+    pub async fn from_yaml_file(path: impl AsRef<Path>) -> RedisResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            RedisError::config(format!("读取配置文件 {} 失败: {}", path.display(), e))
+        })?;
+        Self::from_yaml_str(&content).await
+    }
+
+    /// 使用已建立的连接创建管理器
+    pub fn from_connections(
+        connections: impl IntoIterator<Item = (String, RedisConnection)>,
+    ) -> Self {
+        Self {
+            connections: Arc::new(connections.into_iter().collect()),
+        }
+    }
+
+    /// 按名称获取连接，返回的连接与管理器共享底层连接
+    pub fn get(&self, name: &str) -> RedisResult<RedisConnection> {
+        self.connections.get(name).cloned().ok_or_else(|| {
+            let available: Vec<&str> = self.names().collect();
+            RedisError::config(format!(
+                "未配置名为 {} 的 Redis 连接，可用的连接: [{}]",
+                name,
+                available.join(", ")
+            ))
+        })
+    }
+
+    /// 是否存在指定名称的连接
+    pub fn contains(&self, name: &str) -> bool {
+        self.connections.contains_key(name)
+    }
+
+    /// 所有连接名称（按字母序）
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.connections.keys().map(String::as_str)
+    }
+
+    /// 对所有连接并发执行健康检查，按名称返回结果
+    pub async fn health_check_all(&self) -> BTreeMap<String, RedisHealthStatus> {
+        let mut tasks = JoinSet::new();
+        for (name, connection) in self.connections.iter() {
+            let name = name.clone();
+            let mut connection = connection.clone();
+            tasks.spawn(async move { (name, connection.health_check().await) });
+        }
+
+        let mut statuses = BTreeMap::new();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((name, status)) = joined {
+                statuses.insert(name, status);
+            }
+        }
+        statuses
+    }
+}
+
+impl std::fmt::Debug for RedisManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisManager")
+            .field("connections", &self.names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_ENTRIES: &str = r#"
+cache:
+  url: ${REDIS_URL:-redis://127.0.0.1:6379}
+  connection_timeout_secs: 2
+  retry_count: 0
+queue:
+  url: ${REDIS_URL:-redis://127.0.0.1:6379}
+  connection_timeout_secs: 2
+  retry_count: 0
+  key_prefix: "queue:"
+"#;
+
+    #[test]
+    fn test_parse_two_entries() {
+        let configs = parse_redis_configs(TWO_ENTRIES).unwrap();
+        assert_eq!(configs.keys().collect::<Vec<_>>(), ["cache", "queue"]);
+        assert_eq!(configs["queue"].key_prefix.as_deref(), Some("queue:"));
+        assert_eq!(configs["cache"].key_prefix, None);
+    }
+
+    #[test]
+    fn test_parse_reports_every_invalid_entry() {
+        let error = parse_redis_configs("cache:\n  url: \"\"\nqueue:\n  url: \"\"\n").unwrap_err();
+        let message = error.to_string();
+        assert!(error.is_config_error());
+        assert!(message.contains("cache:"), "{}", message);
+        assert!(message.contains("queue:"), "{}", message);
+
+        assert!(parse_redis_configs("{}").is_err());
+    }
+
+    #[test]
+    fn test_unknown_name_without_connections() {
+        let manager = RedisManager::from_connections(Vec::new());
+        let error = manager.get("cache").unwrap_err();
+        assert!(error.to_string().contains("cache"));
+        assert!(!manager.contains("cache"));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_and_unknown_name() {
+        let Ok(manager) = RedisManager::from_yaml_str(TWO_ENTRIES).await else {
+            return;
+        };
+
+        assert_eq!(manager.names().collect::<Vec<_>>(), ["cache", "queue"]);
+        assert!(manager.contains("cache"));
+        let mut cache = manager.get("cache").unwrap();
+        assert!(cache.ping().await.is_ok());
+
+        let error = manager.get("sessions").unwrap_err();
+        let message = error.to_string();
+        assert!(error.is_config_error());
+        assert!(message.contains("sessions"), "{}", message);
+        assert!(message.contains("[cache, queue]"), "{}", message);
+
+        let statuses = manager.clone().health_check_all().await;
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses.values().all(|status| status.is_healthy));
+    }
+}
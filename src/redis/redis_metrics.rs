@@ -0,0 +1,292 @@
+//! Redis 指标模块
+//!
+//! 记录按命令类型统计的执行次数、按错误类型统计的错误次数以及延迟直方图。
+//! 热路径只使用原子操作，命令类型首次出现时才需要获取写锁。
+//! 启用 `redis-metrics` 特性后同时上报到 `metrics` 门面
+
+use crate::redis::RedisError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// 延迟直方图桶上界（微秒），超过最后一个上界的计入溢出桶
+const LATENCY_BUCKETS_US: [u64; 16] = [
+    100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000, 500_000,
+    1_000_000, 2_500_000, 5_000_000, 10_000_000,
+];
+
+/// 无锁延迟直方图
+#[derive(Debug, Default)]
+struct LatencyHistogram {
+    /// 每个桶的计数，最后一个为溢出桶
+    buckets: [AtomicU64; LATENCY_BUCKETS_US.len() + 1],
+    sum_us: AtomicU64,
+    count: AtomicU64,
+}
+
+impl LatencyHistogram {
+    fn record(&self, latency: Duration) {
+        let micros = latency.as_micros().min(u64::MAX as u128) as u64;
+        let index = LATENCY_BUCKETS_US
+            .iter()
+            .position(|&upper| micros <= upper)
+            .unwrap_or(LATENCY_BUCKETS_US.len());
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(micros, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> LatencySnapshot {
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| LatencyBucket {
+                le_us: LATENCY_BUCKETS_US.get(i).copied(),
+                count: count.load(Ordering::Relaxed),
+            })
+            .collect();
+        LatencySnapshot {
+            count: self.count.load(Ordering::Relaxed),
+            sum_us: self.sum_us.load(Ordering::Relaxed),
+            buckets,
+        }
+    }
+}
+
+/// 单个命令类型的指标
+#[derive(Debug, Default)]
+struct CommandMetrics {
+    count: AtomicU64,
+    errors: AtomicU64,
+    latency: LatencyHistogram,
+}
+
+/// Redis 指标记录器
+#[derive(Debug, Default)]
+pub struct RedisMetrics {
+    commands: RwLock<HashMap<String, Arc<CommandMetrics>>>,
+    errors: RwLock<HashMap<&'static str, Arc<AtomicU64>>>,
+}
+
+impl RedisMetrics {
+    /// 创建指标记录器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次命令执行
+    pub fn record(&self, command: &str, latency: Duration, error: Option<&RedisError>) {
+        let metrics = self.command_metrics(command);
+        metrics.count.fetch_add(1, Ordering::Relaxed);
+        metrics.latency.record(latency);
+
+        if let Some(error) = error {
+            metrics.errors.fetch_add(1, Ordering::Relaxed);
+            self.record_error(error);
+        }
+
+        #[cfg(feature = "redis-metrics")]
+        {
+            let command = command.to_string();
+            metrics::counter!("redis_commands_total", "command" => command.clone()).increment(1);
+            metrics::histogram!("redis_command_duration_seconds", "command" => command)
+                .record(latency.as_secs_f64());
+        }
+    }
+
+    /// 记录一次错误
+    pub fn record_error(&self, error: &RedisError) {
+        let kind = error.kind_name();
+        let counter = {
+            let errors = self.errors.read().unwrap_or_else(|e| e.into_inner());
+            errors.get(kind).cloned()
+        };
+        let counter = counter.unwrap_or_else(|| {
+            let mut errors = self.errors.write().unwrap_or_else(|e| e.into_inner());
+            errors.entry(kind).or_default().clone()
+        });
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(feature = "redis-metrics")]
+        metrics::counter!("redis_errors_total", "kind" => kind).increment(1);
+    }
+
+    /// 获取命令类型对应的指标，首次出现时创建
+    fn command_metrics(&self, command: &str) -> Arc<CommandMetrics> {
+        {
+            let commands = self.commands.read().unwrap_or_else(|e| e.into_inner());
+            if let Some(metrics) = commands.get(command) {
+                return metrics.clone();
+            }
+        }
+
+        let mut commands = self.commands.write().unwrap_or_else(|e| e.into_inner());
+        commands.entry(command.to_string()).or_default().clone()
+    }
+
+    /// 获取当前指标快照
+    pub fn snapshot(&self) -> RedisMetricsSnapshot {
+        let commands: BTreeMap<String, CommandMetricsSnapshot> = self
+            .commands
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, metrics)| {
+                (
+                    name.clone(),
+                    CommandMetricsSnapshot {
+                        count: metrics.count.load(Ordering::Relaxed),
+                        errors: metrics.errors.load(Ordering::Relaxed),
+                        latency: metrics.latency.snapshot(),
+                    },
+                )
+            })
+            .collect();
+        let errors: BTreeMap<String, u64> = self
+            .errors
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(kind, count)| (kind.to_string(), count.load(Ordering::Relaxed)))
+            .collect();
+
+        RedisMetricsSnapshot {
+            total_commands: commands.values().map(|c| c.count).sum(),
+            total_errors: errors.values().sum(),
+            commands,
+            errors,
+        }
+    }
+}
+
+/// Redis 指标快照
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RedisMetricsSnapshot {
+    /// 命令执行总数
+    pub total_commands: u64,
+    /// 错误总数
+    pub total_errors: u64,
+    /// 按命令类型统计
+    pub commands: BTreeMap<String, CommandMetricsSnapshot>,
+    /// 按错误类型统计
+    pub errors: BTreeMap<String, u64>,
+}
+
+impl RedisMetricsSnapshot {
+    /// 获取指定命令的执行次数
+    pub fn command_count(&self, command: &str) -> u64 {
+        self.commands.get(command).map(|c| c.count).unwrap_or(0)
+    }
+}
+
+/// 单个命令类型的指标快照
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandMetricsSnapshot {
+    /// 执行次数
+    pub count: u64,
+    /// 失败次数
+    pub errors: u64,
+    /// 延迟分布
+    pub latency: LatencySnapshot,
+}
+
+/// 延迟直方图快照
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencySnapshot {
+    /// 样本数
+    pub count: u64,
+    /// 延迟总和（微秒）
+    pub sum_us: u64,
+    /// 各桶计数
+    pub buckets: Vec<LatencyBucket>,
+}
+
+impl LatencySnapshot {
+    /// 平均延迟（微秒）
+    pub fn mean_us(&self) -> u64 {
+        self.sum_us.checked_div(self.count).unwrap_or(0)
+    }
+
+    /// 估算分位数对应的延迟上界（微秒），落在溢出桶时返回 None
+    pub fn quantile_us(&self, quantile: f64) -> Option<u64> {
+        if self.count == 0 {
+            return Some(0);
+        }
+        let target = ((self.count as f64) * quantile.clamp(0.0, 1.0))
+            .ceil()
+            .max(1.0) as u64;
+        let mut seen = 0;
+        for bucket in &self.buckets {
+            seen += bucket.count;
+            if seen >= target {
+                return bucket.le_us;
+            }
+        }
+        None
+    }
+}
+
+/// 直方图桶
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyBucket {
+    /// 桶上界（微秒），None 表示溢出桶
+    pub le_us: Option<u64>,
+    /// 落入该桶的样本数
+    pub count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_counts_by_command_and_error() {
+        let metrics = RedisMetrics::new();
+        metrics.record("SET", Duration::from_micros(80), None);
+        metrics.record("SET", Duration::from_micros(300), None);
+        metrics.record("GET", Duration::from_millis(2), None);
+        metrics.record(
+            "GET",
+            Duration::from_millis(20),
+            Some(&RedisError::timeout("GET")),
+        );
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.total_commands, 4);
+        assert_eq!(snapshot.total_errors, 1);
+        assert_eq!(snapshot.command_count("SET"), 2);
+        assert_eq!(snapshot.command_count("GET"), 2);
+        assert_eq!(snapshot.commands["GET"].errors, 1);
+        assert_eq!(snapshot.errors["Timeout"], 1);
+    }
+
+    #[test]
+    fn test_latency_histogram() {
+        let metrics = RedisMetrics::new();
+        for micros in [50, 90, 400, 20_000] {
+            metrics.record("GET", Duration::from_micros(micros), None);
+        }
+        metrics.record("GET", Duration::from_secs(30), None);
+
+        let latency = &metrics.snapshot().commands["GET"].latency;
+        assert_eq!(latency.count, 5);
+        assert_eq!(latency.buckets[0].count, 2);
+        assert_eq!(latency.buckets.last().unwrap().count, 1);
+        assert_eq!(latency.quantile_us(0.4), Some(100));
+        assert_eq!(latency.quantile_us(0.6), Some(500));
+        assert_eq!(latency.quantile_us(1.0), None);
+    }
+
+    #[test]
+    fn test_snapshot_serializes() {
+        let metrics = RedisMetrics::new();
+        metrics.record("PING", Duration::from_micros(10), None);
+
+        let json = serde_json::to_string(&metrics.snapshot()).unwrap();
+        let decoded: RedisMetricsSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, metrics.snapshot());
+    }
+}
@@ -0,0 +1,278 @@
+//! Redis 键迁移模块
+//!
+//! 提供 DUMP / RESTORE 封装，以及在两个连接之间按模式迁移键的 [`migrate_keys`]，
+//! 适用于租户迁移等需要在实例或逻辑库之间搬移一个命名空间的场景。迁移时保留键的剩余存活时间，
+//! 源连接与目标连接的键前缀分别生效，因此也可以在不同前缀之间迁移
+
+use crate::redis::{RedisConnection, RedisError, RedisResult};
+use redis::ToRedisArgs;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+
+/// 目标键已存在时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// 跳过该键，保留目标端的值
+    #[default]
+    Skip,
+    /// 覆盖目标端的值
+    Overwrite,
+    /// 中止迁移并返回错误
+    Error,
+}
+
+/// 迁移进度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationProgress {
+    /// 已扫描的键数
+    pub scanned: u64,
+    /// 已迁移的键数
+    pub migrated: u64,
+    /// 因目标键已存在或源键已过期而跳过的键数
+    pub skipped: u64,
+}
+
+impl RedisConnection {
+    /// 序列化键的值（DUMP），键不存在时返回 None
+    pub async fn dump<K>(&mut self, key: K) -> RedisResult<Option<Vec<u8>>>
+    where
+        K: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("DUMP");
+        cmd.arg(self.prefixed(key));
+        self.query(&cmd).await
+    }
+
+    /// 从 DUMP 结果恢复键（RESTORE），`ttl` 为 None 时不过期
+    ///
+    /// `replace` 为 false 且键已存在时返回 BUSYKEY 错误
+    pub async fn restore<K>(
+        &mut self,
+        key: K,
+        payload: &[u8],
+        ttl: Option<Duration>,
+        replace: bool,
+    ) -> RedisResult<()>
+    where
+        K: ToRedisArgs,
+    {
+        let mut cmd = redis::cmd("RESTORE");
+        cmd.arg(self.prefixed(key))
+            .arg(ttl.map_or(0, |ttl| ttl.as_millis().max(1) as u64))
+            .arg(payload);
+        if replace {
+            cmd.arg("REPLACE");
+        }
+        self.query(&cmd).await
+    }
+}
+
+/// 按模式将源连接中的键迁移到目标连接，每处理完一批 SCAN 结果调用一次 `on_progress`
+///
+/// `batch_size` 作为 SCAN 的 COUNT 参数。迁移不是原子的，迁移期间源端写入的数据可能不会被迁移
+pub async fn migrate_keys<F>(
+    source: &mut RedisConnection,
+    dest: &mut RedisConnection,
+    pattern: &str,
+    batch_size: usize,
+    conflict: ConflictPolicy,
+    mut on_progress: F,
+) -> RedisResult<MigrationProgress>
+where
+    F: FnMut(&MigrationProgress),
+{
+    let pattern = source.prefixed_key(pattern);
+    let prefix_len = source.key_prefix().map_or(0, str::len);
+    let mut progress = MigrationProgress::default();
+    let mut cursor: u64 = 0;
+
+    loop {
+        let (next, batch): (u64, Vec<String>) = source
+            .query(
+                redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(&pattern)
+                    .arg("COUNT")
+                    .arg(batch_size.max(1)),
+            )
+            .await?;
+
+        for key in batch {
+            progress.scanned += 1;
+            // SCAN 返回带前缀的完整键名，去掉前缀后交给连接方法重新处理
+            let key = &key[prefix_len..];
+            if migrate_key(source, dest, key, conflict).await? {
+                progress.migrated += 1;
+            } else {
+                progress.skipped += 1;
+            }
+        }
+        on_progress(&progress);
+
+        if next == 0 {
+            break;
+        }
+        cursor = next;
+    }
+
+    info!(
+        "Redis 键迁移完成: 扫描={}, 迁移={}, 跳过={}",
+        progress.scanned, progress.migrated, progress.skipped
+    );
+    Ok(progress)
+}
+
+/// 迁移单个键，返回是否写入了目标端
+async fn migrate_key(
+    source: &mut RedisConnection,
+    dest: &mut RedisConnection,
+    key: &str,
+    conflict: ConflictPolicy,
+) -> RedisResult<bool> {
+    let ttl_ms: i64 = source
+        .query(redis::cmd("PTTL").arg(source.prefixed_key(key)))
+        .await?;
+    let Some(payload) = source.dump(key).await? else {
+        // 扫描后键已过期或被删除
+        return Ok(false);
+    };
+    // -1 表示没有过期时间，-2 表示键已不存在（DUMP 之前刚好过期）
+    let ttl = match ttl_ms {
+        -2 => return Ok(false),
+        ms if ms > 0 => Some(Duration::from_millis(ms as u64)),
+        _ => None,
+    };
+
+    let replace = conflict == ConflictPolicy::Overwrite;
+    match dest.restore(key, &payload, ttl, replace).await {
+        Ok(()) => Ok(true),
+        Err(RedisError::Redis(e))
+            if conflict == ConflictPolicy::Skip && e.code() == Some("BUSYKEY") =>
+        {
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::RedisConfig;
+    use crate::redis::test_support::connect_test_redis;
+
+    /// 连接测试 Redis 的另一个逻辑库
+    async fn connect_database(conn: &RedisConnection, database_index: u8) -> RedisConnection {
+        let config = RedisConfig {
+            database_index,
+            ..conn.get_config().clone()
+        };
+        RedisConnection::new(config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_dump_and_restore() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let (key, copy) = ("clamber:test:dump", "clamber:test:dump:copy");
+        conn.del(&[key, copy]).await.unwrap();
+        assert_eq!(conn.dump(key).await.unwrap(), None);
+
+        conn.rpush(key, "a").await.unwrap();
+        conn.rpush(key, "b").await.unwrap();
+        let payload = conn.dump(key).await.unwrap().unwrap();
+
+        conn.restore(copy, &payload, Some(Duration::from_secs(60)), false)
+            .await
+            .unwrap();
+        assert_eq!(conn.lrange(copy, 0, -1).await.unwrap(), vec!["a", "b"]);
+        assert!(conn.ttl_remaining(copy).await.unwrap().is_some());
+
+        // 键已存在且未指定 REPLACE 时报错
+        assert!(conn.restore(copy, &payload, None, false).await.is_err());
+        conn.restore(copy, &payload, None, true).await.unwrap();
+        assert_eq!(conn.ttl_remaining(copy).await.unwrap(), None);
+
+        conn.del(&[key, copy]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_migrate_keys_between_databases() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let mut source = connect_database(&conn, 14).await.with_prefix("tenant:");
+        let mut dest = connect_database(&conn, 15).await.with_prefix("tenant:");
+        let keys: Vec<String> = (0..50).map(|i| format!("migrate:{}", i)).collect();
+        source.del(&keys).await.unwrap();
+        dest.del(&keys).await.unwrap();
+
+        for (i, key) in keys.iter().enumerate() {
+            source.set_builtin(key, i).await.unwrap();
+            if i % 5 == 0 {
+                source
+                    .pexpire_at(key, chrono::Utc::now() + chrono::Duration::minutes(10))
+                    .await
+                    .unwrap();
+            }
+        }
+        // 目标端已存在的键按冲突策略处理
+        dest.set_builtin("migrate:0", "existing").await.unwrap();
+
+        let error = migrate_keys(
+            &mut source,
+            &mut dest,
+            "migrate:*",
+            10,
+            ConflictPolicy::Error,
+            |_| {},
+        )
+        .await;
+        assert!(error.is_err());
+
+        let mut reports = Vec::new();
+        let progress = migrate_keys(
+            &mut source,
+            &mut dest,
+            "migrate:*",
+            10,
+            ConflictPolicy::Skip,
+            |progress| reports.push(*progress),
+        )
+        .await
+        .unwrap();
+        assert_eq!(progress.scanned, 50);
+        assert_eq!(progress.migrated + progress.skipped, 50);
+        assert!(progress.skipped >= 1);
+        assert_eq!(reports.last(), Some(&progress));
+        assert!(reports.windows(2).all(|w| w[0].scanned <= w[1].scanned));
+        assert_eq!(
+            dest.get_builtin("migrate:0").await.unwrap(),
+            Some("existing".to_string())
+        );
+
+        let progress = migrate_keys(
+            &mut source,
+            &mut dest,
+            "migrate:*",
+            10,
+            ConflictPolicy::Overwrite,
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(progress.migrated, 50);
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(dest.get_builtin(key).await.unwrap(), Some(i.to_string()));
+            let ttl = dest.ttl_remaining(key).await.unwrap();
+            assert_eq!(ttl.is_some(), i % 5 == 0, "{}", key);
+        }
+
+        source.del(&keys).await.unwrap();
+        dest.del(&keys).await.unwrap();
+    }
+}
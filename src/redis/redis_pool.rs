@@ -0,0 +1,252 @@
+//! Redis 连接池模块
+//!
+//! 单个 ConnectionManager 在大量并发管道命令下会成为瓶颈。
+//! 连接池维护多个独立的 ConnectionManager，按轮询或最少在途请求分发，
+//! 取出的连接可以直接使用 [`RedisConnection`] 的全部命令
+
+use crate::redis::{RedisConfig, RedisConnection, RedisConnectionStats, RedisError, RedisResult};
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::info;
+
+/// 连接池分发策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedisPoolStrategy {
+    /// 轮询
+    #[default]
+    RoundRobin,
+    /// 最少在途请求
+    LeastInFlight,
+}
+
+/// Redis 连接池
+///
+/// 克隆的连接池共享同一组连接
+#[derive(Clone)]
+pub struct RedisPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    slots: Vec<PoolSlot>,
+    strategy: RedisPoolStrategy,
+    /// 轮询计数
+    next: AtomicUsize,
+    config: RedisConfig,
+}
+
+struct PoolSlot {
+    connection: RedisConnection,
+    /// 当前被取出尚未归还的次数
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl RedisPool {
+    /// 根据配置创建连接池，连接数由 `pool_size` 决定
+    pub async fn new(config: RedisConfig) -> RedisResult<Self> {
+        config.validate().map_err(RedisError::config)?;
+
+        let mut slots = Vec::with_capacity(config.pool_size);
+        for _ in 0..config.pool_size {
+            slots.push(PoolSlot {
+                connection: RedisConnection::new(config.clone()).await?,
+                in_flight: Arc::new(AtomicUsize::new(0)),
+            });
+        }
+
+        info!(
+            "Redis 连接池创建成功: 连接数={}, 分发策略={:?}",
+            config.pool_size, config.pool_strategy
+        );
+
+        Ok(Self {
+            inner: Arc::new(PoolInner {
+                slots,
+                strategy: config.pool_strategy,
+                next: AtomicUsize::new(0),
+                config,
+            }),
+        })
+    }
+
+    /// 从 URL 创建指定大小的连接池
+    pub async fn from_url(redis_url: &str, pool_size: usize) -> RedisResult<Self> {
+        let config = RedisConfig {
+            pool_size,
+            ..RedisConfig::from_url(redis_url)
+        };
+        Self::new(config).await
+    }
+
+    /// 取出一个连接，连接在返回值被丢弃时归还
+    pub fn get(&self) -> PooledConnection {
+        let loads: Vec<usize> = self
+            .inner
+            .slots
+            .iter()
+            .map(|slot| slot.in_flight.load(Ordering::Relaxed))
+            .collect();
+        let turn = self.inner.next.fetch_add(1, Ordering::Relaxed);
+        let slot = &self.inner.slots[select_slot(self.inner.strategy, turn, &loads)];
+
+        slot.in_flight.fetch_add(1, Ordering::Relaxed);
+        PooledConnection {
+            connection: slot.connection.clone(),
+            in_flight: slot.in_flight.clone(),
+        }
+    }
+
+    /// 连接池中的连接数
+    pub fn size(&self) -> usize {
+        self.inner.slots.len()
+    }
+
+    /// 测试池中所有连接是否有效
+    pub async fn ping(&self) -> RedisResult<()> {
+        for slot in &self.inner.slots {
+            slot.connection.clone().ping().await?;
+        }
+        Ok(())
+    }
+
+    /// 获取连接池统计信息，包含每个连接的在途请求数
+    pub fn get_pool_stats(&self) -> RedisConnectionStats {
+        let (commands, reconnects) =
+            self.inner
+                .slots
+                .iter()
+                .fold((0, 0), |(commands, reconnects), slot| {
+                    let stats = slot.connection.get_pool_stats();
+                    (
+                        commands + stats.total_commands,
+                        reconnects + stats.total_reconnects,
+                    )
+                });
+
+        let mut stats = RedisConnectionStats::from_config(&self.inner.config, commands, reconnects);
+        stats.max_connections = self.size() as u32;
+        stats.min_connections = self.size() as u32;
+        stats.pool_size = self.size();
+        stats.in_flight = self
+            .inner
+            .slots
+            .iter()
+            .map(|slot| slot.in_flight.load(Ordering::Relaxed))
+            .collect();
+        stats
+    }
+
+    /// 获取创建连接池时使用的配置
+    pub fn get_config(&self) -> &RedisConfig {
+        &self.inner.config
+    }
+}
+
+/// 从连接池取出的连接，可直接调用 [`RedisConnection`] 的方法
+pub struct PooledConnection {
+    connection: RedisConnection,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Deref for PooledConnection {
+    type Target = RedisConnection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.connection
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.connection
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// 根据策略选择连接下标
+fn select_slot(strategy: RedisPoolStrategy, turn: usize, loads: &[usize]) -> usize {
+    let len = loads.len();
+    match strategy {
+        RedisPoolStrategy::RoundRobin => turn % len,
+        // 从轮询位置开始查找，在途请求数相同时分散到不同连接
+        RedisPoolStrategy::LeastInFlight => (0..len)
+            .map(|offset| (turn + offset) % len)
+            .min_by_key(|&i| loads[i])
+            .unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_robin_selection() {
+        let loads = [5, 0, 0];
+        let picks: Vec<usize> = (0..6)
+            .map(|turn| select_slot(RedisPoolStrategy::RoundRobin, turn, &loads))
+            .collect();
+        assert_eq!(picks, vec![0, 1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_least_in_flight_selection() {
+        assert_eq!(
+            select_slot(RedisPoolStrategy::LeastInFlight, 0, &[3, 1, 2]),
+            1
+        );
+        // 负载相同时从轮询位置开始
+        assert_eq!(
+            select_slot(RedisPoolStrategy::LeastInFlight, 2, &[1, 1, 1]),
+            2
+        );
+    }
+
+    #[test]
+    fn test_strategy_deserialize() {
+        let strategy: RedisPoolStrategy = serde_yaml::from_str("least_in_flight").unwrap();
+        assert_eq!(strategy, RedisPoolStrategy::LeastInFlight);
+    }
+
+    #[tokio::test]
+    async fn test_pool_tracks_in_flight_connections() {
+        let Some(conn) = crate::redis::test_support::connect_test_redis().await else {
+            return;
+        };
+        let config = RedisConfig {
+            pool_size: 3,
+            pool_strategy: RedisPoolStrategy::LeastInFlight,
+            ..conn.get_config().clone()
+        };
+        let pool = RedisPool::new(config).await.unwrap();
+        assert_eq!(pool.size(), 3);
+
+        let mut first = pool.get();
+        let second = pool.get();
+        let stats = pool.get_pool_stats();
+        assert_eq!(stats.pool_size, 3);
+        assert_eq!(stats.in_flight.iter().sum::<usize>(), 2);
+        assert!(stats.in_flight.iter().all(|&n| n <= 1));
+
+        first.set_builtin("pool:test", "value").await.unwrap();
+        assert_eq!(
+            first.get_builtin("pool:test").await.unwrap(),
+            Some("value".to_string())
+        );
+        first.del("pool:test").await.unwrap();
+
+        drop(first);
+        drop(second);
+        let stats = pool.get_pool_stats();
+        assert_eq!(stats.in_flight, vec![0, 0, 0]);
+        assert!(stats.total_commands >= 3);
+    }
+}
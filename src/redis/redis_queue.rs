@@ -0,0 +1,574 @@
+//! Redis 工作队列模块
+//!
+//! 基于 Redis 列表的轻量任务队列，无需引入 Kafka 即可在小规模部署中处理后台任务。
+//! 生产者 LPUSH 入队，消费者从另一端取出，保证先进先出：
+//! - 普通模式：BRPOP 取出即视为完成，消费者崩溃时消息丢失
+//! - 可靠模式：LMOVE 到消费者自己的处理中列表，并在 `{name}:inflight` 有序集合中记录可见性截止时间，
+//!   处理完成后 [`Delivery::ack`] 确认；超过可见性超时仍未确认的消息由 [`RedisQueue::reap_expired`] 重新入队
+//!
+//! 队列使用的键均位于 `{name}:` 命名空间下：`pending`（待处理）、`inflight`（在途）、`processing:{consumer_id}`（处理中）
+
+use crate::redis::redis_connection::lock_token;
+use crate::redis::{RedisConnection, RedisError, RedisResult, RedisScript};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// 待处理列表的键名
+const PENDING_KEY: &str = "pending";
+/// 在途有序集合的键名
+const INFLIGHT_KEY: &str = "inflight";
+/// 处理中列表的键名前缀
+const PROCESSING_KEY_PREFIX: &str = "processing:";
+/// 单次回收的最大消息数
+const REAP_BATCH_SIZE: usize = 100;
+
+/// 工作队列配置
+#[derive(Debug, Clone)]
+pub struct RedisQueueConfig {
+    /// 队列最大长度，达到上限后入队失败；None 表示不限制
+    pub max_len: Option<usize>,
+    /// 是否启用可靠模式（需要 ack 确认）
+    pub reliable: bool,
+    /// 消费者标识，决定处理中列表的键名，不能包含 `|`
+    pub consumer_id: String,
+    /// 可靠模式下消息的可见性超时，超时未确认的消息会被重新入队
+    pub visibility_timeout: Duration,
+    /// 可靠模式下队列为空时的轮询间隔
+    pub poll_interval: Duration,
+}
+
+impl Default for RedisQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_len: None,
+            reliable: false,
+            consumer_id: format!("consumer-{}", std::process::id()),
+            visibility_timeout: Duration::from_secs(30),
+            poll_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// 队列中保存的消息，附带唯一 ID 以区分内容相同的消息
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    id: String,
+    payload: T,
+}
+
+/// 类型化的 Redis 工作队列
+///
+/// ```ignore
+/// let config = RedisQueueConfig { reliable: true, ..RedisQueueConfig::default() };
+/// let mut jobs: RedisQueue<Job> = RedisQueue::with_config(&conn, "jobs", config);
+/// jobs.enqueue(&job).await?;
+/// if let Some(delivery) = jobs.dequeue(Duration::from_secs(5)).await? {
+///     handle(delivery.payload()).await;
+///     delivery.ack().await?;
+/// }
+/// ```
+pub struct RedisQueue<T> {
+    /// 追加了队列命名空间前缀的连接
+    connection: RedisConnection,
+    config: Arc<RedisQueueConfig>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+// 手动实现 Clone，避免要求消息类型 T 实现 Clone
+impl<T> Clone for RedisQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            connection: self.connection.clone(),
+            config: self.config.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> RedisQueue<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// 使用默认配置（普通模式、不限长度）创建队列
+    pub fn new(connection: &RedisConnection, name: &str) -> Self {
+        Self::with_config(connection, name, RedisQueueConfig::default())
+    }
+
+    /// 使用指定配置创建队列
+    pub fn with_config(connection: &RedisConnection, name: &str, config: RedisQueueConfig) -> Self {
+        let config = RedisQueueConfig {
+            consumer_id: config.consumer_id.replace('|', "_"),
+            ..config
+        };
+        Self {
+            connection: connection.with_prefix(&format!("{}:", name)),
+            config: Arc::new(config),
+            _marker: PhantomData,
+        }
+    }
+
+    /// 队列配置
+    pub fn config(&self) -> &RedisQueueConfig {
+        &self.config
+    }
+
+    /// 消息入队，队列已达到 `max_len` 时返回 false
+    pub async fn enqueue(&mut self, item: &T) -> RedisResult<bool> {
+        let raw = serde_json::to_string(&Envelope {
+            id: lock_token(),
+            payload: item,
+        })
+        .map_err(|e| RedisError::serialization(e.to_string()))?;
+        let max_len = self.config.max_len.unwrap_or(0);
+
+        let length: i64 = ENQUEUE_SCRIPT
+            .exec(
+                &mut self.connection,
+                &[PENDING_KEY],
+                &[raw, max_len.to_string()],
+            )
+            .await?;
+        Ok(length >= 0)
+    }
+
+    /// 取出一条消息，等待 `timeout` 后仍无消息时返回 None
+    ///
+    /// 普通模式在独占连接上使用 BRPOP，不会阻塞共享连接上的其他命令；
+    /// 可靠模式按 `poll_interval` 轮询，取出的消息需要调用 [`Delivery::ack`] 确认
+    pub async fn dequeue(&mut self, timeout: Duration) -> RedisResult<Option<Delivery<T>>> {
+        if !self.config.reliable {
+            let popped = self.connection.brpop(PENDING_KEY, timeout).await?;
+            return popped.map(|(_, raw)| self.delivery(raw, false)).transpose();
+        }
+
+        let processing = self.processing_key();
+        let deadline = Instant::now() + timeout;
+        loop {
+            let raw: Option<String> = DEQUEUE_SCRIPT
+                .exec(
+                    &mut self.connection,
+                    &[PENDING_KEY, processing.as_str(), INFLIGHT_KEY],
+                    &[
+                        self.config.visibility_timeout.as_millis().to_string(),
+                        self.config.consumer_id.clone(),
+                    ],
+                )
+                .await?;
+            if let Some(raw) = raw {
+                return self.delivery(raw, true).map(Some);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(self.config.poll_interval.min(deadline - now)).await;
+        }
+    }
+
+    /// 将超过可见性超时仍未确认的消息重新放回队列，返回回收的消息数
+    pub async fn reap_expired(&mut self) -> RedisResult<usize> {
+        let processing_prefix = self.connection.prefixed_key(PROCESSING_KEY_PREFIX);
+        let mut total = 0;
+        loop {
+            let reaped: i64 = REAP_SCRIPT
+                .exec(
+                    &mut self.connection,
+                    &[PENDING_KEY, INFLIGHT_KEY],
+                    &[processing_prefix.clone(), REAP_BATCH_SIZE.to_string()],
+                )
+                .await?;
+            total += reaped as usize;
+            if (reaped as usize) < REAP_BATCH_SIZE {
+                break;
+            }
+        }
+
+        if total > 0 {
+            warn!("回收了 {} 条超时未确认的队列消息", total);
+        }
+        Ok(total)
+    }
+
+    /// 启动后台回收任务，每隔 `interval` 执行一次 [`RedisQueue::reap_expired`]
+    ///
+    /// 不再需要时通过返回的 `JoinHandle::abort` 停止任务
+    pub fn spawn_reaper(&self, interval: Duration) -> JoinHandle<()>
+    where
+        T: 'static,
+    {
+        let mut queue = self.clone();
+        info!("启动队列回收任务，间隔 {:?}", interval);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = queue.reap_expired().await {
+                    warn!("队列回收失败: {}", e);
+                }
+            }
+        })
+    }
+
+    /// 待处理的消息数
+    pub async fn len(&mut self) -> RedisResult<usize> {
+        let len = self.connection.llen(PENDING_KEY).await?;
+        Ok(len as usize)
+    }
+
+    /// 队列中是否没有待处理的消息
+    pub async fn is_empty(&mut self) -> RedisResult<bool> {
+        Ok(self.len().await? == 0)
+    }
+
+    /// 已取出但尚未确认的消息数（仅可靠模式）
+    pub async fn in_flight(&mut self) -> RedisResult<usize> {
+        self.connection
+            .query(redis::cmd("ZCARD").arg(self.connection.prefixed_key(INFLIGHT_KEY)))
+            .await
+    }
+
+    /// 当前消费者的处理中列表键名
+    fn processing_key(&self) -> String {
+        format!("{}{}", PROCESSING_KEY_PREFIX, self.config.consumer_id)
+    }
+
+    /// 解析消息并构建投递
+    fn delivery(&self, raw: String, reliable: bool) -> RedisResult<Delivery<T>> {
+        let envelope: Envelope<T> =
+            serde_json::from_str(&raw).map_err(|e| RedisError::deserialization(e.to_string()))?;
+        Ok(Delivery {
+            id: envelope.id,
+            payload: envelope.payload,
+            raw,
+            reliable,
+            connection: self.connection.clone(),
+            config: self.config.clone(),
+        })
+    }
+}
+
+/// 从队列取出的一条消息
+///
+/// 可靠模式下需要调用 [`Delivery::ack`] 或 [`Delivery::nack`]，未确认就被丢弃的消息
+/// 在可见性超时后由回收任务重新入队
+pub struct Delivery<T> {
+    id: String,
+    payload: T,
+    /// 队列中保存的原始消息，用于从处理中列表移除
+    raw: String,
+    reliable: bool,
+    connection: RedisConnection,
+    config: Arc<RedisQueueConfig>,
+}
+
+impl<T> Delivery<T> {
+    /// 消息 ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 消息内容
+    pub fn payload(&self) -> &T {
+        &self.payload
+    }
+
+    /// 取出消息内容（可靠模式下消息仍未确认）
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+
+    /// 确认消息已处理完成，返回 false 表示消息已因可见性超时被重新入队
+    ///
+    /// 普通模式下消息取出时即已移除，直接返回 true
+    pub async fn ack(mut self) -> RedisResult<bool> {
+        if !self.reliable {
+            return Ok(true);
+        }
+        let (processing, member) = self.tracking_keys();
+        let removed: i64 = ACK_SCRIPT
+            .exec(
+                &mut self.connection,
+                &[processing.as_str(), INFLIGHT_KEY],
+                &[self.raw.as_str(), member.as_str()],
+            )
+            .await?;
+        Ok(removed > 0)
+    }
+
+    /// 放弃处理，将消息放回队列头部以便立即重试，返回 false 表示消息已被回收任务重新入队
+    pub async fn nack(mut self) -> RedisResult<bool> {
+        if !self.reliable {
+            self.connection
+                .rpush(PENDING_KEY, self.raw.as_str())
+                .await?;
+            return Ok(true);
+        }
+        let (processing, member) = self.tracking_keys();
+        let requeued: i64 = NACK_SCRIPT
+            .exec(
+                &mut self.connection,
+                &[processing.as_str(), INFLIGHT_KEY, PENDING_KEY],
+                &[self.raw.as_str(), member.as_str()],
+            )
+            .await?;
+        Ok(requeued > 0)
+    }
+
+    /// 处理中列表键名及在途集合中的成员
+    fn tracking_keys(&self) -> (String, String) {
+        (
+            format!("{}{}", PROCESSING_KEY_PREFIX, self.config.consumer_id),
+            format!("{}|{}", self.config.consumer_id, self.raw),
+        )
+    }
+}
+
+/// 入队脚本：长度达到上限时返回 -1，否则 LPUSH 并返回新长度
+static ENQUEUE_SCRIPT: LazyLock<RedisScript> = LazyLock::new(|| {
+    RedisScript::new(
+        r#"
+        local max = tonumber(ARGV[2])
+        if max > 0 and redis.call('LLEN', KEYS[1]) >= max then
+            return -1
+        end
+        return redis.call('LPUSH', KEYS[1], ARGV[1])
+        "#,
+    )
+});
+
+/// 可靠出队脚本：LMOVE 到处理中列表，并以服务端时间记录可见性截止时间
+static DEQUEUE_SCRIPT: LazyLock<RedisScript> = LazyLock::new(|| {
+    RedisScript::new(
+        r#"
+        local raw = redis.call('LMOVE', KEYS[1], KEYS[2], 'RIGHT', 'LEFT')
+        if not raw then
+            return false
+        end
+        local now = redis.call('TIME')
+        local deadline = now[1] * 1000 + math.floor(now[2] / 1000) + tonumber(ARGV[1])
+        redis.call('ZADD', KEYS[3], deadline, ARGV[2] .. '|' .. raw)
+        return raw
+        "#,
+    )
+});
+
+/// 确认脚本：从在途集合与处理中列表移除消息，返回移除数量
+static ACK_SCRIPT: LazyLock<RedisScript> = LazyLock::new(|| {
+    RedisScript::new(
+        r#"
+        redis.call('ZREM', KEYS[2], ARGV[2])
+        return redis.call('LREM', KEYS[1], 1, ARGV[1])
+        "#,
+    )
+});
+
+/// 否认脚本：消息仍在处理中列表时移回待处理列表的出队端
+static NACK_SCRIPT: LazyLock<RedisScript> = LazyLock::new(|| {
+    RedisScript::new(
+        r#"
+        redis.call('ZREM', KEYS[2], ARGV[2])
+        if redis.call('LREM', KEYS[1], 1, ARGV[1]) > 0 then
+            redis.call('RPUSH', KEYS[3], ARGV[1])
+            return 1
+        end
+        return 0
+        "#,
+    )
+});
+
+/// 回收脚本：将可见性截止时间已过的消息从各消费者的处理中列表移回待处理列表
+///
+/// 处理中列表的键名由在途成员中的消费者标识拼接而成，因此不支持 Redis Cluster
+static REAP_SCRIPT: LazyLock<RedisScript> = LazyLock::new(|| {
+    RedisScript::new(
+        r#"
+        local now = redis.call('TIME')
+        local ms = now[1] * 1000 + math.floor(now[2] / 1000)
+        local expired = redis.call('ZRANGEBYSCORE', KEYS[2], '-inf', ms, 'LIMIT', 0, tonumber(ARGV[2]))
+        for _, member in ipairs(expired) do
+            local sep = string.find(member, '|', 1, true)
+            local raw = string.sub(member, sep + 1)
+            redis.call('ZREM', KEYS[2], member)
+            if redis.call('LREM', ARGV[1] .. string.sub(member, 1, sep - 1), 1, raw) > 0 then
+                redis.call('RPUSH', KEYS[1], raw)
+            end
+        end
+        return #expired
+        "#,
+    )
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Job {
+        id: u32,
+        name: String,
+    }
+
+    fn job(id: u32) -> Job {
+        Job {
+            id,
+            name: format!("job-{}", id),
+        }
+    }
+
+    /// 清理队列使用的键
+    async fn reset(conn: &RedisConnection, name: &str, consumers: &[&str]) {
+        let mut scoped = conn.with_prefix(&format!("{}:", name));
+        let mut keys = vec![PENDING_KEY.to_string(), INFLIGHT_KEY.to_string()];
+        keys.extend(
+            consumers
+                .iter()
+                .map(|consumer| format!("{}{}", PROCESSING_KEY_PREFIX, consumer)),
+        );
+        scoped.del(keys).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fifo_and_bounded_enqueue() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let name = "clamber:test:queue:bounded";
+        reset(&conn, name, &[]).await;
+
+        let config = RedisQueueConfig {
+            max_len: Some(2),
+            ..RedisQueueConfig::default()
+        };
+        let mut queue: RedisQueue<Job> = RedisQueue::with_config(&conn, name, config);
+        assert!(queue.enqueue(&job(1)).await.unwrap());
+        assert!(queue.enqueue(&job(2)).await.unwrap());
+        assert!(!queue.enqueue(&job(3)).await.unwrap());
+        assert_eq!(queue.len().await.unwrap(), 2);
+
+        let first = queue
+            .dequeue(Duration::from_secs(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.payload(), &job(1));
+        assert!(first.ack().await.unwrap());
+
+        // 普通模式下 nack 会把消息放回队列头部
+        let second = queue
+            .dequeue(Duration::from_secs(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(second.nack().await.unwrap());
+        let again = queue
+            .dequeue(Duration::from_secs(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(again.into_payload(), job(2));
+
+        assert!(
+            queue
+                .dequeue(Duration::from_secs(1))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reaper_requeues_crashed_delivery() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let name = "clamber:test:queue:reliable";
+        reset(&conn, name, &["worker-a", "worker-b"]).await;
+
+        let reliable = |consumer: &str| RedisQueueConfig {
+            reliable: true,
+            consumer_id: consumer.to_string(),
+            visibility_timeout: Duration::from_millis(300),
+            poll_interval: Duration::from_millis(20),
+            ..RedisQueueConfig::default()
+        };
+        let mut worker_a: RedisQueue<Job> =
+            RedisQueue::with_config(&conn, name, reliable("worker-a"));
+        let mut worker_b: RedisQueue<Job> =
+            RedisQueue::with_config(&conn, name, reliable("worker-b"));
+
+        worker_a.enqueue(&job(7)).await.unwrap();
+
+        // worker-a 取出后未确认即崩溃
+        let delivery = worker_a
+            .dequeue(Duration::from_secs(1))
+            .await
+            .unwrap()
+            .unwrap();
+        let id = delivery.id().to_string();
+        drop(delivery);
+        assert_eq!(worker_a.in_flight().await.unwrap(), 1);
+        assert!(
+            worker_b
+                .dequeue(Duration::from_millis(100))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // 可见性超时前不会回收
+        assert_eq!(worker_b.reap_expired().await.unwrap(), 0);
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(worker_b.reap_expired().await.unwrap(), 1);
+        assert_eq!(worker_b.in_flight().await.unwrap(), 0);
+
+        let redelivered = worker_b
+            .dequeue(Duration::from_secs(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(redelivered.id(), id);
+        assert_eq!(redelivered.payload(), &job(7));
+        assert!(redelivered.ack().await.unwrap());
+
+        assert!(worker_a.is_empty().await.unwrap());
+        assert_eq!(worker_a.in_flight().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_late_ack_after_reap() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let name = "clamber:test:queue:late_ack";
+        reset(&conn, name, &["slow"]).await;
+
+        let config = RedisQueueConfig {
+            reliable: true,
+            consumer_id: "slow".to_string(),
+            visibility_timeout: Duration::from_millis(100),
+            ..RedisQueueConfig::default()
+        };
+        let mut queue: RedisQueue<Job> = RedisQueue::with_config(&conn, name, config);
+        queue.enqueue(&job(1)).await.unwrap();
+
+        let delivery = queue
+            .dequeue(Duration::from_secs(1))
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(queue.reap_expired().await.unwrap(), 1);
+
+        // 消息已被重新入队，迟到的确认返回 false
+        assert!(!delivery.ack().await.unwrap());
+        assert_eq!(queue.len().await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,423 @@
+//! Redis 命令重试模块
+//!
+//! ConnectionManager 只负责断线重连，单条命令失败时错误会直接返回。
+//! 本模块提供命令级别的重试策略，对连接断开、LOADING、BUSY 等暂时性错误按指数退避重试。
+//! 连接断开或超时时命令可能已经执行，这类错误只对重复执行结果不变的命令重试
+
+use crate::redis::{RedisError, RedisResult};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::hash::{BuildHasher, RandomState};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// 命令重试策略
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RetryPolicy {
+    /// 最大尝试次数（包含首次执行），1 表示不重试
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// 首次重试的基础延迟（毫秒），之后每次翻倍
+    #[serde(default = "default_base_delay")]
+    pub base_delay_ms: u64,
+
+    /// 单次重试的最大延迟（毫秒）
+    #[serde(default = "default_max_delay")]
+    pub max_delay_ms: u64,
+
+    /// 是否对延迟加入随机抖动，避免大量客户端同时重试
+    #[serde(default = "default_jitter")]
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            base_delay_ms: default_base_delay(),
+            max_delay_ms: default_max_delay(),
+            jitter: default_jitter(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 创建重试策略（默认启用抖动）
+    pub fn new(max_attempts: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            base_delay_ms: base_delay.as_millis() as u64,
+            max_delay_ms: max_delay.as_millis() as u64,
+            jitter: true,
+        }
+    }
+
+    /// 不进行重试的策略
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// 设置是否启用抖动
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// 是否会进行重试
+    pub fn is_enabled(&self) -> bool {
+        self.max_attempts > 1
+    }
+
+    /// 第 `retry` 次重试（从 1 开始）前的基础等待时间：`base * 2^(retry-1)`，不超过最大延迟
+    pub fn backoff_delay(&self, retry: u32) -> Duration {
+        let factor = 2u64.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base_delay_ms.saturating_mul(factor);
+        Duration::from_millis(delay.min(self.max_delay_ms))
+    }
+
+    /// 第 `retry` 次重试前实际等待的时间
+    ///
+    /// 启用抖动时在 `[delay/2, delay]` 范围内随机取值
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let delay = self.backoff_delay(retry);
+        if !self.jitter {
+            return delay;
+        }
+
+        let half = delay.as_millis() as u64 / 2;
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let random = RandomState::new().hash_one(seed);
+        Duration::from_millis(half + random % (half + 1))
+    }
+
+    /// 按策略执行只读或幂等的操作，暂时性错误会在等待后重试，其余错误立即返回
+    pub async fn execute<T, F, Fut>(&self, operation: F) -> RedisResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = RedisResult<T>>,
+    {
+        self.execute_with(true, operation).await
+    }
+
+    /// 按策略执行操作，`idempotent` 为 false 时只重试命令一定未执行的错误，
+    /// 见 [`RedisError::is_retriable_for`]
+    pub async fn execute_with<T, F, Fut>(
+        &self,
+        idempotent: bool,
+        mut operation: F,
+    ) -> RedisResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = RedisResult<T>>,
+    {
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            match operation().await {
+                Err(e) if attempt < max_attempts && e.is_retriable_for(idempotent) => {
+                    let delay = self.delay_for(attempt);
+                    warn!(
+                        "Redis 命令执行失败（第 {}/{} 次），{:?} 后重试: {}",
+                        attempt, max_attempts, delay, e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl RedisError {
+    /// 判断是否为可重试的暂时性错误
+    ///
+    /// 包括连接断开、IO 错误、超时，以及服务端返回的 LOADING、BUSY、TRYAGAIN；
+    /// WRONGTYPE 等命令本身的错误重试也不会成功，因此不可重试
+    pub fn is_retriable(&self) -> bool {
+        if self.is_rejected_by_server() {
+            return true;
+        }
+        match self {
+            RedisError::Redis(e) => e.is_connection_dropped() || e.is_io_error() || e.is_timeout(),
+            RedisError::Connection { .. } | RedisError::Timeout { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// 判断对指定类型的命令重试是否安全
+    ///
+    /// 连接断开、IO 错误和超时时命令可能已在服务端执行，只有 `idempotent` 的命令才重试；
+    /// INCR、LPUSH 等非幂等命令只在服务端明确拒绝执行（LOADING、BUSY、TRYAGAIN）时重试
+    pub fn is_retriable_for(&self, idempotent: bool) -> bool {
+        if idempotent {
+            self.is_retriable()
+        } else {
+            self.is_rejected_by_server()
+        }
+    }
+
+    /// 服务端暂时拒绝执行命令（LOADING、BUSY、TRYAGAIN），此时命令一定未执行
+    fn is_rejected_by_server(&self) -> bool {
+        match self {
+            RedisError::Redis(e) => {
+                matches!(
+                    e.kind(),
+                    redis::ErrorKind::BusyLoadingError | redis::ErrorKind::TryAgain
+                ) || matches!(e.code(), Some("LOADING" | "BUSY" | "TRYAGAIN"))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// 重复执行时数据与返回值都不变的命令，连接断开或超时后可以安全重试
+///
+/// 未列出的命令（INCR、LPUSH、SET NX、EVAL 等）只在服务端拒绝执行时重试
+const IDEMPOTENT_COMMANDS: &[&str] = &[
+    "BITCOUNT",
+    "BITPOS",
+    "DBSIZE",
+    "EXISTS",
+    "GEODIST",
+    "GEOHASH",
+    "GEOPOS",
+    "GEOSEARCH",
+    "GET",
+    "GETBIT",
+    "GETRANGE",
+    "HEXISTS",
+    "HGET",
+    "HGETALL",
+    "HKEYS",
+    "HLEN",
+    "HMGET",
+    "HSCAN",
+    "HVALS",
+    "INFO",
+    "KEYS",
+    "LINDEX",
+    "LLEN",
+    "LRANGE",
+    "MGET",
+    "PFCOUNT",
+    "PING",
+    "PTTL",
+    "SCAN",
+    "SCARD",
+    "SISMEMBER",
+    "SMEMBERS",
+    "SSCAN",
+    "STRLEN",
+    "TTL",
+    "TYPE",
+    "XLEN",
+    "XRANGE",
+    "ZCARD",
+    "ZCOUNT",
+    "ZRANGE",
+    "ZRANGEBYSCORE",
+    "ZRANK",
+    "ZREVRANGE",
+    "ZREVRANK",
+    "ZSCAN",
+    "ZSCORE",
+];
+
+/// 判断命令（大写名称）重复执行时数据与返回值是否不变
+pub(crate) fn is_idempotent_command(name: &str) -> bool {
+    IDEMPOTENT_COMMANDS.binary_search(&name).is_ok()
+}
+
+fn default_max_attempts() -> u32 {
+    1
+}
+
+fn default_base_delay() -> u64 {
+    50
+}
+
+fn default_max_delay() -> u64 {
+    2000
+}
+
+fn default_jitter() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    /// 注入故障的模拟命令：按顺序返回预设错误，之后返回成功
+    struct FaultInjector {
+        faults: Mutex<Vec<RedisError>>,
+        calls: Mutex<Vec<Instant>>,
+    }
+
+    impl FaultInjector {
+        fn new(faults: Vec<RedisError>) -> Self {
+            Self {
+                faults: Mutex::new(faults),
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+
+        async fn call(&self) -> RedisResult<&'static str> {
+            self.calls.lock().unwrap().push(Instant::now());
+            let mut faults = self.faults.lock().unwrap();
+            if faults.is_empty() {
+                Ok("OK")
+            } else {
+                Err(faults.remove(0))
+            }
+        }
+
+        fn attempts(&self) -> usize {
+            self.calls.lock().unwrap().len()
+        }
+
+        fn gaps(&self) -> Vec<Duration> {
+            let calls = self.calls.lock().unwrap();
+            calls.windows(2).map(|w| w[1] - w[0]).collect()
+        }
+    }
+
+    fn connection_dropped() -> RedisError {
+        RedisError::from(redis::RedisError::from(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "connection reset",
+        )))
+    }
+
+    fn loading() -> RedisError {
+        RedisError::from(redis::RedisError::from((
+            redis::ErrorKind::BusyLoadingError,
+            "LOADING Redis is loading the dataset in memory",
+        )))
+    }
+
+    fn wrong_type() -> RedisError {
+        RedisError::from(redis::RedisError::from((
+            redis::ErrorKind::TypeError,
+            "WRONGTYPE Operation against a key holding the wrong kind of value",
+        )))
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy::new(
+            max_attempts,
+            Duration::from_millis(20),
+            Duration::from_millis(1000),
+        )
+        .with_jitter(false)
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert!(connection_dropped().is_retriable());
+        assert!(loading().is_retriable());
+        assert!(RedisError::timeout("GET").is_retriable());
+        assert!(!wrong_type().is_retriable());
+        assert!(!RedisError::serialization("bad json").is_retriable());
+    }
+
+    #[test]
+    fn test_ambiguous_errors_only_retried_for_idempotent_commands() {
+        // 连接断开或超时时命令可能已执行，非幂等命令不重试
+        assert!(connection_dropped().is_retriable_for(true));
+        assert!(!connection_dropped().is_retriable_for(false));
+        assert!(!RedisError::timeout("INCR").is_retriable_for(false));
+        // 服务端拒绝执行时命令一定未执行，非幂等命令也可以重试
+        assert!(loading().is_retriable_for(false));
+        assert!(!wrong_type().is_retriable_for(true));
+
+        assert!(IDEMPOTENT_COMMANDS.windows(2).all(|w| w[0] < w[1]));
+        assert!(is_idempotent_command("GET"));
+        assert!(is_idempotent_command("ZRANGE"));
+        assert!(!is_idempotent_command("INCR"));
+        assert!(!is_idempotent_command("LPUSH"));
+        assert!(!is_idempotent_command("SET"));
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_operation_not_retried_after_connection_drop() {
+        let injector = FaultInjector::new(vec![connection_dropped()]);
+        let result = policy(5).execute_with(false, || injector.call()).await;
+        assert!(result.is_err());
+        assert_eq!(injector.attempts(), 1);
+
+        let injector = FaultInjector::new(vec![loading()]);
+        let result = policy(5).execute_with(false, || injector.call()).await;
+        assert_eq!(result.unwrap(), "OK");
+        assert_eq!(injector.attempts(), 2);
+    }
+
+    #[test]
+    fn test_backoff_delay_is_exponential_and_capped() {
+        let policy = RetryPolicy::new(10, Duration::from_millis(100), Duration::from_millis(500));
+        let delays: Vec<u64> = (1..=5)
+            .map(|n| policy.backoff_delay(n).as_millis() as u64)
+            .collect();
+        assert_eq!(delays, vec![100, 200, 400, 500, 500]);
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100), Duration::from_millis(1000));
+        for retry in 1..=4 {
+            let base = policy.backoff_delay(retry);
+            let delay = policy.delay_for(retry);
+            assert!(delay >= base / 2 && delay <= base);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transient_errors_are_retried_with_exponential_spacing() {
+        let injector = FaultInjector::new(vec![connection_dropped(), loading()]);
+        let result = policy(5).execute(|| injector.call()).await;
+
+        assert_eq!(result.unwrap(), "OK");
+        assert_eq!(injector.attempts(), 3);
+        let gaps = injector.gaps();
+        assert!(gaps[0] >= Duration::from_millis(20));
+        assert!(gaps[1] >= Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn test_non_retriable_error_is_returned_immediately() {
+        let injector = FaultInjector::new(vec![wrong_type()]);
+        let result = policy(5).execute(|| injector.call()).await;
+
+        assert!(!result.unwrap_err().is_retriable());
+        assert_eq!(injector.attempts(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let injector = FaultInjector::new(vec![loading(), loading(), loading(), loading()]);
+        let result = policy(3).execute(|| injector.call()).await;
+
+        assert!(result.unwrap_err().is_retriable());
+        assert_eq!(injector.attempts(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_policy_does_not_retry() {
+        let injector = FaultInjector::new(vec![connection_dropped()]);
+        let result = RetryPolicy::disabled().execute(|| injector.call()).await;
+
+        assert!(result.is_err());
+        assert_eq!(injector.attempts(), 1);
+    }
+}
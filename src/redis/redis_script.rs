@@ -0,0 +1,183 @@
+//! Redis Lua 脚本模块
+//!
+//! 提供 Lua 脚本的加载与执行，优先使用 EVALSHA，脚本未缓存时自动回退到 EVAL
+
+use crate::redis::{RedisConnection, RedisError, RedisResult};
+use redis::{ErrorKind, FromRedisValue, ToRedisArgs, Value};
+
+/// Lua 脚本返回值转换
+///
+/// 为常用类型提供从 Redis 返回值的转换，转换失败时返回类型转换错误
+pub trait FromRedisScriptValue: Sized {
+    /// 从脚本返回值转换
+    fn from_script_value(value: Value) -> RedisResult<Self>;
+}
+
+macro_rules! impl_from_redis_script_value {
+    ($($ty:ty => $name:expr),* $(,)?) => {
+        $(
+            impl FromRedisScriptValue for $ty {
+                fn from_script_value(value: Value) -> RedisResult<Self> {
+                    <$ty as FromRedisValue>::from_redis_value(&value)
+                        .map_err(|_| RedisError::type_mismatch($name, format!("{:?}", value)))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_redis_script_value! {
+    i64 => "i64",
+    bool => "bool",
+    String => "String",
+    Option<i64> => "Option<i64>",
+    Option<String> => "Option<String>",
+    Vec<i64> => "Vec<i64>",
+    Vec<String> => "Vec<String>",
+}
+
+impl FromRedisScriptValue for Value {
+    fn from_script_value(value: Value) -> RedisResult<Self> {
+        Ok(value)
+    }
+}
+
+/// Redis Lua 脚本
+#[derive(Debug, Clone)]
+pub struct RedisScript {
+    /// 脚本源码
+    source: String,
+    /// 脚本 SHA1 摘要
+    sha: String,
+}
+
+impl RedisScript {
+    /// 从脚本源码创建
+    pub fn new(source: impl Into<String>) -> Self {
+        let source = source.into();
+        let sha = redis::Script::new(&source).get_hash().to_string();
+        Self { source, sha }
+    }
+
+    /// 获取脚本源码
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// 获取脚本 SHA1 摘要
+    pub fn sha(&self) -> &str {
+        &self.sha
+    }
+
+    /// 通过 SCRIPT LOAD 将脚本注册到服务端，返回服务端计算的 SHA1
+    pub async fn load(&self, conn: &mut RedisConnection) -> RedisResult<String> {
+        conn.query(redis::cmd("SCRIPT").arg("LOAD").arg(&self.source))
+            .await
+    }
+
+    /// 执行脚本
+    ///
+    /// 键会自动加上连接配置的键前缀。先尝试 EVALSHA，服务端返回 NOSCRIPT（脚本未加载或已被 SCRIPT FLUSH）时回退到 EVAL，
+    /// EVAL 执行后服务端会重新缓存该脚本
+    pub async fn exec<T, K, A>(
+        &self,
+        conn: &mut RedisConnection,
+        keys: &[K],
+        args: &[A],
+    ) -> RedisResult<T>
+    where
+        T: FromRedisScriptValue,
+        K: ToRedisArgs,
+        A: ToRedisArgs,
+    {
+        let keys = conn.prefixed(keys);
+        let mut evalsha = redis::cmd("EVALSHA");
+        evalsha.arg(&self.sha).arg(keys.len()).arg(&keys).arg(args);
+
+        let value = match conn.query::<Value>(&evalsha).await {
+            Err(RedisError::Redis(e)) if e.kind() == ErrorKind::NoScriptError => {
+                let mut eval = redis::cmd("EVAL");
+                eval.arg(&self.source).arg(keys.len()).arg(&keys).arg(args);
+                conn.query::<Value>(&eval).await?
+            }
+            result => result?,
+        };
+
+        T::from_script_value(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+
+    /// 比较并设置：当前值等于期望值时写入新值并返回 1，否则返回 0
+    const COMPARE_AND_SET: &str = r#"
+        if redis.call('GET', KEYS[1]) == ARGV[1] then
+            redis.call('SET', KEYS[1], ARGV[2])
+            return 1
+        end
+        return 0
+    "#;
+
+    #[test]
+    fn test_script_sha() {
+        let script = RedisScript::new("return 1");
+        assert_eq!(script.sha(), "e0e1f9fabfc9d4800c877a703b823ac0578ff8db");
+        assert_eq!(script.source(), "return 1");
+    }
+
+    #[test]
+    fn test_from_script_value() {
+        assert_eq!(i64::from_script_value(Value::Int(7)).unwrap(), 7);
+        assert_eq!(
+            String::from_script_value(Value::BulkString(b"ok".to_vec())).unwrap(),
+            "ok"
+        );
+        assert_eq!(
+            Vec::<String>::from_script_value(Value::Array(vec![
+                Value::BulkString(b"a".to_vec()),
+                Value::BulkString(b"b".to_vec()),
+            ]))
+            .unwrap(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(
+            Option::<String>::from_script_value(Value::Nil).unwrap(),
+            None
+        );
+
+        let error = i64::from_script_value(Value::BulkString(b"abc".to_vec())).unwrap_err();
+        assert!(matches!(error, RedisError::TypeMismatch { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_compare_and_set_with_flush_fallback() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:script:cas";
+        conn.set_builtin(key, "v1").await.unwrap();
+
+        let script = RedisScript::new(COMPARE_AND_SET);
+        assert_eq!(script.load(&mut conn).await.unwrap(), script.sha());
+
+        let swapped: i64 = script.exec(&mut conn, &[key], &["v1", "v2"]).await.unwrap();
+        assert_eq!(swapped, 1);
+        let swapped: i64 = script.exec(&mut conn, &[key], &["v1", "v3"]).await.unwrap();
+        assert_eq!(swapped, 0);
+
+        // 清空脚本缓存后 EVALSHA 返回 NOSCRIPT，应自动回退到 EVAL
+        let _: () = conn.query(redis::cmd("SCRIPT").arg("FLUSH")).await.unwrap();
+        let swapped: i64 = script.exec(&mut conn, &[key], &["v2", "v4"]).await.unwrap();
+        assert_eq!(swapped, 1);
+        assert_eq!(conn.get_builtin(key).await.unwrap(), Some("v4".to_string()));
+
+        let value: String = conn
+            .eval_script("return redis.call('GET', KEYS[1])", &[key], &[] as &[&str])
+            .await
+            .unwrap();
+        assert_eq!(value, "v4");
+    }
+}
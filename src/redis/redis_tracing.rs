@@ -0,0 +1,248 @@
+//! Redis 命令追踪模块
+//!
+//! 为每条命令创建遵循 OpenTelemetry 数据库语义约定的 tracing span
+//! （`db.system = "redis"`、`db.operation`、`db.redis.key`），命令完成后在 span 上记录耗时，
+//! 超过 `slow_command_threshold_ms` 时输出 warn 事件。
+//! 键名按 [`KeyLogging`] 原样、截断或哈希后记录，也可以完全不记录
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{Level, Span, field, warn};
+
+/// 截断模式下记录的最大字符数
+const TRUNCATED_KEY_CHARS: usize = 64;
+
+/// 追踪时键名的记录方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyLogging {
+    /// 不记录键名
+    Off,
+    /// 记录前 64 个字符
+    #[default]
+    Truncated,
+    /// 记录键名的 FNV-1a 哈希，可用于关联同一个键的命令而不暴露键名
+    Hashed,
+    /// 记录完整键名
+    Full,
+}
+
+/// 命令追踪设置，由同一连接派生的连接共享
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CommandTracer {
+    key_logging: KeyLogging,
+    slow_threshold: Option<Duration>,
+}
+
+impl CommandTracer {
+    /// 创建追踪设置，`slow_threshold_ms` 为 0 时不输出慢命令事件
+    pub(crate) fn new(key_logging: KeyLogging, slow_threshold_ms: u64) -> Self {
+        Self {
+            key_logging,
+            slow_threshold: (slow_threshold_ms > 0)
+                .then(|| Duration::from_millis(slow_threshold_ms)),
+        }
+    }
+
+    /// 是否需要记录键名：未关闭键名记录且命令 span 会被采集
+    pub(crate) fn records_keys(&self) -> bool {
+        self.key_logging != KeyLogging::Off && tracing::enabled!(Level::DEBUG)
+    }
+
+    /// 按记录方式格式化键名，多个键时只记录第一个并附带总数
+    ///
+    /// 不需要记录键名时返回 None，避免无谓的格式化开销
+    pub(crate) fn key_field(&self, keys: &[Vec<u8>]) -> Option<String> {
+        if !self.records_keys() {
+            return None;
+        }
+        let first = keys.first()?;
+
+        let mut key = match self.key_logging {
+            KeyLogging::Off => return None,
+            KeyLogging::Full => String::from_utf8_lossy(first).into_owned(),
+            KeyLogging::Truncated => {
+                let key = String::from_utf8_lossy(first);
+                match key.char_indices().nth(TRUNCATED_KEY_CHARS) {
+                    Some((index, _)) => format!("{}…", &key[..index]),
+                    None => key.into_owned(),
+                }
+            }
+            KeyLogging::Hashed => format!("{:016x}", fnv1a(first)),
+        };
+        if keys.len() > 1 {
+            key.push_str(&format!(" (+{})", keys.len() - 1));
+        }
+        Some(key)
+    }
+
+    /// 创建命令 span
+    pub(crate) fn span(&self, command: &str, key: Option<&str>) -> Span {
+        let span = tracing::debug_span!(
+            "redis.command",
+            db.system = "redis",
+            db.operation = command,
+            db.redis.key = field::Empty,
+            duration_ms = field::Empty,
+            otel.status_code = field::Empty,
+        );
+        if let Some(key) = key {
+            span.record("db.redis.key", key);
+        }
+        span
+    }
+
+    /// 在 span 关闭前记录耗时与结果，超过慢命令阈值时输出 warn 事件
+    pub(crate) fn finish(
+        &self,
+        span: &Span,
+        command: &str,
+        key: Option<&str>,
+        elapsed: Duration,
+        failed: bool,
+    ) {
+        let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+        span.record("duration_ms", elapsed_ms);
+        span.record("otel.status_code", if failed { "ERROR" } else { "OK" });
+
+        if let Some(threshold) = self.slow_threshold {
+            if elapsed > threshold {
+                warn!(
+                    parent: span,
+                    db.operation = command,
+                    db.redis.key = key,
+                    duration_ms = elapsed_ms,
+                    threshold_ms = threshold.as_millis() as u64,
+                    "慢 Redis 命令"
+                );
+            }
+        }
+    }
+}
+
+/// 64 位 FNV-1a 哈希，结果与平台和 Rust 版本无关
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    //! 捕获 span 与事件字段的测试订阅器
+
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer};
+    use tracing_subscriber::registry::LookupSpan;
+
+    /// 记录到的 span 或事件字段
+    pub(crate) type Fields = HashMap<String, String>;
+
+    #[derive(Clone, Default)]
+    pub(crate) struct CapturingLayer {
+        spans: Arc<Mutex<HashMap<u64, Fields>>>,
+        events: Arc<Mutex<Vec<Fields>>>,
+    }
+
+    struct FieldVisitor<'a>(&'a mut Fields);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl<S> Layer<S> for CapturingLayer
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+            let mut fields = Fields::new();
+            fields.insert("name".to_string(), attrs.metadata().name().to_string());
+            attrs.record(&mut FieldVisitor(&mut fields));
+            self.spans.lock().unwrap().insert(id.into_u64(), fields);
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            if let Some(fields) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+                values.record(&mut FieldVisitor(fields));
+            }
+        }
+
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let mut fields = Fields::new();
+            fields.insert("level".to_string(), event.metadata().level().to_string());
+            event.record(&mut FieldVisitor(&mut fields));
+            self.events.lock().unwrap().push(fields);
+        }
+    }
+
+    impl CapturingLayer {
+        /// 所有 span 的字段
+        pub(crate) fn spans(&self) -> Vec<Fields> {
+            self.spans.lock().unwrap().values().cloned().collect()
+        }
+
+        /// 所有事件的字段
+        pub(crate) fn events(&self) -> Vec<Fields> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::CapturingLayer;
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn keys(keys: &[&str]) -> Vec<Vec<u8>> {
+        keys.iter().map(|key| key.as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_key_field_formats() {
+        let _guard = tracing::subscriber::set_default(
+            tracing_subscriber::registry().with(CapturingLayer::default()),
+        );
+
+        let full = CommandTracer::new(KeyLogging::Full, 0);
+        assert_eq!(full.key_field(&keys(&["user:1"])).unwrap(), "user:1");
+        assert_eq!(full.key_field(&keys(&["a", "b", "c"])).unwrap(), "a (+2)");
+        assert_eq!(full.key_field(&[]), None);
+
+        let long = "k".repeat(100);
+        let truncated = CommandTracer::new(KeyLogging::Truncated, 0)
+            .key_field(&keys(&[&long]))
+            .unwrap();
+        assert_eq!(truncated, format!("{}…", "k".repeat(TRUNCATED_KEY_CHARS)));
+
+        let hashed = CommandTracer::new(KeyLogging::Hashed, 0);
+        let first = hashed.key_field(&keys(&["user:1"])).unwrap();
+        assert_eq!(first.len(), 16);
+        assert!(!first.contains("user"));
+        assert_eq!(hashed.key_field(&keys(&["user:1"])).unwrap(), first);
+
+        assert_eq!(
+            CommandTracer::new(KeyLogging::Off, 0).key_field(&keys(&["user:1"])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_key_logging_deserialize() {
+        let logging: KeyLogging = serde_yaml::from_str("hashed").unwrap();
+        assert_eq!(logging, KeyLogging::Hashed);
+        assert_eq!(KeyLogging::default(), KeyLogging::Truncated);
+    }
+}
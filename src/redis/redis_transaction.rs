@@ -0,0 +1,390 @@
+//! Redis 事务模块
+//!
+//! 基于 MULTI/EXEC 提供事务支持，配合 WATCH 实现乐观锁：
+//! 被 WATCH 的键在 EXEC 前被其他客户端修改时，事务不会执行并返回 [`RedisError::TransactionAborted`]
+
+use crate::redis::redis_connection::{command_name, decode_json};
+use crate::redis::{RedisConnection, RedisError, RedisResult};
+use redis::aio::MultiplexedConnection;
+use redis::{FromRedisValue, ToRedisArgs};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::ops::{Deref, DerefMut};
+use tracing::debug;
+
+/// 乐观锁事务的最大尝试次数，超过后返回 [`RedisError::TransactionAborted`]
+const MAX_WATCH_ATTEMPTS: u32 = 64;
+
+/// 事务中排队等待 MULTI/EXEC 提交的写入命令
+///
+/// 所有键都会加上所属连接的键前缀，由 [`RedisTransaction`] 和
+/// [`RedisConnection::multi_exec`] 共用
+pub struct TransactionQueue {
+    /// 创建事务的连接，用于键前缀和命令统计
+    owner: RedisConnection,
+    /// 排队中的命令
+    pipeline: redis::Pipeline,
+}
+
+impl TransactionQueue {
+    /// 为连接创建空的命令队列
+    pub(crate) fn new(owner: &RedisConnection) -> Self {
+        let mut pipeline = redis::pipe();
+        pipeline.atomic();
+        Self {
+            owner: owner.clone(),
+            pipeline,
+        }
+    }
+
+    /// 排队的命令
+    pub(crate) fn pipeline(&self) -> &redis::Pipeline {
+        &self.pipeline
+    }
+
+    /// 排队任意命令，键需要调用方通过 [`RedisConnection::prefixed_key`] 自行加前缀
+    pub fn add_command(&mut self, cmd: redis::Cmd) -> &mut Self {
+        self.pipeline.add_command(cmd);
+        self
+    }
+
+    /// 排队 SET
+    pub fn set<K: ToRedisArgs, V: ToRedisArgs>(&mut self, key: K, value: V) -> &mut Self {
+        self.pipeline.set(self.owner.prefixed(key), value);
+        self
+    }
+
+    /// 排队以 JSON 序列化后的 SET，与 [`RedisConnection::set_json_ex`] 一样按配置压缩
+    pub fn set_json<T: Serialize>(&mut self, key: &str, value: &T) -> RedisResult<&mut Self> {
+        let raw = self.owner.encode_json_value(value)?;
+        Ok(self.set(key, raw))
+    }
+
+    /// 排队 DEL
+    pub fn del<K: ToRedisArgs>(&mut self, key: K) -> &mut Self {
+        self.pipeline.del(self.owner.prefixed(key));
+        self
+    }
+
+    /// 排队 INCRBY
+    pub fn incr<K: ToRedisArgs>(&mut self, key: K, delta: i64) -> &mut Self {
+        self.pipeline.incr(self.owner.prefixed(key), delta);
+        self
+    }
+
+    /// 排队 HSET
+    pub fn hset<K, F, V>(&mut self, key: K, field: F, value: V) -> &mut Self
+    where
+        K: ToRedisArgs,
+        F: ToRedisArgs,
+        V: ToRedisArgs,
+    {
+        self.pipeline.hset(self.owner.prefixed(key), field, value);
+        self
+    }
+
+    /// 排队 EXPIRE
+    pub fn expire<K: ToRedisArgs>(&mut self, key: K, seconds: i64) -> &mut Self {
+        self.pipeline.expire(self.owner.prefixed(key), seconds);
+        self
+    }
+}
+
+/// Redis 事务
+///
+/// 事务使用独占连接，WATCH 期间的读取通过 [`get`](Self::get) 等方法立即执行，
+/// 写入命令通过 [`TransactionQueue`] 的方法先在本地排队，调用 [`exec`](Self::exec)
+/// 时通过 MULTI/EXEC 原子提交。所有键都会加上所属连接的键前缀
+pub struct RedisTransaction {
+    /// 事务独占的连接
+    connection: MultiplexedConnection,
+    /// 排队中的命令
+    queue: TransactionQueue,
+}
+
+impl Deref for RedisTransaction {
+    type Target = TransactionQueue;
+
+    fn deref(&self) -> &Self::Target {
+        &self.queue
+    }
+}
+
+impl DerefMut for RedisTransaction {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.queue
+    }
+}
+
+impl RedisTransaction {
+    /// 为连接创建事务
+    async fn new(owner: &RedisConnection) -> RedisResult<Self> {
+        let connection = owner.dedicated_connection().await?;
+        Ok(Self {
+            connection,
+            queue: TransactionQueue::new(owner),
+        })
+    }
+
+    /// 监视键（WATCH），键在 EXEC 前被修改时事务中止
+    pub async fn watch<K: ToRedisArgs>(&mut self, keys: K) -> RedisResult<()> {
+        let mut cmd = redis::cmd("WATCH");
+        cmd.arg(self.queue.owner.prefixed(keys));
+        self.queue
+            .owner
+            .observe("WATCH", cmd.query_async(&mut self.connection))
+            .await
+    }
+
+    /// 取消监视所有键（UNWATCH）
+    pub async fn unwatch(&mut self) -> RedisResult<()> {
+        self.queue
+            .owner
+            .observe(
+                "UNWATCH",
+                redis::cmd("UNWATCH").query_async(&mut self.connection),
+            )
+            .await
+    }
+
+    /// 在事务连接上立即执行命令，用于 WATCH 之后读取当前值，键需要调用方自行加前缀
+    pub async fn query<T: FromRedisValue>(&mut self, cmd: &redis::Cmd) -> RedisResult<T> {
+        let command = command_name(cmd);
+        self.queue
+            .owner
+            .observe(&command, cmd.query_async(&mut self.connection))
+            .await
+    }
+
+    /// 立即读取键的值
+    pub async fn get<K: ToRedisArgs>(&mut self, key: K) -> RedisResult<Option<String>> {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg(self.queue.owner.prefixed(key));
+        self.query(&cmd).await
+    }
+
+    /// 立即读取 JSON 值并反序列化，压缩过的值会被透明解压
+    pub async fn get_json<T: DeserializeOwned>(&mut self, key: &str) -> RedisResult<Option<T>> {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg(self.queue.owner.prefixed_key(key));
+        decode_json(self.query(&cmd).await?)
+    }
+
+    /// 提交事务，返回各命令的结果
+    ///
+    /// 被 WATCH 的键已被修改时返回 [`RedisError::TransactionAborted`]
+    pub async fn exec<T: FromRedisValue>(mut self) -> RedisResult<T> {
+        self.commit().await
+    }
+
+    /// 提交排队的命令并清空队列，事务可以继续用于下一轮 WATCH
+    async fn commit<T: FromRedisValue>(&mut self) -> RedisResult<T> {
+        let result: Option<T> = self
+            .queue
+            .owner
+            .observe(
+                "EXEC",
+                self.queue.pipeline.query_async(&mut self.connection),
+            )
+            .await?;
+        self.queue.pipeline.clear();
+        result.ok_or_else(|| RedisError::transaction_aborted("被监视的键在提交前已被修改"))
+    }
+}
+
+impl RedisConnection {
+    /// 创建事务，排队的命令在 [`RedisTransaction::exec`] 时原子提交
+    pub async fn transaction(&self) -> RedisResult<RedisTransaction> {
+        RedisTransaction::new(self).await
+    }
+
+    /// 基于 WATCH 的乐观锁事务，被监视的键被并发修改时自动重试
+    ///
+    /// 每一轮先 WATCH `keys`，再调用 `body` 读取当前值并排队写入命令，最后提交；
+    /// `body` 返回错误时放弃事务并直接返回该错误
+    pub async fn watch_transaction<K, T, F>(&self, keys: &[K], mut body: F) -> RedisResult<T>
+    where
+        K: ToRedisArgs,
+        T: FromRedisValue,
+        F: AsyncFnMut(&mut RedisTransaction) -> RedisResult<()>,
+    {
+        let mut tx = self.transaction().await?;
+
+        for attempt in 1..=MAX_WATCH_ATTEMPTS {
+            tx.watch(keys).await?;
+            if let Err(e) = body(&mut tx).await {
+                tx.unwatch().await?;
+                return Err(e);
+            }
+
+            match tx.commit().await {
+                Err(e) if e.is_transaction_aborted() => {
+                    debug!("Redis 事务被并发修改中止，第 {} 次重试", attempt);
+                }
+                result => return result,
+            }
+        }
+
+        Err(RedisError::transaction_aborted(format!(
+            "乐观锁冲突，已重试 {} 次",
+            MAX_WATCH_ATTEMPTS
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::redis::RedisConnection;
+    use crate::redis::test_support::connect_test_redis;
+
+    /// 读取计数器并加一后写回，模拟读-改-写
+    async fn increment(conn: &RedisConnection, key: &str, times: usize) {
+        for _ in 0..times {
+            let _: () = conn
+                .watch_transaction(&[key], async |tx| {
+                    let current: i64 = tx
+                        .get(key)
+                        .await?
+                        .map(|raw| raw.parse().unwrap())
+                        .unwrap_or(0);
+                    // 让出执行权，使另一个事务有机会在读写之间修改键
+                    tokio::task::yield_now().await;
+                    tx.set(key, current + 1);
+                    Ok(())
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_transactions_never_lose_updates() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:tx:counter";
+        conn.del(key).await.unwrap();
+
+        let other = conn.clone();
+        tokio::join!(increment(&conn, key, 50), increment(&other, key, 50));
+
+        assert_eq!(
+            conn.get_builtin(key).await.unwrap(),
+            Some("100".to_string())
+        );
+        conn.del(key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_multi_exec_applies_all_commands() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:tx:multi";
+        conn.del(key).await.unwrap();
+
+        let (first, second): (i64, i64) = conn
+            .multi_exec(|queue| {
+                queue.incr(key, 1).incr(key, 1);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!((first, second), (1, 2));
+        assert_eq!(conn.get_builtin(key).await.unwrap(), Some("2".to_string()));
+        conn.del(key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_multi_exec_applies_key_prefix() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let scoped = conn.with_prefix("clamber:test:tx:scoped:");
+        scoped.del("counter").await.unwrap();
+
+        let _: ((), i64) = scoped
+            .multi_exec(|queue| {
+                queue.set("counter", 5).incr("counter", 1);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            conn.get_builtin("clamber:test:tx:scoped:counter")
+                .await
+                .unwrap(),
+            Some("6".to_string())
+        );
+        assert_eq!(conn.get_builtin("counter").await.unwrap(), None);
+        scoped.del("counter").await.unwrap();
+    }
+
+    #[cfg(feature = "redis-gzip")]
+    #[tokio::test]
+    async fn test_json_uses_compression_codec() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let mut config = conn.get_config().clone();
+        config.compression = crate::redis::CompressionCodec::Gzip;
+        config.compression_threshold_bytes = 16;
+        let mut conn = RedisConnection::new(config).await.unwrap();
+        let key = "clamber:test:tx:json";
+        let value = vec!["payload".repeat(8); 4];
+
+        conn.multi_exec::<(), _>(|queue| {
+            queue.set_json(key, &value)?;
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        // 写入的是压缩后的值，连接与事务都能透明解压读取
+        let raw: Vec<u8> = conn.query(redis::cmd("GET").arg(key)).await.unwrap();
+        assert!(!raw.starts_with(b"["));
+        assert_eq!(
+            conn.get_json::<Vec<String>>(key).await.unwrap(),
+            Some(value.clone())
+        );
+        let mut tx = conn.transaction().await.unwrap();
+        assert_eq!(tx.get_json::<Vec<String>>(key).await.unwrap(), Some(value));
+
+        conn.del(key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_watched_key_modification_aborts_exec() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:tx:watched";
+        conn.set_builtin(key, "original").await.unwrap();
+
+        let mut tx = conn.transaction().await.unwrap();
+        tx.watch(key).await.unwrap();
+        tx.set(key, "from-tx");
+
+        // 其他连接在提交前修改了被监视的键
+        conn.set_builtin(key, "concurrent").await.unwrap();
+        let err = tx.exec::<()>().await.unwrap_err();
+        assert!(err.is_transaction_aborted());
+        assert_eq!(
+            conn.get_builtin(key).await.unwrap(),
+            Some("concurrent".to_string())
+        );
+
+        // 未被修改时正常提交
+        let mut tx = conn.transaction().await.unwrap();
+        tx.watch(key).await.unwrap();
+        tx.set(key, "committed").incr("clamber:test:tx:count", 2);
+        let (_, count): ((), i64) = tx.exec().await.unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            conn.get_builtin(key).await.unwrap(),
+            Some("committed".to_string())
+        );
+
+        conn.del(&[key, "clamber:test:tx:count"]).await.unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+//! Redis 值类型模块
+//!
+//! 封装 TYPE 与 OBJECT ENCODING/IDLETIME 命令，用于排查内存占用等问题；
+//! 类型相关的命令遇到 WRONGTYPE 时，通过 TYPE 查询键的实际类型并返回 [`RedisError::TypeMismatch`]
+
+use crate::redis::{RedisConnection, RedisError, RedisResult};
+use std::time::Duration;
+
+/// TYPE 命令返回的值类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedisValueType {
+    /// 字符串
+    String,
+    /// 列表
+    List,
+    /// 哈希
+    Hash,
+    /// 集合
+    Set,
+    /// 有序集合
+    ZSet,
+    /// 流
+    Stream,
+    /// 键不存在
+    None,
+    /// 模块定义的类型（例如 RedisJSON 的 `ReJSON-RL`）
+    Other(String),
+}
+
+impl RedisValueType {
+    /// 按 TYPE 命令的返回值解析
+    pub fn from_type_name(name: &str) -> Self {
+        match name {
+            "string" => Self::String,
+            "list" => Self::List,
+            "hash" => Self::Hash,
+            "set" => Self::Set,
+            "zset" => Self::ZSet,
+            "stream" => Self::Stream,
+            "none" => Self::None,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// TYPE 命令中的类型名称
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::String => "string",
+            Self::List => "list",
+            Self::Hash => "hash",
+            Self::Set => "set",
+            Self::ZSet => "zset",
+            Self::Stream => "stream",
+            Self::None => "none",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+impl std::fmt::Display for RedisValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl RedisConnection {
+    /// 获取键的值类型，键不存在时返回 [`RedisValueType::None`]
+    pub async fn key_type(&mut self, key: &str) -> RedisResult<RedisValueType> {
+        self.raw_key_type(self.prefixed_key(key).as_bytes()).await
+    }
+
+    /// 获取键的内部编码（OBJECT ENCODING），例如 `listpack`、`hashtable`，键不存在时返回 None
+    pub async fn object_encoding(&mut self, key: &str) -> RedisResult<Option<String>> {
+        self.query(
+            redis::cmd("OBJECT")
+                .arg("ENCODING")
+                .arg(self.prefixed_key(key)),
+        )
+        .await
+    }
+
+    /// 获取键的空闲时间（OBJECT IDLETIME），键不存在时返回 None
+    ///
+    /// 服务端 `maxmemory-policy` 为 LFU 策略时该命令不可用，会返回错误
+    pub async fn object_idletime(&mut self, key: &str) -> RedisResult<Option<Duration>> {
+        let seconds: Option<u64> = self
+            .query(
+                redis::cmd("OBJECT")
+                    .arg("IDLETIME")
+                    .arg(self.prefixed_key(key)),
+            )
+            .await?;
+        Ok(seconds.map(Duration::from_secs))
+    }
+
+    /// 对已加前缀的键执行 TYPE
+    async fn raw_key_type(&mut self, key: &[u8]) -> RedisResult<RedisValueType> {
+        let name: String = self.query(redis::cmd("TYPE").arg(key)).await?;
+        Ok(RedisValueType::from_type_name(&name))
+    }
+
+    /// 命令返回 WRONGTYPE 时查询第一个键的实际类型，转换为 [`RedisError::TypeMismatch`]
+    ///
+    /// 查询失败时保留原始错误
+    pub(crate) async fn explain_wrong_type<T>(
+        &mut self,
+        result: RedisResult<T>,
+        key: &[Vec<u8>],
+        expected: RedisValueType,
+    ) -> RedisResult<T> {
+        let error = match result {
+            Err(e) if e.is_wrong_type() => e,
+            other => return other,
+        };
+        let Some(first) = key.first() else {
+            return Err(error);
+        };
+        match self.raw_key_type(first).await {
+            Ok(actual) => Err(RedisError::type_mismatch(
+                expected.as_str(),
+                actual.as_str(),
+            )),
+            Err(_) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+
+    #[test]
+    fn test_type_name_mapping() {
+        let cases = [
+            ("string", RedisValueType::String),
+            ("list", RedisValueType::List),
+            ("hash", RedisValueType::Hash),
+            ("set", RedisValueType::Set),
+            ("zset", RedisValueType::ZSet),
+            ("stream", RedisValueType::Stream),
+            ("none", RedisValueType::None),
+        ];
+        for (name, expected) in cases {
+            let parsed = RedisValueType::from_type_name(name);
+            assert_eq!(parsed, expected);
+            assert_eq!(parsed.as_str(), name);
+        }
+        assert_eq!(
+            RedisValueType::from_type_name("ReJSON-RL"),
+            RedisValueType::Other("ReJSON-RL".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_key_type_for_each_value_type() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let mut conn = conn.with_prefix("clamber:test:type:");
+        let keys = ["string", "list", "hash", "set", "zset", "stream", "none"];
+        conn.del(&keys).await.unwrap();
+
+        conn.set_builtin("string", "value").await.unwrap();
+        conn.rpush("list", "a").await.unwrap();
+        conn.hset("hash", "field", "value").await.unwrap();
+        let _: i64 = conn
+            .query(redis::cmd("SADD").arg(conn.prefixed_key("set")).arg("a"))
+            .await
+            .unwrap();
+        conn.zadd("zset", "a", 1.0).await.unwrap();
+        let _: String = conn
+            .query(
+                redis::cmd("XADD")
+                    .arg(conn.prefixed_key("stream"))
+                    .arg("*")
+                    .arg("field")
+                    .arg("value"),
+            )
+            .await
+            .unwrap();
+
+        for name in keys {
+            let actual = conn.key_type(name).await.unwrap();
+            assert_eq!(actual, RedisValueType::from_type_name(name));
+        }
+
+        assert!(conn.object_encoding("list").await.unwrap().is_some());
+        assert_eq!(conn.object_encoding("none").await.unwrap(), None);
+        conn.del(&keys).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wrong_type_reports_actual_type() {
+        let Some(mut conn) = connect_test_redis().await else {
+            return;
+        };
+        let key = "clamber:test:type:wrong";
+        conn.set_builtin(key, "value").await.unwrap();
+
+        let error = conn.hget(key, "field").await.unwrap_err();
+        match error {
+            RedisError::TypeMismatch { expected, actual } => {
+                assert_eq!(expected, "hash");
+                assert_eq!(actual, "string");
+            }
+            other => panic!("应返回 TypeMismatch，实际为 {:?}", other),
+        }
+        assert!(matches!(
+            conn.lpush(key, "a").await,
+            Err(RedisError::TypeMismatch { .. })
+        ));
+        conn.del(key).await.unwrap();
+    }
+}
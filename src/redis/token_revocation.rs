@@ -0,0 +1,411 @@
+//! Redis 令牌吊销模块
+//!
+//! 基于 Redis 的 JWT 吊销列表：
+//! - 单个令牌按 `jti` 写入墓碑记录，墓碑在令牌自然过期时一并过期
+//! - 按用户写入吊销水位线，签发时间早于水位线的令牌全部视为已吊销
+//!
+//! [`token_revocation_middleware`] 读取 `Authorization: Bearer` 令牌并拒绝已吊销的令牌。
+//! 中间件只解析令牌载荷中的 `jti`、`sub`、`iat`，签名校验仍由认证层负责
+
+use crate::redis::{RedisConnection, RedisResult, RedisScript};
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// 令牌吊销配置
+#[derive(Debug, Clone)]
+pub struct TokenRevocationConfig {
+    /// Redis 键前缀，墓碑键为 `{prefix}jti:{jti}`，水位线键为 `{prefix}user:{user_id}`
+    pub key_prefix: String,
+    /// 令牌的最长有效期，水位线保留该时长后过期（届时更早签发的令牌都已自然过期）
+    pub max_token_lifetime: Duration,
+    /// 查询 Redis 失败时是否放行请求（默认拒绝并返回 503）
+    pub fail_open: bool,
+}
+
+impl Default for TokenRevocationConfig {
+    fn default() -> Self {
+        Self {
+            key_prefix: "auth:revoked:".to_string(),
+            max_token_lifetime: Duration::from_secs(24 * 60 * 60),
+            fail_open: false,
+        }
+    }
+}
+
+/// 中间件检查所需的令牌声明
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct RevocationClaims {
+    /// 令牌 ID
+    #[serde(default)]
+    pub jti: Option<String>,
+    /// 用户 ID
+    #[serde(default)]
+    pub sub: Option<String>,
+    /// 签发时间（Unix 秒）
+    #[serde(default)]
+    pub iat: Option<i64>,
+}
+
+impl RevocationClaims {
+    /// 从 JWT 中解析载荷（不校验签名），格式无效时返回 None
+    pub fn from_jwt(token: &str) -> Option<Self> {
+        let mut segments = token.split('.');
+        let (_, payload, _) = (segments.next()?, segments.next()?, segments.next()?);
+        if segments.next().is_some() {
+            return None;
+        }
+        serde_json::from_slice(&decode_base64_url(payload)?).ok()
+    }
+}
+
+/// 令牌吊销存储，克隆的存储共享同一个连接与配置
+#[derive(Clone)]
+pub struct TokenRevocationStore {
+    connection: RedisConnection,
+    config: Arc<TokenRevocationConfig>,
+}
+
+impl TokenRevocationStore {
+    /// 使用默认配置创建吊销存储
+    pub fn new(connection: RedisConnection) -> Self {
+        Self::with_config(connection, TokenRevocationConfig::default())
+    }
+
+    /// 使用指定配置创建吊销存储
+    pub fn with_config(connection: RedisConnection, config: TokenRevocationConfig) -> Self {
+        Self {
+            connection,
+            config: Arc::new(config),
+        }
+    }
+
+    /// 吊销单个令牌，墓碑保留到令牌的过期时间 `exp`（Unix 秒），已过期的令牌无需记录
+    pub async fn revoke(&self, jti: &str, exp: i64) -> RedisResult<()> {
+        let Some(ttl) = remaining(exp) else {
+            return Ok(());
+        };
+        let mut conn = self.connection.clone();
+        conn.query(
+            redis::cmd("SET")
+                .arg(conn.prefixed_key(&self.jti_key(jti)))
+                .arg(1)
+                .arg("PX")
+                .arg(ttl.as_millis() as u64),
+        )
+        .await
+    }
+
+    /// 令牌是否已被单独吊销
+    pub async fn is_revoked(&self, jti: &str) -> RedisResult<bool> {
+        let mut conn = self.connection.clone();
+        conn.exists_builtin(self.jti_key(jti)).await
+    }
+
+    /// 吊销用户在 `issued_before`（Unix 秒）之前签发的全部令牌，水位线只会前移
+    pub async fn revoke_all_for_user(&self, user_id: &str, issued_before: i64) -> RedisResult<()> {
+        let expires_at =
+            issued_before.saturating_add(self.config.max_token_lifetime.as_secs() as i64);
+        let Some(ttl) = remaining(expires_at) else {
+            return Ok(());
+        };
+        let mut conn = self.connection.clone();
+        let _: i64 = RAISE_WATERMARK_SCRIPT
+            .exec(
+                &mut conn,
+                &[self.user_key(user_id)],
+                &[issued_before, ttl.as_millis() as i64],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// 获取用户的吊销水位线
+    pub async fn revoked_before(&self, user_id: &str) -> RedisResult<Option<i64>> {
+        let mut conn = self.connection.clone();
+        let raw = conn.get_builtin(self.user_key(user_id)).await?;
+        Ok(raw.and_then(|raw| raw.parse().ok()))
+    }
+
+    /// 检查令牌声明是否已被吊销（单独吊销或早于用户水位线）
+    pub async fn is_claims_revoked(&self, claims: &RevocationClaims) -> RedisResult<bool> {
+        if let Some(jti) = &claims.jti {
+            if self.is_revoked(jti).await? {
+                return Ok(true);
+            }
+        }
+        if let (Some(sub), Some(iat)) = (&claims.sub, claims.iat) {
+            if let Some(watermark) = self.revoked_before(sub).await? {
+                return Ok(iat < watermark);
+            }
+        }
+        Ok(false)
+    }
+
+    fn jti_key(&self, jti: &str) -> String {
+        format!("{}jti:{}", self.config.key_prefix, jti)
+    }
+
+    fn user_key(&self, user_id: &str) -> String {
+        format!("{}user:{}", self.config.key_prefix, user_id)
+    }
+}
+
+/// 令牌吊销中间件，配合 `axum::middleware::from_fn_with_state` 使用
+///
+/// 未携带 Bearer 令牌或令牌无法解析时直接放行，交由认证层处理；已吊销的令牌返回 401
+pub async fn token_revocation_middleware(
+    State(store): State<TokenRevocationStore>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(claims) = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(bearer_token)
+        .and_then(RevocationClaims::from_jwt)
+    else {
+        return next.run(request).await;
+    };
+
+    match store.is_claims_revoked(&claims).await {
+        Ok(false) => next.run(request).await,
+        Ok(true) => unauthorized(),
+        Err(e) if store.config.fail_open => {
+            warn!("令牌吊销检查失败，放行请求: {}", e);
+            next.run(request).await
+        }
+        Err(e) => {
+            warn!("令牌吊销检查失败: {}", e);
+            StatusCode::SERVICE_UNAVAILABLE.into_response()
+        }
+    }
+}
+
+/// 令牌已吊销时的 401 响应
+fn unauthorized() -> Response {
+    let mut response = (StatusCode::UNAUTHORIZED, "token revoked").into_response();
+    response.headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        HeaderValue::from_static(
+            "Bearer error=\"invalid_token\", error_description=\"token revoked\"",
+        ),
+    );
+    response
+}
+
+/// 提取 `Bearer` 令牌
+fn bearer_token(value: &str) -> Option<&str> {
+    let (scheme, token) = value.trim().split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then_some(token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+/// 距离指定时间点（Unix 秒）的剩余时长，已过去时返回 None
+fn remaining(unix_secs: i64) -> Option<Duration> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+    let target = Duration::from_secs(u64::try_from(unix_secs).ok()?);
+    target.checked_sub(now).filter(|ttl| !ttl.is_zero())
+}
+
+/// 解码 base64url（允许省略填充）
+fn decode_base64_url(input: &str) -> Option<Vec<u8>> {
+    let input = input.trim_end_matches('=');
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in input.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'-' => 62,
+            b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Some(output)
+}
+
+/// 仅当新的水位线更晚时写入，并刷新过期时间
+static RAISE_WATERMARK_SCRIPT: LazyLock<RedisScript> = LazyLock::new(|| {
+    RedisScript::new(
+        "local current = tonumber(redis.call('GET', KEYS[1]))
+if current and current >= tonumber(ARGV[1]) then return 0 end
+redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+return 1",
+    )
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support::connect_test_redis;
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    fn unique(name: &str) -> String {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        format!("{}-{}-{}", name, std::process::id(), nanos)
+    }
+
+    /// 编码 base64url（无填充），仅用于构造测试令牌
+    fn encode_base64_url(input: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+        let mut output = String::new();
+        for chunk in input.chunks(3) {
+            let buffer = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |acc, (i, b)| acc | ((*b as u32) << (16 - 8 * i)));
+            for i in 0..=chunk.len() {
+                output.push(ALPHABET[((buffer >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            }
+        }
+        output
+    }
+
+    fn jwt(payload: serde_json::Value) -> String {
+        format!(
+            "{}.{}.signature",
+            encode_base64_url(br#"{"alg":"HS256","typ":"JWT"}"#),
+            encode_base64_url(payload.to_string().as_bytes())
+        )
+    }
+
+    #[test]
+    fn test_claims_from_jwt() {
+        let token = jwt(
+            serde_json::json!({"jti": "t-1", "sub": "u-1", "iat": 1700000000, "role": "admin"}),
+        );
+        let claims = RevocationClaims::from_jwt(&token).unwrap();
+        assert_eq!(claims.jti.as_deref(), Some("t-1"));
+        assert_eq!(claims.sub.as_deref(), Some("u-1"));
+        assert_eq!(claims.iat, Some(1700000000));
+
+        assert!(RevocationClaims::from_jwt("not-a-jwt").is_none());
+        assert!(RevocationClaims::from_jwt("a.b!.c").is_none());
+        assert_eq!(bearer_token("Bearer abc"), Some("abc"));
+        assert_eq!(bearer_token("Basic abc"), None);
+    }
+
+    #[tokio::test]
+    async fn test_revocation_takes_effect_and_expires() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let store = TokenRevocationStore::new(conn);
+        let jti = unique("jti");
+
+        assert!(!store.is_revoked(&jti).await.unwrap());
+        store.revoke(&jti, now() + 1).await.unwrap();
+        assert!(store.is_revoked(&jti).await.unwrap());
+
+        // 墓碑随令牌一起过期
+        tokio::time::sleep(Duration::from_millis(2100)).await;
+        assert!(!store.is_revoked(&jti).await.unwrap());
+
+        // 已过期的令牌不写入墓碑
+        store.revoke(&jti, now() - 10).await.unwrap();
+        assert!(!store.is_revoked(&jti).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_for_user() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let store = TokenRevocationStore::new(conn);
+        let user = unique("user");
+        let issued_at = now();
+        let claims = |iat: i64| RevocationClaims {
+            jti: Some(unique("jti")),
+            sub: Some(user.clone()),
+            iat: Some(iat),
+        };
+
+        assert!(!store.is_claims_revoked(&claims(issued_at)).await.unwrap());
+        store
+            .revoke_all_for_user(&user, issued_at + 1)
+            .await
+            .unwrap();
+        assert!(store.is_claims_revoked(&claims(issued_at)).await.unwrap());
+        assert!(
+            !store
+                .is_claims_revoked(&claims(issued_at + 1))
+                .await
+                .unwrap()
+        );
+
+        // 水位线不会回退
+        store
+            .revoke_all_for_user(&user, issued_at - 100)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.revoked_before(&user).await.unwrap(),
+            Some(issued_at + 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_middleware_rejects_revoked_token() {
+        let Some(conn) = connect_test_redis().await else {
+            return;
+        };
+        let store = TokenRevocationStore::new(conn);
+        let app = Router::new().route("/me", get(|| async { "ok" })).layer(
+            axum::middleware::from_fn_with_state(store.clone(), token_revocation_middleware),
+        );
+        let jti = unique("jti");
+        let token = jwt(serde_json::json!({"jti": jti, "sub": "u-1", "iat": now()}));
+        let request = || {
+            axum::http::Request::get("/me")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        assert_eq!(
+            app.clone().oneshot(request()).await.unwrap().status(),
+            StatusCode::OK
+        );
+        store.revoke(&jti, now() + 60).await.unwrap();
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+
+        // 未携带令牌的请求交由认证层处理
+        let anonymous = axum::http::Request::get("/me").body(Body::empty()).unwrap();
+        assert_eq!(
+            app.oneshot(anonymous).await.unwrap().status(),
+            StatusCode::OK
+        );
+    }
+}